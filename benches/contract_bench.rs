@@ -8,6 +8,7 @@
 //! - Contract validation throughput (proposals per second)
 //! - Audit entry creation cost
 //! - GatingRequest builder overhead
+//! - Request body parsing, as `conative serve` does per request
 //!
 //! Target baselines:
 //! - Single gate decision: <1ms
@@ -253,6 +254,19 @@ fn bench_request_construction(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: parsing a request body, as `conative serve` does per request.
+fn bench_request_parsing(c: &mut Criterion) {
+    let body = serde_json::to_vec(&GatingRequest::new(complex_proposal())).unwrap();
+    let mut group = c.benchmark_group("request_parsing");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+
+    group.bench_function("request", |b| {
+        b.iter(|| black_box(serde_json::from_slice::<GatingRequest>(black_box(&body)).unwrap()))
+    });
+
+    group.finish();
+}
+
 /// Benchmark: ContractRunner construction cost.
 ///
 /// Creating a runner builds the Policy and Oracle. This is typically done
@@ -280,6 +294,7 @@ fn bench_runner_construction(c: &mut Criterion) {
     bench_contract_throughput,
     bench_audit_entry_creation,
     bench_request_construction,
+    bench_request_parsing,
     bench_runner_construction,
 );
 criterion_main!(contract_benches);
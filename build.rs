@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//
+//! Records the commit the binary is built from for `/buildinfo`.
+//!
+//! `CONATIVE_GIT_SHA` set at build time wins (container builds have no
+//! `.git`); otherwise `git rev-parse HEAD`, and nothing outside a checkout.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CONATIVE_GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    let sha = std::env::var("CONATIVE_GIT_SHA").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .map(|s| s.trim().to_string())
+    });
+    if let Some(sha) = sha {
+        println!("cargo:rustc-env=CONATIVE_GIT_SHA={}", sha);
+    }
+}
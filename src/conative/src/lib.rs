@@ -0,0 +1,287 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Conative - stable library API for conative gating
+//!
+//! One dependency for integrators: build a [`Gate`] from a policy, hand it
+//! proposals or full requests, and get decisions back. Everything exported
+//! here follows semver; the `policy-oracle` and `gating-contract` crates
+//! behind it may change between minor versions.
+//!
+//! ```no_run
+//! use conative::{ActionType, Gate, Policy, Proposal, Verdict};
+//!
+//! let gate = Gate::builder().policy(Policy::rsr_default()).build()?;
+//! let decision = gate.check(Proposal {
+//!     id: Default::default(),
+//!     action_type: ActionType::CreateFile { path: "src/app.ts".into() },
+//!     content: "const x = 1;".into(),
+//!     files_affected: vec!["src/app.ts".into()],
+//!     llm_confidence: 0.9,
+//! })?;
+//! assert_eq!(decision.verdict, Verdict::Block);
+//! # Ok::<(), conative::Error>(())
+//! ```
+
+use gating_contract::{policy_code_errors, ContractRunner};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub use gating_contract::{
+    AuditEntry, AuthorizationLevel, Clock, CodeRange, EscalationReason, Evidence, EvidenceType,
+    FixedClock, GatingDecision, GatingRequest, IdSource, ManualClock, Refusal, RefusalCategory,
+    RefusalCode, RequestContext, SeededIds, Verdict, CONTRACT_VERSION,
+};
+pub use policy_oracle::{
+    ActionMap, ActionType, ContextWindow, EvidenceRedaction, GateAction, ImportRule, Policy,
+    Proposal, Severity, UnsafeMode,
+};
+pub use slm_evaluator::{SlmBackend, SlmEvaluator};
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The policy could not be read or parsed
+    #[error("Policy error: {0}")]
+    Policy(String),
+
+    /// The contract failed to evaluate a request
+    #[error(transparent)]
+    Contract(#[from] gating_contract::ContractError),
+}
+
+// ============================================================================
+// GATE
+// ============================================================================
+
+/// Evaluates proposals against a policy
+pub struct Gate {
+    runner: ContractRunner,
+}
+
+impl Gate {
+    /// Gate with the RSR default policy
+    pub fn new() -> Self {
+        Self {
+            runner: ContractRunner::new(),
+        }
+    }
+
+    pub fn builder() -> GateBuilder {
+        GateBuilder::default()
+    }
+
+    /// Decide a full request
+    pub fn evaluate(&self, request: &GatingRequest) -> Result<GatingDecision, Error> {
+        Ok(self.runner.evaluate(request)?)
+    }
+
+    /// Decide a single proposal with a default request context
+    pub fn check(&self, proposal: Proposal) -> Result<GatingDecision, Error> {
+        self.evaluate(&GatingRequest::new(proposal))
+    }
+
+    /// Audit log entry for a decision this gate made
+    pub fn audit(&self, request: &GatingRequest, decision: &GatingDecision) -> AuditEntry {
+        self.runner.audit(request, decision)
+    }
+}
+
+impl Default for Gate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for [`Gate`]; every setting is optional
+#[derive(Default)]
+pub struct GateBuilder {
+    policy: Option<Policy>,
+    policy_file: Option<PathBuf>,
+    slm: Option<SlmEvaluator>,
+    clock: Option<Arc<dyn Clock>>,
+    ids: Option<Arc<dyn IdSource>>,
+    explain: bool,
+}
+
+impl GateBuilder {
+    /// Policy to enforce (default: the RSR policy); not with
+    /// [`GateBuilder::policy_file`]
+    pub fn policy(mut self, policy: Policy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Load the policy from a JSON file at build time; writes to that file
+    /// are then refused as policy tampering. Not with [`GateBuilder::policy`]
+    pub fn policy_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.policy_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// SLM spirit stage, replacing any backend the policy configures
+    pub fn slm(mut self, evaluator: SlmEvaluator) -> Self {
+        self.slm = Some(evaluator);
+        self
+    }
+
+    /// Clock for decision and audit timestamps
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Source of decision and audit IDs
+    pub fn id_source(mut self, ids: Arc<dyn IdSource>) -> Self {
+        self.ids = Some(ids);
+        self
+    }
+
+    /// Record every rule outcome in `GatingDecision::trace`
+    pub fn explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    pub fn build(self) -> Result<Gate, Error> {
+        let policy = match (&self.policy_file, self.policy) {
+            (Some(_), Some(_)) => {
+                return Err(Error::Policy(
+                    "policy and policy_file are mutually exclusive".to_string(),
+                ))
+            }
+            (Some(path), None) => read_policy(path)?,
+            (None, Some(policy)) => policy,
+            (None, None) => Policy::rsr_default(),
+        };
+
+        let mut runner = ContractRunner::with_policy(policy).with_explain(self.explain);
+        if let Some(path) = &self.policy_file {
+            runner = runner.with_policy_path(path.to_string_lossy());
+        }
+        if let Some(slm) = self.slm {
+            runner = runner.with_slm(slm);
+        }
+        if let Some(clock) = self.clock {
+            runner = runner.with_clock(clock);
+        }
+        if let Some(ids) = self.ids {
+            runner = runner.with_id_source(ids);
+        }
+        Ok(Gate { runner })
+    }
+}
+
+fn read_policy(path: &Path) -> Result<Policy, Error> {
+    if path.extension().is_some_and(|e| e == "ncl") {
+        return Err(Error::Policy(format!(
+            "{}: export Nickel policies to JSON first (nickel export --format json)",
+            path.display()
+        )));
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| Error::Policy(format!("{}: {}", path.display(), e)))?;
+    let policy: Policy = serde_json::from_str(&json)
+        .map_err(|e| Error::Policy(format!("{}: {}", path.display(), e)))?;
+    match policy_code_errors(&policy).as_slice() {
+        [] => Ok(policy),
+        errors => Err(Error::Policy(format!(
+            "{}: {}",
+            path.display(),
+            errors.join("; ")
+        ))),
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slm_evaluator::MockBackend;
+
+    fn proposal(path: &str, content: &str) -> Proposal {
+        Proposal {
+            id: uuid::Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: path.to_string(),
+            },
+            content: content.to_string(),
+            files_affected: vec![path.to_string()],
+            llm_confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_gate_builder_matches_default_gate() {
+        let gate = Gate::builder()
+            .policy(Policy::rsr_default())
+            .clock(Arc::new(FixedClock(Default::default())))
+            .build()
+            .unwrap();
+        let decision = gate.check(proposal("src/app.ts", "const x = 1;")).unwrap();
+        assert_eq!(decision.verdict, Verdict::Block);
+        assert_eq!(
+            decision.verdict,
+            Gate::new()
+                .check(proposal("src/app.ts", "const x = 1;"))
+                .unwrap()
+                .verdict
+        );
+        assert_eq!(decision.processing.duration_us, 0);
+
+        let allowed = gate.check(proposal("src/lib.rs", "pub fn f() {}")).unwrap();
+        assert_eq!(allowed.verdict, Verdict::Allow);
+    }
+
+    #[test]
+    fn test_gate_builder_refuses_policy_and_policy_file() {
+        let both = Gate::builder()
+            .policy(Policy::rsr_default())
+            .policy_file("/nonexistent/policy.json")
+            .build();
+        let Err(Error::Policy(message)) = both else {
+            panic!("a gate was built with two policies");
+        };
+        assert_eq!(message, "policy and policy_file are mutually exclusive");
+    }
+
+    #[test]
+    fn test_gate_builder_slm_stage_and_policy_file() {
+        let gate = Gate::builder()
+            .slm(SlmEvaluator::with_backend(Box::new(MockBackend::new(0.95))))
+            .build()
+            .unwrap();
+        let decision = gate.check(proposal("src/lib.rs", "pub fn f() {}")).unwrap();
+        assert!(decision.evaluations.slm.is_some());
+
+        let missing = Gate::builder()
+            .policy_file("/nonexistent/policy.json")
+            .build();
+        assert!(matches!(missing, Err(Error::Policy(_))));
+        let nickel = Gate::builder().policy_file("policy.ncl").build();
+        assert!(matches!(nickel, Err(Error::Policy(_))));
+
+        let mut policy = Policy::rsr_default();
+        policy.patterns.forbidden_patterns[0].code = Some(100);
+        let file =
+            std::env::temp_dir().join(format!("conative-policy-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&file, serde_json::to_string(&policy).unwrap()).unwrap();
+        let refused = Gate::builder().policy_file(&file).build();
+        std::fs::remove_file(&file).unwrap();
+        let Err(Error::Policy(message)) = refused else {
+            panic!("a pattern rule with a language code was loaded");
+        };
+        let rule = &policy.patterns.forbidden_patterns[0].name;
+        assert!(
+            message.contains(&format!("pattern:{}:", rule)),
+            "{}",
+            message
+        );
+    }
+}
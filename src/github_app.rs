@@ -0,0 +1,464 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! `conative github-app`: gating pull requests from webhook deliveries
+//!
+//! Deliveries are verified and acknowledged at once; pull requests that need
+//! a gate are queued for one thread that fetches their changed files,
+//! evaluates them as a set and posts a check run.
+
+use crate::serve::{header, read_body, respond, serve_error, write_envelope, ServedPolicy};
+use crate::{CliError, ErrorKind, OutputFormat};
+use gating_contract::{
+    verify_webhook_signature, CheckRun, CheckRunOutput, PullRequestEvent, GITHUB_CHECK_NAME,
+};
+use policy_oracle::{decode_bytes, Policy};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Largest webhook delivery GitHub sends
+const GITHUB_MAX_PAYLOAD: u64 = 25 * 1024 * 1024;
+
+/// Annotations GitHub accepts in one check run request
+const GITHUB_ANNOTATION_BATCH: usize = 50;
+
+/// Changed files listed per page of a pull request
+const GITHUB_FILES_PER_PAGE: usize = 100;
+
+const GITHUB_API_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How `conative github-app` listens and reaches GitHub
+pub(crate) struct GithubAppOptions {
+    pub(crate) listen: String,
+    pub(crate) webhook_secret: String,
+    pub(crate) api: GithubApi,
+    pub(crate) audit_log: Option<PathBuf>,
+}
+
+/// The GitHub REST API calls the app makes
+pub(crate) struct GithubApi {
+    pub(crate) url: String,
+    pub(crate) token: String,
+}
+
+impl GithubApi {
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(method, &format!("{}{}", self.url, path))
+            .timeout(GITHUB_API_TIMEOUT)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("X-GitHub-Api-Version", "2022-11-28")
+            .set(
+                "User-Agent",
+                concat!("conative/", env!("CARGO_PKG_VERSION")),
+            )
+    }
+
+    /// Every file the pull request adds or changes, as it is at the head;
+    /// removed files have nothing to gate
+    fn changed_files(&self, event: &PullRequestEvent) -> Result<BTreeMap<String, String>, String> {
+        use std::io::Read;
+        let repo = &event.repository.full_name;
+        let mut files = BTreeMap::new();
+        for page in 1.. {
+            let path = format!(
+                "/repos/{}/pulls/{}/files?per_page={}&page={}",
+                repo, event.number, GITHUB_FILES_PER_PAGE, page
+            );
+            let listed: Vec<serde_json::Value> = self
+                .request("GET", &path)
+                .call()
+                .map_err(|e| format!("Listing changed files: {}", e))?
+                .into_json()
+                .map_err(|e| format!("Listing changed files: {}", e))?;
+            for file in &listed {
+                let Some(name) = file["filename"]
+                    .as_str()
+                    .filter(|_| file["status"] != "removed")
+                else {
+                    continue;
+                };
+                let path = format!(
+                    "/repos/{}/contents/{}?ref={}",
+                    repo,
+                    encode_url_path(name),
+                    event.pull_request.head.sha
+                );
+                let mut bytes = Vec::new();
+                self.request("GET", &path)
+                    .set("Accept", "application/vnd.github.raw")
+                    .call()
+                    .map_err(|e| format!("Fetching {}: {}", name, e))?
+                    .into_reader()
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| format!("Fetching {}: {}", name, e))?;
+                files.insert(name.to_string(), decode_bytes(&bytes).text);
+            }
+            if listed.len() < GITHUB_FILES_PER_PAGE {
+                break;
+            }
+        }
+        Ok(files)
+    }
+
+    /// Post `check`, sending annotations past the first batch as updates
+    fn create_check_run(&self, repo: &str, check: CheckRun) -> Result<(), String> {
+        let (check, updates) = annotation_batches(check);
+        let created: serde_json::Value = self
+            .request("POST", &format!("/repos/{}/check-runs", repo))
+            .send_json(&check)
+            .map_err(|e| format!("Creating check run: {}", e))?
+            .into_json()
+            .map_err(|e| format!("Creating check run: {}", e))?;
+        let id = created["id"]
+            .as_u64()
+            .ok_or("Created check run has no id")?;
+        for output in updates {
+            self.request("PATCH", &format!("/repos/{}/check-runs/{}", repo, id))
+                .send_json(serde_json::json!({ "output": output }))
+                .map_err(|e| format!("Adding annotations: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Split `check` into the check run to create, holding the first batch of
+/// annotations, and the outputs that add the rest
+fn annotation_batches(mut check: CheckRun) -> (CheckRun, Vec<CheckRunOutput>) {
+    let first = check.output.annotations.len().min(GITHUB_ANNOTATION_BATCH);
+    let mut rest = check.output.annotations.split_off(first);
+    let mut updates = Vec::new();
+    while !rest.is_empty() {
+        let batch = rest
+            .drain(..rest.len().min(GITHUB_ANNOTATION_BATCH))
+            .collect();
+        updates.push(CheckRunOutput {
+            annotations: batch,
+            ..check.output.clone()
+        });
+    }
+    (check, updates)
+}
+
+/// Check run for a pull request whose every change removes a file; a
+/// required check must still report
+fn deletions_only_check_run(head_sha: &str) -> CheckRun {
+    CheckRun {
+        name: GITHUB_CHECK_NAME.to_string(),
+        head_sha: head_sha.to_string(),
+        status: "completed".to_string(),
+        conclusion: "success".to_string(),
+        output: CheckRunOutput {
+            title: "No changed files to gate".to_string(),
+            summary: "Every change in this pull request removes a file.".to_string(),
+            annotations: Vec::new(),
+        },
+    }
+}
+
+/// Percent-encode a repository path for a URL, keeping its slashes
+fn encode_url_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub(crate) fn run_github_app(policy: Policy, options: &GithubAppOptions) -> i32 {
+    let served = match ServedPolicy::new(policy, options.audit_log.as_deref()) {
+        Ok(s) => s,
+        Err(e) => return CliError::new(ErrorKind::Config, e).report(&OutputFormat::Text),
+    };
+    let server = match tiny_http::Server::http(&options.listen) {
+        Ok(s) => s,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Config,
+                format!("Cannot listen on {}: {}", options.listen, e),
+            )
+            .report(&OutputFormat::Text)
+        }
+    };
+    eprintln!(
+        "Receiving GitHub webhooks on http://{}/webhook",
+        options.listen
+    );
+
+    // Deliveries are acknowledged at once; pull requests are gated in turn
+    let (queue, pending) = std::sync::mpsc::channel::<PullRequestEvent>();
+    let served = &served;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            for event in pending {
+                let name = format!("{}#{}", event.repository.full_name, event.number);
+                match gate_pull_request(served, &options.api, &event) {
+                    Ok(conclusion) => eprintln!(
+                        "Gated {} at {}: {}",
+                        name, event.pull_request.head.sha, conclusion
+                    ),
+                    Err(e) => eprintln!("Failed to gate {}: {}", name, e),
+                }
+            }
+        });
+
+        let mut body = Vec::new();
+        let mut reply = Vec::new();
+        for mut request in server.incoming_requests() {
+            body.clear();
+            reply.clear();
+            let status = receive_webhook(
+                &mut request,
+                &options.webhook_secret,
+                &queue,
+                &mut body,
+                &mut reply,
+            );
+            respond(request, status, &reply, &[]);
+        }
+        drop(queue);
+    });
+    0
+}
+
+/// Check one delivery's signature and queue a pull request that needs a
+/// gate; other events are acknowledged and ignored
+fn receive_webhook(
+    request: &mut tiny_http::Request,
+    secret: &str,
+    queue: &std::sync::mpsc::Sender<PullRequestEvent>,
+    body: &mut Vec<u8>,
+    reply: &mut Vec<u8>,
+) -> u16 {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    if path != "/webhook" {
+        return serve_error(
+            reply,
+            404,
+            CliError::new(ErrorKind::Usage, format!("No endpoint at {}", path)),
+        );
+    }
+    if request.method() != &tiny_http::Method::Post {
+        let message = format!("{} is not allowed on {}", request.method(), path);
+        return serve_error(reply, 405, CliError::new(ErrorKind::Usage, message));
+    }
+    if let Err((status, error)) = read_body(request, GITHUB_MAX_PAYLOAD, body) {
+        return serve_error(reply, status, error);
+    }
+    if !verify_webhook_signature(
+        secret,
+        body,
+        header(request, "X-Hub-Signature-256").unwrap_or_default(),
+    ) {
+        let error = CliError::new(ErrorKind::Auth, "Webhook signature does not match")
+            .with_hint("Set --webhook-secret to the App's webhook secret");
+        return serve_error(reply, 401, error);
+    }
+
+    let mut status = "ignored";
+    if header(request, "X-GitHub-Event") == Some("pull_request") {
+        let event = match serde_json::from_slice::<PullRequestEvent>(body) {
+            Ok(e) => e,
+            Err(e) => {
+                let error = CliError::new(
+                    ErrorKind::Parse,
+                    format!("Failed to parse pull_request event: {}", e),
+                );
+                return serve_error(reply, 400, error);
+            }
+        };
+        if event.needs_gating() {
+            if queue.send(event).is_err() {
+                return serve_error(
+                    reply,
+                    503,
+                    CliError::new(ErrorKind::Internal, "No gate is running"),
+                );
+            }
+            status = "queued";
+        }
+    }
+    write_envelope(reply, None, &serde_json::json!({ "status": status }));
+    if status == "queued" {
+        202
+    } else {
+        200
+    }
+}
+
+/// Gate a pull request's head and post the check run; returns its conclusion
+fn gate_pull_request(
+    served: &ServedPolicy,
+    api: &GithubApi,
+    event: &PullRequestEvent,
+) -> Result<String, String> {
+    let head = &event.pull_request.head.sha;
+    let files = api.changed_files(event)?;
+    let check = if files.is_empty() {
+        deletions_only_check_run(head)
+    } else {
+        let decision = served
+            .evaluate_set(&event.gating_request(files))
+            .map_err(|e| e.message)?;
+        CheckRun::for_decision(head, &decision)
+    };
+    let conclusion = check.conclusion.clone();
+    api.create_check_run(&event.repository.full_name, check)?;
+    Ok(conclusion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gating_contract::CheckAnnotation;
+    use std::sync::mpsc::{channel, Receiver};
+
+    const SECRET: &str = "webhook-secret";
+
+    /// A webhook delivery of `event`, signed with `secret`
+    fn delivery(event: &'static str, body: &str, secret: &str) -> tiny_http::Request {
+        use hmac::Mac;
+        let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        let signature: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let signature = format!("X-Hub-Signature-256: sha256={}", signature);
+        tiny_http::TestRequest::new()
+            .with_method(tiny_http::Method::Post)
+            .with_path("/webhook")
+            .with_header(format!("X-GitHub-Event: {}", event).parse().unwrap())
+            .with_header(signature.parse().unwrap())
+            .with_body(Box::leak(body.to_string().into_boxed_str()))
+            .into()
+    }
+
+    /// A `pull_request` payload as GitHub sends it to a repository webhook,
+    /// with no `installation`
+    fn pull_request(action: &str) -> String {
+        serde_json::json!({
+            "action": action,
+            "number": 7,
+            "pull_request": {
+                "head": { "ref": "feature", "sha": "abc123" },
+                "base": { "ref": "main", "sha": "def456" }
+            },
+            "repository": { "full_name": "org/repo", "default_branch": "main" },
+            "sender": { "login": "octocat" }
+        })
+        .to_string()
+    }
+
+    /// Status and `status` field the webhook answers `request` with, and
+    /// the queue it fed
+    fn receive(mut request: tiny_http::Request) -> (u16, String, Receiver<PullRequestEvent>) {
+        let (queue, pending) = channel();
+        let (mut body, mut reply) = (Vec::new(), Vec::new());
+        let status = receive_webhook(&mut request, SECRET, &queue, &mut body, &mut reply);
+        let reply: serde_json::Value = serde_json::from_slice(&reply).unwrap();
+        let field = reply["status"].as_str().unwrap_or_default().to_string();
+        (status, field, pending)
+    }
+
+    fn annotations(n: u32) -> CheckRun {
+        let mut check = deletions_only_check_run("abc123");
+        check.conclusion = "failure".to_string();
+        check.output.annotations = (1..=n)
+            .map(|line| CheckAnnotation {
+                path: "src/app.ts".to_string(),
+                start_line: line,
+                end_line: line,
+                annotation_level: "failure".to_string(),
+                title: "conative 100".to_string(),
+                message: "TypeScript".to_string(),
+            })
+            .collect();
+        check
+    }
+
+    #[test]
+    fn test_unsupported_events_are_acknowledged_and_ignored() {
+        for event in ["push", "installation", "check_run"] {
+            let (status, field, pending) = receive(delivery(event, "{}", SECRET));
+            assert_eq!((status, field.as_str()), (200, "ignored"), "{}", event);
+            assert!(pending.try_recv().is_err());
+        }
+        // A pull request event that leaves the head alone is not gated
+        let (status, field, pending) =
+            receive(delivery("pull_request", &pull_request("labeled"), SECRET));
+        assert_eq!((status, field.as_str()), (200, "ignored"));
+        assert!(pending.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_pull_request_without_installation_is_queued() {
+        let (status, field, pending) = receive(delivery(
+            "pull_request",
+            &pull_request("synchronize"),
+            SECRET,
+        ));
+        assert_eq!((status, field.as_str()), (202, "queued"));
+        let event = pending.try_recv().unwrap();
+        assert_eq!(event.repository.full_name, "org/repo");
+        assert_eq!(event.pull_request.head.sha, "abc123");
+    }
+
+    #[test]
+    fn test_unsigned_and_malformed_deliveries_are_refused() {
+        let body = pull_request("opened");
+        let (status, _, pending) = receive(delivery("pull_request", &body, "another secret"));
+        assert_eq!(status, 401);
+        assert!(pending.try_recv().is_err());
+
+        let (status, _, pending) =
+            receive(delivery("pull_request", r#"{"action":"opened"}"#, SECRET));
+        assert_eq!(status, 400);
+        assert!(pending.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_check_run_annotations_are_sent_in_batches() {
+        let (check, updates) = annotation_batches(annotations(120));
+        assert_eq!(check.output.annotations.len(), GITHUB_ANNOTATION_BATCH);
+        assert_eq!(check.output.annotations[0].start_line, 1);
+        let sizes: Vec<usize> = updates.iter().map(|o| o.annotations.len()).collect();
+        assert_eq!(sizes, [50, 20]);
+        assert_eq!(updates[1].annotations[0].start_line, 101);
+        assert!(updates.iter().all(|o| o.title == check.output.title));
+
+        let payload = serde_json::to_value(&check).unwrap();
+        assert_eq!(payload["name"], GITHUB_CHECK_NAME);
+        assert_eq!(payload["head_sha"], "abc123");
+        assert_eq!(payload["status"], "completed");
+        assert_eq!(payload["conclusion"], "failure");
+        assert_eq!(payload["output"]["annotations"][0]["path"], "src/app.ts");
+
+        let (check, updates) = annotation_batches(annotations(GITHUB_ANNOTATION_BATCH as u32));
+        assert_eq!(check.output.annotations.len(), GITHUB_ANNOTATION_BATCH);
+        assert!(updates.is_empty());
+    }
+
+    #[test]
+    fn test_deletions_only_pull_request_passes_its_check() {
+        let check = deletions_only_check_run("abc123");
+        assert_eq!(check.name, GITHUB_CHECK_NAME);
+        assert_eq!(check.conclusion, "success");
+        assert!(check.output.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_url_paths_keep_slashes_and_encode_the_rest() {
+        assert_eq!(encode_url_path("src/lib.rs"), "src/lib.rs");
+        assert_eq!(encode_url_path("docs/a b#?.md"), "docs/a%20b%23%3F.md");
+    }
+}
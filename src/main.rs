@@ -17,15 +17,46 @@
 //!
 //! # Reversibility
 //!
-//! This tool is read-only by design. It analyzes but never modifies files.
-//! All operations are safe to run repeatedly.
-
-use clap::{Parser, Subcommand, ValueEnum};
+//! Scanning, checking and evaluating never modify files. A command writes
+//! only when asked to by its name or an explicit flag: `fix --apply`,
+//! `init`, `triage` (on save), `model pull`, `stats --save`,
+//! `training export`, `contract record`, `contract redteam`
+//! generate/minimize/suggest, `--save-baseline`, and `--audit-log` or
+//! `--attest`. `doctor` creates and removes a probe file to check that
+//! state directories are writable.
+
+mod github_app;
+mod rulepacks;
+mod serve;
+
+use clap::parser::ValueSource;
+use clap::{
+    ArgGroup, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+};
 use gating_contract::{
-    AuditEntry, CategoryStats, ContractRunner, GatingRequest, RedTeamCategory, RedTeamSummary,
-    RegressionBaseline, RegressionHarness, TestCase, TestHarness, Verdict,
+    build_slm_evaluator, policy_code_errors, redact_match, redact_matches, AdmissionReview,
+    Attestation, AuditEntry, BypassMinimizer, CalibrationHarness, CategoryGate, ContractError,
+    ContractRunner, CoverageHarness, DiffEvalHarness, DsseEnvelope, FuzzHarness, GatingRequest,
+    GatingSetRequest, HumanLabel, MutationHarness, ObfuscationStrategy, RecordOutcome,
+    RedTeamBaseline, RedTeamCategory, RedTeamGenerator, RedTeamOutcome, RedTeamSummary,
+    RedTeamTestCase, Refusal, RefusalCode, RegressionBaseline, RegressionHarness, ReplayHarness,
+    RuleSuggester, SnapshotHarness, SnapshotStatus, SpiritSample, TestCase, TestFilter,
+    TestHarness, TestResult, Verdict,
 };
-use policy_oracle::{ActionType, DirectoryScanResult, Oracle, Policy, Proposal};
+use github_app::{run_github_app, GithubApi, GithubAppOptions};
+use policy_oracle::{
+    apply_fix_edits, decode_bytes, read_text_file, AcceleratorConfig, ActionType, DecodedContent,
+    DirectoryScanResult, EvidenceRedaction, FailureMode, FileConcern, FileVerdict, FileViolation,
+    FixEdit, LanguageConfig, Oracle, Policy, PolicyVerdict, Proposal, QuickFix, RepoStats,
+    RuleOutcome, RuleTrace, ScanEvent, Severity, SlmBackendConfig, StatsTrend, TriageFile,
+    TriageOutcome, ViolationType,
+};
+use serve::{load_source_registry, load_tenants, run_serve, ServeOptions};
+use slm_evaluator::models::{ModelFormat, ModelRegistry, ModelStore};
+use slm_evaluator::{detect_accelerators, Accelerator, SlmEvaluator};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
@@ -40,6 +71,194 @@ enum OutputFormat {
     Compact,
 }
 
+/// Output format for scan results
+#[derive(Debug, Clone, ValueEnum)]
+enum ScanFormat {
+    /// Human-readable text output
+    Text,
+    /// JSON output for machine processing
+    Json,
+    /// Compact single-line output
+    Compact,
+    /// One CSV row per finding, with a header
+    Csv,
+    /// One JSON object per finding per line
+    Ndjson,
+    /// GitLab Code Quality report
+    Codequality,
+    /// Gitea pull request review payload
+    Gitea,
+}
+
+impl ScanFormat {
+    /// Format used for errors raised by the scan
+    fn output(&self) -> OutputFormat {
+        match self {
+            ScanFormat::Json | ScanFormat::Ndjson | ScanFormat::Codequality | ScanFormat::Gitea => {
+                OutputFormat::Json
+            }
+            ScanFormat::Compact => OutputFormat::Compact,
+            ScanFormat::Text | ScanFormat::Csv => OutputFormat::Text,
+        }
+    }
+}
+
+/// Key findings are summarised by in `scan --group-by`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum GroupBy {
+    /// Path relative to the scanned directory
+    File,
+    /// Rule ID, e.g. forbidden_language:python
+    Rule,
+    /// Language of the file, by policy extensions
+    Language,
+    /// Violation severity; concerns group as "concern"
+    Severity,
+}
+
+/// Output for huge scans: summarised, or streamed as it is found
+#[derive(Debug, Clone, Args)]
+struct ScanOutput {
+    /// Print one line per group of findings instead of each finding
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+
+    /// Show only the N largest groups
+    #[arg(long, value_name = "N", requires = "group_by")]
+    top: Option<usize>,
+
+    /// Print each finding as an NDJSON record as soon as it is found,
+    /// then a summary record, instead of waiting for the whole scan
+    #[arg(long, conflicts_with_all = ["format", "group_by"])]
+    stream: bool,
+}
+
+/// Findings that make a run exit non-zero
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FailOn {
+    /// Fail on hard violations only
+    Violation,
+    /// Fail on violations and soft concerns
+    Concern,
+    /// Always exit 0 when the run completes
+    Never,
+}
+
+/// CI failure thresholds shared by scan, check and validate
+#[derive(Debug, Clone, Args)]
+struct FailPolicy {
+    /// Findings that fail the run (default: concern; validate: violation unless --strict)
+    #[arg(long, value_enum)]
+    fail_on: Option<FailOn>,
+
+    /// Violations tolerated before the run fails
+    #[arg(long, default_value = "0")]
+    max_violations: usize,
+}
+
+/// Which red-team cases run, and what fails the run
+#[derive(Debug, Clone, Args)]
+struct RedteamRun {
+    /// Show details of bypasses
+    #[arg(long)]
+    verbose: bool,
+
+    /// Compare against a saved red-team baseline and report new bypasses
+    #[arg(long, num_args = 0..=1, default_missing_value = ".conative/redteam-baseline.json")]
+    baseline: Option<PathBuf>,
+
+    /// Save this run as the red-team baseline
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Run only these categories (e.g. secret_evasion,boundary)
+    #[arg(long, value_name = "CATEGORY", value_delimiter = ',')]
+    filter_category: Vec<String>,
+
+    /// Unexpected bypasses a category may have, as CATEGORY=N (repeatable);
+    /// other categories allow none
+    #[arg(long, value_name = "CATEGORY=N")]
+    max_bypasses: Vec<CategoryGate>,
+}
+
+/// What `contract eval` records alongside the decision
+#[derive(Debug, Clone, Args)]
+#[command(group(ArgGroup::new("provenance").args(["attest", "in_toto"]).multiple(true)))]
+#[command(group(ArgGroup::new("audit_entry").args(["audit", "audit_log"]).multiple(true)))]
+struct EvalRecords {
+    /// Include audit log entry in output
+    #[arg(long)]
+    audit: bool,
+
+    /// Append the audit entry to this NDJSON log, keeping the share of
+    /// allowed decisions set by the policy's enforcement.audit_sampling
+    #[arg(long, value_name = "FILE")]
+    audit_log: Option<PathBuf>,
+
+    /// Embed the full proposal in the audit entry (for training export)
+    #[arg(long, requires = "audit_entry")]
+    audit_content: bool,
+
+    /// Trace every rule and pipeline stage, including the arbiter's
+    /// score math, in the decision
+    #[arg(long)]
+    explain: bool,
+
+    /// Time every oracle rule and report them slowest first in
+    /// processing.rule_timings
+    #[arg(long)]
+    profile_rules: bool,
+
+    /// Write an attestation to this file when the verdict is Allow
+    #[arg(long, value_name = "FILE")]
+    attest: Option<PathBuf>,
+
+    /// Write the decision as an in-toto statement, for any verdict
+    #[arg(long, value_name = "FILE")]
+    in_toto: Option<PathBuf>,
+
+    /// Sign the attestation, and wrap the in-toto statement in a DSSE
+    /// envelope, with this Ed25519 key (32-byte seed, hex)
+    #[arg(long, value_name = "FILE", requires = "provenance")]
+    attest_key: Option<PathBuf>,
+}
+
+/// When the language server re-checks a document
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum CheckOn {
+    /// On every edit
+    Change,
+    /// Only when the document is saved
+    Save,
+}
+
+/// SLM inference backend
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SlmBackendKind {
+    /// Always-compliant stand-in
+    Placeholder,
+    /// Fixed zero score, for dry runs
+    Mock,
+    /// Local model through llama.cpp (requires --slm-model)
+    LlamaCpp,
+    /// OpenAI-compatible endpoint (requires --slm-endpoint and --slm-model)
+    Openai,
+    /// Encoder classifier directory (requires --slm-model and the `candle` feature)
+    Classifier,
+}
+
+/// Inference hardware
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AcceleratorArg {
+    /// Best available GPU, else CPU
+    Auto,
+    Cpu,
+    Cuda,
+    Metal,
+    Vulkan,
+}
+
 /// Verbosity level
 #[derive(Debug, Clone, ValueEnum)]
 enum Verbosity {
@@ -67,8 +286,19 @@ enum Verbosity {
   2. SLM Evaluator: Neural "spirit of policy" evaluation (v2)
 
 SAFETY
-  This tool is READ-ONLY. It analyzes files but never modifies them.
-  All operations are idempotent and safe to run repeatedly.
+  Scanning, checking and evaluating never modify files. Only these write,
+  and only when named or flagged:
+    fix --apply                      rewrites files with mechanical fixes
+    init                             writes .conative/ policy files
+    triage                           saves the triage file on quit (q)
+    model pull                       downloads model weights
+    stats --save                     saves the stats file
+    training export, contract record write training-data JSON
+    contract redteam generate, minimize, suggest
+                                     write cases and reports
+    --save-baseline                  writes a red-team or regression baseline
+    --audit-log, --attest            append audit entries, write attestations
+  doctor creates and removes a probe file to check state directories.
 
 EXAMPLES
   conative scan ./my-project                 # Scan directory
@@ -82,7 +312,24 @@ enum Verbosity {
   0  All checks passed (Compliant)
   1  Hard violation detected (blocked)
   2  Soft concern detected (warning)
-  3  Error during execution
+  3  Escalated for human review (contract eval, contract record)
+  4  Usage error (invalid flag combination)
+  5  Configuration error (policy failed to load)
+  6  Input missing, unreadable or empty
+  7  Input is not valid JSON for its schema
+  8  Output could not be written
+  9  Model or registry could not be resolved
+  10 Credentials missing or not permitted (server errors)
+  11 Internal error during execution
+
+  scan, check and validate accept --fail-on {violation|concern|never}
+  and --max-violations N to choose which findings fail the run.
+
+  With --format json, errors print {"schema", "error": {"code",
+  "message", "hint", "exit_code"}} on stdout.
+
+  Every JSON output carries a "schema" ID such as "conative-scan-v1";
+  --output-schema prints the JSON Schema of a command's output.
 
 MORE INFO
   https://github.com/hyperpolymath/conative-gating
@@ -104,7 +351,14 @@ enum Verbosity {
 "#)]
 struct Cli {
     /// Output verbosity level
-    #[arg(short, long, value_enum, default_value = "normal", global = true)]
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value = "normal",
+        global = true,
+        env = "CONATIVE_VERBOSITY"
+    )]
     verbosity: Verbosity,
 
     /// Dry run mode - show what would be done without doing it
@@ -112,13 +366,59 @@ struct Cli {
     dry_run: bool,
 
     /// Disable colored output
-    #[arg(long, global = true)]
+    #[arg(long, global = true, env = "CONATIVE_NO_COLOR")]
     no_color: bool,
 
+    /// Reproducible contract output: fixed timestamps, zero durations and
+    /// seeded IDs, for golden decisions and baselines
+    #[arg(long, global = true, env = "CONATIVE_DETERMINISTIC")]
+    deterministic: bool,
+
     /// Custom policy file (Nickel .ncl or JSON)
-    #[arg(short, long, global = true)]
+    #[arg(short, long, global = true, env = "CONATIVE_POLICY_FILE")]
     policy_file: Option<PathBuf>,
 
+    /// Ignore config files; read the policy as JSON from CONATIVE_POLICY
+    /// unless --policy-file is given (sidecar and container deployments)
+    #[arg(long, global = true, env = "CONATIVE_CONFIG_FROM_ENV")]
+    config_from_env: bool,
+
+    /// SLM backend for the spirit stage (overrides the policy)
+    #[arg(long, value_enum, global = true, env = "CONATIVE_SLM_BACKEND")]
+    slm_backend: Option<SlmBackendKind>,
+
+    /// Model file (llama-cpp) or model name (openai)
+    #[arg(long, global = true, env = "CONATIVE_SLM_MODEL")]
+    slm_model: Option<String>,
+
+    /// Base URL of an OpenAI-compatible API, e.g. http://localhost:8080/v1
+    #[arg(long, global = true, env = "CONATIVE_SLM_ENDPOINT")]
+    slm_endpoint: Option<String>,
+
+    /// Bypass the SLM inference cache configured in the policy
+    #[arg(long, global = true, env = "CONATIVE_NO_SLM_CACHE")]
+    no_slm_cache: bool,
+
+    /// Hardware for local inference; unavailable GPUs fall back to CPU
+    #[arg(long, value_enum, global = true, env = "CONATIVE_SLM_ACCELERATOR")]
+    slm_accelerator: Option<AcceleratorArg>,
+
+    /// Model layers to offload to the GPU (llama-cpp)
+    #[arg(long, global = true, env = "CONATIVE_SLM_GPU_LAYERS")]
+    slm_gpu_layers: Option<u32>,
+
+    /// Inference threads (llama-cpp)
+    #[arg(long, global = true, env = "CONATIVE_SLM_THREADS")]
+    slm_threads: Option<u32>,
+
+    /// Context window in tokens (llama-cpp)
+    #[arg(long, global = true, env = "CONATIVE_SLM_CTX_SIZE")]
+    slm_ctx_size: Option<u32>,
+
+    /// Print the JSON Schema of the command's --format json output and exit
+    #[arg(long, global = true)]
+    output_schema: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -132,6 +432,11 @@ enum Commands {
     ///
     /// Skips hidden directories, node_modules, target/, and _build/
     /// by default. Use --include-hidden to scan hidden files.
+    ///
+    /// EXAMPLES
+    ///   conative scan ./my-project
+    ///   conative scan . --group-by language --top 5
+    ///   conative scan . --stream | jq -c 'select(.kind == "violation")'
     #[command(visible_alias = "s")]
     Scan {
         /// Path to scan (defaults to current directory)
@@ -140,7 +445,7 @@ enum Commands {
 
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
-        format: OutputFormat,
+        format: ScanFormat,
 
         /// Include hidden files and directories
         #[arg(long)]
@@ -157,17 +462,32 @@ enum Commands {
         /// File patterns to exclude (glob syntax)
         #[arg(short = 'E', long)]
         exclude: Vec<String>,
+
+        /// Follow symlinks that resolve inside the scanned directory
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Triage file whose suppressed findings are left out
+        #[arg(long, default_value = ".conative/triage.json")]
+        triage_file: PathBuf,
+
+        #[command(flatten)]
+        output: ScanOutput,
+
+        #[command(flatten)]
+        fail: FailPolicy,
     },
 
     /// Check a single file or inline content
     ///
     /// Validates the provided content against policy rules.
-    /// Either --file or --content must be specified.
+    /// Either --file or --content must be specified. Content read from
+    /// stdin is decoded like a file, so binary and non-UTF-8 input is safe.
     ///
     /// EXAMPLES
     ///   conative check --file src/utils.ts
     ///   conative check --content "const x: string = 'hello'"
-    ///   cat file.py | conative check --content -
+    ///   cat file.py | conative check --content - --assume-path file.py
     #[command(visible_alias = "c")]
     Check {
         /// File path to check
@@ -178,13 +498,21 @@ enum Commands {
         #[arg(short = 'C', long, group = "input")]
         content: Option<String>,
 
-        /// Assumed file path for content (affects language detection)
+        /// Path the content is checked as (affects language detection);
+        /// with --file it replaces the file's own path
         #[arg(short = 'a', long)]
         assume_path: Option<String>,
 
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
+
+        /// Show every rule checked, whether it matched, and why it was skipped
+        #[arg(long)]
+        explain: bool,
+
+        #[command(flatten)]
+        fail: FailPolicy,
     },
 
     /// Display the current policy configuration
@@ -202,7 +530,8 @@ enum Commands {
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
 
-        /// Show only specific section (languages, toolchain, patterns)
+        /// Show only specific section (languages, toolchain, patterns, protected, binary, obfuscation, imports,
+        /// unsafe, paths, scan, conditions, new_repository)
         #[arg(short, long)]
         section: Option<String>,
     },
@@ -220,10 +549,23 @@ enum Commands {
     ///     "files_affected": ["path1", "path2"],
     ///     "llm_confidence": 0.95
     ///   }
+    ///
+    /// For edits, {"EditFile": {"path": "...", "before": "old contents"}}
+    /// also checks that required content (e.g. license headers) survives.
+    ///
+    /// Several files, directories (every *.json below them) or quoted glob
+    /// patterns are validated together and summarised; the run exits with
+    /// the worst outcome.
+    ///
+    /// EXAMPLES
+    ///   conative validate proposal.json
+    ///   llm-agent --emit-proposal | conative validate -
+    ///   conative validate proposals/ 'queue/*.json' --format text
     #[command(visible_alias = "v")]
     Validate {
-        /// Proposal JSON file (use '-' for stdin)
-        proposal: PathBuf,
+        /// Proposal JSON files, directories or glob patterns ('-' for stdin)
+        #[arg(required = true)]
+        proposals: Vec<PathBuf>,
 
         /// Output format
         #[arg(short, long, value_enum, default_value = "json")]
@@ -232,12 +574,17 @@ enum Commands {
         /// Return non-zero exit code on any concern (not just violations)
         #[arg(long)]
         strict: bool,
+
+        #[command(flatten)]
+        fail: FailPolicy,
     },
 
     /// Initialize policy configuration in current directory
     ///
-    /// Creates a .conative/ directory with default configuration files
-    /// that can be customized for project-specific policies.
+    /// Creates a .conative/ directory with configuration files that can
+    /// be customized for project-specific policies. A wizard asks for
+    /// allowed languages, secret rules, toolchain constraints and the
+    /// enforcement profile; --yes writes the default policy instead.
     ///
     /// FILES CREATED
     ///   .conative/policy.ncl   - Main policy configuration
@@ -254,6 +601,10 @@ enum Commands {
         /// Create minimal configuration
         #[arg(long)]
         minimal: bool,
+
+        /// Skip the wizard and write the default policy
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Generate shell completions
@@ -292,6 +643,8 @@ enum Commands {
     ///
     /// EXAMPLES
     ///   conative contract test training/           # Run all tests in directory
+    ///   conative contract test --category language --shard 1/4  # One CI job's share
+    ///   conative contract test --jobs 8            # Run cases on 8 threads
     ///   conative contract eval request.json        # Evaluate single request
     ///   conative contract eval request.json --audit  # With audit log output
     #[command(visible_alias = "ct")]
@@ -299,1563 +652,9510 @@ enum Commands {
         #[command(subcommand)]
         action: ContractAction,
     },
-}
 
-#[derive(Subcommand)]
-enum ContractAction {
-    /// Run contract tests from test case files
+    /// Manage training data for contract tests and SLM fine-tuning
     ///
-    /// Reads JSON test case files and validates contract behavior.
-    /// Returns non-zero exit code if any tests fail.
-    Test {
-        /// Directory or file containing test cases
-        #[arg(default_value = "training")]
-        path: PathBuf,
+    /// EXAMPLES
+    ///   conative training export audit.ndjson
+    ///   conative training export audit.ndjson --labels reviews.json
+    Training {
+        #[command(subcommand)]
+        action: TrainingAction,
+    },
+
+    /// Work with recorded audit logs
+    ///
+    /// EXAMPLES
+    ///   conative audit replay --from audit.jsonl
+    ///   conative audit replay --from audit.jsonl --corpus proposals/
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
 
+    /// Check configuration, rules, models and state directories
+    ///
+    /// Verifies which policy would be used, that its regexes and globs
+    /// compile, that the configured SLM model files and runtime are
+    /// present, and that .conative/ and the SLM cache are writable.
+    /// Exits 1 if any check fails.
+    Doctor {
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
-
-        /// Stop on first failure
-        #[arg(long)]
-        fail_fast: bool,
     },
 
-    /// Evaluate a gating request through the contract
+    /// Summarise the repository's policy posture
     ///
-    /// Processes a GatingRequest JSON and returns a GatingDecision.
-    Eval {
-        /// Request JSON file (use '-' for stdin)
-        request: PathBuf,
+    /// Scans the tree and reports files per language tier, violation
+    /// density per directory and the most triggered rules, compared
+    /// with the last saved stats file.
+    ///
+    /// EXAMPLES
+    ///   conative stats
+    ///   conative stats --save
+    ///   conative stats src --format json
+    Stats {
+        /// Path to scan (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
 
         /// Output format
-        #[arg(short, long, value_enum, default_value = "json")]
+        #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
 
-        /// Include audit log entry in output
+        /// Stats file to compare against
+        #[arg(long, default_value = ".conative/stats.json")]
+        stats_file: PathBuf,
+
+        /// Save these stats as the new comparison point
         #[arg(long)]
-        audit: bool,
+        save: bool,
+
+        /// Directories and rules to list in text output
+        #[arg(long, default_value = "10")]
+        top: usize,
     },
 
-    /// Display contract schema information
+    /// Show which files a policy change would flip before merging it
     ///
-    /// Shows the contract version, input/output schemas, and refusal codes.
-    Schema {
+    /// Scans the tree once under the current policy (the active one, or
+    /// --current) and the proposed one, reading each file a single time,
+    /// and lists the files whose verdict differs with the rules behind it.
+    ///
+    /// EXAMPLES
+    ///   conative diff-policy-impact proposed-policy.json
+    ///   conative diff-policy-impact new.ncl src --current old.ncl --fail-on-change
+    DiffPolicyImpact {
+        /// Proposed policy (JSON, or Nickel evaluated through `nickel export`)
+        proposed: PathBuf,
+
+        /// Path to scan (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Policy to compare against instead of the active one
+        #[arg(long, value_name = "POLICY")]
+        current: Option<PathBuf>,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
 
-        /// Show only specific section (inputs, outputs, refusals, audit)
-        #[arg(short, long)]
-        section: Option<String>,
+        /// Exit 1 when any file changes verdict
+        #[arg(long)]
+        fail_on_change: bool,
     },
 
-    /// Run red-team adversarial tests
+    /// Inspect layered configuration
     ///
-    /// Executes adversarial test cases designed to bypass the gating system.
-    /// Reports on bypass rates, false positives, and security score.
+    /// PRECEDENCE (highest first)
+    ///   1. Command-line flags
+    ///   2. CONATIVE_* environment variables
+    ///   3. Repository config: .conative/config.toml
+    ///   4. Global config: ~/.config/conative/config.toml
+    ///   5. Built-in defaults
     ///
-    /// CATEGORIES
-    ///   bypass:      Attempts to bypass via docs/comments
-    ///   obfuscation: Marker splitting, case variation
-    ///   encoding:    Base64/hex encoded secrets
-    ///   boundary:    Empty files, unicode, edge cases
-    ///   injection:   Polyglot files, hidden secrets
-    #[command(visible_alias = "rt")]
-    Redteam {
-        /// Directory containing red-team test cases
-        #[arg(default_value = "training/redteam")]
+    /// Config files use the global flag names as keys, e.g.
+    ///   policy_file = ".conative/policy.json"
+    ///   slm_backend = "llama-cpp"
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Triage scan findings interactively
+    ///
+    /// Lists violations and concerns with their evidence and records
+    /// each as suppress, fix-later or escalate in the triage file.
+    /// Suppressed findings are left out of later scans.
+    ///
+    /// KEYS
+    ///   Up/Down  move    s  suppress     f  fix later
+    ///   e  escalate      u  clear        q  save and quit
+    ///   Esc  quit without saving
+    Triage {
+        /// Path to scan (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Triage file to update
+        #[arg(long, default_value = ".conative/triage.json")]
+        triage_file: PathBuf,
+
+        /// Follow symlinks that resolve inside the scanned directory
+        #[arg(long)]
+        follow_symlinks: bool,
+    },
+
+    /// Preview and apply mechanical fixes for safe violations
+    ///
+    /// Prints the planned changes as a diff and writes nothing unless
+    /// --apply is given. Safe fixes rewrite matches of patterns that
+    /// define a `fix`, insert missing required headers and remove
+    /// forbidden-language files that were already ported. Fixes that
+    /// would delete or comment out code are listed for review instead.
+    ///
+    /// EXAMPLES
+    ///   conative fix
+    ///   conative fix src --apply
+    Fix {
+        /// Path to fix (defaults to current directory)
+        #[arg(default_value = ".")]
         path: PathBuf,
 
+        /// Write the previewed changes
+        #[arg(long)]
+        apply: bool,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
+    },
+
+    /// Run a language server over stdio
+    ///
+    /// Checks open documents against the active policy and publishes
+    /// diagnostics carrying refusal codes, with each remediation
+    /// offered as a code action.
+    ///
+    /// EXAMPLES
+    ///   conative lsp
+    ///   conative lsp --check-on save
+    Lsp {
+        /// When to re-check a document
+        #[arg(long, value_enum, default_value = "change")]
+        check_on: CheckOn,
+    },
 
-        /// Show details of bypasses
+    /// Serve gating decisions over HTTP
+    ///
+    /// Keeps the policy loaded and answers each POST /v1/evaluate (a
+    /// GatingRequest, as read by `contract eval`) with its decision, and
+    /// each POST /v1/admission (a Kubernetes AdmissionReview) in kind.
+    /// GET /healthz reports that the server is up, /readyz runs the
+    /// doctor's rule and model checks on the served policy (and fails
+    /// while the queue is full or a reload has failed), and /buildinfo
+    /// reports the version, commit and policy fingerprint.
+    ///
+    /// With --tenants, named policies are served alongside the default one.
+    /// A request goes to the tenant its X-Conative-Tenant header names, else
+    /// to the first whose repositories match its RequestContext.repository,
+    /// else to the default policy. Each tenant has its own audit log, and
+    /// GET /metrics counts decisions by tenant and verdict.
+    ///
+    /// With --auth-registry, evaluation requests must send a registered
+    /// API key as a bearer token. The key's credential fixes the request's
+    /// source, and only a credential with the policy_override capability
+    /// may send a policy_override; without a registry none may.
+    ///
+    /// A fixed pool of workers evaluates requests from a bounded queue;
+    /// when the queue is full a request is answered 429 with refusal
+    /// code 901 and a Retry-After header instead of waiting.
+    ///
+    /// SIGHUP, or with --watch a change to the policy file, reloads the
+    /// policy. One that fails to load or compile is rejected and the
+    /// current policy keeps serving.
+    ///
+    /// EXAMPLES
+    ///   conative serve
+    ///   conative serve --listen 0.0.0.0:8470 --workers 8 --queue 256
+    ///   conative serve -p policy.json --watch --audit-log audit.jsonl
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8470", env = "CONATIVE_LISTEN")]
+        listen: String,
+
+        /// Requests evaluated at once (default: one per CPU)
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1..), env = "CONATIVE_WORKERS")]
+        workers: Option<u16>,
+
+        /// Requests waiting for a worker before new ones are refused
+        #[arg(long, default_value_t = 64, env = "CONATIVE_QUEUE")]
+        queue: usize,
+
+        /// Largest request body accepted, in bytes
+        #[arg(long, default_value_t = 8 * 1024 * 1024, env = "CONATIVE_MAX_BODY")]
+        max_body: u64,
+
+        /// Reload the policy whenever --policy-file changes
         #[arg(long)]
-        verbose: bool,
+        watch: bool,
+
+        /// Append an audit entry per decision, and one per policy reload,
+        /// to this NDJSON log
+        #[arg(long, value_name = "FILE", env = "CONATIVE_AUDIT_LOG")]
+        audit_log: Option<PathBuf>,
+
+        /// JSON registry of accepted API keys, their sources and capabilities
+        #[arg(long, value_name = "FILE", env = "CONATIVE_AUTH_REGISTRY")]
+        auth_registry: Option<PathBuf>,
+
+        /// JSON list of named policies, the repositories routed to each and
+        /// their audit logs
+        #[arg(long, value_name = "FILE", env = "CONATIVE_TENANTS")]
+        tenants: Option<PathBuf>,
     },
 
-    /// Regression testing against baseline
+    /// Gate pull requests as a GitHub App webhook receiver
     ///
-    /// Compare current test results against a saved baseline to detect
-    /// regressions (tests that used to pass but now fail) and improvements.
+    /// Answers the App's webhooks on POST /webhook; a delivery whose
+    /// X-Hub-Signature-256 does not match --webhook-secret is refused. A
+    /// pull_request event that opens, reopens or pushes to a pull request
+    /// fetches every changed file at the new head and evaluates them as
+    /// one proposal set. The decision is posted as a "Conative Gating"
+    /// check run with an annotation per finding; a blocked or escalated
+    /// set fails the check, so branch protection requiring it blocks the
+    /// merge.
     ///
-    /// WORKFLOW
-    ///   1. Run tests and save baseline: conative contract regression --save
-    ///   2. Make changes to codebase
-    ///   3. Compare against baseline: conative contract regression
-    #[command(visible_alias = "reg")]
-    Regression {
-        /// Directory containing test cases
-        #[arg(default_value = "training")]
-        path: PathBuf,
+    /// EXAMPLES
+    ///   conative github-app --webhook-secret "$SECRET" --token "$TOKEN"
+    ///   conative github-app --listen 0.0.0.0:8471 --api-url https://ghe.example.org/api/v3
+    GithubApp {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8471")]
+        listen: String,
+
+        /// Secret the App signs its webhook deliveries with
+        #[arg(long, env = "GITHUB_WEBHOOK_SECRET", hide_env_values = true)]
+        webhook_secret: String,
+
+        /// Token that may read pull requests and contents and write checks
+        #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        token: String,
+
+        /// GitHub REST API root
+        #[arg(long, default_value = "https://api.github.com", env = "GITHUB_API_URL")]
+        api_url: String,
+
+        /// Append an audit entry per gated pull request to this NDJSON log
+        #[arg(long, value_name = "FILE", env = "CONATIVE_AUDIT_LOG")]
+        audit_log: Option<PathBuf>,
+    },
 
-        /// Baseline file path
-        #[arg(short, long, default_value = ".conative/baseline.json")]
-        baseline: PathBuf,
+    /// Manage SLM model weights
+    ///
+    /// EXAMPLES
+    ///   conative model pull spirit-q4
+    ///   conative model pull spirit-q4 --registry https://example.org/models.json
+    Model {
+        #[command(subcommand)]
+        action: ModelAction,
+    },
+}
 
-        /// Save current results as new baseline
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective settings
+    Show {
+        /// Show which layer each setting came from
         #[arg(long)]
-        save: bool,
+        origin: bool,
 
         /// Output format
         #[arg(short, long, value_enum, default_value = "text")]
         format: OutputFormat,
+    },
+}
 
-        /// Fail on any regression
+#[derive(Subcommand)]
+enum ModelAction {
+    /// Download model weights from a registry and verify their SHA-256
+    ///
+    /// Verified weights are stored under ~/.cache/conative/models (or
+    /// --dir). An existing file with the right digest is not downloaded
+    /// again.
+    Pull {
+        /// Model name in the registry
+        name: String,
+
+        /// Registry JSON (URL or path)
+        #[arg(
+            long,
+            env = "CONATIVE_MODEL_REGISTRY",
+            default_value = "config/models.json"
+        )]
+        registry: String,
+
+        /// Model store directory
+        #[arg(long, env = "CONATIVE_MODEL_DIR")]
+        dir: Option<PathBuf>,
+
+        /// Download even if a verified copy exists
         #[arg(long)]
-        strict: bool,
+        force: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Show which accelerator local inference would use
+    Devices {
+        /// llama.cpp binary to query
+        #[arg(long, default_value = "llama-cli")]
+        binary: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
     },
 }
 
-fn main() {
-    tracing_subscriber::fmt::init();
+#[derive(Subcommand)]
+enum TrainingAction {
+    /// Convert audited decisions into training-data JSON files
+    ///
+    /// Reads audit entries (NDJSON or concatenated JSON, as produced by
+    /// `contract eval --audit --audit-content`) and writes one training
+    /// file per decision. Human labels (JSON array of
+    /// {request_id, verdict, reviewer, note}) override the recorded
+    /// verdict and resolve escalations.
+    Export {
+        /// Audit log files to read
+        #[arg(required = true)]
+        audit: Vec<PathBuf>,
+
+        /// Human labels from the escalation queue
+        #[arg(short, long)]
+        labels: Option<PathBuf>,
 
-    let cli = Cli::parse();
-    let oracle = Oracle::with_rsr_defaults();
+        /// Directory to write training files to
+        #[arg(short, long, default_value = "training/exported")]
+        output: PathBuf,
 
-    let exit_code = match cli.command {
-        Commands::Scan {
-            path,
-            format,
-            include_hidden: _,
-            depth: _,
-            include: _,
-            exclude: _,
-        } => {
-            if cli.dry_run {
-                println!("[dry-run] Would scan: {}", path.display());
-                println!("[dry-run] Format: {:?}", format);
-                0
-            } else {
-                scan_directory(&oracle, &path, &format, &cli.verbosity)
-            }
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    /// Re-run audited proposals and report verdict drift
+    ///
+    /// Each audit entry is re-evaluated under the current policy and
+    /// compared with its recorded verdict and refusal code. Proposals
+    /// come from the entry (recorded with --audit-content) or from the
+    /// corpus, matched on content hash. Exits 1 if any decision drifted.
+    Replay {
+        /// Audit log files to read
+        #[arg(long, required = true)]
+        from: Vec<PathBuf>,
+
+        /// Directory of proposal or request JSON files for entries
+        /// recorded without content
+        #[arg(long)]
+        corpus: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+/// Obfuscation strategy for generated red-team cases
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Strategy {
+    /// Upper-case extension and content
+    Case,
+    /// Break whitespace inside detection markers
+    Split,
+    /// Wrap content in a base64 payload
+    Base64,
+    /// Swap Latin letters for Unicode confusables
+    Confusable,
+    /// Hide content inside comments
+    Comment,
+}
+
+impl From<Strategy> for ObfuscationStrategy {
+    fn from(s: Strategy) -> Self {
+        match s {
+            Strategy::Case => ObfuscationStrategy::CaseChange,
+            Strategy::Split => ObfuscationStrategy::MarkerSplit,
+            Strategy::Base64 => ObfuscationStrategy::Base64Wrap,
+            Strategy::Confusable => ObfuscationStrategy::UnicodeConfusable,
+            Strategy::Comment => ObfuscationStrategy::CommentHiding,
         }
-        Commands::Check {
+    }
+}
+
+#[derive(Subcommand)]
+enum RedteamAction {
+    /// Generate obfuscated variants of violating seed cases
+    ///
+    /// Reads test cases expected to Block or Warn and writes one new
+    /// red-team JSON file per seed and strategy, expanding the
+    /// adversarial corpus automatically.
+    ///
+    /// EXAMPLES
+    ///   conative contract redteam generate training/violations
+    ///   conative contract redteam generate seeds/ --strategy split,base64
+    Generate {
+        /// Directory or file containing seed test cases
+        #[arg(default_value = "training/violations")]
+        seeds: PathBuf,
+
+        /// Directory to write generated cases to
+        #[arg(short, long, default_value = "training/redteam/generated")]
+        output: PathBuf,
+
+        /// Strategies to apply (default: all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        strategy: Vec<Strategy>,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Shrink bypassing cases to the smallest content still bypassing
+    ///
+    /// Delta-debugs each red-team case the gate fails to block and writes
+    /// the result next to the original as NAME.min.json, a case with the
+    /// same expectations for rule authoring.
+    ///
+    /// EXAMPLES
+    ///   conative contract redteam minimize training/redteam/encoding
+    ///   conative contract redteam minimize case.json --keep "sk-"
+    Minimize {
+        /// Directory or file containing red-team test cases
+        #[arg(default_value = "training/redteam")]
+        path: PathBuf,
+
+        /// Text every reduction must keep, anchoring the attack (repeatable)
+        #[arg(long, value_name = "TEXT")]
+        keep: Vec<String>,
+
+        /// Give up shrinking a case after this many evaluations
+        #[arg(long, default_value_t = 2000)]
+        max_evaluations: usize,
+
+        /// Overwrite existing .min.json files
+        #[arg(long)]
+        force: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Report bypasses with candidate rules that would block them
+    ///
+    /// Runs the red-team cases and, for each the gate fails to block,
+    /// suggests forbidden patterns drawn from its content that match no
+    /// benign content. The JSON report lists each rule ready to merge
+    /// into patterns.forbidden_patterns after review.
+    ///
+    /// EXAMPLES
+    ///   conative contract redteam suggest
+    ///   conative contract redteam suggest training/redteam --benign training/compliant --benign examples/
+    Suggest {
+        /// Directory or file containing red-team test cases
+        #[arg(default_value = "training/redteam")]
+        path: PathBuf,
+
+        /// Test cases whose content no suggested rule may match (repeatable)
+        #[arg(long, value_name = "PATH", default_value = "training/compliant")]
+        benign: Vec<PathBuf>,
+
+        /// File to write the report to
+        #[arg(short, long, default_value = ".conative/redteam-report.json")]
+        output: PathBuf,
+
+        /// Most rules suggested for one bypass
+        #[arg(long, default_value_t = 3)]
+        max_per_bypass: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum ContractAction {
+    /// Run contract tests from test case files
+    ///
+    /// Reads JSON test case files and validates contract behavior.
+    /// Returns non-zero exit code if any tests fail.
+    Test {
+        /// Directory or file containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Stop on first failure
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Run only cases with this tag (repeatable; any matches)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Run only cases in this category (repeatable; any matches)
+        #[arg(long = "category", value_name = "CATEGORY")]
+        categories: Vec<String>,
+
+        /// Run only cases whose name contains this
+        #[arg(long, value_name = "TEXT")]
+        name_filter: Option<String>,
+
+        /// Run the i-th of n shards of the selected cases, e.g. 2/4
+        #[arg(long, value_name = "I/N", value_parser = gating_contract::parse_shard)]
+        shard: Option<(usize, usize)>,
+
+        /// Run cases on this many threads (0 = one per CPU)
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+
+    /// Evaluate a gating request through the contract
+    ///
+    /// Processes a GatingRequest JSON and returns a GatingDecision. A
+    /// GatingSetRequest (with `proposals` instead of `proposal`) is
+    /// evaluated atomically: one verdict for all of its files. A
+    /// Kubernetes AdmissionReview is answered with an AdmissionReview
+    /// response allowing or denying the object.
+    Eval {
+        /// Request JSON file (use '-' for stdin)
+        request: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        records: EvalRecords,
+    },
+
+    /// Evaluate a request like `eval`, and record it as a test case
+    ///
+    /// A drop-in for `contract eval` in hooks: the decision and exit code
+    /// are the same, and a sample of requests is redacted (pattern fixes
+    /// applied, context dropped) and written in the training-data format.
+    /// Requests whose metadata sets `conative.record` to `deny` are never
+    /// recorded.
+    Record {
+        /// Request JSON file (use '-' for stdin)
+        request: PathBuf,
+
+        /// Directory to write recorded cases to
+        #[arg(short, long, default_value = "training/recorded")]
+        output: PathBuf,
+
+        /// Fraction of requests to record (0.0-1.0)
+        #[arg(long, default_value = "1.0")]
+        sample: f64,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        records: EvalRecords,
+    },
+
+    /// Verify a signed attestation written by `contract eval --attest`
+    ///
+    /// Also accepts the DSSE envelope written by `--in-toto` with
+    /// `--attest-key`. Exits 0 when the signature matches, 1 otherwise.
+    Verify {
+        /// Attestation JSON file
+        attestation: PathBuf,
+
+        /// Base64 Ed25519 public key the attestation must be signed with
+        #[arg(long)]
+        public_key: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Display contract schema information
+    ///
+    /// Shows the contract version, input/output schemas, and refusal codes.
+    Schema {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Show only specific section (inputs, outputs, refusals, audit)
+        #[arg(short, long)]
+        section: Option<String>,
+    },
+
+    /// Run red-team adversarial tests
+    ///
+    /// Executes adversarial test cases designed to bypass the gating system.
+    /// Reports on bypass rates, false positives, and security score.
+    ///
+    /// CATEGORIES
+    ///   bypass:      Attempts to bypass via docs/comments
+    ///   obfuscation: Marker splitting, case variation
+    ///   encoding:    Base64/hex encoded secrets
+    ///   boundary:    Empty files, unicode, edge cases
+    ///   injection:   Polyglot files, hidden secrets
+    ///
+    /// EXAMPLES
+    ///   conative contract redteam
+    ///   conative contract redteam generate training/violations
+    ///   conative contract redteam minimize training/redteam
+    ///   conative contract redteam --filter-category encoding,boundary
+    ///   conative contract redteam --max-bypasses encoding=2 --max-bypasses boundary=0
+    #[command(visible_alias = "rt", args_conflicts_with_subcommands = true)]
+    Redteam {
+        #[command(subcommand)]
+        action: Option<RedteamAction>,
+
+        /// Directory containing red-team test cases
+        #[arg(default_value = "training/redteam")]
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        run: RedteamRun,
+    },
+
+    /// Regression testing against baseline
+    ///
+    /// Compare current test results against a saved baseline to detect
+    /// regressions (tests that used to pass but now fail) and improvements.
+    ///
+    /// WORKFLOW
+    ///   1. Run tests and save baseline: conative contract regression --save
+    ///   2. Make changes to codebase
+    ///   3. Compare against baseline: conative contract regression
+    ///
+    /// The baseline records the policy fingerprint, so each verdict change
+    /// is marked as a likely policy change or code change.
+    #[command(visible_alias = "reg")]
+    Regression {
+        /// Directory containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Baseline file path
+        #[arg(short, long, default_value = ".conative/baseline.json")]
+        baseline: PathBuf,
+
+        /// Save current results as new baseline
+        #[arg(long)]
+        save: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Fail on any regression
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Report refusal-code and rule coverage of a test corpus
+    ///
+    /// Runs every test case and maps which refusal codes and policy
+    /// rule IDs the corpus exercises, listing the ones with no coverage.
+    ///
+    /// EXAMPLES
+    ///   conative contract coverage training/
+    ///   conative contract coverage training/ --format json
+    #[command(visible_alias = "cov")]
+    Coverage {
+        /// Directory or file containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Fuzz the oracle with generated proposals
+    ///
+    /// Generates random proposals (mutated extensions, unicode noise,
+    /// giant content, empty files) and checks contract invariants:
+    /// no panics, deterministic verdicts, and Allow/Block stability
+    /// under whitespace-only edits.
+    ///
+    /// EXAMPLES
+    ///   conative contract fuzz --iterations 5000
+    ///   conative contract fuzz --seed 42        # Reproduce a run
+    Fuzz {
+        /// Number of proposals to generate
+        #[arg(short = 'n', long, default_value = "1000")]
+        iterations: usize,
+
+        /// PRNG seed (random if omitted; printed for reproduction)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Mutation-test the active policy against the corpus
+    ///
+    /// Systematically weakens the policy (drops a language, marker,
+    /// extension, exception, toolchain rule or pattern; weakens regexes)
+    /// and re-runs the corpus. Mutations that no test notices reveal
+    /// rules the corpus does not actually guard.
+    ///
+    /// EXAMPLES
+    ///   conative contract mutate training/
+    ///   conative contract mutate training/ --strict   # Fail on survivors
+    Mutate {
+        /// Directory or file containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+
+        /// Fail if any mutation survives
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Compare full decision JSON against golden snapshots
+    ///
+    /// Serializes each test case's GatingDecision (with IDs, timestamps
+    /// and durations redacted) and diffs it against a stored snapshot,
+    /// catching serialization or taxonomy changes that verdict-only
+    /// regression checks miss.
+    ///
+    /// EXAMPLES
+    ///   conative contract snapshot training/
+    ///   conative contract snapshot training/ --update   # Accept changes
+    Snapshot {
+        /// Directory or file containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Directory holding snapshot files
+        #[arg(long, default_value = ".conative/snapshots")]
+        snapshots: PathBuf,
+
+        /// Write new/changed snapshots instead of failing
+        #[arg(long)]
+        update: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Compare oracle-only and oracle+SLM verdicts over a corpus
+    ///
+    /// Runs every test case through both pipelines and lists the cases
+    /// where they disagree (e.g. oracle Allow but SLM Block), with the
+    /// expected verdict alongside. Useful for tuning slm_weight and for
+    /// mining training data.
+    ///
+    /// EXAMPLES
+    ///   conative contract diff-eval training/
+    ///   conative contract diff-eval training/ --slm-weight 2.0
+    DiffEval {
+        /// Directory or file containing test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// Override the policy's SLM inhibition weight
+        #[arg(long)]
+        slm_weight: Option<f64>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Score and calibrate the SLM evaluator on a labeled corpus
+    ///
+    /// Uses `spirit_violation` (and `concern_type` as the category) from
+    /// training files as ground truth. Reports precision/recall/F1 per
+    /// spirit category, a reliability curve for spirit_score, expected
+    /// calibration error and Brier score.
+    ///
+    /// EXAMPLES
+    ///   conative contract calibrate training/
+    ///   conative contract calibrate training/ --threshold 0.5 --bins 5
+    Calibrate {
+        /// Directory or file containing labeled test cases
+        #[arg(default_value = "training")]
+        path: PathBuf,
+
+        /// spirit_score at or above which a sample counts as flagged
+        #[arg(long, default_value = "0.7")]
+        threshold: f64,
+
+        /// Number of calibration bins
+        #[arg(long, default_value = "10")]
+        bins: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+// ============ CLI Error Functions ============
+
+/// Failure classes, each with its own exit code so wrappers can branch
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ErrorKind {
+    /// Evaluation or rendering failed unexpectedly
+    Internal,
+    /// Flags or arguments do not form a valid invocation
+    Usage,
+    /// Policy could not be loaded or compiled
+    Config,
+    /// An input file or directory is missing, unreadable or empty
+    Input,
+    /// An input was read but is not valid JSON for its schema
+    Parse,
+    /// A file or directory could not be written
+    Output,
+    /// An SLM model or registry could not be resolved
+    Model,
+    /// A request's credentials are missing or do not permit it
+    Auth,
+}
+
+impl ErrorKind {
+    fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Internal => 11,
+            ErrorKind::Usage => 4,
+            ErrorKind::Config => 5,
+            ErrorKind::Input => 6,
+            ErrorKind::Parse => 7,
+            ErrorKind::Output => 8,
+            ErrorKind::Model => 9,
+            ErrorKind::Auth => 10,
+        }
+    }
+}
+
+/// Error envelope printed as JSON under `--format json`
+#[derive(Debug, serde::Serialize)]
+struct CliError {
+    code: ErrorKind,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+    exit_code: i32,
+}
+
+impl CliError {
+    fn new(code: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            hint: None,
+            exit_code: code.exit_code(),
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Print the error for the active format and return its exit code
+    fn report(self, format: &OutputFormat) -> i32 {
+        match format {
+            OutputFormat::Json => {
+                print_json(OutputSchema::Error, &serde_json::json!({ "error": &self }))
+            }
+            OutputFormat::Text | OutputFormat::Compact => {
+                eprintln!("{}", self.message);
+                if let Some(hint) = &self.hint {
+                    eprintln!("{}", hint);
+                }
+            }
+        }
+        self.exit_code
+    }
+}
+
+/// Report an invalid invocation as a usage error; `--help` and
+/// `--version` print as usual
+///
+/// The arguments did not parse, so `--format json` is looked for in the
+/// raw arguments to decide whether to print the error envelope.
+fn report_clap_error(error: clap::Error) -> i32 {
+    use clap::error::ErrorKind as ClapErrorKind;
+    if matches!(
+        error.kind(),
+        ClapErrorKind::DisplayHelp | ClapErrorKind::DisplayVersion
+    ) {
+        error.exit();
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().enumerate().any(|(i, arg)| match arg.as_str() {
+        "--format=json" | "-fjson" => true,
+        "--format" | "-f" => args.get(i + 1).is_some_and(|value| value == "json"),
+        _ => false,
+    });
+    if !json {
+        let _ = error.print();
+        return ErrorKind::Usage.exit_code();
+    }
+    // Rendered as "error: <message>" followed by usage and a --help tip
+    let rendered = error.render().to_string();
+    let (message, hint) = rendered.split_once('\n').unwrap_or((&rendered, ""));
+    let mut error = CliError::new(ErrorKind::Usage, message.trim_start_matches("error: "));
+    if !hint.trim().is_empty() {
+        error = error.with_hint(hint.trim());
+    }
+    error.report(&OutputFormat::Json)
+}
+
+impl FailPolicy {
+    /// Exit code for a completed run; violations within the tolerance are
+    /// reported as concerns rather than ignored
+    fn exit_code(&self, violations: usize, concerns: usize, default: FailOn) -> i32 {
+        match self.fail_on.unwrap_or(default) {
+            FailOn::Never => 0,
+            _ if violations > self.max_violations => 1,
+            FailOn::Concern if violations + concerns > 0 => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Versioned shape of a command's `--format json` output
+///
+/// Each output carries `"schema": "conative-<kind>-v<N>"` beside its
+/// fields; a change that breaks parsers bumps the version. Outputs that
+/// already have a `schema` field of their own keep it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputSchema {
+    Error,
+    Scan,
+    ScanGroups,
+    Check,
+    Validate,
+    ValidateBatch,
+    Policy,
+    Decision,
+    ContractTest,
+    ContractSchema,
+    Verify,
+    Redteam,
+    RedteamGenerate,
+    RedteamMinimize,
+    RedteamSuggest,
+    Regression,
+    Coverage,
+    Fuzz,
+    Mutation,
+    Snapshot,
+    DiffEval,
+    Calibration,
+    TrainingExport,
+    AuditReplay,
+    ModelPull,
+    ModelDevices,
+    Doctor,
+    Stats,
+    PolicyImpact,
+    Settings,
+    Fix,
+    Refusal,
+    BuildInfo,
+}
+
+impl OutputSchema {
+    fn id(self) -> &'static str {
+        match self {
+            OutputSchema::Error => "conative-error-v1",
+            OutputSchema::Scan => "conative-scan-v1",
+            OutputSchema::ScanGroups => "conative-scan-groups-v1",
+            OutputSchema::Check => "conative-check-v1",
+            OutputSchema::Validate => "conative-validate-v1",
+            OutputSchema::ValidateBatch => "conative-validate-batch-v1",
+            OutputSchema::Policy => "conative-policy-v1",
+            OutputSchema::Decision => "conative-decision-v1",
+            OutputSchema::ContractTest => "conative-contract-test-v1",
+            OutputSchema::ContractSchema => gating_contract::CONTRACT_SCHEMA,
+            OutputSchema::Verify => "conative-verify-v1",
+            OutputSchema::Redteam => "conative-redteam-v1",
+            OutputSchema::RedteamGenerate => "conative-redteam-generate-v1",
+            OutputSchema::RedteamMinimize => "conative-redteam-minimize-v1",
+            OutputSchema::RedteamSuggest => "redteam-bypass-report-v1",
+            OutputSchema::Regression => "conative-regression-v1",
+            OutputSchema::Coverage => "conative-coverage-v1",
+            OutputSchema::Fuzz => "conative-fuzz-v1",
+            OutputSchema::Mutation => "conative-mutation-v1",
+            OutputSchema::Snapshot => "conative-snapshot-v1",
+            OutputSchema::DiffEval => "conative-diff-eval-v1",
+            OutputSchema::Calibration => "conative-calibration-v1",
+            OutputSchema::TrainingExport => "conative-training-export-v1",
+            OutputSchema::AuditReplay => "conative-audit-replay-v1",
+            OutputSchema::ModelPull => "conative-model-pull-v1",
+            OutputSchema::ModelDevices => "conative-model-devices-v1",
+            OutputSchema::Doctor => "conative-doctor-v1",
+            OutputSchema::Stats => "conative-stats-v1",
+            OutputSchema::PolicyImpact => "conative-policy-impact-v1",
+            OutputSchema::Settings => "conative-settings-v1",
+            OutputSchema::Fix => "conative-fix-v1",
+            OutputSchema::Refusal => "conative-refusal-v1",
+            OutputSchema::BuildInfo => "conative-buildinfo-v1",
+        }
+    }
+
+    /// Top-level fields beside `schema` and their JSON types; a trailing
+    /// `?` marks a field that may be absent
+    fn fields(self) -> &'static [(&'static str, &'static str)] {
+        const EVALUATION: &[(&str, &str)] = &[
+            ("proposal_id", "string"),
+            ("verdict", "string|object"),
+            ("rules_checked", "array"),
+            ("violations", "array"),
+            ("concerns", "array"),
+            ("trace?", "array"),
+            ("timed_out?", "boolean"),
+            ("rule_timings?", "array"),
+        ];
+        match self {
+            OutputSchema::Error => &[("error", "object")],
+            OutputSchema::Scan => &[
+                ("path", "string"),
+                ("verdict", "string|object"),
+                ("files_scanned", "integer"),
+                ("violations", "array"),
+                ("concerns", "array"),
+                ("truncated?", "array"),
+                ("exempted?", "array"),
+            ],
+            OutputSchema::ScanGroups => &[
+                ("path", "string"),
+                ("verdict", "string|object"),
+                ("files_scanned", "integer"),
+                ("group_by", "string"),
+                ("total_groups", "integer"),
+                ("groups", "array"),
+            ],
+            OutputSchema::Check | OutputSchema::Validate => EVALUATION,
+            OutputSchema::ValidateBatch => &[
+                ("total", "integer"),
+                ("compliant", "integer"),
+                ("concerns", "integer"),
+                ("violations", "integer"),
+                ("errors", "integer"),
+                ("results", "array"),
+            ],
+            OutputSchema::Policy => &[
+                ("name", "string"),
+                ("languages", "object"),
+                ("toolchain", "object"),
+                ("patterns", "object"),
+                ("enforcement", "object"),
+                ("protected", "object"),
+                ("binary", "object"),
+                ("obfuscation", "object"),
+                ("unsafe_code", "object"),
+                ("imports", "object"),
+                ("paths", "object"),
+                ("scan", "object"),
+                ("new_repository", "object"),
+                ("conditions", "object"),
+            ],
+            OutputSchema::Decision => &[
+                ("request_id", "string"),
+                ("decision_id", "string"),
+                ("timestamp", "string"),
+                ("verdict", "string"),
+                ("refusal", "object|null"),
+                ("evaluations", "object"),
+                ("processing", "object"),
+                ("files?", "array"),
+                ("trace?", "array"),
+                ("escalation?", "string|object"),
+                ("audit?", "object"),
+            ],
+            OutputSchema::ContractTest => &[
+                ("total", "integer"),
+                ("passed", "integer"),
+                ("failed", "integer"),
+                ("quarantined", "integer"),
+                ("total_duration_us", "integer"),
+                ("results", "array"),
+            ],
+            OutputSchema::ContractSchema => &[
+                ("version", "string"),
+                ("inputs", "object"),
+                ("outputs", "object"),
+                ("refusal_codes", "array"),
+            ],
+            OutputSchema::Verify => &[
+                ("valid", "boolean"),
+                ("error", "string|null"),
+                ("attestation?", "object"),
+                ("statement?", "object|null"),
+            ],
+            OutputSchema::Redteam => &[
+                ("summary", "object"),
+                ("trend?", "object"),
+                ("gate_failures?", "array"),
+            ],
+            OutputSchema::RedteamGenerate => &[
+                ("seeds", "integer"),
+                ("written", "array"),
+                ("skipped", "array"),
+            ],
+            OutputSchema::RedteamMinimize => &[("minimized", "array"), ("skipped", "array")],
+            OutputSchema::RedteamSuggest => &[
+                ("created_at", "string"),
+                ("contract_version", "string"),
+                ("git_commit", "string|null"),
+                ("bypasses", "array"),
+                ("suggestions", "array"),
+            ],
+            OutputSchema::Regression => &[
+                ("timestamp", "string"),
+                ("baseline_commit", "string|null"),
+                ("current_version", "string"),
+                ("baseline_policy", "string|null"),
+                ("current_policy", "string|null"),
+                ("total_compared", "integer"),
+                ("regressions", "array"),
+                ("improvements", "array"),
+                ("behavior_changes", "array"),
+                ("stable_count", "integer"),
+                ("new_tests", "array"),
+                ("removed_tests", "array"),
+            ],
+            OutputSchema::Coverage => &[
+                ("total_tests", "integer"),
+                ("codes", "array"),
+                ("rules", "array"),
+                ("errors", "array"),
+            ],
+            OutputSchema::Fuzz => &[
+                ("seed", "integer"),
+                ("iterations", "integer"),
+                ("verdicts", "object"),
+                ("failures", "array"),
+            ],
+            OutputSchema::Mutation => &[
+                ("policy_name", "string"),
+                ("total_tests", "integer"),
+                ("baseline_failures", "array"),
+                ("results", "array"),
+            ],
+            OutputSchema::Snapshot => &[("snapshot_dir", "string"), ("results", "array")],
+            OutputSchema::DiffEval => &[
+                ("total", "integer"),
+                ("slm_weight", "number"),
+                ("agreements", "integer"),
+                ("disagreements", "array"),
+                ("oracle_correct", "integer"),
+                ("pipeline_correct", "integer"),
+            ],
+            OutputSchema::Calibration => &[
+                ("total", "integer"),
+                ("positives", "integer"),
+                ("threshold", "number"),
+                ("overall", "object"),
+                ("categories", "array"),
+                ("bins", "array"),
+                ("expected_calibration_error", "number"),
+                ("brier_score", "number"),
+                ("errors", "array"),
+            ],
+            OutputSchema::TrainingExport => &[
+                ("audit_entries", "integer"),
+                ("exported", "integer"),
+                ("human_labeled", "integer"),
+                ("skipped", "object"),
+                ("output", "string"),
+            ],
+            OutputSchema::AuditReplay => &[
+                ("entries", "integer"),
+                ("replayed", "integer"),
+                ("stable", "integer"),
+                ("drifts", "array"),
+                ("missing", "array"),
+            ],
+            OutputSchema::ModelPull => &[
+                ("name", "string"),
+                ("path", "string"),
+                ("fingerprint", "string"),
+                ("bytes", "integer"),
+                ("downloaded", "boolean"),
+            ],
+            OutputSchema::ModelDevices => &[
+                ("binary", "string"),
+                ("available", "array"),
+                ("selected", "string"),
+            ],
+            OutputSchema::Doctor => &[("checks", "array")],
+            OutputSchema::Stats => &[
+                ("stats", "object"),
+                ("previous", "object|null"),
+                ("trend", "object|null"),
+            ],
+            OutputSchema::PolicyImpact => &[
+                ("path", "string"),
+                ("files_scanned", "integer"),
+                ("changes", "array"),
+            ],
+            OutputSchema::Settings => &[("settings", "array")],
+            OutputSchema::Fix => &[
+                ("applied", "boolean"),
+                ("fixes", "array"),
+                ("review", "array"),
+                ("diff", "string"),
+            ],
+            OutputSchema::Refusal => &[("refusal", "object")],
+            OutputSchema::BuildInfo => &[
+                ("version", "string"),
+                ("git_sha", "string|null"),
+                ("policy_name", "string"),
+                ("policy_fingerprint", "string"),
+            ],
+        }
+    }
+
+    /// JSON Schema (draft 2020-12) of the output's top level
+    fn json_schema(self) -> serde_json::Value {
+        let mut properties = serde_json::Map::new();
+        properties.insert(
+            "schema".to_string(),
+            serde_json::json!({ "const": self.id() }),
+        );
+        let mut required = vec!["schema"];
+        for (field, types) in self.fields() {
+            let name = field.trim_end_matches('?');
+            if name == *field {
+                required.push(name);
+            }
+            let types: Vec<&str> = types.split('|').collect();
+            let schema = match types.as_slice() {
+                [single] => serde_json::json!({ "type": single }),
+                several => serde_json::json!({ "type": several }),
+            };
+            properties.insert(name.to_string(), schema);
+        }
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": self.id(),
+            "type": "object",
+            "required": required,
+            "properties": properties,
+        })
+    }
+}
+
+/// A JSON output: its schema ID, then the payload's own fields
+#[derive(serde::Serialize)]
+struct Envelope<'a, T: serde::Serialize> {
+    schema: &'static str,
+    #[serde(flatten)]
+    payload: &'a T,
+}
+
+/// Print `payload` (which must serialize as an object) under `schema`
+fn print_json<T: serde::Serialize>(schema: OutputSchema, payload: &T) {
+    let envelope = Envelope {
+        schema: schema.id(),
+        payload,
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&envelope)
+            .expect("invariant: JSON serialization of struct cannot fail")
+    );
+}
+
+impl Cli {
+    /// Schema of the invoked command's JSON output, if it has one
+    fn output_schema(&self) -> Option<OutputSchema> {
+        let schema = match &self.command {
+            Commands::Scan { output, .. } if output.stream => return None,
+            Commands::Scan { output, .. } if output.group_by.is_some() => OutputSchema::ScanGroups,
+            Commands::Scan { .. } => OutputSchema::Scan,
+            Commands::Check { .. } => OutputSchema::Check,
+            Commands::Policy { .. } => OutputSchema::Policy,
+            Commands::Validate { proposals, .. } if is_proposal_batch(proposals) => {
+                OutputSchema::ValidateBatch
+            }
+            Commands::Validate { .. } => OutputSchema::Validate,
+            Commands::Doctor { .. } => OutputSchema::Doctor,
+            Commands::Stats { .. } => OutputSchema::Stats,
+            Commands::DiffPolicyImpact { .. } => OutputSchema::PolicyImpact,
+            Commands::Fix { .. } => OutputSchema::Fix,
+            Commands::Init { .. }
+            | Commands::Completions { .. }
+            | Commands::Man
+            | Commands::Triage { .. }
+            | Commands::Lsp { .. }
+            | Commands::Serve { .. }
+            | Commands::GithubApp { .. } => return None,
+            Commands::Contract { action } => match action {
+                ContractAction::Redteam {
+                    action: Some(action),
+                    ..
+                } => match action {
+                    RedteamAction::Generate { .. } => OutputSchema::RedteamGenerate,
+                    RedteamAction::Minimize { .. } => OutputSchema::RedteamMinimize,
+                    RedteamAction::Suggest { .. } => OutputSchema::RedteamSuggest,
+                },
+                ContractAction::Redteam { action: None, .. } => OutputSchema::Redteam,
+                ContractAction::Test { .. } => OutputSchema::ContractTest,
+                ContractAction::Eval { .. } | ContractAction::Record { .. } => {
+                    OutputSchema::Decision
+                }
+                ContractAction::Verify { .. } => OutputSchema::Verify,
+                ContractAction::Schema { .. } => OutputSchema::ContractSchema,
+                ContractAction::Regression { .. } => OutputSchema::Regression,
+                ContractAction::Coverage { .. } => OutputSchema::Coverage,
+                ContractAction::Fuzz { .. } => OutputSchema::Fuzz,
+                ContractAction::Mutate { .. } => OutputSchema::Mutation,
+                ContractAction::Snapshot { .. } => OutputSchema::Snapshot,
+                ContractAction::DiffEval { .. } => OutputSchema::DiffEval,
+                ContractAction::Calibrate { .. } => OutputSchema::Calibration,
+            },
+            Commands::Training { action } => match action {
+                TrainingAction::Export { .. } => OutputSchema::TrainingExport,
+            },
+            Commands::Audit { action } => match action {
+                AuditAction::Replay { .. } => OutputSchema::AuditReplay,
+            },
+            Commands::Model { action } => match action {
+                ModelAction::Pull { .. } => OutputSchema::ModelPull,
+                ModelAction::Devices { .. } => OutputSchema::ModelDevices,
+            },
+            Commands::Config { action } => match action {
+                ConfigAction::Show { .. } => OutputSchema::Settings,
+            },
+        };
+        Some(schema)
+    }
+
+    /// Format of the invoked command, for errors raised before dispatch
+    fn output_format(&self) -> OutputFormat {
+        let format = match &self.command {
+            Commands::Scan { format, .. } => return format.output(),
+            Commands::Check { format, .. }
+            | Commands::Policy { format, .. }
+            | Commands::Validate { format, .. }
+            | Commands::Doctor { format }
+            | Commands::Stats { format, .. }
+            | Commands::DiffPolicyImpact { format, .. }
+            | Commands::Fix { format, .. } => format,
+            Commands::Init { .. }
+            | Commands::Completions { .. }
+            | Commands::Man
+            | Commands::Triage { .. }
+            | Commands::Lsp { .. }
+            | Commands::Serve { .. }
+            | Commands::GithubApp { .. } => &OutputFormat::Text,
+            Commands::Contract { action } => match action {
+                ContractAction::Redteam {
+                    action:
+                        Some(
+                            RedteamAction::Generate { format, .. }
+                            | RedteamAction::Minimize { format, .. }
+                            | RedteamAction::Suggest { format, .. },
+                        ),
+                    ..
+                } => format,
+                ContractAction::Test { format, .. }
+                | ContractAction::Eval { format, .. }
+                | ContractAction::Verify { format, .. }
+                | ContractAction::Record { format, .. }
+                | ContractAction::Schema { format, .. }
+                | ContractAction::Redteam { format, .. }
+                | ContractAction::Regression { format, .. }
+                | ContractAction::Coverage { format, .. }
+                | ContractAction::Fuzz { format, .. }
+                | ContractAction::Mutate { format, .. }
+                | ContractAction::Snapshot { format, .. }
+                | ContractAction::DiffEval { format, .. }
+                | ContractAction::Calibrate { format, .. } => format,
+            },
+            Commands::Training { action } => match action {
+                TrainingAction::Export { format, .. } => format,
+            },
+            Commands::Audit { action } => match action {
+                AuditAction::Replay { format, .. } => format,
+            },
+            Commands::Model { action } => match action {
+                ModelAction::Pull { format, .. } | ModelAction::Devices { format, .. } => format,
+            },
+            Commands::Config { action } => match action {
+                ConfigAction::Show { format, .. } => format,
+            },
+        };
+        format.clone()
+    }
+}
+
+fn main() {
+    // Logs stay off stdout, which carries reports and the LSP stream
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let parsed = Cli::command()
+        .try_get_matches()
+        .and_then(|matches| Ok((Cli::from_arg_matches(&matches)?, matches)));
+    let (mut cli, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => std::process::exit(report_clap_error(e)),
+    };
+    let settings = match apply_config_layers(&mut cli, &matches) {
+        Ok(s) => s,
+        Err(e) => {
+            let code = CliError::new(ErrorKind::Config, e).report(&cli.output_format());
+            std::process::exit(code);
+        }
+    };
+    init_color(cli.no_color);
+    let _ = DETERMINISTIC.set(cli.deterministic);
+
+    if cli.output_schema {
+        let code = match cli.output_schema() {
+            Some(schema) => {
+                let json = serde_json::to_string_pretty(&schema.json_schema());
+                println!(
+                    "{}",
+                    json.expect("invariant: JSON serialization of struct cannot fail")
+                );
+                0
+            }
+            None => CliError::new(ErrorKind::Usage, "This command has no JSON output")
+                .report(&OutputFormat::Text),
+        };
+        std::process::exit(code);
+    }
+
+    // Settings are shown even when the policy they name fails to load
+    if let Commands::Config {
+        action: ConfigAction::Show { origin, format },
+    } = &cli.command
+    {
+        std::process::exit(show_settings(&settings, *origin, format));
+    }
+
+    let loaded = load_configured_policy(&cli);
+
+    // Doctor reports a policy that fails to load instead of exiting on it
+    if let Commands::Doctor { format } = &cli.command {
+        let code = if cli.dry_run {
+            println!("[dry-run] Would check configuration, rules, models and state directories");
+            0
+        } else {
+            run_doctor(&cli, &loaded, format)
+        };
+        std::process::exit(code);
+    }
+
+    let policy = match loaded {
+        Ok(p) => p,
+        Err(e) => {
+            let code = CliError::new(ErrorKind::Config, format!("Failed to load policy: {}", e))
+                .report(&cli.output_format());
+            std::process::exit(code);
+        }
+    };
+
+    // Serve reloads its policy through the same flags, so it keeps the CLI
+    if let Commands::Serve {
+        listen,
+        workers,
+        queue,
+        max_body,
+        watch,
+        audit_log,
+        auth_registry,
+        tenants,
+    } = &cli.command
+    {
+        let auth = match auth_registry
+            .as_deref()
+            .map(load_source_registry)
+            .transpose()
+        {
+            Ok(a) => a,
+            Err(e) => {
+                std::process::exit(CliError::new(ErrorKind::Config, e).report(&OutputFormat::Text))
+            }
+        };
+        let tenants = match tenants.as_deref().map(load_tenants).transpose() {
+            Ok(t) => t.unwrap_or_default(),
+            Err(e) => {
+                std::process::exit(CliError::new(ErrorKind::Config, e).report(&OutputFormat::Text))
+            }
+        };
+        let options = ServeOptions {
+            listen: listen.clone(),
+            workers: workers.map(usize::from).unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            }),
+            queue: *queue,
+            max_body: *max_body,
+            policy_file: cli.policy_file.clone(),
+            watch: *watch,
+            audit_log: audit_log.clone(),
+            auth,
+            tenants,
+        };
+        let code = if *watch && options.policy_file.is_none() && options.tenants.tenants.is_empty()
+        {
+            CliError::new(ErrorKind::Usage, "--watch needs a policy file")
+                .with_hint("Pass --policy-file or --tenants")
+                .report(&OutputFormat::Text)
+        } else if cli.dry_run {
+            println!("[dry-run] Would serve decisions on {}", options.listen);
+            println!(
+                "[dry-run] Workers: {}, queue: {}",
+                options.workers, options.queue
+            );
+            for tenant in &options.tenants.tenants {
+                println!(
+                    "[dry-run] Tenant {}: {}",
+                    tenant.name,
+                    tenant.policy_file.display()
+                );
+            }
+            0
+        } else {
+            // Tenant policies take the same SLM overrides as the default one
+            run_serve(policy, &options, &|path| match path {
+                None => load_configured_policy(&cli),
+                Some(path) => {
+                    let mut policy = load_policy(Some(path))?;
+                    apply_slm_overrides(&mut policy, &cli)?;
+                    Ok(policy)
+                }
+            })
+        };
+        std::process::exit(code);
+    }
+
+    if let Commands::GithubApp {
+        listen,
+        webhook_secret,
+        token,
+        api_url,
+        audit_log,
+    } = &cli.command
+    {
+        let options = GithubAppOptions {
+            listen: listen.clone(),
+            webhook_secret: webhook_secret.clone(),
+            api: GithubApi {
+                url: api_url.trim_end_matches('/').to_string(),
+                token: token.clone(),
+            },
+            audit_log: audit_log.clone(),
+        };
+        let code = if cli.dry_run {
+            println!(
+                "[dry-run] Would receive GitHub webhooks on {}",
+                options.listen
+            );
+            println!("[dry-run] Check runs posted to {}", options.api.url);
+            0
+        } else {
+            run_github_app(policy, &options)
+        };
+        std::process::exit(code);
+    }
+
+    let oracle = Oracle::new(policy.clone());
+
+    let exit_code = match cli.command {
+        Commands::Scan {
+            path,
+            format,
+            include_hidden: _,
+            depth: _,
+            include: _,
+            exclude: _,
+            follow_symlinks,
+            triage_file,
+            output,
+            fail,
+        } => {
+            if cli.dry_run {
+                println!("[dry-run] Would scan: {}", path.display());
+                println!("[dry-run] Format: {:?}", format);
+                println!("[dry-run] Follow symlinks: {}", follow_symlinks);
+                0
+            } else {
+                let oracle = Oracle::new(policy.clone()).with_follow_symlinks(follow_symlinks);
+                if output.stream {
+                    stream_scan(&oracle, &path, &cli.verbosity, &triage_file, &fail)
+                } else {
+                    scan_directory(
+                        &oracle,
+                        &path,
+                        &format,
+                        &cli.verbosity,
+                        &triage_file,
+                        &output,
+                        &fail,
+                    )
+                }
+            }
+        }
+        Commands::Check {
             file,
             content,
             assume_path,
             format,
+            explain,
+            fail,
+        } => {
+            if cli.dry_run {
+                match (&file, content.as_deref()) {
+                    (Some(f), _) => println!("[dry-run] Would check: {}", f.display()),
+                    (None, Some("-")) => println!("[dry-run] Would check content from stdin"),
+                    _ => println!("[dry-run] Would check inline content"),
+                }
+                0
+            } else {
+                let oracle = Oracle::new(policy.clone()).with_explain(explain);
+                check_content(
+                    &oracle,
+                    file,
+                    content,
+                    assume_path,
+                    &format,
+                    &cli.verbosity,
+                    &fail,
+                )
+            }
+        }
+        Commands::Policy { format, section } => {
+            show_policy(&policy, &format, section.as_deref());
+            0
+        }
+        Commands::Validate {
+            proposals,
+            format,
+            strict,
+            fail,
+        } => {
+            if cli.dry_run {
+                let inputs: Vec<String> =
+                    proposals.iter().map(|p| p.display().to_string()).collect();
+                println!("[dry-run] Would validate: {}", inputs.join(", "));
+                0
+            } else if is_proposal_batch(&proposals) {
+                validate_proposals(&oracle, &proposals, &format, strict, &fail)
+            } else {
+                validate_proposal(&oracle, &proposals[0], &format, strict, &fail)
+            }
+        }
+        Commands::Init {
+            force,
+            minimal,
+            yes,
+        } => {
+            if cli.dry_run {
+                println!("[dry-run] Would create .conative/ directory");
+                println!(
+                    "[dry-run] Force: {}, Minimal: {}, Wizard: {}",
+                    force,
+                    minimal,
+                    !(yes || minimal)
+                );
+                0
+            } else {
+                init_config(&policy, force, minimal, yes)
+            }
+        }
+        Commands::Completions { shell } => {
+            generate_completions(shell);
+            0
+        }
+        Commands::Man => {
+            generate_man_page();
+            0
+        }
+        Commands::Training { action } => match action {
+            TrainingAction::Export {
+                audit,
+                labels,
+                output,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would export training data from {} audit log(s) into {}",
+                        audit.len(),
+                        output.display()
+                    );
+                    0
+                } else {
+                    run_training_export(&audit, labels.as_deref(), &output, &format)
+                }
+            }
+        },
+        Commands::Audit { action } => match action {
+            AuditAction::Replay {
+                from,
+                corpus,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would replay {} audit log(s)", from.len());
+                    if let Some(corpus) = &corpus {
+                        println!("[dry-run] Corpus: {}", corpus.display());
+                    }
+                    0
+                } else {
+                    run_audit_replay(
+                        &policy,
+                        cli.policy_file.as_deref(),
+                        &from,
+                        corpus.as_deref(),
+                        &format,
+                    )
+                }
+            }
+        },
+        Commands::Doctor { .. } => unreachable!("doctor runs before the policy is loaded"),
+        Commands::Config { .. } => unreachable!("config runs before the policy is loaded"),
+        Commands::Serve { .. } | Commands::GithubApp { .. } => {
+            unreachable!("servers run before dispatch")
+        }
+        Commands::Stats {
+            path,
+            format,
+            stats_file,
+            save,
+            top,
+        } => {
+            if cli.dry_run {
+                println!("[dry-run] Would summarise: {}", path.display());
+                println!("[dry-run] Compare with: {}", stats_file.display());
+                0
+            } else {
+                run_stats(&oracle, &path, &stats_file, save, top, &format)
+            }
+        }
+        Commands::DiffPolicyImpact {
+            proposed,
+            path,
+            current,
+            format,
+            fail_on_change,
+        } => {
+            if cli.dry_run {
+                println!("[dry-run] Would compare policies on: {}", path.display());
+                println!("[dry-run] Proposed policy: {}", proposed.display());
+                0
+            } else {
+                run_policy_impact(
+                    &policy,
+                    current.as_deref(),
+                    &proposed,
+                    &path,
+                    &format,
+                    fail_on_change,
+                )
+            }
+        }
+        Commands::Triage {
+            path,
+            triage_file,
+            follow_symlinks,
+        } => {
+            if cli.dry_run {
+                println!("[dry-run] Would triage: {}", path.display());
+                println!("[dry-run] Triage file: {}", triage_file.display());
+                0
+            } else {
+                let oracle = Oracle::new(policy.clone()).with_follow_symlinks(follow_symlinks);
+                run_triage(&oracle, &path, &triage_file)
+            }
+        }
+        Commands::Fix {
+            path,
+            apply,
+            format,
         } => {
             if cli.dry_run {
-                println!("[dry-run] Would check: {:?} or content", file);
+                println!("[dry-run] Would plan fixes for: {}", path.display());
+                0
+            } else {
+                run_fix(&oracle, &path, apply, &format)
+            }
+        }
+        Commands::Lsp { check_on } => {
+            if cli.dry_run {
+                println!(
+                    "[dry-run] Would serve LSP on stdio, checking on {:?}",
+                    check_on
+                );
+                0
+            } else {
+                run_lsp(&oracle, check_on)
+            }
+        }
+        Commands::Model { action } => match action {
+            ModelAction::Pull {
+                name,
+                registry,
+                dir,
+                force,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would pull model {} from {}", name, registry);
+                    0
+                } else {
+                    run_model_pull(&name, &registry, dir, force, &format)
+                }
+            }
+            ModelAction::Devices { binary, format } => run_model_devices(&binary, &format),
+        },
+        Commands::Contract { action } => match action {
+            ContractAction::Test {
+                path,
+                format,
+                fail_fast,
+                tags,
+                categories,
+                name_filter,
+                shard,
+                jobs,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would run contract tests from: {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    let filter = TestFilter {
+                        tags,
+                        categories,
+                        name: name_filter,
+                        shard,
+                    };
+                    run_contract_tests(
+                        &policy,
+                        &path,
+                        &format,
+                        fail_fast,
+                        &filter,
+                        jobs,
+                        &cli.verbosity,
+                    )
+                }
+            }
+            ContractAction::Eval {
+                request,
+                format,
+                records,
+            } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would evaluate request: {}", request.display());
+                    if let Some(attest) = &records.attest {
+                        println!("[dry-run] Would write attestation to: {}", attest.display());
+                    }
+                    if let Some(log) = &records.audit_log {
+                        println!("[dry-run] Would append audit entry to: {}", log.display());
+                    }
+                    0
+                } else {
+                    eval_contract_request(
+                        &policy,
+                        cli.policy_file.as_deref(),
+                        &request,
+                        &format,
+                        &records,
+                        None,
+                    )
+                }
+            }
+            ContractAction::Record {
+                request,
+                output,
+                sample,
+                format,
+                records,
+            } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would evaluate request: {}", request.display());
+                    println!(
+                        "[dry-run] Would record {:.0}% of requests into {}",
+                        sample * 100.0,
+                        output.display()
+                    );
+                    0
+                } else {
+                    let record = Some((output.as_path(), sample));
+                    eval_contract_request(
+                        &policy,
+                        cli.policy_file.as_deref(),
+                        &request,
+                        &format,
+                        &records,
+                        record,
+                    )
+                }
+            }
+            ContractAction::Verify {
+                attestation,
+                public_key,
+                format,
+            } => verify_attestation(&attestation, public_key.as_deref(), &format),
+            ContractAction::Schema { format, section } => {
+                show_contract_schema(&format, section.as_deref());
                 0
+            }
+            ContractAction::Redteam {
+                action:
+                    Some(RedteamAction::Generate {
+                        seeds,
+                        output,
+                        strategy,
+                        force,
+                        format,
+                    }),
+                ..
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would generate red-team cases from {} into {}",
+                        seeds.display(),
+                        output.display()
+                    );
+                    0
+                } else {
+                    let strategies = strategy
+                        .into_iter()
+                        .map(ObfuscationStrategy::from)
+                        .collect();
+                    run_redteam_generate(
+                        &policy,
+                        &seeds,
+                        &output,
+                        strategies,
+                        force,
+                        &format,
+                        &cli.verbosity,
+                    )
+                }
+            }
+            ContractAction::Redteam {
+                action:
+                    Some(RedteamAction::Minimize {
+                        path,
+                        keep,
+                        max_evaluations,
+                        force,
+                        format,
+                    }),
+                ..
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would minimize bypassing red-team cases in {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    let minimizer = BypassMinimizer::new(contract_runner(&policy))
+                        .with_keep(keep)
+                        .with_max_evaluations(max_evaluations);
+                    run_redteam_minimize(&minimizer, &path, force, &format, &cli.verbosity)
+                }
+            }
+            ContractAction::Redteam {
+                action:
+                    Some(RedteamAction::Suggest {
+                        path,
+                        benign,
+                        output,
+                        max_per_bypass,
+                        format,
+                    }),
+                ..
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would suggest rules for red-team bypasses in {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    let suggester = RuleSuggester::new().with_max_per_bypass(max_per_bypass);
+                    run_redteam_suggest(
+                        &policy,
+                        suggester,
+                        &path,
+                        &benign,
+                        &output,
+                        &format,
+                        &cli.verbosity,
+                    )
+                }
+            }
+            ContractAction::Redteam {
+                action: None,
+                path,
+                format,
+                run,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would run red-team tests from: {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    run_redteam_tests(&policy, &path, &format, &run, &cli.verbosity)
+                }
+            }
+            ContractAction::Regression {
+                path,
+                baseline,
+                save,
+                format,
+                strict,
+            } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would run regression tests");
+                    println!(
+                        "[dry-run] Tests: {}, Baseline: {}",
+                        path.display(),
+                        baseline.display()
+                    );
+                    0
+                } else {
+                    run_regression_tests(
+                        &policy,
+                        &path,
+                        &baseline,
+                        save,
+                        &format,
+                        strict,
+                        &cli.verbosity,
+                    )
+                }
+            }
+            ContractAction::Coverage { path, format } => {
+                if cli.dry_run {
+                    println!("[dry-run] Would compute coverage for: {}", path.display());
+                    0
+                } else {
+                    run_coverage_report(&policy, &path, &format, &cli.verbosity)
+                }
+            }
+            ContractAction::Fuzz {
+                iterations,
+                seed,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would fuzz the oracle with {} proposals",
+                        iterations
+                    );
+                    0
+                } else {
+                    run_fuzz(&policy, iterations, seed, &format)
+                }
+            }
+            ContractAction::Mutate {
+                path,
+                format,
+                strict,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would mutation-test policy against: {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    run_policy_mutation(&policy, &path, &format, strict, &cli.verbosity)
+                }
+            }
+            ContractAction::Snapshot {
+                path,
+                snapshots,
+                update,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would {} snapshots in {} for: {}",
+                        if update { "update" } else { "check" },
+                        snapshots.display(),
+                        path.display()
+                    );
+                    0
+                } else {
+                    run_snapshot_tests(&policy, &path, &snapshots, update, &format, &cli.verbosity)
+                }
+            }
+            ContractAction::DiffEval {
+                path,
+                slm_weight,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would diff oracle and oracle+SLM verdicts for: {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    run_diff_eval(&policy, &path, slm_weight, &format, &cli.verbosity)
+                }
+            }
+            ContractAction::Calibrate {
+                path,
+                threshold,
+                bins,
+                format,
+            } => {
+                if cli.dry_run {
+                    println!(
+                        "[dry-run] Would calibrate the SLM evaluator on: {}",
+                        path.display()
+                    );
+                    0
+                } else {
+                    run_calibration(&policy, &path, threshold, bins, &format, &cli.verbosity)
+                }
+            }
+        },
+    };
+
+    std::process::exit(exit_code);
+}
+
+fn scan_directory(
+    oracle: &Oracle,
+    path: &Path,
+    format: &ScanFormat,
+    verbosity: &Verbosity,
+    triage_file: &Path,
+    output: &ScanOutput,
+    fail: &FailPolicy,
+) -> i32 {
+    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+        eprintln!("Scanning: {}", path.display());
+    }
+    if output.group_by.is_some() && matches!(format, ScanFormat::Codequality | ScanFormat::Gitea) {
+        return CliError::new(
+            ErrorKind::Usage,
+            "--group-by cannot be used with report formats",
+        )
+        .with_hint("Use --format text, compact, json, csv or ndjson")
+        .report(&format.output());
+    }
+
+    let triage = match TriageFile::load(triage_file) {
+        Ok(t) => t,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to load triage file: {}", e),
+            )
+            .report(&format.output());
+        }
+    };
+
+    match oracle.scan_directory(path) {
+        Ok(mut result) => {
+            let suppressed = result.apply_triage(&triage);
+            if suppressed > 0 && matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                eprintln!("Suppressed by {}: {}", triage_file.display(), suppressed);
+            }
+            if let Some(group_by) = output.group_by {
+                print_scan_groups(oracle, &result, group_by, output.top, format);
+                return fail.exit_code(
+                    result.violations.len(),
+                    result.concerns.len(),
+                    FailOn::Concern,
+                );
+            }
+            match format {
+                ScanFormat::Json => {
+                    print_json(OutputSchema::Scan, &result);
+                }
+                ScanFormat::Csv => {
+                    println!("kind,rule,severity,file,line,message");
+                    for row in scan_rows(oracle, &result) {
+                        let fields = [
+                            row.kind.to_string(),
+                            row.rule,
+                            row.severity.unwrap_or_default(),
+                            row.file,
+                            row.line.map(|l| l.to_string()).unwrap_or_default(),
+                            row.message,
+                        ];
+                        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                        println!("{}", fields.join(","));
+                    }
+                }
+                ScanFormat::Ndjson => {
+                    for row in scan_rows(oracle, &result) {
+                        println!(
+                            "{}",
+                            serde_json::to_string(&row)
+                                .expect("invariant: JSON serialization of struct cannot fail")
+                        );
+                    }
+                }
+                ScanFormat::Codequality => {
+                    let report = codequality_report(oracle, &result);
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&report)
+                            .expect("invariant: JSON serialization of struct cannot fail")
+                    );
+                }
+                ScanFormat::Gitea => {
+                    let review = gitea_review(oracle, &result);
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&review)
+                            .expect("invariant: JSON serialization of struct cannot fail")
+                    );
+                }
+                ScanFormat::Compact => {
+                    let status = if !result.violations.is_empty() {
+                        "VIOLATION"
+                    } else if !result.concerns.is_empty() {
+                        "CONCERN"
+                    } else {
+                        "OK"
+                    };
+                    println!(
+                        "{} {} files={} violations={} concerns={}",
+                        status,
+                        result.path.display(),
+                        result.files_scanned,
+                        result.violations.len(),
+                        result.concerns.len()
+                    );
+                }
+                ScanFormat::Text => {
+                    print_scan_result(oracle, &result);
+                }
+            }
+
+            fail.exit_code(
+                result.violations.len(),
+                result.concerns.len(),
+                FailOn::Concern,
+            )
+        }
+        Err(e) => CliError::new(ErrorKind::Input, format!("Error scanning directory: {}", e))
+            .report(&format.output()),
+    }
+}
+
+/// Findings of a scan sharing one `--group-by` key
+#[derive(Debug, serde::Serialize)]
+struct ScanGroup {
+    key: String,
+    findings: usize,
+    violations: usize,
+    concerns: usize,
+    files: usize,
+    /// Deepest directory holding all of the group's files, when it is
+    /// below the scanned root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    under: Option<String>,
+}
+
+/// Largest groups of a scan, as printed by `scan --group-by`
+#[derive(Debug, serde::Serialize)]
+struct ScanGroups<'a> {
+    path: &'a Path,
+    verdict: &'a PolicyVerdict,
+    files_scanned: usize,
+    group_by: GroupBy,
+    /// Groups before --top cut the list
+    total_groups: usize,
+    groups: &'a [ScanGroup],
+}
+
+/// Group a scan's findings, largest group first (ties by key)
+fn scan_groups(policy: &Policy, result: &DirectoryScanResult, group_by: GroupBy) -> Vec<ScanGroup> {
+    let language = |file: &Path, violation: Option<&ViolationType>| {
+        if let Some(ViolationType::ForbiddenLanguage { language, .. }) = violation {
+            return language.clone();
+        }
+        let name = file.to_string_lossy().to_lowercase();
+        let languages = &policy.languages;
+        languages
+            .tier1
+            .iter()
+            .chain(&languages.tier2)
+            .chain(&languages.forbidden)
+            .find(|l| {
+                l.extensions
+                    .iter()
+                    .any(|e| name.ends_with(&e.to_lowercase()))
+            })
+            .map_or_else(|| "other".to_string(), |l| l.name.clone())
+    };
+    // Concerns have no violation, and group by severity as "concern"
+    let key = |file: &Path, rule: String, violation: Option<&ViolationType>| match group_by {
+        GroupBy::File => result.relative_path(file),
+        GroupBy::Rule => rule,
+        GroupBy::Language => language(file, violation),
+        GroupBy::Severity => violation.map_or_else(
+            || "concern".to_string(),
+            |v| format!("{:?}", v.severity()).to_lowercase(),
+        ),
+    };
+
+    // key -> (violations, concerns, files)
+    let mut grouped: BTreeMap<String, (usize, usize, BTreeSet<String>)> = BTreeMap::new();
+    for v in &result.violations {
+        let entry = grouped
+            .entry(key(&v.file, v.violation.rule_id(), Some(&v.violation)))
+            .or_default();
+        entry.0 += 1;
+        entry.2.insert(result.relative_path(&v.file));
+    }
+    for c in &result.concerns {
+        let entry = grouped
+            .entry(key(&c.file, c.concern.rule_id(), None))
+            .or_default();
+        entry.1 += 1;
+        entry.2.insert(result.relative_path(&c.file));
+    }
+
+    let mut groups: Vec<ScanGroup> = grouped
+        .into_iter()
+        .map(|(key, (violations, concerns, files))| ScanGroup {
+            under: (files.len() > 1)
+                .then(|| common_directory(&files))
+                .flatten(),
+            key,
+            findings: violations + concerns,
+            violations,
+            concerns,
+            files: files.len(),
+        })
+        .collect();
+    // Stable, so equal groups keep the map's key order
+    groups.sort_by_key(|g| Reverse(g.findings));
+    groups
+}
+
+/// Directory every relative path lies under, if any
+fn common_directory(files: &BTreeSet<String>) -> Option<String> {
+    let mut paths = files.iter().map(|f| Path::new(f.as_str()));
+    let mut common = paths.next()?.parent()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&common) {
+            common = common.parent()?.to_path_buf();
+        }
+    }
+    let common = common.to_string_lossy();
+    (!common.is_empty()).then(|| format!("{}/", common))
+}
+
+fn print_scan_groups(
+    oracle: &Oracle,
+    result: &DirectoryScanResult,
+    group_by: GroupBy,
+    top: Option<usize>,
+    format: &ScanFormat,
+) {
+    let groups = scan_groups(oracle.policy(), result, group_by);
+    let total_groups = groups.len();
+    let shown = &groups[..top.unwrap_or(total_groups).min(total_groups)];
+    let rest = &groups[shown.len()..];
+
+    match format {
+        ScanFormat::Json => print_json(
+            OutputSchema::ScanGroups,
+            &ScanGroups {
+                path: &result.path,
+                verdict: &result.verdict,
+                files_scanned: result.files_scanned,
+                group_by,
+                total_groups,
+                groups: shown,
+            },
+        ),
+        ScanFormat::Ndjson => {
+            for group in shown {
+                println!(
+                    "{}",
+                    serde_json::to_string(group)
+                        .expect("invariant: JSON serialization of struct cannot fail")
+                );
+            }
+        }
+        ScanFormat::Csv => {
+            println!("group,findings,violations,concerns,files,under");
+            for g in shown {
+                let under = g.under.as_deref().unwrap_or_default();
+                println!(
+                    "{},{},{},{},{},{}",
+                    csv_field(&g.key),
+                    g.findings,
+                    g.violations,
+                    g.concerns,
+                    g.files,
+                    csv_field(under)
+                );
+            }
+        }
+        ScanFormat::Compact => {
+            for g in shown {
+                let under = g
+                    .under
+                    .as_deref()
+                    .map(|u| format!(" under={}", u))
+                    .unwrap_or_default();
+                println!(
+                    "group {} findings={} violations={} concerns={} files={}{}",
+                    g.key, g.findings, g.violations, g.concerns, g.files, under
+                );
+            }
+            println!(
+                "scan {} files={} groups={} violations={} concerns={}",
+                result.path.display(),
+                result.files_scanned,
+                total_groups,
+                result.violations.len(),
+                result.concerns.len()
+            );
+        }
+        ScanFormat::Text | ScanFormat::Codequality | ScanFormat::Gitea => {
+            println!("=== Conative Gating Scan Summary ===\n");
+            println!("Path: {}", result.path.display());
+            println!("Files scanned: {}", result.files_scanned);
+            println!("Verdict: {}\n", verdict_label(&result.verdict));
+            let by = format!("{:?}", group_by).to_lowercase();
+            if shown.len() < total_groups {
+                println!("By {} (top {} of {}):", by, shown.len(), total_groups);
+            } else {
+                println!("By {}:", by);
+            }
+            for g in shown {
+                let mut line = format!(
+                    "  {}: {} findings ({} violations, {} concerns)",
+                    g.key, g.findings, g.violations, g.concerns
+                );
+                if group_by != GroupBy::File {
+                    line.push_str(&format!(" in {} files", g.files));
+                }
+                if let Some(under) = &g.under {
+                    line.push_str(&format!(" under {}", under));
+                }
+                println!("{}", line);
+            }
+            if !rest.is_empty() {
+                let findings: usize = rest.iter().map(|g| g.findings).sum();
+                println!("  ... {} more groups ({} findings)", rest.len(), findings);
+            }
+            if total_groups == 0 {
+                println!("No violations or concerns found.");
+            }
+        }
+    }
+}
+
+/// One violation or concern from a scan, as a CSV row or NDJSON object
+#[derive(serde::Serialize)]
+struct ScanRow {
+    kind: &'static str,
+    rule: String,
+    severity: Option<String>,
+    file: String,
+    /// One-based line of the match, when it can be located in content
+    line: Option<usize>,
+    message: String,
+}
+
+impl ScanRow {
+    fn violation(oracle: &Oracle, sources: &mut SourceCache, v: &FileViolation) -> Self {
+        let message = v.violation.clone().into_string();
+        let (line, message) = match sources.locate(oracle, v) {
+            Some((text, range)) => (
+                Some(line_column(text, range.start).0 + 1),
+                masked_message(oracle, &v.violation, text, range, message),
+            ),
+            None => (None, message),
+        };
+        ScanRow {
+            kind: "violation",
+            rule: v.violation.rule_id(),
+            severity: Some(format!("{:?}", v.violation.severity()).to_lowercase()),
+            file: v.file.display().to_string(),
+            line,
+            message,
+        }
+    }
+
+    fn concern(c: &FileConcern) -> Self {
+        ScanRow {
+            kind: "concern",
+            rule: c.concern.rule_id(),
+            severity: None,
+            file: c.file.display().to_string(),
+            line: None,
+            message: c.concern.clone().into_string(),
+        }
+    }
+}
+
+fn scan_rows(oracle: &Oracle, result: &DirectoryScanResult) -> Vec<ScanRow> {
+    let mut sources = SourceCache::new(oracle);
+    let mut rows: Vec<ScanRow> = result
+        .violations
+        .iter()
+        .map(|v| ScanRow::violation(oracle, &mut sources, v))
+        .collect();
+    rows.extend(result.concerns.iter().map(ScanRow::concern));
+    rows
+}
+
+/// Last record of `scan --stream`
+#[derive(serde::Serialize)]
+struct StreamSummary<'a> {
+    kind: &'static str,
+    path: &'a Path,
+    files_scanned: usize,
+    violations: usize,
+    concerns: usize,
+    /// Findings left out because triage suppressed them
+    suppressed: usize,
+    exempted: usize,
+    truncated: usize,
+}
+
+/// Scan printing one NDJSON record per finding as each file is checked
+///
+/// Records share ScanRow's shape; exemptions come as kind "exempted" with
+/// the exception's reason as message. Each record is written under the
+/// stdout lock and flushed, so a reader never sees a partial line, and the
+/// scan stops once the reader goes away.
+fn stream_scan(
+    oracle: &Oracle,
+    path: &Path,
+    verbosity: &Verbosity,
+    triage_file: &Path,
+    fail: &FailPolicy,
+) -> i32 {
+    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+        eprintln!("Streaming scan: {}", path.display());
+    }
+    let triage = match TriageFile::load(triage_file) {
+        Ok(t) => t,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to load triage file: {}", e),
+            )
+            .report(&OutputFormat::Json);
+        }
+    };
+
+    let mut summary = StreamSummary {
+        kind: "summary",
+        path,
+        files_scanned: 0,
+        violations: 0,
+        concerns: 0,
+        suppressed: 0,
+        exempted: 0,
+        truncated: 0,
+    };
+    let mut stdout = std::io::stdout().lock();
+    // Findings arrive file by file, so only the current file's source is kept
+    let mut sources = SourceCache::new(oracle);
+    let mut source_file = PathBuf::new();
+    let scanned = oracle.scan_each(path, |event| match event {
+        ScanEvent::Violation(v) => {
+            if triage.suppresses(path, &v.violation.rule_id(), &v.file) {
+                summary.suppressed += 1;
+                return ControlFlow::Continue(());
+            }
+            if v.file != source_file {
+                sources = SourceCache::new(oracle);
+                source_file = v.file.clone();
+            }
+            summary.violations += 1;
+            write_record(&mut stdout, &ScanRow::violation(oracle, &mut sources, &v))
+        }
+        ScanEvent::Concern(c) => {
+            if triage.suppresses(path, &c.concern.rule_id(), &c.file) {
+                summary.suppressed += 1;
+                return ControlFlow::Continue(());
+            }
+            summary.concerns += 1;
+            write_record(&mut stdout, &ScanRow::concern(&c))
+        }
+        ScanEvent::Exempted(e) => {
+            summary.exempted += 1;
+            write_record(
+                &mut stdout,
+                &ScanRow {
+                    kind: "exempted",
+                    rule: e.rule,
+                    severity: None,
+                    file: e.file.display().to_string(),
+                    line: None,
+                    message: e.reason,
+                },
+            )
+        }
+        ScanEvent::Truncated(_) => {
+            summary.truncated += 1;
+            ControlFlow::Continue(())
+        }
+    });
+
+    // The walk is listed before the first record, so a failed scan has
+    // printed nothing yet
+    summary.files_scanned = match scanned {
+        Ok(files) => files,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error scanning directory: {}", e))
+                .report(&OutputFormat::Json);
+        }
+    };
+    let _ = write_record(&mut stdout, &summary);
+    fail.exit_code(summary.violations, summary.concerns, FailOn::Concern)
+}
+
+/// Write one NDJSON record and flush it; a closed reader stops the caller
+fn write_record(out: &mut impl std::io::Write, record: &impl serde::Serialize) -> ControlFlow<()> {
+    let line =
+        serde_json::to_string(record).expect("invariant: JSON serialization of struct cannot fail");
+    match writeln!(out, "{}", line).and_then(|_| out.flush()) {
+        Ok(()) => ControlFlow::Continue(()),
+        Err(_) => ControlFlow::Break(()),
+    }
+}
+
+/// GitLab Code Quality issues, one per finding
+///
+/// Fingerprints hash the rule and path only, so an issue keeps its
+/// identity when lines move.
+fn codequality_report(oracle: &Oracle, result: &DirectoryScanResult) -> Vec<serde_json::Value> {
+    use sha2::{Digest, Sha256};
+    scan_rows(oracle, result)
+        .into_iter()
+        .map(|row| {
+            let path = result.relative_path(Path::new(&row.file));
+            let severity = match row.severity.as_deref() {
+                Some("critical") => "critical",
+                Some("high") => "major",
+                Some("medium") => "minor",
+                _ => "info",
+            };
+            let fingerprint = Sha256::digest(format!("{}\0{}", row.rule, path));
+            serde_json::json!({
+                "description": row.message,
+                "check_name": row.rule,
+                "fingerprint": fingerprint.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+                "severity": severity,
+                "location": { "path": path, "lines": { "begin": row.line.unwrap_or(1) } },
+            })
+        })
+        .collect()
+}
+
+/// Body for Gitea's create-pull-review API; violations request changes
+fn gitea_review(oracle: &Oracle, result: &DirectoryScanResult) -> serde_json::Value {
+    let comments: Vec<serde_json::Value> = scan_rows(oracle, result)
+        .into_iter()
+        .map(|row| {
+            let level = row.severity.as_deref().unwrap_or("concern");
+            serde_json::json!({
+                "path": result.relative_path(Path::new(&row.file)),
+                "new_position": row.line.unwrap_or(1),
+                "body": format!("**{}** `{}`: {}", level, row.rule, row.message),
+            })
+        })
+        .collect();
+    let event = if result.violations.is_empty() {
+        "COMMENT"
+    } else {
+        "REQUEST_CHANGES"
+    };
+    serde_json::json!({
+        "event": event,
+        "body": format!(
+            "Conative Gating: {} violations, {} concerns in {} files",
+            result.violations.len(),
+            result.concerns.len(),
+            result.files_scanned
+        ),
+        "comments": comments,
+    })
+}
+
+/// Quote a CSV field when it holds a delimiter, quote or line break
+///
+/// A field a spreadsheet would read as a formula (`=`, `+`, `-`, `@`, or a
+/// leading tab or carriage return) gets a `'` prefix so it stays text.
+fn csv_field(value: &str) -> String {
+    let value = match value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        true => format!("'{}", value),
+        false => value.to_string(),
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Scanned files re-read for findings that can be located in content
+struct SourceCache {
+    max_bytes: u64,
+    files: HashMap<PathBuf, Option<String>>,
+}
+
+impl SourceCache {
+    fn new(oracle: &Oracle) -> Self {
+        Self {
+            max_bytes: oracle.policy().scan.max_file_bytes,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Source text and matched range for a violation; files over the scan
+    /// budget are not re-read
+    fn locate(
+        &mut self,
+        oracle: &Oracle,
+        v: &FileViolation,
+    ) -> Option<(&str, std::ops::Range<usize>)> {
+        let max_bytes = self.max_bytes;
+        let source = self.files.entry(v.file.clone()).or_insert_with(|| {
+            let small = std::fs::metadata(&v.file).is_ok_and(|m| m.len() <= max_bytes);
+            small
+                .then(|| read_text_file(&v.file).ok().map(|d| d.text))
+                .flatten()
+        });
+        let text = source.as_deref()?;
+        oracle
+            .locate_violation(&v.violation, text)
+            .map(|range| (text, range))
+    }
+}
+
+/// Matches of a security violation that may quote a secret, and the
+/// policy's masking for them; `None` when the source can be shown as is
+fn secret_ranges(
+    oracle: &Oracle,
+    violation: &ViolationType,
+    text: &str,
+    range: std::ops::Range<usize>,
+) -> Option<(Vec<std::ops::Range<usize>>, EvidenceRedaction)> {
+    let policy = oracle.policy();
+    if !Refusal::from_violation(policy, violation).masks_evidence() {
+        return None;
+    }
+    let mut ranges = oracle.match_ranges(violation, text);
+    if !ranges.contains(&range) {
+        ranges = vec![range];
+    }
+    Some((ranges, policy.enforcement.evidence_redaction))
+}
+
+/// Source for a code frame, masked like decision evidence, with the
+/// matched range moved onto the masked text
+fn masked_source<'a>(
+    oracle: &Oracle,
+    violation: &ViolationType,
+    text: &'a str,
+    range: std::ops::Range<usize>,
+) -> (std::borrow::Cow<'a, str>, std::ops::Range<usize>) {
+    let Some((ranges, level)) = secret_ranges(oracle, violation, text, range.clone()) else {
+        return (std::borrow::Cow::Borrowed(text), range);
+    };
+    let (shown, located) = redact_matches(text, &ranges, level);
+    let index = ranges.iter().position(|r| *r == range).unwrap_or(0);
+    (std::borrow::Cow::Owned(shown), located[index].clone())
+}
+
+/// `message` with any secret the violation matched masked, for output
+/// that prints it beside or instead of a code frame
+fn masked_message(
+    oracle: &Oracle,
+    violation: &ViolationType,
+    text: &str,
+    range: std::ops::Range<usize>,
+    message: String,
+) -> String {
+    let Some((ranges, level)) = secret_ranges(oracle, violation, text, range) else {
+        return message;
+    };
+    ranges
+        .into_iter()
+        .map(|r| &text[r])
+        .filter(|secret| !secret.is_empty())
+        .fold(message, |message, secret| {
+            message.replace(secret, &redact_match(secret, level))
+        })
+}
+
+fn print_scan_result(oracle: &Oracle, result: &DirectoryScanResult) {
+    println!("=== Conative Gating Scan Results ===\n");
+    println!("Path: {}", result.path.display());
+    println!("Files scanned: {}", result.files_scanned);
+    println!("Verdict: {}\n", verdict_label(&result.verdict));
+
+    let mut sources = SourceCache::new(oracle);
+    for v in &result.violations {
+        let located = sources.locate(oracle, v);
+        let message = match located {
+            Some((text, ref range)) => masked_message(
+                oracle,
+                &v.violation,
+                text,
+                range.clone(),
+                v.violation.clone().into_string(),
+            ),
+            None => v.violation.clone().into_string(),
+        };
+        let frame = located.map(|(text, range)| masked_source(oracle, &v.violation, text, range));
+        render_finding(&Finding {
+            level: "error",
+            severity: Some(v.violation.severity()),
+            rule: Some(v.violation.rule_id()),
+            message,
+            file: Some(v.file.display().to_string()),
+            frame,
+            remediation: violation_remediation(oracle.policy(), &v.violation),
+        });
+    }
+
+    for c in &result.concerns {
+        render_finding(&Finding {
+            level: "warning",
+            severity: None,
+            rule: Some(c.concern.rule_id()),
+            message: c.concern.clone().into_string(),
+            file: Some(c.file.display().to_string()),
+            frame: None,
+            remediation: None,
+        });
+    }
+
+    if !result.truncated.is_empty() {
+        println!("CHECKED UP TO THE SIZE LIMIT ({}):", result.truncated.len());
+        for file in &result.truncated {
+            println!("  {}", file.display());
+        }
+        println!();
+    }
+
+    if !result.exempted.is_empty() {
+        println!("EXEMPTED BY POLICY EXCEPTIONS ({}):", result.exempted.len());
+        for e in &result.exempted {
+            println!(
+                "  {} [{}]: {}",
+                result.relative_path(&e.file),
+                e.rule,
+                e.reason
+            );
+        }
+        println!();
+    }
+
+    if result.violations.is_empty() && result.concerns.is_empty() {
+        println!("No violations or concerns found.");
+    }
+}
+
+fn check_content(
+    oracle: &Oracle,
+    file: Option<PathBuf>,
+    content: Option<String>,
+    assume_path: Option<String>,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+    fail: &FailPolicy,
+) -> i32 {
+    let (content_str, file_path, encoding) = match (file, content) {
+        (Some(f), _) => {
+            if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                eprintln!("Reading file: {}", f.display());
+            }
+            let decoded = match read_text_file(&f) {
+                Ok(c) => c,
+                Err(e) => {
+                    return CliError::new(ErrorKind::Input, format!("Failed to read file: {}", e))
+                        .report(format);
+                }
+            };
+            if decoded.is_unusual() && matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                eprintln!("Transcoded from {}", decoded.encoding);
+            }
+            let path = assume_path.unwrap_or_else(|| f.to_string_lossy().to_string());
+            (decoded.text, path, decoded.encoding)
+        }
+        (None, Some(c)) if c == "-" => {
+            let decoded = match read_stdin() {
+                Ok(d) => d,
+                Err(e) => {
+                    return e
+                        .with_hint(
+                            "Pipe the content in, e.g. cat file.py | conative check --content -",
+                        )
+                        .report(format);
+                }
+            };
+            if decoded.is_unusual() && matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                eprintln!("Transcoded from {}", decoded.encoding);
+            }
+            let path = assume_path.unwrap_or_else(|| "stdin".to_string());
+            (decoded.text, path, decoded.encoding)
+        }
+        (None, Some(c)) => {
+            let path = assume_path.unwrap_or_else(|| "stdin".to_string());
+            (c, path, "UTF-8".to_string())
+        }
+        (None, None) => {
+            return CliError::new(
+                ErrorKind::Usage,
+                "Either --file or --content must be provided",
+            )
+            .report(format);
+        }
+    };
+
+    let proposal = Proposal {
+        id: Uuid::new_v4(),
+        action_type: ActionType::CreateFile {
+            path: file_path.clone(),
+        },
+        content: content_str,
+        files_affected: vec![file_path],
+        llm_confidence: 1.0,
+    };
+
+    match oracle.check_decoded(&proposal, &encoding) {
+        Ok(result) => {
+            match format {
+                OutputFormat::Json => {
+                    print_json(OutputSchema::Check, &result);
+                }
+                OutputFormat::Compact => {
+                    let status = if !result.violations.is_empty() {
+                        "VIOLATION"
+                    } else if !result.concerns.is_empty() {
+                        "CONCERN"
+                    } else {
+                        "OK"
+                    };
+                    println!(
+                        "{} violations={} concerns={}",
+                        status,
+                        result.violations.len(),
+                        result.concerns.len()
+                    );
+                }
+                OutputFormat::Text => {
+                    println!("=== Check Result ===\n");
+                    println!("Verdict: {}\n", verdict_label(&result.verdict));
+
+                    for v in &result.violations {
+                        let located = oracle.locate_violation(&v.violation_type, &proposal.content);
+                        let message = v.violation_type.clone().into_string();
+                        let message = match &located {
+                            Some(range) => masked_message(
+                                oracle,
+                                &v.violation_type,
+                                &proposal.content,
+                                range.clone(),
+                                message,
+                            ),
+                            None => message,
+                        };
+                        let frame = located.map(|range| {
+                            masked_source(oracle, &v.violation_type, &proposal.content, range)
+                        });
+                        render_finding(&Finding {
+                            level: "error",
+                            severity: Some(v.severity),
+                            rule: Some(v.rule.clone()),
+                            message,
+                            file: violation_file(&v.violation_type).map(str::to_string),
+                            frame,
+                            remediation: violation_remediation(oracle.policy(), &v.violation_type),
+                        });
+                    }
+
+                    for c in &result.concerns {
+                        render_finding(&Finding {
+                            level: "warning",
+                            severity: None,
+                            rule: Some(c.rule.clone()),
+                            message: c.concern_type.clone().into_string(),
+                            file: None,
+                            frame: None,
+                            remediation: Some(c.suggestion.clone()),
+                        });
+                    }
+
+                    if result.violations.is_empty() && result.concerns.is_empty() {
+                        println!("Content is compliant.");
+                    }
+                    print_rule_trace(&result.trace);
+                }
+            }
+
+            fail.exit_code(
+                result.violations.len(),
+                result.concerns.len(),
+                FailOn::Concern,
+            )
+        }
+        Err(e) => CliError::new(
+            ErrorKind::Internal,
+            format!("Error checking content: {}", e),
+        )
+        .report(format),
+    }
+}
+
+/// Print an `--explain` trace; prints nothing when explaining is off
+fn print_rule_trace<'a>(trace: impl IntoIterator<Item = &'a RuleTrace>) {
+    let mut trace = trace.into_iter().peekable();
+    if trace.peek().is_none() {
+        return;
+    }
+    println!("\nExplanation:");
+    for step in trace {
+        let mark = match step.outcome {
+            RuleOutcome::Matched => "✗",
+            RuleOutcome::NoMatch => "✓",
+            RuleOutcome::Skipped => "-",
+        };
+        println!("  {} {:<40} {}", mark, step.rule, step.detail);
+    }
+}
+
+fn show_policy(policy: &Policy, format: &OutputFormat, section: Option<&str>) {
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Policy, policy);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "policy tier1={} tier2={} forbidden={} exceptions={}",
+                policy.languages.tier1.len(),
+                policy.languages.tier2.len(),
+                policy.languages.forbidden.len(),
+                policy.languages.exceptions.len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== {} ===\n", policy.name);
+
+            let show_all = section.is_none();
+            let section = section.unwrap_or("");
+
+            if show_all || section == "languages" {
+                println!("TIER 1 (Preferred):");
+                for lang in &policy.languages.tier1 {
+                    println!("  + {} ({})", lang.name, lang.extensions.join(", "));
+                }
+                println!("\nTIER 2 (Acceptable):");
+                for lang in &policy.languages.tier2 {
+                    println!("  ~ {} ({})", lang.name, lang.extensions.join(", "));
+                }
+                println!("\nFORBIDDEN:");
+                for lang in &policy.languages.forbidden {
+                    println!("  - {} ({})", lang.name, lang.extensions.join(", "));
+                    if let Some(code) = lang.code {
+                        println!("    refusal code: {}", code);
+                    }
+                }
+                println!("\nEXCEPTIONS:");
+                for exc in &policy.languages.exceptions {
+                    println!(
+                        "  {} allowed in: {} ({})",
+                        exc.language,
+                        exc.allowed_paths.join(", "),
+                        exc.reason
+                    );
+                }
+            }
+
+            if show_all || section == "toolchain" {
+                println!("\nTOOLCHAIN RULES:");
+                for rule in &policy.toolchain.rules {
+                    println!("  {}", rule.describe());
+                    if let Some(code) = rule.code {
+                        println!("    refusal code: {}", code);
+                    }
+                }
+                for rule in &policy.toolchain.lockfiles {
+                    println!(
+                        "  {} changes need {}",
+                        rule.manifest,
+                        rule.lockfiles.join(" or ")
+                    );
+                    if let Some(ref trigger) = rule.trigger {
+                        println!("    when matching: {}", trigger);
+                    }
+                }
+            }
+
+            if show_all || section == "patterns" {
+                println!("\nFORBIDDEN PATTERNS:");
+                for pattern in &policy.patterns.forbidden_patterns {
+                    println!("  {} - {}", pattern.name, pattern.reason);
+                    if let Some(code) = pattern.code {
+                        println!("    refusal code: {}", code);
+                    }
+                    if let Some(ref fix) = pattern.fix {
+                        println!("    quick fix: {}", fix);
+                    }
+                }
+                println!("\nREQUIRED PATTERNS (kept on edit):");
+                for required in &policy.patterns.required_patterns {
+                    println!("  {} - {}", required.name, required.reason);
+                    if let Some(ref header) = required.header {
+                        println!("    header: {}", header);
+                    }
+                }
+            }
+
+            if show_all || section == "protected" {
+                println!("\nPROTECTED WRITES:");
+                for rule in &policy.protected.rules {
+                    let mut scope = rule.paths.clone();
+                    scope.extend(rule.branches.iter().map(|b| format!("branch {}", b)));
+                    println!(
+                        "  {:?} {} [{}] - {}",
+                        rule.action,
+                        rule.name,
+                        scope.join(", "),
+                        rule.reason
+                    );
+                }
+            }
+
+            if show_all || section == "binary" {
+                let binary = &policy.binary;
+                println!("\nBINARY CONTENT:");
+                println!("  allowed: {}", binary.allow);
+                println!("  allowed paths: {}", binary.allowed_paths.join(", "));
+                println!("  inspect archives: {}", binary.inspect_archives);
+            }
+
+            if show_all || section == "obfuscation" {
+                let obfuscation = &policy.obfuscation;
+                println!("\nSPLIT STRINGS:");
+                println!("  fold concatenation: {}", obfuscation.fold_concatenation);
+                println!(
+                    "  fragmentation: {} or more fragments of at most {} characters on average",
+                    obfuscation.min_fragments, obfuscation.max_fragment_chars
+                );
+            }
+
+            if show_all || section == "imports" {
+                println!("\nFORBIDDEN IMPORTS:");
+                for rule in &policy.imports.rules {
+                    let scope = match rule.paths.is_empty() {
+                        true => String::new(),
+                        false => format!(" under {}", rule.paths.join(", ")),
+                    };
+                    let modules = rule.modules.join(", ");
+                    println!(
+                        "  {} ({}{}): {} - {}",
+                        rule.name, rule.language, scope, modules, rule.reason
+                    );
+                }
+            }
+
+            if show_all || section == "unsafe" {
+                let unsafe_code = &policy.unsafe_code;
+                println!("\nUNSAFE BLOCKS (Rust):");
+                println!("  mode: {:?}", unsafe_code.mode);
+                println!("  allowed paths: {}", unsafe_code.allowed_paths.join(", "));
+            }
+
+            if show_all || section == "paths" {
+                println!("\nPATH MATCHING:");
+                println!("  case-insensitive: {}", policy.paths.case_insensitive);
+            }
+
+            if show_all || section == "scan" {
+                let scan = &policy.scan;
+                println!("\nSCAN LIMITS:");
+                println!("  content checks: {}", scan.content_checks);
+                println!("  max file bytes: {}", scan.max_file_bytes);
+                println!(
+                    "  chunk bytes: {} (overlap {})",
+                    scan.chunk_bytes, scan.chunk_overlap
+                );
+            }
+
+            if show_all || section == "conditions" {
+                println!("\nCONDITIONAL RULES (request metadata):");
+                for rule in &policy.conditions.rules {
+                    let mut scope = vec![format!("when {}", rule.when.join(" and "))];
+                    scope.extend((!rule.paths.is_empty()).then(|| rule.paths.join(", ")));
+                    scope.extend(
+                        (!rule.require.is_empty())
+                            .then(|| format!("requires {}", rule.require.join(", "))),
+                    );
+                    println!(
+                        "  {:?} {} [{}] - {}",
+                        rule.action,
+                        rule.name,
+                        scope.join("; "),
+                        rule.reason
+                    );
+                }
+            }
+
+            if show_all || section == "new_repository" {
+                println!("\nNEW REPOSITORIES (required files):");
+                for file in &policy.new_repository.required_files {
+                    println!(
+                        "  {} [{}] - {}",
+                        file.name,
+                        file.paths.join(", "),
+                        file.reason
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn validate_proposal(
+    oracle: &Oracle,
+    proposal_path: &Path,
+    format: &OutputFormat,
+    strict: bool,
+    fail: &FailPolicy,
+) -> i32 {
+    let proposal = match read_proposal(proposal_path) {
+        Ok(p) => p,
+        Err(e) => return e.report(format),
+    };
+
+    match oracle.check_proposal(&proposal) {
+        Ok(result) => {
+            match format {
+                OutputFormat::Json | OutputFormat::Compact => {
+                    print_json(OutputSchema::Validate, &result);
+                }
+                OutputFormat::Text => {
+                    println!("Proposal: {}", result.proposal_id);
+                    println!("Verdict: {}", verdict_label(&result.verdict));
+                    println!("Rules checked: {}", result.rules_checked.len());
+                    println!("Violations: {}", result.violations.len());
+                    println!("Concerns: {}", result.concerns.len());
+                }
+            }
+
+            let default = if strict {
+                FailOn::Concern
+            } else {
+                FailOn::Violation
+            };
+            fail.exit_code(result.violations.len(), result.concerns.len(), default)
+        }
+        Err(e) => CliError::new(
+            ErrorKind::Internal,
+            format!("Error validating proposal: {}", e),
+        )
+        .report(format),
+    }
+}
+
+/// Whether `validate` was given more than one proposal file, i.e. several
+/// inputs, a directory or a glob pattern
+fn is_proposal_batch(inputs: &[PathBuf]) -> bool {
+    match inputs {
+        [single] => single.is_dir() || (!single.exists() && is_glob(&single.to_string_lossy())),
+        _ => true,
+    }
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Read all of stdin as raw bytes and decode it like a file. A terminal
+/// is refused rather than waited on.
+fn read_stdin() -> Result<DecodedContent, CliError> {
+    use std::io::{IsTerminal, Read};
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(CliError::new(
+            ErrorKind::Usage,
+            "'-' reads stdin, but stdin is a terminal",
+        ));
+    }
+    let mut bytes = Vec::new();
+    stdin
+        .read_to_end(&mut bytes)
+        .map_err(|e| CliError::new(ErrorKind::Input, format!("Failed to read stdin: {}", e)))?;
+    Ok(decode_bytes(&bytes))
+}
+
+/// Read and parse one proposal; '-' reads stdin
+fn read_proposal(path: &Path) -> Result<Proposal, CliError> {
+    let content = if path == Path::new("-") {
+        read_stdin()?.text
+    } else {
+        read_text_file(path)
+            .map_err(|e| {
+                CliError::new(
+                    ErrorKind::Input,
+                    format!("Failed to read proposal file: {}", e),
+                )
+            })?
+            .text
+    };
+    serde_json::from_str(&content).map_err(|e| {
+        CliError::new(
+            ErrorKind::Parse,
+            format!("Failed to parse proposal JSON: {}", e),
+        )
+    })
+}
+
+/// Proposal files named by `validate` inputs: directories give every
+/// `*.json` below them and patterns their matches, each in sorted order
+fn proposal_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, CliError> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                walk(&path, files)?;
+            } else if path.extension().is_some_and(|e| e == "json") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    for input in inputs {
+        let pattern = input.to_string_lossy();
+        if input.is_dir() {
+            walk(input, &mut files).map_err(|e| {
+                CliError::new(
+                    ErrorKind::Input,
+                    format!("Failed to read {}: {}", input.display(), e),
+                )
+            })?;
+        } else if !input.exists() && is_glob(&pattern) {
+            let matches = glob::glob(&pattern).map_err(|e| {
+                CliError::new(
+                    ErrorKind::Usage,
+                    format!("Invalid pattern {}: {}", pattern, e),
+                )
+            })?;
+            let before = files.len();
+            files.extend(matches.filter_map(Result::ok).filter(|p| p.is_file()));
+            files[before..].sort();
+            if files.len() == before {
+                return Err(CliError::new(
+                    ErrorKind::Input,
+                    format!("No proposals match {}", pattern),
+                ));
+            }
+        } else {
+            files.push(input.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// One proposal's outcome in a batch `validate`
+#[derive(Debug, serde::Serialize)]
+struct ValidatedProposal {
+    file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verdict: Option<PolicyVerdict>,
+    violations: usize,
+    concerns: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Summary of a batch `validate`, counted per proposal
+#[derive(Debug, serde::Serialize)]
+struct ValidateBatch<'a> {
+    total: usize,
+    compliant: usize,
+    concerns: usize,
+    violations: usize,
+    errors: usize,
+    results: &'a [ValidatedProposal],
+}
+
+/// Validate every proposal the inputs name and summarise them
+///
+/// A file that cannot be read or parsed is reported in place; it fails
+/// the run only when no proposal already does.
+fn validate_proposals(
+    oracle: &Oracle,
+    inputs: &[PathBuf],
+    format: &OutputFormat,
+    strict: bool,
+    fail: &FailPolicy,
+) -> i32 {
+    let files = match proposal_files(inputs) {
+        Ok(files) => files,
+        Err(e) => return e.report(format),
+    };
+
+    let mut results = Vec::new();
+    let mut first_error = None;
+    for file in files {
+        let checked = read_proposal(&file).and_then(|proposal| {
+            oracle.check_proposal(&proposal).map_err(|e| {
+                CliError::new(
+                    ErrorKind::Internal,
+                    format!("Error validating proposal: {}", e),
+                )
+            })
+        });
+        results.push(match checked {
+            Ok(result) => ValidatedProposal {
+                file,
+                violations: result.violations.len(),
+                concerns: result.concerns.len(),
+                verdict: Some(result.verdict),
+                error: None,
+            },
+            Err(e) => {
+                let error = Some(e.message.clone());
+                first_error.get_or_insert(e.exit_code);
+                ValidatedProposal {
+                    file,
+                    verdict: None,
+                    violations: 0,
+                    concerns: 0,
+                    error,
+                }
+            }
+        });
+    }
+
+    let count = |wanted: fn(&PolicyVerdict) -> bool| {
+        results
+            .iter()
+            .filter(|r| r.verdict.as_ref().is_some_and(wanted))
+            .count()
+    };
+    let compliant = count(|v| matches!(v, PolicyVerdict::Compliant));
+    let with_concerns = count(|v| matches!(v, PolicyVerdict::SoftConcern(_)));
+    let with_violations = count(|v| matches!(v, PolicyVerdict::HardViolation(_)));
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+
+    match format {
+        OutputFormat::Json => print_json(
+            OutputSchema::ValidateBatch,
+            &ValidateBatch {
+                total: results.len(),
+                compliant,
+                concerns: with_concerns,
+                violations: with_violations,
+                errors,
+                results: &results,
+            },
+        ),
+        OutputFormat::Compact => {
+            for r in &results {
+                let status = match &r.verdict {
+                    Some(PolicyVerdict::Compliant) => "OK",
+                    Some(PolicyVerdict::SoftConcern(_)) => "CONCERN",
+                    Some(PolicyVerdict::HardViolation(_)) => "VIOLATION",
+                    None => "ERROR",
+                };
+                println!("{} {}", status, r.file.display());
+            }
+            println!(
+                "validate total={} compliant={} concerns={} violations={} errors={}",
+                results.len(),
+                compliant,
+                with_concerns,
+                with_violations,
+                errors
+            );
+        }
+        OutputFormat::Text => {
+            for r in &results {
+                match (&r.verdict, &r.error) {
+                    (Some(verdict), _) => println!(
+                        "{}: {} ({} violations, {} concerns)",
+                        r.file.display(),
+                        verdict_label(verdict),
+                        r.violations,
+                        r.concerns
+                    ),
+                    (None, error) => println!(
+                        "{}: {}",
+                        r.file.display(),
+                        error.as_deref().unwrap_or_default()
+                    ),
+                }
+            }
+            println!(
+                "\nValidated {} proposals: {} compliant, {} with concerns, {} with violations, {} unreadable",
+                results.len(),
+                compliant,
+                with_concerns,
+                with_violations,
+                errors
+            );
+        }
+    }
+
+    let violations = results.iter().map(|r| r.violations).sum();
+    let concerns = results.iter().map(|r| r.concerns).sum();
+    let default = if strict {
+        FailOn::Concern
+    } else {
+        FailOn::Violation
+    };
+    match fail.exit_code(violations, concerns, default) {
+        0 => first_error.unwrap_or(0),
+        code => code,
+    }
+}
+
+fn init_config(policy: &Policy, force: bool, minimal: bool, yes: bool) -> i32 {
+    use std::io::IsTerminal;
+    let config_dir = PathBuf::from(".conative");
+
+    if config_dir.exists() && !force {
+        return CliError::new(ErrorKind::Usage, "Configuration directory already exists")
+            .with_hint("Use --force to overwrite")
+            .report(&OutputFormat::Text);
+    }
+
+    // Answers are collected before anything is written, so a cancelled
+    // wizard leaves the directory untouched
+    let tailored = if yes || minimal {
+        None
+    } else if !std::io::stdin().is_terminal() {
+        return CliError::new(
+            ErrorKind::Usage,
+            "The init wizard needs an interactive terminal",
+        )
+        .with_hint("Use --yes to write the default policy")
+        .report(&OutputFormat::Text);
+    } else {
+        match run_init_wizard(policy) {
+            Ok(p) => Some(render_nickel_policy(&p)),
+            Err(e) => {
+                return CliError::new(ErrorKind::Usage, format!("Init wizard cancelled: {}", e))
+                    .report(&OutputFormat::Text);
+            }
+        }
+    };
+
+    if let Err(e) = std::fs::create_dir_all(&config_dir) {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Failed to create .conative directory: {}", e),
+        )
+        .report(&OutputFormat::Text);
+    }
+
+    let policy_content = if let Some(tailored) = &tailored {
+        tailored.as_str()
+    } else if minimal {
+        r#"# Minimal Conative Policy
+# Extend the RSR default with project-specific rules
+
+let base = import "schema.ncl" in
+{
+  name = "Project Policy",
+  extends = "rsr-default",
+}
+"#
+    } else {
+        include_str!("../config/policy.ncl")
+    };
+
+    let policy_path = config_dir.join("policy.ncl");
+    if let Err(e) = std::fs::write(&policy_path, policy_content) {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Failed to write policy.ncl: {}", e),
+        )
+        .report(&OutputFormat::Text);
+    }
+
+    // Create local.ncl (gitignored)
+    let local_content = r#"# Local policy overrides (not committed to git)
+# Use this for machine-specific or developer-specific settings
+
+{
+  # local_exceptions = [],
+}
+"#;
+    let local_path = config_dir.join("local.ncl");
+    if let Err(e) = std::fs::write(&local_path, local_content) {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Failed to write local.ncl: {}", e),
+        )
+        .report(&OutputFormat::Text);
+    }
+
+    println!("Initialized Conative configuration in .conative/");
+    println!("  .conative/policy.ncl  - Main policy configuration");
+    println!("  .conative/local.ncl   - Local overrides (add to .gitignore)");
+    println!();
+    println!("To revert: rm -rf .conative/");
+
+    0
+}
+
+fn generate_completions(shell: clap_complete::Shell) {
+    clap_complete::generate(
+        shell,
+        &mut Cli::command(),
+        "conative",
+        &mut std::io::stdout(),
+    );
+}
+
+fn generate_man_page() {
+    let man = clap_mangen::Man::new(Cli::command());
+    let mut buffer: Vec<u8> = Vec::new();
+    if let Err(e) = man.render(&mut buffer) {
+        let code = CliError::new(
+            ErrorKind::Internal,
+            format!("Failed to generate man page: {}", e),
+        )
+        .report(&OutputFormat::Text);
+        std::process::exit(code);
+    }
+    print!("{}", String::from_utf8_lossy(&buffer));
+}
+
+/// Load the active policy: RSR defaults, a JSON export, or a Nickel file
+/// (evaluated through `nickel export`), with its rule packs merged in.
+fn load_policy(path: Option<&Path>) -> Result<Policy, String> {
+    let Some(path) = path else {
+        return Ok(Policy::rsr_default());
+    };
+
+    let json = if path.extension().map(|e| e == "ncl").unwrap_or(false) {
+        let output = std::process::Command::new("nickel")
+            .args(["export", "--format", "json"])
+            .arg(path)
+            .output()
+            .map_err(|e| format!("failed to run nickel: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        String::from_utf8(output.stdout).map_err(|e| e.to_string())?
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?
+    };
+
+    let policy = serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    resolve_policy(policy)
+}
+
+/// Include the policy's rule packs, fetching any missing from its registry,
+/// and refuse codes its rules may not report
+fn resolve_policy(mut policy: Policy) -> Result<Policy, String> {
+    for pack in rulepacks::fetch_rulepacks(&policy.rulepacks)? {
+        tracing::debug!("Fetched rule pack {}", pack);
+    }
+    for pack in policy.resolve_rulepacks().map_err(|e| e.to_string())? {
+        tracing::debug!(
+            "Included rule pack {}@{} ({})",
+            pack.name,
+            pack.version,
+            pack.sha256
+        );
+    }
+    match policy_code_errors(&policy).as_slice() {
+        [] => Ok(policy),
+        errors => Err(errors.join("; ")),
+    }
+}
+
+/// Policy for this run: `CONATIVE_POLICY` under --config-from-env when no
+/// policy file is named, else the policy file or the RSR default
+/// The policy the flags select, with their SLM overrides applied
+fn load_configured_policy(cli: &Cli) -> Result<Policy, String> {
+    let mut policy = load_cli_policy(cli)?;
+    apply_slm_overrides(&mut policy, cli)?;
+    Ok(policy)
+}
+
+fn load_cli_policy(cli: &Cli) -> Result<Policy, String> {
+    if cli.config_from_env && cli.policy_file.is_none() {
+        if let Ok(json) = std::env::var("CONATIVE_POLICY") {
+            let policy =
+                serde_json::from_str(&json).map_err(|e| format!("CONATIVE_POLICY: {}", e))?;
+            return resolve_policy(policy);
+        }
+    }
+    load_policy(cli.policy_file.as_deref())
+}
+
+fn eval_admission_review(runner: &ContractRunner, content: &str, format: &OutputFormat) -> i32 {
+    let review = match serde_json::from_str::<AdmissionReview>(content) {
+        Ok(r) => r,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to parse AdmissionReview: {}", e),
+            )
+            .report(format);
+        }
+    };
+    let reply = match runner.evaluate_admission(&review) {
+        Ok(r) => r,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error evaluating AdmissionReview: {}", e),
+            )
+            .report(format);
+        }
+    };
+    let response = reply
+        .response
+        .as_ref()
+        .expect("invariant: evaluate_admission always sets a response");
+
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&reply)
+                    .expect("invariant: JSON serialization of struct cannot fail")
+            );
+        }
+        OutputFormat::Compact => {
+            let message = response
+                .status
+                .as_ref()
+                .map(|s| s.message.as_str())
+                .unwrap_or_default();
+            println!(
+                "allowed={} uid={} {}",
+                response.allowed, response.uid, message
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Admission Review ===\n");
+            println!("UID:     {}", response.uid);
+            println!("Allowed: {}", response.allowed);
+            if let Some(status) = &response.status {
+                println!("Status:  {} {}", status.code, status.message);
+            }
+            for warning in &response.warnings {
+                println!("Warning: {}", warning);
+            }
+        }
+    }
+
+    if response.allowed {
+        0
+    } else {
+        1
+    }
+}
+
+/// Apply `--slm-*` flags on top of the policy's SLM backend setting
+fn apply_slm_overrides(policy: &mut Policy, cli: &Cli) -> Result<(), String> {
+    if cli.no_slm_cache {
+        policy.enforcement.slm_cache = None;
+    }
+    if let Some(kind) = cli.slm_backend {
+        policy.enforcement.slm_backend = Some(slm_backend_from_flags(kind, cli)?);
+    }
+
+    if let Some(SlmBackendConfig::LlamaCpp { runtime, .. }) = &mut policy.enforcement.slm_backend {
+        if let Some(accelerator) = cli.slm_accelerator {
+            runtime.accelerator = match accelerator {
+                AcceleratorArg::Auto => AcceleratorConfig::Auto,
+                AcceleratorArg::Cpu => AcceleratorConfig::Cpu,
+                AcceleratorArg::Cuda => AcceleratorConfig::Cuda,
+                AcceleratorArg::Metal => AcceleratorConfig::Metal,
+                AcceleratorArg::Vulkan => AcceleratorConfig::Vulkan,
+            };
+        }
+        runtime.n_gpu_layers = cli.slm_gpu_layers.or(runtime.n_gpu_layers);
+        runtime.threads = cli.slm_threads.or(runtime.threads);
+        runtime.context_size = cli.slm_ctx_size.or(runtime.context_size);
+    }
+    Ok(())
+}
+
+fn slm_backend_from_flags(kind: SlmBackendKind, cli: &Cli) -> Result<SlmBackendConfig, String> {
+    let model = || {
+        cli.slm_model
+            .clone()
+            .ok_or_else(|| "--slm-model is required for this SLM backend".to_string())
+    };
+
+    Ok(match kind {
+        SlmBackendKind::Placeholder => SlmBackendConfig::Placeholder,
+        SlmBackendKind::Mock => SlmBackendConfig::Mock { score: 0.0 },
+        SlmBackendKind::LlamaCpp => SlmBackendConfig::LlamaCpp {
+            model_path: model()?,
+            binary: None,
+            sha256: None,
+            runtime: Default::default(),
+        },
+        SlmBackendKind::Openai => SlmBackendConfig::OpenAi {
+            endpoint: cli
+                .slm_endpoint
+                .clone()
+                .ok_or_else(|| "--slm-endpoint is required for the openai backend".to_string())?,
+            model: model()?,
+            api_key_env: Some("CONATIVE_SLM_API_KEY".to_string()),
+        },
+        SlmBackendKind::Classifier => SlmBackendConfig::Classifier {
+            model_dir: model()?,
+        },
+    })
+}
+
+/// Evaluator for commands that always exercise the spirit stage
+fn slm_evaluator(policy: &Policy) -> SlmEvaluator {
+    match &policy.enforcement.slm_backend {
+        Some(config) => build_slm_evaluator(config, &policy.enforcement),
+        None => SlmEvaluator::new(),
+    }
+}
+
+// Helper trait for ConcernType
+trait IntoString {
+    fn into_string(self) -> String;
+}
+
+impl IntoString for policy_oracle::ConcernType {
+    fn into_string(self) -> String {
+        match self {
+            policy_oracle::ConcernType::VerbositySmell => "Verbosity smell".to_string(),
+            policy_oracle::ConcernType::PatternDeviation => "Pattern deviation".to_string(),
+            policy_oracle::ConcernType::UnusualStructure => "Unusual structure".to_string(),
+            policy_oracle::ConcernType::Tier2Language { language } => {
+                format!("Tier 2 language: {}", language)
+            }
+            policy_oracle::ConcernType::UnusualEncoding { encoding } => {
+                format!("Unusual encoding: {}", encoding)
+            }
+            policy_oracle::ConcernType::AmbiguousLanguage { language, score } => {
+                format!("Possible {} (score {:.2})", language, score.score)
+            }
+            policy_oracle::ConcernType::SuspiciousObfuscation { fragments, line } => {
+                format!("String split into {} fragments on line {}", fragments, line)
+            }
+        }
+    }
+}
+
+impl IntoString for ViolationType {
+    fn into_string(self) -> String {
+        match self {
+            ViolationType::ForbiddenLanguage { language, .. } => {
+                format!("Forbidden language '{}' detected", language)
+            }
+            ViolationType::ForbiddenToolchain { tool, missing } if missing.is_empty() => {
+                format!("Toolchain violation: {} is forbidden here", tool)
+            }
+            ViolationType::ForbiddenToolchain { tool, missing } => {
+                format!("Toolchain violation: {} requires {}", tool, missing)
+            }
+            ViolationType::SecurityViolation { description } => {
+                format!("Security violation: {}", description)
+            }
+            ViolationType::ForbiddenPattern { pattern, .. } => {
+                format!("Forbidden pattern '{}' detected", pattern)
+            }
+            ViolationType::RequiredPatternRemoved { pattern, .. } => {
+                format!("Required content '{}' was removed", pattern)
+            }
+            ViolationType::OpaqueContent { kind, detail, .. } => {
+                format!("Opaque {} content: {}", kind, detail)
+            }
+            ViolationType::LockfileNotUpdated {
+                manifest,
+                lockfiles,
+            } => {
+                format!("{} changes without {}", manifest, lockfiles.join(" or "))
+            }
+            ViolationType::UnsafeBlock { blocks, .. } => {
+                let lines: Vec<String> = blocks
+                    .iter()
+                    .map(|(start, end)| format!("{}-{}", start, end))
+                    .collect();
+                format!("Unsafe blocks refused on lines {}", lines.join(", "))
+            }
+            ViolationType::ForbiddenImport { rule, imports, .. } => {
+                let modules: Vec<&str> = imports.iter().map(|(m, _)| m.as_str()).collect();
+                format!("Import rule '{}' bans {}", rule, modules.join(", "))
+            }
+        }
+    }
+}
+
+// ============ Contract Runner Functions ============
+
+/// Whether contract runners are deterministic; set once from `--deterministic`
+static DETERMINISTIC: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+fn deterministic() -> bool {
+    DETERMINISTIC.get().copied().unwrap_or(false)
+}
+
+/// Runner for `policy`, deterministic under `--deterministic`
+fn contract_runner(policy: &Policy) -> ContractRunner {
+    let runner = ContractRunner::with_policy(policy.clone());
+    if deterministic() {
+        runner.with_deterministic(0)
+    } else {
+        runner
+    }
+}
+
+fn run_contract_tests(
+    policy: &Policy,
+    path: &Path,
+    format: &OutputFormat,
+    fail_fast: bool,
+    filter: &TestFilter,
+    jobs: usize,
+    verbosity: &Verbosity,
+) -> i32 {
+    let mut harness = TestHarness::with_runner(contract_runner(policy));
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
+        }
+    };
+
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
+    }
+
+    let loaded = test_cases.len();
+    let selected: Vec<TestCase> = test_cases
+        .into_iter()
+        .filter(|t| filter.matches(t))
+        .collect();
+    if selected.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases in {} match the filters", path.display()),
+        )
+        .report(format);
+    }
+    // A shard may be empty when there are more shards than cases
+    let test_cases = filter.shard(selected);
+
+    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+        match filter.shard {
+            Some((i, n)) => eprintln!(
+                "Running {} of {} test cases (shard {}/{})...",
+                test_cases.len(),
+                loaded,
+                i,
+                n
+            ),
+            None => eprintln!("Running {} of {} test cases...", test_cases.len(), loaded),
+        }
+    }
+
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let verbose = matches!(verbosity, Verbosity::Verbose | Verbosity::Debug);
+    let print_result = |result: &TestResult| {
+        let status = match (result.passed, result.quarantined) {
+            (true, _) => "PASS",
+            (false, true) => "XFAIL",
+            (false, false) => "FAIL",
+        };
+        eprintln!("  {} {} ({}μs)", status, result.name, result.duration_us);
+    };
+
+    if jobs > 1 {
+        // Each case gets its own runner; results are reported in file order
+        let results =
+            harness.run_parallel(&test_cases, jobs, fail_fast, || contract_runner(policy));
+        if verbose {
+            results.iter().for_each(print_result);
+        }
+    } else {
+        for test in &test_cases {
+            let result = harness.run_test(test);
+
+            if verbose {
+                print_result(&result);
+            }
+
+            if fail_fast && result.is_hard_failure() {
+                break;
+            }
+        }
+    }
+
+    let summary = harness.summary();
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::ContractTest, &summary);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "tests={} passed={} failed={} quarantined={} duration={}μs",
+                summary.total,
+                summary.passed,
+                summary.failed,
+                summary.quarantined,
+                summary.total_duration_us
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Contract Test Results ===\n");
+            println!("Total:   {}", summary.total);
+            println!("Passed:  {}", summary.passed);
+            println!("Failed:  {}", summary.failed);
+            if summary.quarantined > 0 {
+                println!("Quarantined: {}", summary.quarantined);
+            }
+            println!("Duration: {}μs\n", summary.total_duration_us);
+
+            let quarantined = summary.quarantined_tests();
+            if !quarantined.is_empty() {
+                println!("Quarantined failures (not failing the run):");
+                for name in quarantined {
+                    println!("  - {}", name);
+                }
+                println!();
+            }
+            let released = summary.unexpected_passes();
+            if !released.is_empty() {
+                println!("Quarantined tests now passing (remove the quarantine):");
+                for name in released {
+                    println!("  - {}", name);
+                }
+                println!();
+            }
+
+            if !summary.all_passed() {
+                println!("Failed tests:");
+                for name in summary.failed_tests() {
+                    println!("  - {}", name);
+                }
+
+                // Show details of failures
+                for result in &summary.results {
+                    if result.is_hard_failure() {
+                        println!("\n  {} ERROR:", result.name);
+                        if let Some(err) = &result.error {
+                            println!("    {}", err);
+                        }
+                    }
+                }
+            } else {
+                println!("All tests passed!");
+            }
+        }
+    }
+
+    if summary.all_passed() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Load test cases from a file or directory
+fn load_test_cases(path: &Path, verbosity: &Verbosity) -> Result<Vec<TestCase>, String> {
+    let mut cases = Vec::new();
+
+    if path.is_file() {
+        cases.push(load_test_case_file(path)?);
+    } else if path.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let entry_path = entry.path();
+
+            if entry_path.is_dir() {
+                // Recurse into subdirectories
+                cases.extend(load_test_cases(&entry_path, verbosity)?);
+            } else if entry_path.extension().map(|s| s == "json").unwrap_or(false) {
+                match load_test_case_file(&entry_path) {
+                    Ok(case) => cases.push(case),
+                    Err(e) => {
+                        if matches!(verbosity, Verbosity::Debug) {
+                            eprintln!("Skipping {}: {}", entry_path.display(), e);
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+
+    Ok(cases)
+}
+
+/// Load a single test case from a training data JSON file
+fn load_test_case_file(path: &Path) -> Result<TestCase, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    // Parse the training data format
+    #[derive(serde::Deserialize)]
+    #[allow(dead_code)]
+    struct TrainingData {
+        proposal: Proposal,
+        expected_verdict: String,
+        #[serde(default)]
+        reasoning: String,
+        #[serde(default)]
+        category: String,
+        #[serde(default)]
+        violation_type: Option<String>,
+        #[serde(default)]
+        concern_type: Option<String>,
+        #[serde(default)]
+        spirit_violation: bool,
+        #[serde(default)]
+        expected_code: Option<u16>,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default, alias = "expected_failure")]
+        quarantined: bool,
+    }
+
+    let data: TrainingData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let expected_verdict = match data.expected_verdict.as_str() {
+        "Compliant" => Verdict::Allow,
+        "HardViolation" => Verdict::Block,
+        "SoftConcern" => Verdict::Warn,
+        other => return Err(format!("Unknown verdict: {}", other)),
+    };
+
+    // Map the expected category based on violation_type, concern_type, or category
+    let expected_category = if data.spirit_violation {
+        // Spirit violations require SLM - these will fail until SLM is implemented
+        Some(gating_contract::RefusalCategory::VerbositySmell)
+    } else if let Some(ref vtype) = data.violation_type {
+        match vtype.as_str() {
+            "ForbiddenLanguage" => Some(gating_contract::RefusalCategory::ForbiddenLanguage),
+            "ForbiddenToolchain" => Some(gating_contract::RefusalCategory::ForbiddenToolchain),
+            "SecurityViolation" => Some(gating_contract::RefusalCategory::SecurityViolation),
+            "ForbiddenPattern" => Some(gating_contract::RefusalCategory::ForbiddenPattern),
+            _ => None,
+        }
+    } else if let Some(ref ctype) = data.concern_type {
+        match ctype.as_str() {
+            "VerbositySmell" => Some(gating_contract::RefusalCategory::VerbositySmell),
+            "PatternDeviation" | "UnusualStructure" => {
+                Some(gating_contract::RefusalCategory::StructuralAnomaly)
+            }
+            _ => None,
+        }
+    } else {
+        match data.category.as_str() {
+            "language" => {
+                if data.expected_verdict == "HardViolation" {
+                    Some(gating_contract::RefusalCategory::ForbiddenLanguage)
+                } else {
+                    None
+                }
+            }
+            "toolchain" => Some(gating_contract::RefusalCategory::ForbiddenToolchain),
+            "pattern" | "security" => Some(gating_contract::RefusalCategory::ForbiddenPattern),
+            "spirit" => Some(gating_contract::RefusalCategory::VerbositySmell),
+            _ => None,
+        }
+    };
+
+    Ok(TestCase {
+        name: path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        description: data.reasoning,
+        request: GatingRequest::new(data.proposal),
+        expected_verdict,
+        expected_category,
+        expected_code: parse_expected_code(data.expected_code)?,
+        category: data.category,
+        tags: data.tags,
+        quarantined: data.quarantined,
+    })
+}
+
+/// Map a numeric `expected_code` from training JSON onto the refusal taxonomy
+fn parse_expected_code(code: Option<u16>) -> Result<Option<RefusalCode>, String> {
+    match code {
+        Some(n) => RefusalCode::from_numeric(n)
+            .map(Some)
+            .ok_or_else(|| format!("Unknown refusal code: {}", n)),
+        None => Ok(None),
+    }
+}
+
+fn eval_contract_request(
+    policy: &Policy,
+    policy_path: Option<&Path>,
+    request_path: &Path,
+    format: &OutputFormat,
+    records: &EvalRecords,
+    record: Option<(&Path, f64)>,
+) -> i32 {
+    let content = match read_text_file(request_path) {
+        Ok(c) => c.text,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Failed to read request file: {}", e),
+            )
+            .report(format);
+        }
+    };
+
+    let mut runner = contract_runner(policy)
+        .with_explain(records.explain)
+        .with_profile(records.profile_rules);
+    if let Some(path) = policy_path {
+        runner = runner.with_policy_path(path.to_string_lossy());
+    }
+
+    // An AdmissionReview is answered in kind
+    let value = serde_json::from_str::<serde_json::Value>(&content).unwrap_or_default();
+    if value["kind"] == "AdmissionReview" {
+        return eval_admission_review(&runner, &content, format);
+    }
+
+    // A request carrying `proposals` is a multi-file set judged as one
+    let is_set = value.get("proposals").is_some();
+    let parsed = if is_set {
+        serde_json::from_str::<GatingSetRequest>(&content).map(|set| {
+            let subjects = set.proposals.files.clone().into_iter().collect();
+            (
+                set.summary_request(),
+                runner.evaluate_set(&set),
+                subjects,
+                set.file_requests(),
+            )
+        })
+    } else {
+        serde_json::from_str::<GatingRequest>(&content).map(|request| {
+            let decision = runner.evaluate(&request);
+            let subjects = request
+                .proposal
+                .files_affected
+                .iter()
+                .map(|file| (file.clone(), request.proposal.content.clone()))
+                .collect();
+            (request.clone(), decision, subjects, vec![request])
+        })
+    };
+    let (request, decision, subjects, file_requests): (_, _, Vec<(String, String)>, _) =
+        match parsed {
+            Ok(r) => r,
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Parse,
+                    format!("Failed to parse request JSON: {}", e),
+                )
+                .report(format);
+            }
+        };
+    let decision = match decision {
+        Ok(d) => d,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Internal,
+                format!("Error evaluating request: {}", e),
+            )
+            .report(format);
+        }
+    };
+    let audit_entry = || {
+        let entry = runner.audit(&request, &decision);
+        if records.audit_content {
+            entry.with_proposal(&request.proposal)
+        } else {
+            entry
+        }
+    };
+
+    let seed = match records
+        .attest_key
+        .as_deref()
+        .map(read_attest_key)
+        .transpose()
+    {
+        Ok(s) => s,
+        Err(e) => return CliError::new(ErrorKind::Input, e).report(format),
+    };
+    let write_json = |path: &Path, json: String| {
+        std::fs::write(path, json + "\n").map_err(|e| {
+            CliError::new(
+                ErrorKind::Output,
+                format!("Error writing {}: {}", path.display(), e),
+            )
+            .report(format)
+        })
+    };
+
+    let mut recorded = Vec::new();
+    if let Some((dir, sample)) = record {
+        recorded = record_requests(&runner, &file_requests, dir, sample);
+    }
+
+    let mut attested = None;
+    if let (Some(path), Some(attestation)) = (&records.attest, runner.attest(&request, &decision)) {
+        let attestation = match &seed {
+            Some(seed) => attestation.sign(seed),
+            None => attestation,
+        };
+        let json = serde_json::to_string_pretty(&attestation)
+            .expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(code) = write_json(path, json) {
+            return code;
+        }
+        attested = Some(path);
+    }
+    if let Some(path) = &records.in_toto {
+        let statement = runner.in_toto(
+            subjects.iter().map(|(f, c)| (f.as_str(), c.as_str())),
+            &decision,
+        );
+        let json = match &seed {
+            Some(seed) => serde_json::to_string_pretty(&statement.sign(seed)),
+            None => serde_json::to_string_pretty(&statement),
+        };
+        let json = json.expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(code) = write_json(path, json) {
+            return code;
+        }
+    }
+    let mut logged = None;
+    if let Some(path) = &records.audit_log {
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(ContractError::from)
+            .and_then(|file| runner.audit_log(file).write(audit_entry()));
+        match written {
+            Ok(kept) => logged = Some((path, kept)),
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Output,
+                    format!("Error writing {}: {}", path.display(), e),
+                )
+                .report(format);
+            }
+        }
+    }
+
+    match format {
+        OutputFormat::Json => {
+            // The audit entry sits beside the decision's fields
+            #[derive(serde::Serialize)]
+            struct Output<'a> {
+                #[serde(flatten)]
+                decision: &'a gating_contract::GatingDecision,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                audit: Option<AuditEntry>,
+            }
+            let audit = records.audit.then(&audit_entry);
+            print_json(
+                OutputSchema::Decision,
+                &Output {
+                    decision: &decision,
+                    audit,
+                },
+            );
+        }
+        OutputFormat::Compact => {
+            let refusal_code = decision
+                .refusal
+                .as_ref()
+                .map(|r| r.code.numeric())
+                .unwrap_or(0);
+            println!(
+                "verdict={:?} code={} duration={}μs{}",
+                decision.verdict,
+                refusal_code,
+                decision.processing.duration_us,
+                if decision.processing.timed_out {
+                    " timed_out=true"
+                } else {
+                    ""
+                }
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Gating Decision ===\n");
+            println!("Request ID:  {}", decision.request_id);
+            println!("Decision ID: {}", decision.decision_id);
+            println!("Verdict:     {:?}", decision.verdict);
+            println!("Duration:    {}μs", decision.processing.duration_us);
+            if decision.processing.timed_out {
+                println!(
+                    "Timed out:   yes ({} rule groups checked)",
+                    decision.processing.rules_checked
+                );
+            }
+            if let Some(ref escalation) = decision.escalation {
+                println!("Escalation:  {}", escalation);
+            }
+
+            if let Some(ref refusal) = decision.refusal {
+                println!("\nRefusal Details:");
+                println!("  Category: {}", refusal.category.display_name());
+                println!("  Code:     {}", refusal.code.numeric());
+                println!("  Message:  {}", refusal.message);
+                if let Some(ref remediation) = refusal.remediation {
+                    println!("  Fix:      {}", remediation);
+                    for (i, step) in remediation.steps.iter().enumerate() {
+                        println!("    {}. {}", i + 1, step);
+                    }
+                    if let Some(ref language) = remediation.alternative_language {
+                        println!("  Port to:  {}", language);
+                    }
+                    if let Some(ref url) = remediation.docs_url {
+                        println!("  Docs:     {}", url);
+                    }
+                }
+                for fix in &refusal.fixes {
+                    println!("  Quick fix: {} ({})", fix.title, fix.file);
+                }
+            }
+
+            if !decision.files.is_empty() {
+                println!("\nFiles:");
+                for file in &decision.files {
+                    match &file.refusal_code {
+                        Some(code) => {
+                            println!("  {:?} {} ({})", file.verdict, file.path, code.numeric())
+                        }
+                        None => println!("  {:?} {}", file.verdict, file.path),
+                    }
+                }
+            }
+
+            let rules = decision.evaluations.oracle.iter().flat_map(|o| &o.trace);
+            print_rule_trace(rules.chain(&decision.trace));
+            if !decision.processing.rule_timings.is_empty() {
+                println!("\nRule Timings:");
+                for (rule, us) in &decision.processing.rule_timings {
+                    println!("  {:>8}μs  {}", us, rule);
+                }
+            }
+
+            if let Some(path) = attested {
+                println!("\nAttestation: {}", path.display());
+            }
+            if let Some(path) = &records.in_toto {
+                println!("in-toto statement: {}", path.display());
+            }
+            match logged {
+                Some((path, true)) => println!("Audit log: {}", path.display()),
+                Some((path, false)) => println!("Audit log: {} (not sampled)", path.display()),
+                None => {}
+            }
+            for path in &recorded {
+                println!("Recorded: {}", path.display());
+            }
+
+            if records.audit {
+                let audit = audit_entry();
+                println!("\nAudit Log Entry:");
+                println!("{}", serde_json::to_string_pretty(&audit).expect("invariant: JSON serialization of struct cannot fail"));
+            }
+        }
+    }
+
+    decision.verdict.exit_code()
+}
+
+/// Record sampled requests as training files; failures are logged, never
+/// allowed to change the gating result
+fn record_requests(
+    runner: &ContractRunner,
+    requests: &[GatingRequest],
+    dir: &Path,
+    sample: f64,
+) -> Vec<PathBuf> {
+    let mut written = Vec::new();
+    for request in requests {
+        let example = match runner.record(request, sample) {
+            Ok(RecordOutcome::Recorded { example }) => example,
+            Ok(outcome) => {
+                tracing::debug!("Not recording {}: {:?}", request.request_id, outcome);
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Recording {} failed: {}", request.request_id, e);
+                continue;
+            }
+        };
+        let file = dir.join(format!("recorded_{}.json", example.audit_id));
+        let json = serde_json::to_string_pretty(&example)
+            .expect("invariant: JSON serialization of struct cannot fail");
+        match std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&file, json + "\n")) {
+            Ok(()) => written.push(file),
+            Err(e) => tracing::warn!("Error writing {}: {}", file.display(), e),
+        }
+    }
+    written
+}
+
+/// Read a 32-byte Ed25519 seed stored as hex
+fn read_attest_key(path: &Path) -> Result<[u8; 32], String> {
+    let hex = std::fs::read_to_string(path)
+        .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+    let hex = hex.trim();
+    let bytes: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect();
+    bytes.and_then(|b| b.try_into().ok()).ok_or_else(|| {
+        format!(
+            "{}: expected 64 hex characters (a 32-byte Ed25519 seed)",
+            path.display()
+        )
+    })
+}
+
+fn verify_attestation(path: &Path, public_key: Option<&str>, format: &OutputFormat) -> i32 {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error reading attestation {}: {}", path.display(), e),
+            )
+            .report(format);
+        }
+    };
+    if let Ok(envelope) = serde_json::from_str::<DsseEnvelope>(&content) {
+        return verify_envelope(&envelope, public_key, format);
+    }
+
+    let attestation = match serde_json::from_str::<Attestation>(&content) {
+        Ok(a) => a,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error reading attestation {}: {}", path.display(), e),
+            )
+            .report(format);
+        }
+    };
+
+    let result = attestation.verify(public_key);
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "valid": result.is_ok(),
+                "error": result.as_ref().err().map(|e| e.to_string()),
+                "attestation": attestation,
+            });
+            print_json(OutputSchema::Verify, &report);
+        }
+        OutputFormat::Compact => match &result {
+            Ok(()) => println!(
+                "VALID {} {}",
+                attestation.decision_id, attestation.content_hash
+            ),
+            Err(e) => println!("INVALID {}", e),
+        },
+        OutputFormat::Text => {
+            match &result {
+                Ok(()) => println!("Attestation is valid"),
+                Err(e) => println!("Attestation is NOT valid: {}", e),
+            }
+            println!(
+                "  Decision: {} ({:?})",
+                attestation.decision_id, attestation.verdict
+            );
+            println!(
+                "  Policy:   {} {}",
+                attestation.policy_name, attestation.policy_fingerprint
+            );
+            println!("  Files:    {}", attestation.files.join(", "));
+            println!("  Content:  {}", attestation.content_hash);
+            if let Some(signature) = &attestation.signature {
+                println!("  Signer:   {}", signature.public_key);
+            }
+        }
+    }
+
+    if result.is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+fn verify_envelope(
+    envelope: &DsseEnvelope,
+    public_key: Option<&str>,
+    format: &OutputFormat,
+) -> i32 {
+    let result = envelope.verify(public_key);
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "valid": result.is_ok(),
+                "error": result.as_ref().err().map(|e| e.to_string()),
+                "statement": result.as_ref().ok(),
+            });
+            print_json(OutputSchema::Verify, &report);
+        }
+        OutputFormat::Compact => match &result {
+            Ok(s) => println!(
+                "VALID {} {:?}",
+                s.predicate.decision_id, s.predicate.verdict
+            ),
+            Err(e) => println!("INVALID {}", e),
+        },
+        OutputFormat::Text => match &result {
+            Ok(statement) => {
+                println!("in-toto statement is valid");
+                println!(
+                    "  Decision: {} ({:?})",
+                    statement.predicate.decision_id, statement.predicate.verdict
+                );
+                println!(
+                    "  Policy:   {} {}",
+                    statement.predicate.policy_name, statement.predicate.policy_fingerprint
+                );
+                for subject in &statement.subject {
+                    let digest = subject
+                        .digest
+                        .get("sha256")
+                        .map(String::as_str)
+                        .unwrap_or("");
+                    println!("  Subject:  {} sha256:{}", subject.name, digest);
+                }
+            }
+            Err(e) => println!("in-toto statement is NOT valid: {}", e),
+        },
+    }
+
+    if result.is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+fn show_contract_schema(format: &OutputFormat, section: Option<&str>) {
+    match format {
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Schema {
+                version: &'static str,
+                schema: &'static str,
+                inputs: InputSchema,
+                outputs: OutputSchema,
+                refusal_codes: Vec<RefusalCodeInfo>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct InputSchema {
+                gating_request: Vec<&'static str>,
+                gating_set_request: Vec<&'static str>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct OutputSchema {
+                gating_decision: Vec<&'static str>,
+                verdicts: Vec<&'static str>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct RefusalCodeInfo {
+                code: u16,
+                name: &'static str,
+                category: &'static str,
+            }
+
+            let schema = Schema {
+                version: gating_contract::CONTRACT_VERSION,
+                schema: gating_contract::CONTRACT_SCHEMA,
+                inputs: InputSchema {
+                    gating_request: vec![
+                        "request_id: UUID",
+                        "timestamp: DateTime<Utc>",
+                        "proposal: Proposal",
+                        "context: RequestContext",
+                        "policy_override: Option<Policy>",
+                    ],
+                    gating_set_request: vec![
+                        "request_id: UUID",
+                        "timestamp: DateTime<Utc>",
+                        "proposals: ProposalSet",
+                        "context: RequestContext",
+                        "policy_override: Option<Policy>",
+                    ],
+                },
+                outputs: OutputSchema {
+                    gating_decision: vec![
+                        "request_id: UUID",
+                        "decision_id: UUID",
+                        "timestamp: DateTime<Utc>",
+                        "verdict: Verdict",
+                        "refusal: Option<Refusal>",
+                        "evaluations: EvaluationChain",
+                        "processing: ProcessingMetadata",
+                        "files: Vec<FileVerdict>",
+                        "trace: Vec<RuleTrace>",
+                    ],
+                    verdicts: vec!["Allow", "Warn", "Escalate", "Block"],
+                },
+                refusal_codes: vec![
+                    RefusalCodeInfo {
+                        code: 100,
+                        name: "Lang100TypeScript",
+                        category: "ForbiddenLanguage",
+                    },
+                    RefusalCodeInfo {
+                        code: 101,
+                        name: "Lang101Python",
+                        category: "ForbiddenLanguage",
+                    },
+                    RefusalCodeInfo {
+                        code: 102,
+                        name: "Lang102Go",
+                        category: "ForbiddenLanguage",
+                    },
+                    RefusalCodeInfo {
+                        code: 103,
+                        name: "Lang103Java",
+                        category: "ForbiddenLanguage",
+                    },
+                    RefusalCodeInfo {
+                        code: 200,
+                        name: "Tool200NpmWithoutDeno",
+                        category: "ForbiddenToolchain",
+                    },
+                    RefusalCodeInfo {
+                        code: 300,
+                        name: "Sec300HardcodedSecret",
+                        category: "SecurityViolation",
+                    },
+                    RefusalCodeInfo {
+                        code: 500,
+                        name: "Spirit500Verbosity",
+                        category: "VerbositySmell",
+                    },
+                ],
+            };
+
+            println!("{}", serde_json::to_string_pretty(&schema).expect("invariant: JSON serialization of struct cannot fail"));
+        }
+        OutputFormat::Compact | OutputFormat::Text => {
+            let show_all = section.is_none();
+            let section = section.unwrap_or("");
+
+            println!("=== Gating Contract Schema ===\n");
+            println!("Version: {}", gating_contract::CONTRACT_VERSION);
+            println!("Schema:  {}", gating_contract::CONTRACT_SCHEMA);
+
+            if show_all || section == "inputs" {
+                println!("\n--- INPUTS ---\n");
+                println!("GatingRequest:");
+                println!("  request_id:      UUID (unique request identifier)");
+                println!("  timestamp:       DateTime<Utc> (when request was created)");
+                println!("  proposal:        Proposal (action_type, content, files_affected)");
+                println!("  context:         RequestContext (source, session, repository)");
+                println!("  policy_override: Option<Policy> (custom policy if needed)");
+                println!("\nGatingSetRequest (multi-file, one verdict):");
+                println!(
+                    "  proposals:       ProposalSet (id, files: path -> content, llm_confidence)"
+                );
+                println!("  (other fields as GatingRequest)");
+            }
+
+            if show_all || section == "outputs" {
+                println!("\n--- OUTPUTS ---\n");
+                println!("GatingDecision:");
+                println!("  request_id:  UUID (correlation with request)");
+                println!("  decision_id: UUID (unique decision identifier)");
+                println!("  timestamp:   DateTime<Utc> (when decision was made)");
+                println!("  verdict:     Verdict (Allow | Warn | Escalate | Block)");
+                println!("  refusal:     Option<Refusal> (details if not allowed)");
+                println!("  evaluations: EvaluationChain (oracle, slm, arbiter results)");
+                println!("  processing:  ProcessingMetadata (duration, rules checked, --profile-rules timings)");
+                println!("  files:       Vec<FileVerdict> (per-file verdicts for proposal sets)");
+                println!("  trace:       Vec<RuleTrace> (pipeline stages, with --explain)");
+                println!("\nVerdicts:");
+                println!("  Allow    (0) - Proposal proceeds");
+                println!("  Warn     (2) - Proceed with warning");
+                println!("  Escalate (3) - Requires human review");
+                println!("  Block    (1) - Proposal rejected");
+            }
+
+            if show_all || section == "refusals" {
+                println!("\n--- REFUSAL TAXONOMY ---\n");
+                println!("Hard Policy Violations (Oracle):");
+                println!("  100-199  ForbiddenLanguage   (TypeScript, Python, Go, Java...)");
+                println!("  200-299  ForbiddenToolchain  (npm without deno, yarn...)");
+                println!("  300-399  SecurityViolation   (hardcoded secrets, insecure hash...)");
+                println!("  400-499  ForbiddenPattern    (forbidden imports, unsafe blocks...)");
+                println!("\nSpirit Violations (SLM):");
+                println!("  500-599  SpiritViolation     (verbosity, over-documentation...)");
+                println!("\nAdversarial Input:");
+                println!("  600-699  AdversarialInput    (prompt injection, policy tampering...)");
+                println!("\nSystem Codes:");
+                println!("  900-999  SystemError         (invalid request, rate limited...)");
+            }
+
+            if show_all || section == "audit" {
+                println!("\n--- AUDIT LOG FORMAT ---\n");
+                println!("AuditEntry:");
+                println!("  schema:           String (contract schema identifier)");
+                println!("  audit_id:         UUID");
+                println!("  request_id:       UUID");
+                println!("  decision_id:      UUID");
+                println!("  timestamp:        DateTime<Utc>");
+                println!("  verdict:          Verdict");
+                println!("  refusal_code:     Option<u16>");
+                println!("  refusal_category: Option<RefusalCategory>");
+                println!("  source:           String");
+                println!("  repository:       Option<String>");
+                println!("  session_id:       Option<String>");
+                println!("  rules_checked:    Vec<String>");
+                println!("  rules_triggered:  Vec<String>");
+                println!("  duration_us:      u64");
+                println!("  contract_version: String");
+                println!("  content_hash:     String (SHA for verification)");
+            }
+        }
+    }
+}
+
+// ============ Red-Team Test Functions ============
+
+fn run_redteam_tests(
+    policy: &Policy,
+    path: &Path,
+    format: &OutputFormat,
+    run: &RedteamRun,
+    verbosity: &Verbosity,
+) -> i32 {
+    let RedteamRun {
+        verbose,
+        baseline: baseline_path,
+        save_baseline,
+        ..
+    } = run;
+    let (verbose, baseline_path) = (*verbose, baseline_path.as_deref());
+    let mut harness = TestHarness::with_runner(contract_runner(policy));
+    let mut test_cases = match load_redteam_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error loading red-team tests: {}", e),
+            )
+            .report(format);
+        }
+    };
+    if !run.filter_category.is_empty() {
+        let wanted: Vec<RedTeamCategory> = run
+            .filter_category
+            .iter()
+            .map(|c| RedTeamCategory::from_str(c))
+            .collect();
+        test_cases.retain(|case| wanted.contains(&case.redteam_category));
+    }
+
+    if test_cases.is_empty() {
+        let filtered = match run.filter_category.is_empty() {
+            true => String::new(),
+            false => format!(" for categories {}", run.filter_category.join(", ")),
+        };
+        return CliError::new(
+            ErrorKind::Input,
+            format!(
+                "No red-team test cases found in: {}{}",
+                path.display(),
+                filtered
+            ),
+        )
+        .report(format);
+    }
+
+    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+        eprintln!("Running {} red-team tests...", test_cases.len());
+    }
+
+    let mut outcomes = Vec::new();
+    for test in &test_cases {
+        let result = harness.run_test(&test.base);
+        let outcome = RedTeamOutcome::from_result(test, &result);
+
+        if verbose && matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+            let status = if outcome.is_false_positive() {
+                "FALSE+"
+            } else if outcome.is_bypass() && outcome.known_limitation {
+                "KNOWN"
+            } else if outcome.is_bypass() {
+                "BYPASS"
             } else {
-                check_content(&oracle, file, content, assume_path, &format, &cli.verbosity)
+                "BLOCKED"
+            };
+            eprintln!(
+                "  {} [{:?}] {}",
+                status, outcome.redteam_category, outcome.name
+            );
+        }
+        outcomes.push(outcome);
+    }
+
+    let summary = RedTeamSummary::from_outcomes(&outcomes);
+    let bypasses: Vec<&RedTeamOutcome> = outcomes.iter().filter(|o| o.is_bypass()).collect();
+    let false_positives: Vec<&RedTeamOutcome> =
+        outcomes.iter().filter(|o| o.is_false_positive()).collect();
+
+    if *save_baseline {
+        let target = baseline_path.unwrap_or(Path::new(".conative/redteam-baseline.json"));
+        let mut baseline = RedTeamBaseline::from_outcomes(&outcomes, current_git_commit());
+        if deterministic() {
+            baseline = baseline.with_timestamp(chrono::DateTime::UNIX_EPOCH);
+        }
+        if let Some(parent) = target.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                return CliError::new(
+                    ErrorKind::Output,
+                    format!("Failed to create baseline directory: {}", e),
+                )
+                .report(format);
+            }
+        }
+        let json = baseline
+            .to_json()
+            .expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(e) = std::fs::write(target, json) {
+            return CliError::new(
+                ErrorKind::Output,
+                format!("Failed to write baseline: {}", e),
+            )
+            .report(format);
+        }
+        println!("Red-team baseline saved to: {}", target.display());
+        println!(
+            "Security score: {}/100, {} bypass(es)",
+            baseline.security_score,
+            baseline.bypasses.len()
+        );
+        return 0;
+    }
+
+    let trend = match baseline_path {
+        Some(p) => {
+            let loaded = std::fs::read_to_string(p)
+                .map_err(|e| e.to_string())
+                .and_then(|json| RedTeamBaseline::from_json(&json).map_err(|e| e.to_string()));
+            match loaded {
+                Ok(baseline) => Some(baseline.compare(&outcomes)),
+                Err(e) => {
+                    return CliError::new(
+                        ErrorKind::Input,
+                        format!("Failed to load red-team baseline {}: {}", p.display(), e),
+                    )
+                    .with_hint("Run with --save-baseline to create one")
+                    .report(format);
+                }
+            }
+        }
+        None => None,
+    };
+
+    // Against a baseline only explicit gates apply; without one, ungated
+    // categories may not have any unexpected bypasses
+    let gate_failures = summary.gate_failures(&run.max_bypasses, trend.is_none().then_some(0));
+
+    match format {
+        OutputFormat::Json => {
+            let mut report = serde_json::json!({ "summary": summary });
+            if let Some(trend) = &trend {
+                report["trend"] = serde_json::json!(trend);
+            }
+            if !run.max_bypasses.is_empty() {
+                report["gate_failures"] = serde_json::json!(gate_failures);
+            }
+            print_json(OutputSchema::Redteam, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "redteam total={} blocked={} bypassed={} known={} fps={} score={}",
+                summary.total,
+                summary.blocked,
+                summary.bypassed,
+                summary.known_limitations,
+                summary.false_positives,
+                summary.security_score()
+            );
+            if let Some(trend) = &trend {
+                println!(
+                    "redteam-trend score_delta={:+} new_bypasses={} fixed={} new_fps={}",
+                    trend.score_delta,
+                    trend.new_bypasses.len(),
+                    trend.fixed_bypasses.len(),
+                    trend.new_false_positives.len()
+                );
+            }
+            for failure in &gate_failures {
+                println!(
+                    "redteam-gate category={} bypassed={} max={}",
+                    failure.category, failure.bypassed, failure.max_bypasses
+                );
+            }
+        }
+        OutputFormat::Text => {
+            println!("=== Red-Team Test Results ===\n");
+            println!("Total Tests:     {}", summary.total);
+            println!(
+                "Blocked:         {} ({:.1}%)",
+                summary.blocked,
+                (summary.blocked as f64 / summary.total as f64) * 100.0
+            );
+            println!(
+                "Bypassed:        {} ({:.1}%)",
+                summary.bypassed,
+                summary.bypass_rate * 100.0
+            );
+            println!(
+                "False Positives: {} ({:.1}%)",
+                summary.false_positives,
+                summary.false_positive_rate * 100.0
+            );
+            if summary.known_limitations > 0 {
+                println!(
+                    "Known Limits:    {} (expected bypasses)",
+                    summary.known_limitations
+                );
+            }
+            println!("\nSecurity Score:  {}/100", summary.security_score());
+
+            if !bypasses.is_empty() {
+                println!("\n--- Bypasses ---");
+                for outcome in &bypasses {
+                    let note = if outcome.known_limitation {
+                        " (known limitation)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "  {} [{:?}, {:?}]{}",
+                        outcome.name, outcome.actual_verdict, outcome.bypass_severity, note
+                    );
+                    if verbose {
+                        println!("    Attack: {}", outcome.attack_vector);
+                    }
+                }
+            }
+
+            if !false_positives.is_empty() {
+                println!("\n--- False Positives ---");
+                for outcome in &false_positives {
+                    println!("  {}", outcome.name);
+                    if verbose {
+                        println!("    Attack: {}", outcome.attack_vector);
+                    }
+                }
+            }
+
+            println!("\n--- By Severity ---");
+            let mut severities: Vec<_> = summary.by_severity.iter().collect();
+            severities.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight));
+            for (severity, stats) in severities {
+                println!(
+                    "  {} (x{}): {} total, {} blocked, {} bypassed ({} known)",
+                    severity,
+                    stats.weight,
+                    stats.total,
+                    stats.blocked,
+                    stats.bypassed,
+                    stats.known_limitations
+                );
+            }
+
+            println!("\n--- By Category ---");
+            for (cat, stats) in &summary.by_category {
+                println!(
+                    "  {}: {} total, {} blocked, {} bypassed ({} known), {} fps",
+                    cat,
+                    stats.total,
+                    stats.blocked,
+                    stats.bypassed,
+                    stats.known_limitations,
+                    stats.false_positives
+                );
+            }
+
+            if let Some(trend) = &trend {
+                println!("\n--- Trend vs Baseline ---");
+                if let Some(commit) = &trend.baseline_commit {
+                    println!("  Baseline commit: {}", commit);
+                }
+                println!(
+                    "  Security score:  {} -> {} ({:+})",
+                    trend.baseline_score, trend.current_score, trend.score_delta
+                );
+                println!(
+                    "  Bypass rate:     {:+.1}%",
+                    trend.bypass_rate_delta * 100.0
+                );
+                for name in &trend.new_bypasses {
+                    println!("  NEW BYPASS:      {}", name);
+                }
+                for name in &trend.fixed_bypasses {
+                    println!("  fixed bypass:    {}", name);
+                }
+                for name in &trend.new_false_positives {
+                    println!("  NEW FALSE+:      {}", name);
+                }
+                for name in &trend.fixed_false_positives {
+                    println!("  fixed false+:    {}", name);
+                }
+            }
+
+            if !run.max_bypasses.is_empty() || !gate_failures.is_empty() {
+                println!("\n--- Category Gates ---");
+                for gate in &run.max_bypasses {
+                    println!(
+                        "  {:?}: at most {} unexpected bypasses",
+                        gate.category, gate.max_bypasses
+                    );
+                }
+                for failure in &gate_failures {
+                    println!(
+                        "  FAILED {}: {} unexpected bypasses (max {})",
+                        failure.category, failure.bypassed, failure.max_bypasses
+                    );
+                }
+            }
+        }
+    }
+
+    // Against a baseline, newly introduced bypasses fail the run too
+    let failed = trend.as_ref().is_some_and(|t| t.has_regressions()) || !gate_failures.is_empty();
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+/// Load red-team test cases with metadata
+fn load_redteam_cases(path: &Path, verbosity: &Verbosity) -> Result<Vec<RedTeamTestCase>, String> {
+    let mut cases = Vec::new();
+
+    if path.is_file() {
+        if let Some(case) = load_redteam_file(path)? {
+            cases.push(case);
+        }
+    } else if path.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let entry_path = entry.path();
+
+            if entry_path.is_dir() {
+                cases.extend(load_redteam_cases(&entry_path, verbosity)?);
+            } else if entry_path.extension().map(|s| s == "json").unwrap_or(false) {
+                match load_redteam_file(&entry_path) {
+                    Ok(Some(case)) => cases.push(case),
+                    Ok(None) => {}
+                    Err(e) => {
+                        if matches!(verbosity, Verbosity::Debug) {
+                            eprintln!("Skipping {}: {}", entry_path.display(), e);
+                        }
+                    }
+                }
             }
         }
-        Commands::Policy { format, section } => {
-            show_policy(&format, section.as_deref());
-            0
+    } else {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+
+    Ok(cases)
+}
+
+/// Load a single red-team test case
+fn load_redteam_file(path: &Path) -> Result<Option<RedTeamTestCase>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    #[derive(serde::Deserialize)]
+    struct RedTeamData {
+        proposal: Proposal,
+        expected_verdict: String,
+        #[serde(default)]
+        reasoning: String,
+        #[serde(default)]
+        redteam_category: Option<String>,
+        #[serde(default)]
+        attack_vector: Option<String>,
+        #[serde(default)]
+        expected_code: Option<u16>,
+        #[serde(default)]
+        bypass_severity: Option<Severity>,
+        #[serde(default)]
+        known_limitation: bool,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default, alias = "expected_failure")]
+        quarantined: bool,
+    }
+
+    let data: RedTeamData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    // Skip non-redteam tests
+    let redteam_cat = match &data.redteam_category {
+        Some(c) => RedTeamCategory::from_str(c),
+        None => return Ok(None),
+    };
+
+    let expected_verdict = match data.expected_verdict.as_str() {
+        "Compliant" => Verdict::Allow,
+        "HardViolation" => Verdict::Block,
+        "SoftConcern" => Verdict::Warn,
+        other => return Err(format!("Unknown verdict: {}", other)),
+    };
+
+    let test_case = TestCase {
+        name: path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        description: data.reasoning,
+        request: GatingRequest::new(data.proposal),
+        expected_verdict,
+        expected_category: None,
+        expected_code: parse_expected_code(data.expected_code)?,
+        category: data.redteam_category.clone().unwrap_or_default(),
+        tags: data.tags,
+        quarantined: data.quarantined,
+    };
+
+    Ok(Some(RedTeamTestCase {
+        base: test_case,
+        redteam_category: redteam_cat,
+        attack_vector: data.attack_vector.unwrap_or_default(),
+        bypass_severity: data.bypass_severity.unwrap_or(Severity::High),
+        known_limitation: data.known_limitation,
+    }))
+}
+
+/// Red-team case files under `path`, skipping earlier minimizer output
+fn redteam_case_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
+        let entry_path = entry.map_err(|e| e.to_string())?.path();
+        if entry_path.is_dir() {
+            files.extend(redteam_case_files(&entry_path)?);
+        } else if entry_path.extension().is_some_and(|e| e == "json")
+            && !entry_path.to_string_lossy().ends_with(".min.json")
+        {
+            files.push(entry_path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn run_redteam_minimize(
+    minimizer: &BypassMinimizer,
+    path: &Path,
+    force: bool,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let files = match redteam_case_files(path) {
+        Ok(files) => files,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error loading red-team tests: {}", e),
+            )
+            .report(format);
         }
-        Commands::Validate {
-            proposal,
-            format,
-            strict,
-        } => {
-            if cli.dry_run {
-                println!("[dry-run] Would validate: {}", proposal.display());
-                0
-            } else {
-                validate_proposal(&oracle, &proposal, &format, strict)
+    };
+
+    let mut minimized = Vec::new();
+    let mut skipped = Vec::new();
+    for file in &files {
+        let case = match load_redteam_file(file) {
+            Ok(Some(case)) => case,
+            Ok(None) => continue,
+            Err(e) => {
+                if matches!(verbosity, Verbosity::Debug) {
+                    eprintln!("Skipping {}: {}", file.display(), e);
+                }
+                continue;
             }
+        };
+        let Some(result) = minimizer.minimize(&case) else {
+            continue;
+        };
+
+        let target = file.with_extension("min.json");
+        if target.exists() && !force {
+            skipped.push(target);
+            continue;
         }
-        Commands::Init { force, minimal } => {
-            if cli.dry_run {
-                println!("[dry-run] Would create .conative/ directory");
-                println!("[dry-run] Force: {}, Minimal: {}", force, minimal);
-                0
-            } else {
-                init_config(force, minimal)
+        // Keep the original's expectations and metadata; only the content shrinks
+        let written = std::fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| {
+                serde_json::from_str::<serde_json::Value>(&raw).map_err(|e| e.to_string())
+            })
+            .and_then(|mut case| {
+                case["proposal"]["content"] = serde_json::Value::String(result.content.clone());
+                case["minimized_from"] = serde_json::Value::String(result.name.clone());
+                let json = serde_json::to_string_pretty(&case)
+                    .expect("invariant: JSON serialization of value cannot fail");
+                std::fs::write(&target, json + "\n").map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            return CliError::new(
+                ErrorKind::Output,
+                format!("Error writing {}: {}", target.display(), e),
+            )
+            .report(format);
+        }
+        minimized.push((target, result));
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "minimized": minimized
+                    .iter()
+                    .map(|(file, result)| serde_json::json!({ "file": file, "result": result }))
+                    .collect::<Vec<_>>(),
+                "skipped": skipped,
+            });
+            print_json(OutputSchema::RedteamMinimize, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "redteam-minimize minimized={} skipped={}",
+                minimized.len(),
+                skipped.len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Bypass Minimization ===\n");
+            if minimized.is_empty() && skipped.is_empty() {
+                println!("No bypassing cases found in: {}", path.display());
+            }
+            for (file, result) in &minimized {
+                println!(
+                    "  {}: {} -> {} bytes ({:?}, {} evaluations)",
+                    result.name,
+                    result.original_len,
+                    result.content.len(),
+                    result.verdict,
+                    result.evaluations
+                );
+                println!("    -> {}", file.display());
+            }
+            if !skipped.is_empty() {
+                println!("\nSkipped: {} (already exist; use --force)", skipped.len());
             }
         }
-        Commands::Completions { shell } => {
-            generate_completions(shell);
-            0
+    }
+
+    0
+}
+
+fn run_redteam_suggest(
+    policy: &Policy,
+    suggester: RuleSuggester,
+    path: &Path,
+    benign_paths: &[PathBuf],
+    output: &Path,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let cases = match load_redteam_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Input,
+                format!("Error loading red-team tests: {}", e),
+            )
+            .report(format);
         }
-        Commands::Man => {
-            generate_man_page();
-            0
+    };
+    let mut benign = Vec::new();
+    for benign_path in benign_paths {
+        match load_test_cases(benign_path, verbosity) {
+            Ok(loaded) => {
+                benign.extend(loaded.into_iter().map(|case| case.request.proposal.content))
+            }
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Input,
+                    format!("Error loading benign cases: {}", e),
+                )
+                .report(format);
+            }
         }
-        Commands::Contract { action } => match action {
-            ContractAction::Test {
-                path,
-                format,
-                fail_fast,
-            } => {
-                if cli.dry_run {
+    }
+
+    let mut harness = TestHarness::with_runner(contract_runner(policy));
+    let outcomes: Vec<RedTeamOutcome> = cases
+        .iter()
+        .map(|case| RedTeamOutcome::from_result(case, &harness.run_test(&case.base)))
+        .collect();
+    let mut report = suggester
+        .with_benign(benign)
+        .report(&cases, &outcomes, current_git_commit());
+    if deterministic() {
+        report = report.with_timestamp(chrono::DateTime::UNIX_EPOCH);
+    }
+
+    let json = report
+        .to_json()
+        .expect("invariant: JSON serialization of struct cannot fail");
+    let written = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(output, json.clone() + "\n"));
+    if let Err(e) = written {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Error writing {}: {}", output.display(), e),
+        )
+        .report(format);
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", json),
+        OutputFormat::Compact => {
+            println!(
+                "redteam-suggest bypasses={} suggestions={} report={}",
+                report.bypasses.len(),
+                report.suggestions.len(),
+                output.display()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Rule Suggestions ===\n");
+            if report.bypasses.is_empty() {
+                println!("No bypasses in: {}", path.display());
+            }
+            for bypass in &report.bypasses {
+                let note = if bypass.known_limitation {
+                    " (known limitation)"
+                } else {
+                    ""
+                };
+                println!(
+                    "  {} [{:?}, {:?}]{}",
+                    bypass.name, bypass.actual_verdict, bypass.bypass_severity, note
+                );
+                if bypass.suggested_rules.is_empty() {
+                    println!("    no candidate avoids the benign content");
+                }
+                for name in &bypass.suggested_rules {
+                    let Some(suggestion) = report.suggestions.iter().find(|s| &s.rule.name == name)
+                    else {
+                        continue;
+                    };
                     println!(
-                        "[dry-run] Would run contract tests from: {}",
-                        path.display()
+                        "    {}: /{}/ (from {:?}, matches {} bypass(es))",
+                        name,
+                        suggestion.rule.regex,
+                        suggestion.source,
+                        suggestion.matches.len()
                     );
-                    0
-                } else {
-                    run_contract_tests(&path, &format, fail_fast, &cli.verbosity)
                 }
             }
-            ContractAction::Eval {
-                request,
-                format,
-                audit,
-            } => {
-                if cli.dry_run {
-                    println!("[dry-run] Would evaluate request: {}", request.display());
-                    0
-                } else {
-                    eval_contract_request(&request, &format, audit)
+            println!("\nReport written to: {}", output.display());
+            println!("Review each rule, then merge it into patterns.forbidden_patterns");
+        }
+    }
+
+    0
+}
+
+fn run_redteam_generate(
+    policy: &Policy,
+    seeds: &Path,
+    output: &Path,
+    strategies: Vec<ObfuscationStrategy>,
+    force: bool,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let seed_cases = match load_test_cases(seeds, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading seed cases: {}", e))
+                .report(format);
+        }
+    };
+
+    let mut generator = RedTeamGenerator::new(policy.clone());
+    if !strategies.is_empty() {
+        generator = generator.with_strategies(strategies);
+    }
+    let generated = generator.generate(&seed_cases);
+
+    if generated.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No violating seed cases found in: {}", seeds.display()),
+        )
+        .report(format);
+    }
+
+    if let Err(e) = std::fs::create_dir_all(output) {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Error creating {}: {}", output.display(), e),
+        )
+        .report(format);
+    }
+
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+    for case in &generated {
+        let file = output.join(format!("{}.json", case.name));
+        if file.exists() && !force {
+            skipped.push(file);
+            continue;
+        }
+        let json = serde_json::to_string_pretty(case)
+            .expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(e) = std::fs::write(&file, json + "\n") {
+            return CliError::new(
+                ErrorKind::Output,
+                format!("Error writing {}: {}", file.display(), e),
+            )
+            .report(format);
+        }
+        written.push(file);
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "seeds": seed_cases.len(),
+                "written": written,
+                "skipped": skipped,
+            });
+            print_json(OutputSchema::RedteamGenerate, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "redteam-generate seeds={} written={} skipped={}",
+                seed_cases.len(),
+                written.len(),
+                skipped.len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Red-Team Case Generation ===\n");
+            println!("Seeds:   {}", seed_cases.len());
+            println!("Written: {}", written.len());
+            println!("Skipped: {} (already exist; use --force)", skipped.len());
+            if !matches!(verbosity, Verbosity::Quiet) {
+                for file in &written {
+                    println!("  + {}", file.display());
                 }
             }
-            ContractAction::Schema { format, section } => {
-                show_contract_schema(&format, section.as_deref());
-                0
+        }
+    }
+
+    0
+}
+
+// ============ Regression Test Functions ============
+
+/// Current git commit hash, if run inside a git checkout
+fn current_git_commit() -> Option<String> {
+    std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+}
+
+fn run_regression_tests(
+    policy: &Policy,
+    path: &Path,
+    baseline_path: &Path,
+    save_baseline: bool,
+    format: &OutputFormat,
+    strict: bool,
+    verbosity: &Verbosity,
+) -> i32 {
+    // Run tests first
+    let mut harness = TestHarness::with_runner(contract_runner(policy));
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
+        }
+    };
+
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
+    }
+
+    for test in &test_cases {
+        harness.run_test(test);
+    }
+
+    let summary = harness.summary();
+
+    if save_baseline {
+        // Create directory if needed
+        if let Some(parent) = baseline_path.parent() {
+            if !parent.exists() {
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    return CliError::new(
+                        ErrorKind::Output,
+                        format!("Failed to create baseline directory: {}", e),
+                    )
+                    .report(format);
+                }
             }
-            ContractAction::Redteam {
-                path,
-                format,
-                verbose,
-            } => {
-                if cli.dry_run {
+        }
+
+        let mut baseline =
+            RegressionBaseline::from_summary(&summary, current_git_commit()).with_policy(policy);
+        if deterministic() {
+            baseline = baseline.with_timestamp(chrono::DateTime::UNIX_EPOCH);
+        }
+        match baseline.to_json() {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(baseline_path, &json) {
+                    return CliError::new(
+                        ErrorKind::Output,
+                        format!("Failed to write baseline: {}", e),
+                    )
+                    .report(format);
+                }
+                println!("Baseline saved to: {}", baseline_path.display());
+                println!(
+                    "Tests: {} total, {} passed, {} failed",
+                    summary.total, summary.passed, summary.failed
+                );
+                return 0;
+            }
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Internal,
+                    format!("Failed to serialize baseline: {}", e),
+                )
+                .report(format);
+            }
+        }
+    }
+
+    // Compare against baseline
+    let mut reg_harness = RegressionHarness::new().with_policy(policy);
+    if baseline_path.exists() {
+        if let Err(e) = reg_harness.load_baseline(baseline_path) {
+            return CliError::new(ErrorKind::Input, format!("Failed to load baseline: {}", e))
+                .with_hint("Run with --save to create a new baseline")
+                .report(format);
+        }
+    } else {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No baseline found at: {}", baseline_path.display()),
+        )
+        .with_hint("Run with --save to create a new baseline")
+        .report(format);
+    }
+
+    reg_harness.add_results(summary.results.clone());
+    let report = reg_harness.compare();
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Regression, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "regression compared={} stable={} regressed={} improved={} changed={} new={} removed={}",
+                report.total_compared,
+                report.stable_count,
+                report.regressions.len(),
+                report.improvements.len(),
+                report.behavior_changes.len(),
+                report.new_tests.len(),
+                report.removed_tests.len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Regression Report ===\n");
+            println!("{}", report.summary_text());
+
+            if let Some(ref commit) = report.baseline_commit {
+                println!("\nBaseline commit: {}", commit);
+            }
+            match report.policy_changed() {
+                Some(true) => println!(
+                    "Policy changed since the baseline; verdict changes likely come from it"
+                ),
+                Some(false) => {
+                    println!("Policy unchanged since the baseline; verdict changes come from code")
+                }
+                None => {
+                    println!("Baseline has no policy fingerprint; re-save it to attribute changes")
+                }
+            }
+
+            if !report.regressions.is_empty() {
+                println!("\n--- REGRESSIONS ({}) ---", report.regressions.len());
+                for reg in &report.regressions {
                     println!(
-                        "[dry-run] Would run red-team tests from: {}",
-                        path.display()
+                        "  {} [{:?} -> {:?}] ({})",
+                        reg.test_name, reg.baseline_verdict, reg.current_verdict, reg.likely_cause
+                    );
+                    if let Some(ref err) = reg.error_message {
+                        println!("    Error: {}", err);
+                    }
+                }
+            }
+
+            if !report.improvements.is_empty() {
+                println!("\n--- IMPROVEMENTS ({}) ---", report.improvements.len());
+                for imp in &report.improvements {
+                    println!(
+                        "  {} [{:?} -> {:?}]",
+                        imp.test_name, imp.baseline_verdict, imp.current_verdict
                     );
-                    0
-                } else {
-                    run_redteam_tests(&path, &format, verbose, &cli.verbosity)
                 }
             }
-            ContractAction::Regression {
-                path,
-                baseline,
-                save,
-                format,
-                strict,
-            } => {
-                if cli.dry_run {
-                    println!("[dry-run] Would run regression tests");
+
+            if !report.behavior_changes.is_empty() {
+                println!(
+                    "\n--- BEHAVIOR CHANGES ({}) ---",
+                    report.behavior_changes.len()
+                );
+                for change in &report.behavior_changes {
                     println!(
-                        "[dry-run] Tests: {}, Baseline: {}",
-                        path.display(),
-                        baseline.display()
+                        "  {} [{:?} -> {:?}] ({})",
+                        change.test_name,
+                        change.baseline_verdict,
+                        change.current_verdict,
+                        change.likely_cause
                     );
-                    0
-                } else {
-                    run_regression_tests(&path, &baseline, save, &format, strict, &cli.verbosity)
                 }
             }
-        },
-    };
 
-    std::process::exit(exit_code);
+            if !report.new_tests.is_empty() {
+                println!("\n--- NEW TESTS ({}) ---", report.new_tests.len());
+                for name in &report.new_tests {
+                    println!("  {}", name);
+                }
+            }
+
+            if !report.removed_tests.is_empty() {
+                println!("\n--- REMOVED TESTS ({}) ---", report.removed_tests.len());
+                for name in &report.removed_tests {
+                    println!("  {}", name);
+                }
+            }
+
+            if report.has_regressions() {
+                println!(
+                    "\nWARNING: {} regression(s) detected!",
+                    report.regressions.len()
+                );
+            } else if report.stable_count == report.total_compared {
+                println!("\nAll tests stable.");
+            }
+        }
+    }
+
+    if strict && report.has_regressions() {
+        1
+    } else if report.has_regressions() {
+        2 // Warning exit code
+    } else {
+        0
+    }
 }
 
-fn scan_directory(
-    oracle: &Oracle,
+// ============ Coverage Functions ============
+
+fn run_coverage_report(
+    policy: &Policy,
     path: &Path,
     format: &OutputFormat,
     verbosity: &Verbosity,
 ) -> i32 {
-    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-        eprintln!("Scanning: {}", path.display());
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
+        }
+    };
+
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
     }
 
-    match oracle.scan_directory(path) {
-        Ok(result) => {
-            match format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&result).expect("invariant: JSON serialization of struct cannot fail"));
-                }
-                OutputFormat::Compact => {
-                    let status = if !result.violations.is_empty() {
-                        "VIOLATION"
-                    } else if !result.concerns.is_empty() {
-                        "CONCERN"
-                    } else {
-                        "OK"
-                    };
-                    println!(
-                        "{} {} files={} violations={} concerns={}",
-                        status,
-                        result.path.display(),
-                        result.files_scanned,
-                        result.violations.len(),
-                        result.concerns.len()
-                    );
+    let harness = CoverageHarness::with_runner(contract_runner(policy));
+    let report = harness.run(&test_cases);
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Coverage, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "coverage tests={} codes={:.1}% uncovered_codes={} rules={:.1}% uncovered_rules={}",
+                report.total_tests,
+                report.code_coverage_percent(),
+                report.uncovered_codes().len(),
+                report.rule_coverage_percent(),
+                report.uncovered_rules().len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Contract Coverage Report ===\n");
+            println!("Test cases:     {}", report.total_tests);
+            println!("Code coverage:  {:.1}%", report.code_coverage_percent());
+            println!("Rule coverage:  {:.1}%", report.rule_coverage_percent());
+
+            println!("\n--- Refusal Codes ---");
+            for code in &report.codes {
+                let marker = if code.hits == 0 { "-" } else { "+" };
+                println!(
+                    "  {} {} {:<28} hits={} asserted={}",
+                    marker, code.code, code.name, code.hits, code.asserted
+                );
+            }
+
+            println!("\n--- Policy Rules ---");
+            for rule in &report.rules {
+                let marker = if rule.hits == 0 { "-" } else { "+" };
+                println!("  {} {:<40} hits={}", marker, rule.rule, rule.hits);
+            }
+
+            let uncovered = report.uncovered_codes();
+            if !uncovered.is_empty() {
+                println!("\nUncovered codes ({}):", uncovered.len());
+                for code in uncovered {
+                    println!("  {} {}", code.code, code.name);
                 }
-                OutputFormat::Text => {
-                    print_scan_result(&result);
+            }
+
+            let uncovered = report.uncovered_rules();
+            if !uncovered.is_empty() {
+                println!("\nUncovered rules ({}):", uncovered.len());
+                for rule in uncovered {
+                    println!("  {}", rule.rule);
                 }
             }
 
-            if !result.violations.is_empty() {
-                1 // Hard violation
-            } else if !result.concerns.is_empty() {
-                2 // Soft concern
-            } else {
-                0 // Compliant
+            if !report.errors.is_empty() {
+                println!("\nErrors ({}):", report.errors.len());
+                for err in &report.errors {
+                    println!("  {}", err);
+                }
             }
         }
-        Err(e) => {
-            eprintln!("Error scanning directory: {}", e);
-            3
-        }
     }
+
+    0
 }
 
-fn print_scan_result(result: &DirectoryScanResult) {
-    println!("=== Conative Gating Scan Results ===\n");
-    println!("Path: {}", result.path.display());
-    println!("Files scanned: {}", result.files_scanned);
-    println!("Verdict: {:?}\n", result.verdict);
+// ============ Fuzz Functions ============
+
+fn run_fuzz(policy: &Policy, iterations: usize, seed: Option<u64>, format: &OutputFormat) -> i32 {
+    let seed = seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    });
 
-    if !result.violations.is_empty() {
-        println!("VIOLATIONS ({}):", result.violations.len());
-        for v in &result.violations {
-            println!("  {} - {:?}", v.file.display(), v.violation);
+    let harness = FuzzHarness::with_runner(contract_runner(policy), seed);
+    let report = harness.run(iterations);
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Fuzz, &report);
         }
-        println!();
-    }
+        OutputFormat::Compact => {
+            println!(
+                "fuzz seed={} iterations={} failures={}",
+                report.seed,
+                report.iterations,
+                report.failures.len()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Oracle Fuzz Results ===\n");
+            println!("Seed:       {}", report.seed);
+            println!("Iterations: {}", report.iterations);
+            println!("Failures:   {}", report.failures.len());
+
+            println!("\n--- Verdicts ---");
+            for (verdict, count) in &report.verdicts {
+                println!("  {}: {}", verdict, count);
+            }
 
-    if !result.concerns.is_empty() {
-        println!("CONCERNS ({}):", result.concerns.len());
-        for c in &result.concerns {
-            println!("  {} - {:?}", c.file.display(), c.concern);
+            if !report.failures.is_empty() {
+                println!("\n--- Invariant Failures ---");
+                for failure in &report.failures {
+                    println!(
+                        "  #{} {:?} {} ({} bytes)",
+                        failure.iteration, failure.invariant, failure.path, failure.content_len
+                    );
+                    println!("    {}", failure.detail);
+                    println!("    content: {:?}", failure.content_excerpt);
+                }
+                println!(
+                    "\nReproduce with: conative contract fuzz --seed {}",
+                    report.seed
+                );
+            }
         }
-        println!();
     }
 
-    if result.violations.is_empty() && result.concerns.is_empty() {
-        println!("No violations or concerns found.");
+    if report.passed() {
+        0
+    } else {
+        1
     }
 }
 
-fn check_content(
-    oracle: &Oracle,
-    file: Option<PathBuf>,
-    content: Option<String>,
-    assume_path: Option<String>,
+// ============ Mutation Testing Functions ============
+
+fn run_policy_mutation(
+    policy: &Policy,
+    path: &Path,
     format: &OutputFormat,
+    strict: bool,
     verbosity: &Verbosity,
 ) -> i32 {
-    let (content_str, file_path) = match (file, content) {
-        (Some(f), _) => {
-            if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-                eprintln!("Reading file: {}", f.display());
-            }
-            let content = match std::fs::read_to_string(&f) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("Failed to read file: {}", e);
-                    return 3;
-                }
-            };
-            (content, f.to_string_lossy().to_string())
-        }
-        (None, Some(c)) => {
-            let path = assume_path.unwrap_or_else(|| "stdin".to_string());
-            (c, path)
-        }
-        (None, None) => {
-            eprintln!("Either --file or --content must be provided");
-            return 3;
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
         }
     };
 
-    let proposal = Proposal {
-        id: Uuid::new_v4(),
-        action_type: ActionType::CreateFile {
-            path: file_path.clone(),
-        },
-        content: content_str,
-        files_affected: vec![file_path],
-        llm_confidence: 1.0,
-    };
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
+    }
 
-    match oracle.check_proposal(&proposal) {
-        Ok(result) => {
-            match format {
-                OutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&result).expect("invariant: JSON serialization of struct cannot fail"));
+    let report = MutationHarness::new(policy.clone()).run(&test_cases);
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Mutation, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "mutation mutants={} killed={} survived={} score={:.1}%",
+                report.results.len(),
+                report.killed(),
+                report.survivors().len(),
+                report.mutation_score()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Policy Mutation Report ===\n");
+            println!("Policy:         {}", report.policy_name);
+            println!("Test cases:     {}", report.total_tests);
+            println!("Mutants:        {}", report.results.len());
+            println!("Killed:         {}", report.killed());
+            println!("Survived:       {}", report.survivors().len());
+            println!("Mutation score: {:.1}%", report.mutation_score());
+
+            if !report.baseline_failures.is_empty() {
+                println!(
+                    "\nNote: {} test(s) already fail under the unmutated policy and cannot detect mutants",
+                    report.baseline_failures.len()
+                );
+            }
+
+            let survivors = report.survivors();
+            if !survivors.is_empty() {
+                println!("\n--- Undetected Mutations ---");
+                for result in survivors {
+                    println!("  - {}", result.description);
                 }
-                OutputFormat::Compact => {
-                    let status = if !result.violations.is_empty() {
-                        "VIOLATION"
-                    } else if !result.concerns.is_empty() {
-                        "CONCERN"
-                    } else {
-                        "OK"
-                    };
+            }
+
+            if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                println!("\n--- Killed Mutations ---");
+                for result in report.results.iter().filter(|r| r.killed) {
                     println!(
-                        "{} violations={} concerns={}",
-                        status,
-                        result.violations.len(),
-                        result.concerns.len()
+                        "  + {} (by {})",
+                        result.description,
+                        result.killed_by.join(", ")
                     );
                 }
-                OutputFormat::Text => {
-                    println!("=== Check Result ===\n");
-                    println!("Verdict: {:?}\n", result.verdict);
+            }
+        }
+    }
 
-                    if !result.violations.is_empty() {
-                        println!("VIOLATIONS:");
-                        for v in &result.violations {
-                            println!("  [{}] {:?}", v.rule, v.violation_type);
-                        }
-                    }
+    if strict && !report.survivors().is_empty() {
+        1
+    } else {
+        0
+    }
+}
 
-                    if !result.concerns.is_empty() {
-                        println!("CONCERNS:");
-                        for c in &result.concerns {
-                            println!(
-                                "  [{}] {} - {}",
-                                c.rule,
-                                c.suggestion,
-                                c.concern_type.clone().into_string()
-                            );
-                        }
-                    }
+// ============ Snapshot Functions ============
 
-                    if result.violations.is_empty() && result.concerns.is_empty() {
-                        println!("Content is compliant.");
+fn run_snapshot_tests(
+    policy: &Policy,
+    path: &Path,
+    snapshots: &Path,
+    update: bool,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
+        }
+    };
+
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
+    }
+
+    let harness =
+        SnapshotHarness::with_runner(contract_runner(policy), snapshots).with_update(update);
+    let report = match harness.run(&test_cases) {
+        Ok(r) => r,
+        Err(e) => {
+            return CliError::new(ErrorKind::Internal, format!("Snapshot error: {}", e))
+                .report(format);
+        }
+    };
+
+    let matched = report.count(|s| matches!(s, SnapshotStatus::Matched));
+    let mismatched = report.count(|s| matches!(s, SnapshotStatus::Mismatched { .. }));
+    let missing = report.count(|s| matches!(s, SnapshotStatus::Missing));
+    let written = report.count(|s| matches!(s, SnapshotStatus::Created | SnapshotStatus::Updated));
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Snapshot, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "snapshot total={} matched={} mismatched={} missing={} written={}",
+                report.results.len(),
+                matched,
+                mismatched,
+                missing,
+                written
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Snapshot Report ===\n");
+            println!("Snapshots:  {}", report.snapshot_dir.display());
+            println!("Total:      {}", report.results.len());
+            println!("Matched:    {}", matched);
+            println!("Mismatched: {}", mismatched);
+            println!("Missing:    {}", missing);
+            if update {
+                println!("Written:    {}", written);
+            }
+
+            for result in &report.results {
+                match &result.status {
+                    SnapshotStatus::Mismatched { diff } => {
+                        println!("\n--- {} (mismatch) ---", result.name);
+                        for line in diff {
+                            println!("  {}", line);
+                        }
                     }
+                    SnapshotStatus::Missing => {
+                        println!("\n--- {} (missing; run with --update) ---", result.name);
+                    }
+                    SnapshotStatus::Created | SnapshotStatus::Updated
+                        if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) =>
+                    {
+                        println!("  wrote {}", harness.snapshot_path(&result.name).display());
+                    }
+                    _ => {}
                 }
             }
-
-            if !result.violations.is_empty() {
-                1
-            } else if !result.concerns.is_empty() {
-                2
-            } else {
-                0
-            }
         }
+    }
+
+    if report.passed() {
+        0
+    } else {
+        1
+    }
+}
+
+// ============ Differential Evaluation Functions ============
+
+fn run_diff_eval(
+    policy: &Policy,
+    path: &Path,
+    slm_weight: Option<f64>,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let test_cases = match load_test_cases(path, verbosity) {
+        Ok(cases) => cases,
         Err(e) => {
-            eprintln!("Error checking content: {}", e);
-            3
+            return CliError::new(ErrorKind::Input, format!("Error loading test cases: {}", e))
+                .report(format);
         }
+    };
+
+    if test_cases.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No test cases found in: {}", path.display()),
+        )
+        .report(format);
+    }
+
+    let mut policy = policy.clone();
+    if let Some(weight) = slm_weight {
+        policy.enforcement.slm_weight = weight;
     }
-}
 
-fn show_policy(format: &OutputFormat, section: Option<&str>) {
-    let policy = Policy::rsr_default();
+    let evaluator = slm_evaluator(&policy);
+    let report = match DiffEvalHarness::new(policy, evaluator).run(&test_cases) {
+        Ok(r) => r,
+        Err(e) => {
+            return CliError::new(ErrorKind::Internal, format!("Evaluation error: {}", e))
+                .report(format);
+        }
+    };
 
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&policy).expect("invariant: JSON serialization of struct cannot fail"));
+            print_json(OutputSchema::DiffEval, &report);
         }
         OutputFormat::Compact => {
             println!(
-                "policy tier1={} tier2={} forbidden={} exceptions={}",
-                policy.languages.tier1.len(),
-                policy.languages.tier2.len(),
-                policy.languages.forbidden.len(),
-                policy.languages.exceptions.len()
+                "diff-eval total={} agree={} disagree={} oracle_correct={} pipeline_correct={} slm_weight={}",
+                report.total,
+                report.agreements,
+                report.disagreements.len(),
+                report.oracle_correct,
+                report.pipeline_correct,
+                report.slm_weight
             );
         }
         OutputFormat::Text => {
-            println!("=== RSR Default Policy ===\n");
-
-            let show_all = section.is_none();
-            let section = section.unwrap_or("");
+            println!("=== Differential Evaluation ===\n");
+            println!("Test cases:        {}", report.total);
+            println!("SLM weight:        {}", report.slm_weight);
+            println!("Agreements:        {}", report.agreements);
+            println!("Disagreements:     {}", report.disagreements.len());
+            println!(
+                "Oracle correct:    {}/{}",
+                report.oracle_correct, report.total
+            );
+            println!(
+                "Pipeline correct:  {}/{}",
+                report.pipeline_correct, report.total
+            );
 
-            if show_all || section == "languages" {
-                println!("TIER 1 (Preferred):");
-                for lang in &policy.languages.tier1 {
-                    println!("  + {} ({})", lang.name, lang.extensions.join(", "));
-                }
-                println!("\nTIER 2 (Acceptable):");
-                for lang in &policy.languages.tier2 {
-                    println!("  ~ {} ({})", lang.name, lang.extensions.join(", "));
-                }
-                println!("\nFORBIDDEN:");
-                for lang in &policy.languages.forbidden {
-                    println!("  - {} ({})", lang.name, lang.extensions.join(", "));
+            if !report.disagreements.is_empty() {
+                println!("\n--- By Transition (oracle->pipeline) ---");
+                let mut transitions: Vec<_> = report.transitions().into_iter().collect();
+                transitions.sort();
+                for (transition, count) in transitions {
+                    println!("  {}: {}", transition, count);
                 }
-                println!("\nEXCEPTIONS:");
-                for exc in &policy.languages.exceptions {
+
+                println!("\n--- Disagreements ---");
+                for d in &report.disagreements {
+                    let marker = if d.slm_helped() { "+" } else { "-" };
                     println!(
-                        "  {} allowed in: {} ({})",
-                        exc.language,
-                        exc.allowed_paths.join(", "),
-                        exc.reason
+                        "  {} {} [{}] expected {:?}",
+                        marker,
+                        d.name,
+                        d.transition(),
+                        d.expected_verdict
                     );
+                    if let (Some(score), Some(reasoning)) = (d.spirit_score, &d.reasoning) {
+                        if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
+                            println!("      spirit={:.2} {}", score, reasoning);
+                        }
+                    }
                 }
             }
+        }
+    }
 
-            if show_all || section == "toolchain" {
-                println!("\nTOOLCHAIN RULES:");
-                for rule in &policy.toolchain.rules {
-                    println!("  {} requires {}", rule.tool, rule.requires);
-                }
-            }
+    0
+}
 
-            if show_all || section == "patterns" {
-                println!("\nFORBIDDEN PATTERNS:");
-                for pattern in &policy.patterns.forbidden_patterns {
-                    println!("  {} - {}", pattern.name, pattern.reason);
-                }
-            }
+// ============ Audit Replay Functions ============
+
+/// Read every `.json` file under `dir` as a proposal, or as a request,
+/// test case or training example carrying one under `proposal`
+fn load_replay_corpus(dir: &Path) -> Result<Vec<Proposal>, String> {
+    let mut proposals = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            proposals.extend(load_replay_corpus(&path)?);
+            continue;
+        }
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
         }
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()))
+            .and_then(|mut value| {
+                if let Some(proposal) = value.get_mut("proposal") {
+                    value = proposal.take();
+                } else if let Some(proposal) = value.pointer_mut("/request/proposal") {
+                    value = proposal.take();
+                }
+                serde_json::from_value::<Proposal>(value).map_err(|e| e.to_string())
+            });
+        proposals.push(parsed.map_err(|e| format!("{}: {}", path.display(), e))?);
     }
+    Ok(proposals)
 }
 
-fn validate_proposal(
-    oracle: &Oracle,
-    proposal_path: &Path,
+fn run_audit_replay(
+    policy: &Policy,
+    policy_path: Option<&Path>,
+    audit_paths: &[PathBuf],
+    corpus_dir: Option<&Path>,
     format: &OutputFormat,
-    strict: bool,
 ) -> i32 {
-    let content = match std::fs::read_to_string(proposal_path) {
-        Ok(c) => c,
+    let mut entries = Vec::new();
+    for path in audit_paths {
+        match load_audit_entries(path) {
+            Ok(e) => entries.extend(e),
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Input,
+                    format!("Error reading audit log {}: {}", path.display(), e),
+                )
+                .report(format);
+            }
+        }
+    }
+
+    let corpus = match corpus_dir.map(load_replay_corpus).transpose() {
+        Ok(c) => c.unwrap_or_default(),
         Err(e) => {
-            eprintln!("Failed to read proposal file: {}", e);
-            return 3;
+            return CliError::new(ErrorKind::Input, format!("Error reading corpus: {}", e))
+                .report(format);
         }
     };
 
-    let proposal: Proposal = match serde_json::from_str(&content) {
-        Ok(p) => p,
+    let mut runner = contract_runner(policy);
+    if let Some(path) = policy_path {
+        runner = runner.with_policy_path(path.to_string_lossy());
+    }
+    let report = match ReplayHarness::new()
+        .with_runner(runner)
+        .with_corpus(corpus)
+        .run(&entries)
+    {
+        Ok(r) => r,
         Err(e) => {
-            eprintln!("Failed to parse proposal JSON: {}", e);
-            return 3;
+            return CliError::new(ErrorKind::Internal, format!("Replay failed: {}", e))
+                .report(format);
         }
     };
 
-    match oracle.check_proposal(&proposal) {
-        Ok(result) => {
-            match format {
-                OutputFormat::Json | OutputFormat::Compact => {
-                    println!("{}", serde_json::to_string_pretty(&result).expect("invariant: JSON serialization of struct cannot fail"));
-                }
-                OutputFormat::Text => {
-                    println!("Proposal: {}", result.proposal_id);
-                    println!("Verdict: {:?}", result.verdict);
-                    println!("Rules checked: {}", result.rules_checked.len());
-                    println!("Violations: {}", result.violations.len());
-                    println!("Concerns: {}", result.concerns.len());
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::AuditReplay, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "audit-replay entries={} replayed={} stable={} drifted={} missing={}",
+                report.entries,
+                report.replayed,
+                report.stable,
+                report.drifts.len(),
+                report.missing.len()
+            );
+            for drift in &report.drifts {
+                println!(
+                    "DRIFT {} {} {}",
+                    drift.request_id,
+                    drift.files.join(","),
+                    drift.transition()
+                );
+            }
+        }
+        OutputFormat::Text => {
+            println!("=== Audit Replay ===\n");
+            println!("{}", report.summary_text());
+            if !report.drifts.is_empty() {
+                println!("\nDrifted decisions:");
+                for drift in &report.drifts {
+                    println!(
+                        "  {} {}: {} (recorded under contract {})",
+                        drift.request_id,
+                        drift.files.join(", "),
+                        drift.transition(),
+                        drift.recorded_version
+                    );
                 }
             }
-
-            if !result.violations.is_empty() {
-                1
-            } else if strict && !result.concerns.is_empty() {
-                2
-            } else {
-                0
+            if !report.missing.is_empty() {
+                println!(
+                    "\n{} entries had no content; record with --audit-content or pass --corpus",
+                    report.missing.len()
+                );
             }
         }
-        Err(e) => {
-            eprintln!("Error validating proposal: {}", e);
-            3
-        }
+    }
+
+    if report.has_drift() {
+        1
+    } else {
+        0
     }
 }
 
-fn init_config(force: bool, minimal: bool) -> i32 {
-    let config_dir = PathBuf::from(".conative");
+// ============ Training Export Functions ============
 
-    if config_dir.exists() && !force {
-        eprintln!("Configuration directory already exists. Use --force to overwrite.");
-        return 1;
+/// Read audit entries from NDJSON or concatenated JSON. Decisions with an
+/// "audit" field (from `contract eval -f json --audit`) are unwrapped to
+/// their audit entry.
+fn load_audit_entries(path: &Path) -> Result<Vec<AuditEntry>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    for value in serde_json::Deserializer::from_str(&content).into_iter::<serde_json::Value>() {
+        let mut value = value.map_err(|e| e.to_string())?;
+        // Policy reloads logged by `conative serve` are not decisions
+        if value["event"] == gating_contract::POLICY_CHANGE_EVENT {
+            continue;
+        }
+        if let Some(audit) = value.get_mut("audit") {
+            value = audit.take();
+        }
+        entries.push(serde_json::from_value(value).map_err(|e| e.to_string())?);
     }
+    Ok(entries)
+}
 
-    if let Err(e) = std::fs::create_dir_all(&config_dir) {
-        eprintln!("Failed to create .conative directory: {}", e);
-        return 3;
+fn run_training_export(
+    audit_paths: &[PathBuf],
+    labels_path: Option<&Path>,
+    output: &Path,
+    format: &OutputFormat,
+) -> i32 {
+    let mut entries = Vec::new();
+    for path in audit_paths {
+        match load_audit_entries(path) {
+            Ok(e) => entries.extend(e),
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Input,
+                    format!("Error reading audit log {}: {}", path.display(), e),
+                )
+                .report(format);
+            }
+        }
     }
 
-    let policy_content = if minimal {
-        r#"# Minimal Conative Policy
-# Extend the RSR default with project-specific rules
-
-let base = import "schema.ncl" in
-{
-  name = "Project Policy",
-  extends = "rsr-default",
-}
-"#
-    } else {
-        include_str!("../config/policy.ncl")
+    let labels: Vec<HumanLabel> = match labels_path {
+        Some(path) => {
+            let parsed = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(l) => l,
+                Err(e) => {
+                    return CliError::new(
+                        ErrorKind::Input,
+                        format!("Error reading labels {}: {}", path.display(), e),
+                    )
+                    .report(format);
+                }
+            }
+        }
+        None => Vec::new(),
     };
 
-    let policy_path = config_dir.join("policy.ncl");
-    if let Err(e) = std::fs::write(&policy_path, policy_content) {
-        eprintln!("Failed to write policy.ncl: {}", e);
-        return 3;
-    }
-
-    // Create local.ncl (gitignored)
-    let local_content = r#"# Local policy overrides (not committed to git)
-# Use this for machine-specific or developer-specific settings
+    let export = gating_contract::export_training_data(&entries, &labels);
 
-{
-  # local_exceptions = [],
-}
-"#;
-    let local_path = config_dir.join("local.ncl");
-    if let Err(e) = std::fs::write(&local_path, local_content) {
-        eprintln!("Failed to write local.ncl: {}", e);
-        return 3;
+    if let Err(e) = std::fs::create_dir_all(output) {
+        return CliError::new(
+            ErrorKind::Output,
+            format!("Error creating {}: {}", output.display(), e),
+        )
+        .report(format);
+    }
+    for example in &export.examples {
+        let file = output.join(format!("audit_{}.json", example.audit_id));
+        let json = serde_json::to_string_pretty(example)
+            .expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(e) = std::fs::write(&file, json + "\n") {
+            return CliError::new(
+                ErrorKind::Output,
+                format!("Error writing {}: {}", file.display(), e),
+            )
+            .report(format);
+        }
     }
 
-    println!("Initialized Conative configuration in .conative/");
-    println!("  .conative/policy.ncl  - Main policy configuration");
-    println!("  .conative/local.ncl   - Local overrides (add to .gitignore)");
-    println!();
-    println!("To revert: rm -rf .conative/");
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "audit_entries": entries.len(),
+                "exported": export.examples.len(),
+                "human_labeled": export.human_labeled,
+                "skipped": export.skipped,
+                "output": output,
+            });
+            print_json(OutputSchema::TrainingExport, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "training-export entries={} exported={} human={} no_content={} hash_mismatch={} unresolved={}",
+                entries.len(),
+                export.examples.len(),
+                export.human_labeled,
+                export.skipped.no_content,
+                export.skipped.hash_mismatch,
+                export.skipped.unresolved_escalation
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Training Data Export ===\n");
+            println!("Audit entries:  {}", entries.len());
+            println!(
+                "Exported:       {} -> {}",
+                export.examples.len(),
+                output.display()
+            );
+            println!("Human-labeled:  {}", export.human_labeled);
+            if export.skipped.no_content > 0 {
+                println!(
+                    "Skipped:        {} without content (record with --audit-content)",
+                    export.skipped.no_content
+                );
+            }
+            if export.skipped.hash_mismatch > 0 {
+                println!(
+                    "Skipped:        {} with content hash mismatch",
+                    export.skipped.hash_mismatch
+                );
+            }
+            if export.skipped.unresolved_escalation > 0 {
+                println!(
+                    "Skipped:        {} unresolved escalation(s) (add human labels)",
+                    export.skipped.unresolved_escalation
+                );
+            }
+        }
+    }
 
     0
 }
 
-fn generate_completions(shell: clap_complete::Shell) {
-    use clap::CommandFactory;
-    clap_complete::generate(
-        shell,
-        &mut Cli::command(),
-        "conative",
-        &mut std::io::stdout(),
-    );
-}
+// ============ Calibration Functions ============
 
-fn generate_man_page() {
-    use clap::CommandFactory;
-    let man = clap_mangen::Man::new(Cli::command());
-    let mut buffer: Vec<u8> = Vec::new();
-    if let Err(e) = man.render(&mut buffer) {
-        eprintln!("Failed to generate man page: {}", e);
-        std::process::exit(3);
+/// Load labeled spirit samples from training-data JSON files
+fn load_spirit_samples(path: &Path, verbosity: &Verbosity) -> Result<Vec<SpiritSample>, String> {
+    #[derive(serde::Deserialize)]
+    struct LabeledData {
+        proposal: Proposal,
+        #[serde(default)]
+        spirit_violation: bool,
+        #[serde(default)]
+        concern_type: Option<String>,
     }
-    print!("{}", String::from_utf8_lossy(&buffer));
+
+    let mut samples = Vec::new();
+    if path.is_file() {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let data: LabeledData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        samples.push(SpiritSample {
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            proposal: data.proposal,
+            spirit_violation: data.spirit_violation,
+            category: data.concern_type.filter(|_| data.spirit_violation),
+        });
+    } else if path.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
+            let entry_path = entry.map_err(|e| e.to_string())?.path();
+            if entry_path.is_dir() || entry_path.extension().map(|s| s == "json").unwrap_or(false) {
+                match load_spirit_samples(&entry_path, verbosity) {
+                    Ok(s) => samples.extend(s),
+                    Err(e) => {
+                        if matches!(verbosity, Verbosity::Debug) {
+                            eprintln!("Skipping {}: {}", entry_path.display(), e);
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    Ok(samples)
 }
 
-// Helper trait for ConcernType
-trait IntoString {
-    fn into_string(self) -> String;
-}
+fn run_calibration(
+    policy: &Policy,
+    path: &Path,
+    threshold: f64,
+    bins: usize,
+    format: &OutputFormat,
+    verbosity: &Verbosity,
+) -> i32 {
+    let samples = match load_spirit_samples(path, verbosity) {
+        Ok(s) => s,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error loading samples: {}", e))
+                .report(format);
+        }
+    };
+
+    if samples.is_empty() {
+        return CliError::new(
+            ErrorKind::Input,
+            format!("No labeled samples found in: {}", path.display()),
+        )
+        .report(format);
+    }
+
+    let report = CalibrationHarness::new(slm_evaluator(policy))
+        .with_threshold(threshold)
+        .with_bins(bins)
+        .run(&samples);
+
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::Calibration, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "calibrate total={} positives={} precision={:.3} recall={:.3} f1={:.3} ece={:.3} brier={:.3}",
+                report.total,
+                report.positives,
+                report.overall.precision,
+                report.overall.recall,
+                report.overall.f1,
+                report.expected_calibration_error,
+                report.brier_score
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== SLM Calibration Report ===\n");
+            println!(
+                "Samples:    {} ({} spirit violations)",
+                report.total, report.positives
+            );
+            println!("Threshold:  {}", report.threshold);
+            println!("ECE:        {:.3}", report.expected_calibration_error);
+            println!("Brier:      {:.3}", report.brier_score);
+
+            println!("\n--- Detection ---");
+            println!(
+                "  {:<20} {:>7} {:>9} {:>7} {:>7}",
+                "category", "support", "precision", "recall", "f1"
+            );
+            for m in std::iter::once(&report.overall).chain(&report.categories) {
+                println!(
+                    "  {:<20} {:>7} {:>9.3} {:>7.3} {:>7.3}",
+                    m.category, m.support, m.precision, m.recall, m.f1
+                );
+            }
+
+            println!("\n--- Reliability Curve ---");
+            for bin in report.bins.iter().filter(|b| b.count > 0) {
+                println!(
+                    "  [{:.2}, {:.2})  n={:<4} mean_score={:.3} observed={:.3}",
+                    bin.lower, bin.upper, bin.count, bin.mean_score, bin.observed_rate
+                );
+            }
 
-impl IntoString for policy_oracle::ConcernType {
-    fn into_string(self) -> String {
-        match self {
-            policy_oracle::ConcernType::VerbositySmell => "Verbosity smell".to_string(),
-            policy_oracle::ConcernType::PatternDeviation => "Pattern deviation".to_string(),
-            policy_oracle::ConcernType::UnusualStructure => "Unusual structure".to_string(),
-            policy_oracle::ConcernType::Tier2Language { language } => {
-                format!("Tier 2 language: {}", language)
+            if !report.errors.is_empty() {
+                println!("\n--- Evaluator Errors ---");
+                for (name, error) in &report.errors {
+                    println!("  {}: {}", name, error);
+                }
             }
         }
     }
+
+    0
 }
 
-// ============ Contract Runner Functions ============
+// ============ Model Functions ============
 
-fn run_contract_tests(
-    path: &Path,
+fn run_model_pull(
+    name: &str,
+    registry: &str,
+    dir: Option<PathBuf>,
+    force: bool,
     format: &OutputFormat,
-    fail_fast: bool,
-    verbosity: &Verbosity,
 ) -> i32 {
-    let mut harness = TestHarness::new();
-    let test_cases = match load_test_cases(path, verbosity) {
-        Ok(cases) => cases,
+    let registry = match ModelRegistry::load(registry) {
+        Ok(r) => r,
         Err(e) => {
-            eprintln!("Error loading test cases: {}", e);
-            return 3;
+            return CliError::new(
+                ErrorKind::Model,
+                format!("Error loading model registry: {}", e),
+            )
+            .report(format);
         }
     };
+    let Some(entry) = registry.find(name) else {
+        return CliError::new(
+            ErrorKind::Model,
+            format!("Model not found in registry: {}", name),
+        )
+        .report(format);
+    };
 
-    if test_cases.is_empty() {
-        eprintln!("No test cases found in: {}", path.display());
-        return 3;
-    }
+    let store = ModelStore::new(dir.unwrap_or_else(ModelStore::default_root));
+    let pulled = match store.pull(entry, force) {
+        Ok(p) => p,
+        Err(e) => {
+            return CliError::new(ErrorKind::Model, format!("Error pulling {}: {}", name, e))
+                .report(format);
+        }
+    };
 
-    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-        eprintln!("Running {} test cases...", test_cases.len());
+    match format {
+        OutputFormat::Json => {
+            print_json(OutputSchema::ModelPull, &pulled);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "model-pull {} {} {} bytes={}",
+                pulled.name,
+                pulled.fingerprint,
+                pulled.path.display(),
+                pulled.bytes
+            );
+        }
+        OutputFormat::Text => {
+            let status = if pulled.downloaded {
+                "Downloaded"
+            } else {
+                "Already present"
+            };
+            println!("{}: {}", status, pulled.name);
+            println!("  Path:        {}", pulled.path.display());
+            println!("  Fingerprint: {}", pulled.fingerprint);
+            println!("  Size:        {} bytes", pulled.bytes);
+            if entry.format == ModelFormat::Gguf {
+                println!(
+                    "\nUse with: --slm-backend llama-cpp --slm-model {}",
+                    pulled.path.display()
+                );
+            }
+        }
     }
 
-    for test in &test_cases {
-        let result = harness.run_test(test);
+    0
+}
+
+fn run_model_devices(binary: &str, format: &OutputFormat) -> i32 {
+    let available = detect_accelerators(binary);
+    let selected = Accelerator::Auto.resolve(&available);
+    let names: Vec<&str> = available.iter().map(|a| a.name()).collect();
 
-        if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-            let status = if result.passed { "PASS" } else { "FAIL" };
-            eprintln!("  {} {} ({}μs)", status, test.name, result.duration_us);
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "binary": binary,
+                "available": names,
+                "selected": selected.name(),
+            });
+            print_json(OutputSchema::ModelDevices, &report);
+        }
+        OutputFormat::Compact => {
+            println!(
+                "devices available={} selected={}",
+                names.join(","),
+                selected.name()
+            );
+        }
+        OutputFormat::Text => {
+            println!("=== Inference Devices ({}) ===\n", binary);
+            if names.is_empty() {
+                println!("GPU:      none detected");
+            } else {
+                println!("GPU:      {}", names.join(", "));
+            }
+            println!("Selected: {} (--slm-accelerator auto)", selected.name());
         }
+    }
 
-        if fail_fast && !result.passed {
-            break;
+    0
+}
+
+// ============ Doctor Functions ============
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DoctorStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(serde::Serialize)]
+struct DoctorCheck {
+    name: String,
+    status: DoctorStatus,
+    detail: String,
+}
+
+impl DoctorCheck {
+    fn new(name: impl Into<String>, status: DoctorStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
         }
     }
+}
 
-    let summary = harness.summary();
+fn run_doctor(cli: &Cli, loaded: &Result<Policy, String>, format: &OutputFormat) -> i32 {
+    let mut checks = vec![DoctorCheck::new(
+        "build",
+        DoctorStatus::Ok,
+        format!(
+            "conative {} (classifier backend {})",
+            env!("CARGO_PKG_VERSION"),
+            if cfg!(feature = "candle") {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        ),
+    )];
+
+    // Config discovery
+    let repo_policy = Path::new(".conative/policy.ncl");
+    checks.push(match cli.policy_file.as_deref() {
+        Some(path) if !path.exists() => DoctorCheck::new(
+            "config",
+            DoctorStatus::Fail,
+            format!("{} not found", path.display()),
+        ),
+        Some(path) => DoctorCheck::new(
+            "config",
+            DoctorStatus::Ok,
+            format!("--policy-file {}", path.display()),
+        ),
+        None if repo_policy.exists() => DoctorCheck::new(
+            "config",
+            DoctorStatus::Warn,
+            "built-in RSR default; .conative/policy.ncl exists but is only used with --policy-file",
+        ),
+        None => DoctorCheck::new("config", DoctorStatus::Ok, "built-in RSR default"),
+    });
+    if cli
+        .policy_file
+        .as_deref()
+        .is_some_and(|p| p.extension().is_some_and(|e| e == "ncl"))
+        && find_on_path("nickel").is_none()
+    {
+        checks.push(DoctorCheck::new(
+            "nickel",
+            DoctorStatus::Fail,
+            "nickel not found on PATH (needed for .ncl policies)",
+        ));
+    }
+
+    match loaded {
+        Ok(policy) => {
+            policy_checks(policy, &mut checks);
+            let mut writable = vec![PathBuf::from(".conative")];
+            writable.extend(
+                policy
+                    .enforcement
+                    .slm_cache
+                    .as_ref()
+                    .map(|c| PathBuf::from(&c.dir)),
+            );
+            for dir in writable {
+                checks.push(write_check(&dir));
+            }
+        }
+        Err(e) => checks.push(DoctorCheck::new("policy", DoctorStatus::Fail, e.clone())),
+    }
 
+    let count =
+        |wanted: fn(&DoctorStatus) -> bool| checks.iter().filter(|c| wanted(&c.status)).count();
+    let failed = count(|s| matches!(s, DoctorStatus::Fail));
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&summary).expect("invariant: JSON serialization of struct cannot fail"));
+            print_json(
+                OutputSchema::Doctor,
+                &serde_json::json!({ "checks": checks }),
+            );
         }
         OutputFormat::Compact => {
             println!(
-                "tests={} passed={} failed={} duration={}μs",
-                summary.total, summary.passed, summary.failed, summary.total_duration_us
+                "doctor ok={} warn={} fail={}",
+                count(|s| matches!(s, DoctorStatus::Ok)),
+                count(|s| matches!(s, DoctorStatus::Warn)),
+                failed
             );
         }
         OutputFormat::Text => {
-            println!("=== Contract Test Results ===\n");
-            println!("Total:   {}", summary.total);
-            println!("Passed:  {}", summary.passed);
-            println!("Failed:  {}", summary.failed);
-            println!("Duration: {}μs\n", summary.total_duration_us);
-
-            if !summary.all_passed() {
-                println!("Failed tests:");
-                for name in summary.failed_tests() {
-                    println!("  - {}", name);
-                }
-
-                // Show details of failures
-                for result in &summary.results {
-                    if !result.passed {
-                        println!("\n  {} ERROR:", result.name);
-                        if let Some(err) = &result.error {
-                            println!("    {}", err);
-                        }
-                    }
-                }
-            } else {
-                println!("All tests passed!");
+            println!("=== Conative Doctor ===\n");
+            for check in &checks {
+                let status = match check.status {
+                    DoctorStatus::Ok => "ok",
+                    DoctorStatus::Warn => "warn",
+                    DoctorStatus::Fail => "FAIL",
+                };
+                println!("  [{:<4}] {:<8} {}", status, check.name, check.detail);
             }
         }
     }
 
-    if summary.all_passed() {
-        0
-    } else {
+    if failed > 0 {
         1
+    } else {
+        0
     }
 }
 
-/// Load test cases from a file or directory
-fn load_test_cases(path: &Path, verbosity: &Verbosity) -> Result<Vec<TestCase>, String> {
-    let mut cases = Vec::new();
+/// Whether `policy`'s rules compile and its SLM models are present
+fn policy_checks(policy: &Policy, checks: &mut Vec<DoctorCheck>) {
+    checks.push(DoctorCheck::new(
+        "policy",
+        DoctorStatus::Ok,
+        format!("loaded \"{}\"", policy.name),
+    ));
+    let mut errors = policy.compile_errors();
+    errors.extend(policy_code_errors(policy));
+    if errors.is_empty() {
+        checks.push(DoctorCheck::new(
+            "rules",
+            DoctorStatus::Ok,
+            format!(
+                "{} patterns and {} protected rules compile",
+                policy.patterns.forbidden_patterns.len() + policy.patterns.required_patterns.len(),
+                policy.protected.rules.len()
+            ),
+        ));
+    }
+    checks.extend(
+        errors
+            .into_iter()
+            .map(|e| DoctorCheck::new("rules", DoctorStatus::Fail, e)),
+    );
 
-    if path.is_file() {
-        cases.push(load_test_case_file(path)?);
-    } else if path.is_dir() {
-        for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let entry_path = entry.path();
+    match &policy.enforcement.slm_backend {
+        Some(backend) => model_checks(backend, checks),
+        None => checks.push(DoctorCheck::new(
+            "model",
+            DoctorStatus::Ok,
+            "no SLM backend configured",
+        )),
+    }
+}
 
-            if entry_path.is_dir() {
-                // Recurse into subdirectories
-                cases.extend(load_test_cases(&entry_path, verbosity)?);
-            } else if entry_path.extension().map(|s| s == "json").unwrap_or(false) {
-                match load_test_case_file(&entry_path) {
-                    Ok(case) => cases.push(case),
-                    Err(e) => {
-                        if matches!(verbosity, Verbosity::Debug) {
-                            eprintln!("Skipping {}: {}", entry_path.display(), e);
-                        }
-                    }
+/// Whether the files an SLM backend needs are present
+fn model_checks(config: &SlmBackendConfig, checks: &mut Vec<DoctorCheck>) {
+    match config {
+        SlmBackendConfig::Placeholder | SlmBackendConfig::Mock { .. } => {
+            checks.push(DoctorCheck::new(
+                "model",
+                DoctorStatus::Ok,
+                "built-in backend, no model needed",
+            ));
+        }
+        SlmBackendConfig::LlamaCpp {
+            model_path,
+            binary,
+            sha256,
+            ..
+        } => {
+            checks.push(if Path::new(model_path).is_file() {
+                let pin = if sha256.is_some() {
+                    "pinned"
+                } else {
+                    "unpinned"
+                };
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Ok,
+                    format!("{} ({})", model_path, pin),
+                )
+            } else {
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Fail,
+                    format!("{} not found", model_path),
+                )
+            });
+            let binary = binary.as_deref().unwrap_or("llama-cli");
+            checks.push(match find_on_path(binary) {
+                Some(path) => {
+                    DoctorCheck::new("runtime", DoctorStatus::Ok, path.display().to_string())
                 }
+                None => DoctorCheck::new(
+                    "runtime",
+                    DoctorStatus::Fail,
+                    format!("{} not found on PATH", binary),
+                ),
+            });
+        }
+        SlmBackendConfig::OpenAi {
+            endpoint,
+            api_key_env,
+            ..
+        } => {
+            let key_missing = api_key_env
+                .as_deref()
+                .is_some_and(|var| std::env::var_os(var).is_none());
+            checks.push(if key_missing {
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Warn,
+                    format!(
+                        "{} (API key variable {} is unset)",
+                        endpoint,
+                        api_key_env.as_deref().unwrap_or_default()
+                    ),
+                )
+            } else {
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Ok,
+                    format!("{} (not probed)", endpoint),
+                )
+            });
+        }
+        SlmBackendConfig::Classifier { model_dir } => {
+            let dir = Path::new(model_dir);
+            let missing: Vec<&str> = ["config.json", "tokenizer.json", "model.safetensors"]
+                .into_iter()
+                .filter(|f| !dir.join(f).is_file())
+                .collect();
+            checks.push(if !cfg!(feature = "candle") {
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Fail,
+                    "classifier backend needs a build with --features candle",
+                )
+            } else if missing.is_empty() {
+                DoctorCheck::new("model", DoctorStatus::Ok, model_dir.clone())
+            } else {
+                DoctorCheck::new(
+                    "model",
+                    DoctorStatus::Fail,
+                    format!("{} missing {}", model_dir, missing.join(", ")),
+                )
+            });
+        }
+        SlmBackendConfig::Cascade {
+            prefilter, full, ..
+        } => {
+            model_checks(prefilter, checks);
+            model_checks(full, checks);
+        }
+        SlmBackendConfig::Ensemble { members, .. } => {
+            for member in members {
+                model_checks(&member.backend, checks);
             }
         }
-    } else {
-        return Err(format!("Path does not exist: {}", path.display()));
     }
+}
 
-    Ok(cases)
+/// Executable `name` as given (if it has a path) or on `PATH`
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    if name.contains(std::path::MAIN_SEPARATOR) || name.contains('/') {
+        let path = PathBuf::from(name);
+        return path.is_file().then_some(path);
+    }
+    std::env::var_os("PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(name))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .find(|candidate| candidate.is_file())
 }
 
-/// Load a single test case from a training data JSON file
-fn load_test_case_file(path: &Path) -> Result<TestCase, String> {
-    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+/// Probe that state can be written under `dir` (or where it would be created)
+fn write_check(dir: &Path) -> DoctorCheck {
+    let name = format!("write {}", dir.display());
+    let existing = dir
+        .ancestors()
+        .find(|a| a.as_os_str().is_empty() || a.is_dir());
+    let probe_dir = match existing {
+        Some(a) if a.as_os_str().is_empty() => Path::new("."),
+        Some(a) => a,
+        None => return DoctorCheck::new(name, DoctorStatus::Fail, "no existing parent directory"),
+    };
+    let probe = probe_dir.join(format!(".conative-doctor-{}", Uuid::new_v4()));
+    match std::fs::write(&probe, b"").and_then(|_| std::fs::remove_file(&probe)) {
+        Ok(()) if probe_dir == dir => DoctorCheck::new(name, DoctorStatus::Ok, "writable"),
+        Ok(()) => DoctorCheck::new(
+            name,
+            DoctorStatus::Ok,
+            format!("will be created under {}", probe_dir.display()),
+        ),
+        Err(e) => DoctorCheck::new(name, DoctorStatus::Fail, e.to_string()),
+    }
+}
 
-    // Parse the training data format
-    #[derive(serde::Deserialize)]
-    #[allow(dead_code)]
-    struct TrainingData {
-        proposal: Proposal,
-        expected_verdict: String,
-        #[serde(default)]
-        reasoning: String,
-        #[serde(default)]
-        category: String,
-        #[serde(default)]
-        violation_type: Option<String>,
-        #[serde(default)]
-        concern_type: Option<String>,
-        #[serde(default)]
-        spirit_violation: bool,
+// ============ Report Rendering Functions ============
+
+/// Whether Text output may use ANSI styles; set once from `--no-color`
+static COLOR: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+/// Enable styling unless `--no-color`, `NO_COLOR` or a non-terminal stdout
+fn init_color(no_color: bool) {
+    use std::io::IsTerminal;
+    let enabled =
+        !no_color && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal();
+    let _ = COLOR.set(enabled);
+}
+
+fn paint(style: &str, text: &str) -> String {
+    if COLOR.get().copied().unwrap_or(false) {
+        format!("\x1b[{}m{}\x1b[0m", style, text)
+    } else {
+        text.to_string()
     }
+}
 
-    let data: TrainingData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+/// One violation or concern ready to print
+struct Finding<'a> {
+    /// `error` for violations, `warning` for concerns
+    level: &'static str,
+    severity: Option<Severity>,
+    rule: Option<String>,
+    message: String,
+    file: Option<String>,
+    /// Source text, secrets masked, and the byte range that matched
+    frame: Option<(std::borrow::Cow<'a, str>, std::ops::Range<usize>)>,
+    remediation: Option<String>,
+}
 
-    let expected_verdict = match data.expected_verdict.as_str() {
-        "Compliant" => Verdict::Allow,
-        "HardViolation" => Verdict::Block,
-        "SoftConcern" => Verdict::Warn,
-        other => return Err(format!("Unknown verdict: {}", other)),
+/// Print a finding with a linter-style code frame
+fn render_finding(finding: &Finding) {
+    let style = if finding.level == "error" {
+        "1;31"
+    } else {
+        "1;33"
+    };
+    let badge = match &finding.severity {
+        Some(severity) => format!(
+            "{}[{}]",
+            finding.level,
+            format!("{:?}", severity).to_lowercase()
+        ),
+        None => finding.level.to_string(),
     };
+    let rule = finding
+        .rule
+        .as_ref()
+        .map(|r| format!(" {}", paint("2", &format!("({})", r))))
+        .unwrap_or_default();
+    println!(
+        "{}: {}{}",
+        paint(style, &badge),
+        paint("1", &finding.message),
+        rule
+    );
 
-    // Map the expected category based on violation_type, concern_type, or category
-    let expected_category = if data.spirit_violation {
-        // Spirit violations require SLM - these will fail until SLM is implemented
-        Some(gating_contract::RefusalCategory::VerbositySmell)
-    } else if let Some(ref vtype) = data.violation_type {
-        match vtype.as_str() {
-            "ForbiddenLanguage" => Some(gating_contract::RefusalCategory::ForbiddenLanguage),
-            "ForbiddenToolchain" => Some(gating_contract::RefusalCategory::ForbiddenToolchain),
-            "SecurityViolation" => Some(gating_contract::RefusalCategory::SecurityViolation),
-            "ForbiddenPattern" => Some(gating_contract::RefusalCategory::ForbiddenPattern),
-            _ => None,
+    let arrow = paint("1;34", "-->");
+    match (&finding.file, &finding.frame) {
+        (file, Some((content, range))) => {
+            let (line, column) = line_column(content, range.start);
+            let location = format!(
+                "{}:{}:{}",
+                file.as_deref().unwrap_or("<content>"),
+                line + 1,
+                column + 1
+            );
+            println!("  {} {}", arrow, location);
+            print_code_frame(content, range.clone(), style);
         }
-    } else if let Some(ref ctype) = data.concern_type {
-        match ctype.as_str() {
-            "VerbositySmell" => Some(gating_contract::RefusalCategory::VerbositySmell),
-            "PatternDeviation" | "UnusualStructure" => {
-                Some(gating_contract::RefusalCategory::StructuralAnomaly)
-            }
-            _ => None,
+        (Some(file), None) => println!("  {} {}", arrow, file),
+        (None, None) => {}
+    }
+
+    if let Some(remediation) = &finding.remediation {
+        println!("  {} {}", paint("1;36", "= help:"), remediation);
+    }
+    println!();
+}
+
+/// Zero-based line and character column of a byte offset
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, content[line_start..offset].chars().count())
+}
+
+/// Two lines of context either side of the match, underlined on its first line
+fn print_code_frame(content: &str, range: std::ops::Range<usize>, style: &str) {
+    const CONTEXT: usize = 2;
+    let (line, _) = line_column(content, range.start);
+    let lines: Vec<&str> = content.lines().collect();
+    let first = line.saturating_sub(CONTEXT);
+    let last = (line + CONTEXT).min(lines.len().saturating_sub(1));
+    let width = (last + 1).to_string().len();
+    let gutter = paint("1;34", "|");
+
+    println!("{:width$} {}", "", gutter, width = width);
+    for (index, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let number = paint("1;34", &format!("{:>width$}", index + 1, width = width));
+        let row = format!("{} {} {}", number, gutter, text);
+        println!("{}", row.trim_end());
+        if index == line {
+            let line_start = content[..range.start]
+                .rfind('\n')
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            let end = range.end.min(line_start + text.len()).max(range.start);
+            // Keep tabs so the carets line up with the source above
+            let pad: String = content[line_start..range.start]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = "^".repeat(content[range.start..end].chars().count().max(1));
+            println!(
+                "{:width$} {} {}{}",
+                "",
+                gutter,
+                pad,
+                paint(style, &carets),
+                width = width
+            );
         }
-    } else {
-        match data.category.as_str() {
-            "language" => {
-                if data.expected_verdict == "HardViolation" {
-                    Some(gating_contract::RefusalCategory::ForbiddenLanguage)
-                } else {
-                    None
-                }
+    }
+    println!("{:width$} {}", "", gutter, width = width);
+}
+
+/// Verdict name for Text output, colored by outcome
+fn verdict_label(verdict: &PolicyVerdict) -> String {
+    match verdict {
+        PolicyVerdict::Compliant => paint("1;32", "compliant"),
+        PolicyVerdict::HardViolation(_) => paint("1;31", "hard violation"),
+        PolicyVerdict::SoftConcern(_) => paint("1;33", "soft concern"),
+    }
+}
+
+/// Suggested fix for a violation, drawn from the policy where possible
+fn violation_remediation(policy: &Policy, violation: &ViolationType) -> Option<String> {
+    match violation {
+        ViolationType::ForbiddenLanguage { language, .. } => {
+            let allowed: Vec<&str> = policy
+                .languages
+                .exceptions
+                .iter()
+                .filter(|e| &e.language == language)
+                .flat_map(|e| e.allowed_paths.iter().map(String::as_str))
+                .collect();
+            let alternative = policy
+                .languages
+                .forbidden
+                .iter()
+                .find(|l| &l.name == language)
+                .and_then(|l| l.alternative_summary());
+            if !allowed.is_empty() {
+                Some(format!(
+                    "{} is only allowed under {}",
+                    language,
+                    allowed.join(", ")
+                ))
+            } else if alternative.is_some() {
+                alternative
+            } else {
+                let tier1: Vec<&str> = policy
+                    .languages
+                    .tier1
+                    .iter()
+                    .map(|l| l.name.as_str())
+                    .collect();
+                Some(format!(
+                    "Use a Tier 1 language instead ({})",
+                    tier1.join(", ")
+                ))
             }
-            "toolchain" => Some(gating_contract::RefusalCategory::ForbiddenToolchain),
-            "pattern" | "security" => Some(gating_contract::RefusalCategory::ForbiddenPattern),
-            "spirit" => Some(gating_contract::RefusalCategory::VerbositySmell),
-            _ => None,
         }
-    };
-
-    Ok(TestCase {
-        name: path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string(),
-        description: data.reasoning,
-        request: GatingRequest::new(data.proposal),
-        expected_verdict,
-        expected_category,
-        expected_code: None,
-    })
+        ViolationType::ForbiddenToolchain { tool, missing } if missing.is_empty() => policy
+            .toolchain
+            .rules
+            .iter()
+            .find(|r| &r.tool == tool && r.forbids())
+            .map(|r| r.describe()),
+        ViolationType::ForbiddenToolchain { tool, missing } => {
+            Some(format!("Add {} to use {}", missing, tool))
+        }
+        ViolationType::SecurityViolation { .. } => {
+            Some("Remove hardcoded secrets and use environment variables".to_string())
+        }
+        ViolationType::ForbiddenPattern { pattern, .. } => policy
+            .patterns
+            .forbidden_patterns
+            .iter()
+            .find(|p| &p.name == pattern)
+            .map(|p| p.reason.clone()),
+        ViolationType::RequiredPatternRemoved { pattern, .. } => Some(format!(
+            "Keep the content matched by '{}' in the edit",
+            pattern
+        )),
+        ViolationType::OpaqueContent { .. } => Some(
+            "Commit the source instead, or list the path under binary.allowed_paths".to_string(),
+        ),
+        ViolationType::LockfileNotUpdated { lockfiles, .. } => Some(format!(
+            "Regenerate {} and include it in the change",
+            lockfiles.join(" or ")
+        )),
+        ViolationType::UnsafeBlock {
+            needs_safety_comment: true,
+            ..
+        } => Some("Add a // SAFETY: comment above each unsafe block".to_string()),
+        ViolationType::ForbiddenImport { rule, .. } => policy
+            .imports
+            .rules
+            .iter()
+            .find(|r| &r.name == rule)
+            .map(|r| r.reason.clone()),
+        ViolationType::UnsafeBlock { .. } => match &policy.unsafe_code.allowed_paths[..] {
+            [] => Some("Rewrite without unsafe".to_string()),
+            paths => Some(format!(
+                "Rewrite without unsafe, or move the code under {}",
+                paths.join(", ")
+            )),
+        },
+    }
 }
 
-fn eval_contract_request(request_path: &Path, format: &OutputFormat, include_audit: bool) -> i32 {
-    let content = match std::fs::read_to_string(request_path) {
-        Ok(c) => c,
+/// File named by a violation, when it carries one
+fn violation_file(violation: &ViolationType) -> Option<&str> {
+    match violation {
+        ViolationType::ForbiddenLanguage { file, .. }
+        | ViolationType::ForbiddenPattern { file, .. }
+        | ViolationType::RequiredPatternRemoved { file, .. }
+        | ViolationType::OpaqueContent { file, .. }
+        | ViolationType::LockfileNotUpdated { manifest: file, .. }
+        | ViolationType::UnsafeBlock { file, .. }
+        | ViolationType::ForbiddenImport { file, .. } => {
+            Some(file.as_str()).filter(|f| !f.is_empty())
+        }
+        ViolationType::ForbiddenToolchain { .. } | ViolationType::SecurityViolation { .. } => None,
+    }
+}
+
+// ============ Policy Impact Functions ============
+
+fn run_policy_impact(
+    active: &Policy,
+    current: Option<&Path>,
+    proposed: &Path,
+    path: &Path,
+    format: &OutputFormat,
+    fail_on_change: bool,
+) -> i32 {
+    let load = |file: &Path| {
+        let policy = load_policy(Some(file))?;
+        match policy.compile_errors().first() {
+            Some(error) => Err(format!("{}: {}", file.display(), error)),
+            None => Ok(policy),
+        }
+    };
+    let current = match current.map(load).transpose() {
+        Ok(policy) => policy.unwrap_or_else(|| active.clone()),
         Err(e) => {
-            eprintln!("Failed to read request file: {}", e);
-            return 3;
+            return CliError::new(
+                ErrorKind::Config,
+                format!("Failed to load current policy: {}", e),
+            )
+            .report(format);
         }
     };
-
-    let request: GatingRequest = match serde_json::from_str(&content) {
-        Ok(r) => r,
+    let proposed = match load(proposed) {
+        Ok(policy) => policy,
         Err(e) => {
-            eprintln!("Failed to parse request JSON: {}", e);
-            return 3;
+            return CliError::new(
+                ErrorKind::Config,
+                format!("Failed to load proposed policy: {}", e),
+            )
+            .report(format);
         }
     };
 
-    let runner = ContractRunner::new();
-    let decision = match runner.evaluate(&request) {
-        Ok(d) => d,
+    let impact = match Oracle::new(current).policy_impact(&Oracle::new(proposed), path) {
+        Ok(impact) => impact,
         Err(e) => {
-            eprintln!("Error evaluating request: {}", e);
-            return 3;
+            return CliError::new(ErrorKind::Input, format!("Error scanning directory: {}", e))
+                .report(format);
         }
     };
 
+    let label = |verdict: FileVerdict| match verdict {
+        FileVerdict::Compliant => "compliant",
+        FileVerdict::Concern => "concern",
+        FileVerdict::Violation => "violation",
+    };
     match format {
-        OutputFormat::Json => {
-            if include_audit {
-                let audit = runner.audit(&request, &decision);
-                #[derive(serde::Serialize)]
-                struct Output {
-                    decision: gating_contract::GatingDecision,
-                    audit: AuditEntry,
-                }
+        OutputFormat::Json => print_json(OutputSchema::PolicyImpact, &impact),
+        OutputFormat::Compact => {
+            for change in &impact.changes {
+                let direction = if change.proposed > change.current {
+                    "STRICTER"
+                } else {
+                    "LOOSER"
+                };
                 println!(
-                    "{}",
-                    serde_json::to_string_pretty(&Output {
-                        decision: decision.clone(),
-                        audit
-                    })
-                    .expect("invariant: JSON serialization of struct cannot fail")
+                    "{} {} {}->{}",
+                    direction,
+                    impact.relative_path(&change.file),
+                    label(change.current),
+                    label(change.proposed)
                 );
-            } else {
-                println!("{}", serde_json::to_string_pretty(&decision).expect("invariant: JSON serialization of struct cannot fail"));
             }
-        }
-        OutputFormat::Compact => {
-            let refusal_code = decision
-                .refusal
-                .as_ref()
-                .map(|r| r.code.numeric())
-                .unwrap_or(0);
             println!(
-                "verdict={:?} code={} duration={}μs",
-                decision.verdict, refusal_code, decision.processing.duration_us
+                "impact files={} changed={} stricter={} looser={}",
+                impact.files_scanned,
+                impact.changes.len(),
+                impact.stricter(),
+                impact.looser()
             );
         }
         OutputFormat::Text => {
-            println!("=== Gating Decision ===\n");
-            println!("Request ID:  {}", decision.request_id);
-            println!("Decision ID: {}", decision.decision_id);
-            println!("Verdict:     {:?}", decision.verdict);
-            println!("Duration:    {}μs", decision.processing.duration_us);
-
-            if let Some(ref refusal) = decision.refusal {
-                println!("\nRefusal Details:");
-                println!("  Category: {}", refusal.category.display_name());
-                println!("  Code:     {}", refusal.code.numeric());
-                println!("  Message:  {}", refusal.message);
-                if let Some(ref remediation) = refusal.remediation {
-                    println!("  Fix:      {}", remediation);
-                }
+            println!("=== Policy Impact ===\n");
+            println!("Path: {}", impact.path.display());
+            println!("Files scanned: {}", impact.files_scanned);
+            println!(
+                "Verdict changes: {} ({} stricter, {} looser)\n",
+                impact.changes.len(),
+                impact.stricter(),
+                impact.looser()
+            );
+            for change in &impact.changes {
+                let rules: Vec<String> = change
+                    .rules_added
+                    .iter()
+                    .map(|r| format!("+{}", r))
+                    .chain(change.rules_removed.iter().map(|r| format!("-{}", r)))
+                    .collect();
+                println!(
+                    "  {}: {} -> {} ({})",
+                    impact.relative_path(&change.file),
+                    label(change.current),
+                    label(change.proposed),
+                    rules.join(", ")
+                );
             }
-
-            if include_audit {
-                let audit = runner.audit(&request, &decision);
-                println!("\nAudit Log Entry:");
-                println!("{}", serde_json::to_string_pretty(&audit).expect("invariant: JSON serialization of struct cannot fail"));
+            if impact.changes.is_empty() {
+                println!("No file changes verdict.");
             }
         }
     }
 
-    decision.verdict.exit_code()
+    i32::from(fail_on_change && !impact.changes.is_empty())
 }
 
-fn show_contract_schema(format: &OutputFormat, section: Option<&str>) {
+// ============ Stats Functions ============
+
+/// Stats file written by `conative stats --save`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedStats {
+    created_at: chrono::DateTime<chrono::Utc>,
+    git_commit: Option<String>,
+    stats: RepoStats,
+}
+
+fn run_stats(
+    oracle: &Oracle,
+    path: &Path,
+    stats_file: &Path,
+    save: bool,
+    top: usize,
+    format: &OutputFormat,
+) -> i32 {
+    let stats = match oracle.repository_stats(path) {
+        Ok(s) => s,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error scanning directory: {}", e))
+                .report(format);
+        }
+    };
+
+    let previous: Option<SavedStats> = if stats_file.exists() {
+        let loaded = std::fs::read_to_string(stats_file)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        match loaded {
+            Ok(saved) => Some(saved),
+            Err(e) => {
+                return CliError::new(
+                    ErrorKind::Parse,
+                    format!("Failed to load stats {}: {}", stats_file.display(), e),
+                )
+                .with_hint("Run with --save to replace it")
+                .report(format);
+            }
+        }
+    } else {
+        None
+    };
+    let trend = previous.as_ref().map(|p| stats.trend(&p.stats));
+
     match format {
         OutputFormat::Json => {
-            #[derive(serde::Serialize)]
-            struct Schema {
-                version: &'static str,
-                schema: &'static str,
-                inputs: InputSchema,
-                outputs: OutputSchema,
-                refusal_codes: Vec<RefusalCodeInfo>,
-            }
+            let report = serde_json::json!({
+                "stats": &stats,
+                "previous": previous.as_ref().map(|p| serde_json::json!({
+                    "created_at": p.created_at,
+                    "git_commit": p.git_commit,
+                })),
+                "trend": &trend,
+            });
+            print_json(OutputSchema::Stats, &report);
+        }
+        OutputFormat::Compact => {
+            let delta = trend
+                .as_ref()
+                .map(|t| format!(" delta_violations={:+}", t.violations))
+                .unwrap_or_default();
+            println!(
+                "stats files={} violations={} concerns={} directories={}{}",
+                stats.files_scanned,
+                stats.violations,
+                stats.concerns,
+                stats.directories.len(),
+                delta
+            );
+        }
+        OutputFormat::Text => print_stats(&stats, previous.as_ref(), trend.as_ref(), top),
+    }
 
-            #[derive(serde::Serialize)]
-            struct InputSchema {
-                gating_request: Vec<&'static str>,
+    if save {
+        let saved = SavedStats {
+            created_at: chrono::Utc::now(),
+            git_commit: current_git_commit(),
+            stats,
+        };
+        if let Some(parent) = stats_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                return CliError::new(
+                    ErrorKind::Output,
+                    format!("Failed to create stats directory: {}", e),
+                )
+                .report(format);
             }
+        }
+        let json = serde_json::to_string_pretty(&saved)
+            .expect("invariant: JSON serialization of struct cannot fail");
+        if let Err(e) = std::fs::write(stats_file, json + "\n") {
+            return CliError::new(ErrorKind::Output, format!("Failed to write stats: {}", e))
+                .report(format);
+        }
+        if matches!(format, OutputFormat::Text) {
+            println!("Stats saved to: {}", stats_file.display());
+        }
+    }
 
-            #[derive(serde::Serialize)]
-            struct OutputSchema {
-                gating_decision: Vec<&'static str>,
-                verdicts: Vec<&'static str>,
-            }
+    0
+}
 
-            #[derive(serde::Serialize)]
-            struct RefusalCodeInfo {
-                code: u16,
-                name: &'static str,
-                category: &'static str,
-            }
+fn print_stats(
+    stats: &RepoStats,
+    previous: Option<&SavedStats>,
+    trend: Option<&StatsTrend>,
+    top: usize,
+) {
+    let change = |delta: Option<i64>| match delta {
+        Some(d) if d != 0 => format!(" ({:+})", d),
+        _ => String::new(),
+    };
 
-            let schema = Schema {
-                version: gating_contract::CONTRACT_VERSION,
-                schema: gating_contract::CONTRACT_SCHEMA,
-                inputs: InputSchema {
-                    gating_request: vec![
-                        "request_id: UUID",
-                        "timestamp: DateTime<Utc>",
-                        "proposal: Proposal",
-                        "context: RequestContext",
-                        "policy_override: Option<Policy>",
-                    ],
-                },
-                outputs: OutputSchema {
-                    gating_decision: vec![
-                        "request_id: UUID",
-                        "decision_id: UUID",
-                        "timestamp: DateTime<Utc>",
-                        "verdict: Verdict",
-                        "refusal: Option<Refusal>",
-                        "evaluations: EvaluationChain",
-                        "processing: ProcessingMetadata",
-                    ],
-                    verdicts: vec!["Allow", "Warn", "Escalate", "Block"],
-                },
-                refusal_codes: vec![
-                    RefusalCodeInfo {
-                        code: 100,
-                        name: "Lang100TypeScript",
-                        category: "ForbiddenLanguage",
-                    },
-                    RefusalCodeInfo {
-                        code: 101,
-                        name: "Lang101Python",
-                        category: "ForbiddenLanguage",
-                    },
-                    RefusalCodeInfo {
-                        code: 102,
-                        name: "Lang102Go",
-                        category: "ForbiddenLanguage",
-                    },
-                    RefusalCodeInfo {
-                        code: 103,
-                        name: "Lang103Java",
-                        category: "ForbiddenLanguage",
-                    },
-                    RefusalCodeInfo {
-                        code: 200,
-                        name: "Tool200NpmWithoutDeno",
-                        category: "ForbiddenToolchain",
-                    },
-                    RefusalCodeInfo {
-                        code: 300,
-                        name: "Sec300HardcodedSecret",
-                        category: "SecurityViolation",
-                    },
-                    RefusalCodeInfo {
-                        code: 500,
-                        name: "Spirit500Verbosity",
-                        category: "VerbositySmell",
-                    },
-                ],
-            };
+    println!("=== Conative Policy Posture ===\n");
+    println!(
+        "Files scanned: {}{}",
+        stats.files_scanned,
+        change(trend.map(|t| t.files_scanned))
+    );
+    println!(
+        "Violations: {}{}",
+        stats.violations,
+        change(trend.map(|t| t.violations))
+    );
+    println!(
+        "Concerns: {}{}\n",
+        stats.concerns,
+        change(trend.map(|t| t.concerns))
+    );
 
-            println!("{}", serde_json::to_string_pretty(&schema).expect("invariant: JSON serialization of struct cannot fail"));
+    println!("FILES BY TIER:");
+    for tier in ["tier1", "tier2", "forbidden", "unclassified"] {
+        let count = stats.tiers.get(tier).copied().unwrap_or(0);
+        let delta = trend.and_then(|t| t.tiers.get(tier).copied());
+        println!("  {:<13} {}{}", tier, count, change(delta));
+    }
+    println!();
+
+    if !stats.directories.is_empty() {
+        println!("VIOLATION DENSITY BY DIRECTORY:");
+        for dir in stats.directories.iter().take(top) {
+            println!(
+                "  {:>6.2}  {} ({} violations / {} files)",
+                dir.density, dir.path, dir.violations, dir.files
+            );
         }
-        OutputFormat::Compact | OutputFormat::Text => {
-            let show_all = section.is_none();
-            let section = section.unwrap_or("");
+        println!();
+    }
 
-            println!("=== Gating Contract Schema ===\n");
-            println!("Version: {}", gating_contract::CONTRACT_VERSION);
-            println!("Schema:  {}", gating_contract::CONTRACT_SCHEMA);
+    if !stats.rules.is_empty() {
+        println!("TOP RULES:");
+        for rule in stats.rules.iter().take(top) {
+            let delta = trend.and_then(|t| t.rules.get(&rule.rule).copied());
+            println!("  {:>5}  {}{}", rule.count, rule.rule, change(delta));
+        }
+        println!();
+    }
 
-            if show_all || section == "inputs" {
-                println!("\n--- INPUTS ---\n");
-                println!("GatingRequest:");
-                println!("  request_id:      UUID (unique request identifier)");
-                println!("  timestamp:       DateTime<Utc> (when request was created)");
-                println!("  proposal:        Proposal (action_type, content, files_affected)");
-                println!("  context:         RequestContext (source, session, repository)");
-                println!("  policy_override: Option<Policy> (custom policy if needed)");
-            }
+    match previous {
+        Some(p) => println!(
+            "Compared with stats from {}{}",
+            p.created_at.format("%Y-%m-%d %H:%M UTC"),
+            p.git_commit
+                .as_deref()
+                .map(|c| format!(" ({})", c))
+                .unwrap_or_default()
+        ),
+        None => println!("No saved stats to compare with. Run with --save to record these."),
+    }
+}
 
-            if show_all || section == "outputs" {
-                println!("\n--- OUTPUTS ---\n");
-                println!("GatingDecision:");
-                println!("  request_id:  UUID (correlation with request)");
-                println!("  decision_id: UUID (unique decision identifier)");
-                println!("  timestamp:   DateTime<Utc> (when decision was made)");
-                println!("  verdict:     Verdict (Allow | Warn | Escalate | Block)");
-                println!("  refusal:     Option<Refusal> (details if not allowed)");
-                println!("  evaluations: EvaluationChain (oracle, slm, arbiter results)");
-                println!("  processing:  ProcessingMetadata (duration, rules checked)");
-                println!("\nVerdicts:");
-                println!("  Allow    (0) - Proposal proceeds");
-                println!("  Warn     (2) - Proceed with warning");
-                println!("  Escalate (3) - Requires human review");
-                println!("  Block    (1) - Proposal rejected");
-            }
+// ============ Triage Functions ============
+
+/// A scan finding with the evidence shown in the triage view
+struct TriageItem {
+    rule: String,
+    /// Path relative to the scanned root, as the triage file keys it
+    file: String,
+    message: String,
+    severity: Option<Severity>,
+    /// Numbered source lines around the match
+    context: Vec<(usize, String)>,
+    /// One-based line of the match within `context`
+    line: Option<usize>,
+    remediation: Option<String>,
+}
 
-            if show_all || section == "refusals" {
-                println!("\n--- REFUSAL TAXONOMY ---\n");
-                println!("Hard Policy Violations (Oracle):");
-                println!("  100-199  ForbiddenLanguage   (TypeScript, Python, Go, Java...)");
-                println!("  200-299  ForbiddenToolchain  (npm without deno, yarn...)");
-                println!("  300-399  SecurityViolation   (hardcoded secrets, insecure hash...)");
-                println!("  400-499  ForbiddenPattern    (forbidden imports, unsafe blocks...)");
-                println!("\nSpirit Violations (SLM):");
-                println!("  500-599  SpiritViolation     (verbosity, over-documentation...)");
-                println!("\nSystem Codes:");
-                println!("  900-999  SystemError         (invalid request, rate limited...)");
-            }
+struct TriageApp {
+    items: Vec<TriageItem>,
+    triage: TriageFile,
+    state: ratatui::widgets::ListState,
+    dirty: bool,
+}
 
-            if show_all || section == "audit" {
-                println!("\n--- AUDIT LOG FORMAT ---\n");
-                println!("AuditEntry:");
-                println!("  schema:           String (contract schema identifier)");
-                println!("  audit_id:         UUID");
-                println!("  request_id:       UUID");
-                println!("  decision_id:      UUID");
-                println!("  timestamp:        DateTime<Utc>");
-                println!("  verdict:          Verdict");
-                println!("  refusal_code:     Option<u16>");
-                println!("  refusal_category: Option<RefusalCategory>");
-                println!("  source:           String");
-                println!("  repository:       Option<String>");
-                println!("  session_id:       Option<String>");
-                println!("  rules_checked:    Vec<String>");
-                println!("  rules_triggered:  Vec<String>");
-                println!("  duration_us:      u64");
-                println!("  contract_version: String");
-                println!("  content_hash:     String (SHA for verification)");
+impl TriageApp {
+    fn mark(&mut self, outcome: Option<TriageOutcome>) {
+        if let Some(item) = self.state.selected().and_then(|i| self.items.get(i)) {
+            self.triage.set(&item.rule, &item.file, outcome);
+            self.dirty = true;
+            self.state.select_next();
+        }
+    }
+}
+
+fn run_triage(oracle: &Oracle, path: &Path, triage_file: &Path) -> i32 {
+    use std::io::IsTerminal;
+    let format = &OutputFormat::Text;
+    if !std::io::stdout().is_terminal() {
+        return CliError::new(ErrorKind::Usage, "Triage needs an interactive terminal")
+            .with_hint("Use conative scan --format json for non-interactive output")
+            .report(format);
+    }
+
+    let triage = match TriageFile::load(triage_file) {
+        Ok(t) => t,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to load triage file: {}", e),
+            )
+            .report(format);
+        }
+    };
+    let result = match oracle.scan_directory(path) {
+        Ok(r) => r,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Error scanning directory: {}", e))
+                .report(format);
+        }
+    };
+
+    let items = triage_items(oracle, &result);
+    if items.is_empty() {
+        println!("No findings to triage.");
+        return 0;
+    }
+
+    let mut app = TriageApp {
+        items,
+        triage,
+        state: ratatui::widgets::ListState::default().with_selected(Some(0)),
+        dirty: false,
+    };
+    let mut terminal = ratatui::init();
+    let outcome = triage_loop(&mut terminal, &mut app);
+    ratatui::restore();
+
+    match outcome {
+        Ok(true) if app.dirty => {
+            if let Err(e) = app.triage.save(triage_file) {
+                return CliError::new(
+                    ErrorKind::Output,
+                    format!("Failed to write triage file: {}", e),
+                )
+                .report(format);
             }
+            println!(
+                "Saved {} triage outcomes to {}",
+                app.triage.entries.len(),
+                triage_file.display()
+            );
+            0
+        }
+        Ok(_) => 0,
+        Err(e) => {
+            CliError::new(ErrorKind::Internal, format!("Terminal error: {}", e)).report(format)
         }
     }
 }
 
-// ============ Red-Team Test Functions ============
-
-fn run_redteam_tests(
-    path: &Path,
-    format: &OutputFormat,
-    verbose: bool,
-    verbosity: &Verbosity,
-) -> i32 {
-    use std::collections::HashMap;
+fn triage_items(oracle: &Oracle, result: &DirectoryScanResult) -> Vec<TriageItem> {
+    const CONTEXT: usize = 3;
+    let mut sources = SourceCache::new(oracle);
+    let mut items: Vec<TriageItem> = result
+        .violations
+        .iter()
+        .map(|v| {
+            let (context, line) = match sources.locate(oracle, v) {
+                Some((text, range)) => {
+                    let (text, range) = masked_source(oracle, &v.violation, text, range);
+                    let (line, _) = line_column(&text, range.start);
+                    let context = text
+                        .lines()
+                        .enumerate()
+                        .skip(line.saturating_sub(CONTEXT))
+                        .take(CONTEXT * 2 + 1)
+                        .map(|(i, l)| (i + 1, l.to_string()))
+                        .collect();
+                    (context, Some(line + 1))
+                }
+                None => (Vec::new(), None),
+            };
+            TriageItem {
+                rule: v.violation.rule_id(),
+                file: result.relative_path(&v.file),
+                message: v.violation.clone().into_string(),
+                severity: Some(v.violation.severity()),
+                context,
+                line,
+                remediation: violation_remediation(oracle.policy(), &v.violation),
+            }
+        })
+        .collect();
+    items.extend(result.concerns.iter().map(|c| TriageItem {
+        rule: c.concern.rule_id(),
+        file: result.relative_path(&c.file),
+        message: c.concern.clone().into_string(),
+        severity: None,
+        context: Vec::new(),
+        line: None,
+        remediation: None,
+    }));
+    items
+}
 
-    let mut harness = TestHarness::new();
-    let test_cases = match load_redteam_cases(path, verbosity) {
-        Ok(cases) => cases,
-        Err(e) => {
-            eprintln!("Error loading red-team tests: {}", e);
-            return 3;
+/// Handle keys until the user quits; `true` means save
+fn triage_loop(
+    terminal: &mut ratatui::DefaultTerminal,
+    app: &mut TriageApp,
+) -> std::io::Result<bool> {
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    loop {
+        terminal.draw(|frame| draw_triage(frame, app))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') => return Ok(true),
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Down | KeyCode::Char('j') => app.state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => app.state.select_previous(),
+            KeyCode::Char('s') => app.mark(Some(TriageOutcome::Suppress)),
+            KeyCode::Char('f') => app.mark(Some(TriageOutcome::FixLater)),
+            KeyCode::Char('e') => app.mark(Some(TriageOutcome::Escalate)),
+            KeyCode::Char('u') => app.mark(None),
+            _ => {}
         }
+    }
+}
+
+fn draw_triage(frame: &mut ratatui::Frame, app: &mut TriageApp) {
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color, Style, Stylize};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, List, ListItem, Paragraph, Wrap};
+
+    let outcome_label = |outcome: Option<TriageOutcome>| match outcome {
+        Some(TriageOutcome::Suppress) => Span::from("suppress").dark_gray(),
+        Some(TriageOutcome::FixLater) => Span::from("fix later").yellow(),
+        Some(TriageOutcome::Escalate) => Span::from("escalate").magenta(),
+        None => Span::from("untriaged"),
     };
 
-    if test_cases.is_empty() {
-        eprintln!("No red-team test cases found in: {}", path.display());
-        return 3;
+    let [main, help] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [list_area, detail_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+
+    let rows: Vec<ListItem> = app
+        .items
+        .iter()
+        .map(|item| {
+            let mark = match app.triage.outcome(&item.rule, &item.file) {
+                Some(TriageOutcome::Suppress) => "S",
+                Some(TriageOutcome::FixLater) => "F",
+                Some(TriageOutcome::Escalate) => "E",
+                None => " ",
+            };
+            let name = Path::new(&item.file)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ListItem::new(format!("[{}] {} {}", mark, item.rule, name))
+        })
+        .collect();
+    let list = List::new(rows)
+        .block(Block::bordered().title(format!(" Findings ({}) ", app.items.len())))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut app.state);
+
+    let mut lines = Vec::new();
+    if let Some(item) = app.state.selected().and_then(|i| app.items.get(i)) {
+        let level = match &item.severity {
+            Some(severity) => Span::from(format!(
+                "error[{}]",
+                format!("{:?}", severity).to_lowercase()
+            ))
+            .red()
+            .bold(),
+            None => Span::from("warning").yellow().bold(),
+        };
+        lines.push(Line::from(vec![
+            level,
+            Span::from(": "),
+            Span::from(item.message.as_str()).bold(),
+        ]));
+        let location = match item.line {
+            Some(line) => format!("{}:{}", item.file, line),
+            None => item.file.clone(),
+        };
+        lines.push(Line::from(format!("--> {}", location)).blue());
+        lines.push(Line::from(vec![
+            Span::from(format!("rule: {}  outcome: ", item.rule)),
+            outcome_label(app.triage.outcome(&item.rule, &item.file)),
+        ]));
+        lines.push(Line::default());
+        let width = item
+            .context
+            .last()
+            .map(|(n, _)| n.to_string().len())
+            .unwrap_or(1);
+        for (number, text) in &item.context {
+            let row = Line::from(format!("{:>width$} | {}", number, text, width = width));
+            lines.push(if Some(*number) == item.line {
+                row.fg(Color::Red)
+            } else {
+                row
+            });
+        }
+        if let Some(remediation) = &item.remediation {
+            lines.push(Line::default());
+            lines.push(Line::from(format!("help: {}", remediation)).cyan());
+        }
     }
+    let detail = Paragraph::new(lines)
+        .block(Block::bordered().title(" Evidence "))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(detail, detail_area);
+
+    let keys =
+        "Up/Down move  s suppress  f fix later  e escalate  u clear  q save and quit  Esc discard";
+    frame.render_widget(Paragraph::new(keys).dark_gray(), help);
+}
 
-    if matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-        eprintln!("Running {} red-team tests...", test_cases.len());
+// ============ Init Wizard Functions ============
+
+/// Enforcement profiles offered by the init wizard:
+/// label, escalate threshold, block threshold, failure mode
+const ENFORCEMENT_PROFILES: [(&str, f64, f64, FailureMode); 3] = [
+    (
+        "strict - escalate early and block at 0.5",
+        0.3,
+        0.5,
+        FailureMode::FailClosed,
+    ),
+    (
+        "balanced - the RSR default thresholds",
+        0.4,
+        0.7,
+        FailureMode::FailClosed,
+    ),
+    (
+        "lenient - block only confident findings, fail open",
+        0.6,
+        0.85,
+        FailureMode::FailOpen,
+    ),
+];
+
+/// Prompt for languages, content rules, toolchain rules and enforcement
+/// profile, starting from `base`
+fn run_init_wizard(base: &Policy) -> dialoguer::Result<Policy> {
+    use dialoguer::theme::ColorfulTheme;
+    use dialoguer::{MultiSelect, Select};
+
+    let theme = ColorfulTheme::default();
+    let mut policy = base.clone();
+
+    // Previously forbidden languages that are now allowed land in tier 2
+    let languages = &base.languages;
+    let all: Vec<(&LanguageConfig, &str)> = languages
+        .tier1
+        .iter()
+        .map(|l| (l, "tier 1"))
+        .chain(languages.tier2.iter().map(|l| (l, "tier 2")))
+        .chain(languages.forbidden.iter().map(|l| (l, "forbidden")))
+        .collect();
+    let labels: Vec<String> = all
+        .iter()
+        .map(|(l, tier)| format!("{} ({})", l.name, tier))
+        .collect();
+    let defaults: Vec<bool> = all.iter().map(|(_, tier)| *tier != "forbidden").collect();
+    let allowed = MultiSelect::with_theme(&theme)
+        .with_prompt("Allowed languages (space toggles, enter confirms)")
+        .items(&labels)
+        .defaults(&defaults)
+        .interact()?;
+    policy.languages.tier1.clear();
+    policy.languages.tier2.clear();
+    policy.languages.forbidden.clear();
+    for (index, (lang, tier)) in all.iter().enumerate() {
+        let target = match (allowed.contains(&index), *tier) {
+            (true, "tier 1") => &mut policy.languages.tier1,
+            (true, _) => &mut policy.languages.tier2,
+            (false, _) => &mut policy.languages.forbidden,
+        };
+        target.push((*lang).clone());
+    }
+    let forbidden: Vec<String> = policy
+        .languages
+        .forbidden
+        .iter()
+        .map(|l| l.name.clone())
+        .collect();
+    policy
+        .languages
+        .exceptions
+        .retain(|e| forbidden.contains(&e.language));
+
+    let patterns = &base.patterns.forbidden_patterns;
+    if !patterns.is_empty() {
+        let labels: Vec<String> = patterns
+            .iter()
+            .map(|p| format!("{} - {}", p.name, p.reason))
+            .collect();
+        let kept = MultiSelect::with_theme(&theme)
+            .with_prompt("Secret and content rules")
+            .items(&labels)
+            .defaults(&vec![true; patterns.len()])
+            .interact()?;
+        policy.patterns.forbidden_patterns = kept.iter().map(|&i| patterns[i].clone()).collect();
     }
 
-    // Run all tests and collect results with category info
-    let mut category_results: HashMap<String, (Vec<bool>, Vec<bool>, Vec<bool>)> = HashMap::new();
-    let mut bypasses = Vec::new();
-    let mut false_positives = Vec::new();
+    let rules = &base.toolchain.rules;
+    if !rules.is_empty() {
+        let labels: Vec<String> = rules.iter().map(|r| r.describe()).collect();
+        let kept = MultiSelect::with_theme(&theme)
+            .with_prompt("Toolchain constraints")
+            .items(&labels)
+            .defaults(&vec![true; rules.len()])
+            .interact()?;
+        policy.toolchain.rules = kept.iter().map(|&i| rules[i].clone()).collect();
+    }
 
-    for (test, redteam_category, attack_vector, is_fp_check) in &test_cases {
-        let result = harness.run_test(test);
+    let labels: Vec<&str> = ENFORCEMENT_PROFILES
+        .iter()
+        .map(|(label, ..)| *label)
+        .collect();
+    let profile = Select::with_theme(&theme)
+        .with_prompt("Enforcement profile")
+        .items(&labels)
+        .default(1)
+        .interact()?;
+    let (_, escalate, block, failure_mode) = ENFORCEMENT_PROFILES[profile];
+    policy.enforcement.escalate_threshold = escalate;
+    policy.enforcement.block_threshold = block;
+    policy.enforcement.failure_mode = failure_mode;
+
+    Ok(policy)
+}
 
-        let cat_key = format!("{:?}", redteam_category);
-        let entry =
-            category_results
-                .entry(cat_key.clone())
-                .or_insert((Vec::new(), Vec::new(), Vec::new()));
+/// Render a policy as a plain Nickel record that exports back to the same JSON
+fn render_nickel_policy(policy: &Policy) -> String {
+    let value =
+        serde_json::to_value(policy).expect("invariant: JSON serialization of struct cannot fail");
+    let mut out = String::from(
+        "# Conative Policy generated by `conative init`\n\
+         # Field documentation lives in config/schema.ncl\n\n",
+    );
+    write_nickel_value(&value, 0, &mut out);
+    out.push('\n');
+    out
+}
 
-        if *is_fp_check {
-            // False positive check: should pass (Allow)
-            let is_fp = !result.passed && result.actual_verdict == Verdict::Block;
-            entry.2.push(is_fp);
-            if is_fp {
-                false_positives.push((test.name.clone(), attack_vector.clone()));
+fn write_nickel_value(value: &serde_json::Value, indent: usize, out: &mut String) {
+    use serde_json::Value;
+    let pad = "  ".repeat(indent + 1);
+    match value {
+        Value::Object(fields) => {
+            // Unset options are omitted; every optional field has a serde default
+            let fields: Vec<_> = fields.iter().filter(|(_, v)| !v.is_null()).collect();
+            if fields.is_empty() {
+                out.push_str("{}");
+                return;
             }
-        } else {
-            // Attack test: should block
-            let was_blocked = result.actual_verdict == Verdict::Block;
-            entry.0.push(was_blocked);
-            if !was_blocked {
-                bypasses.push((
-                    test.name.clone(),
-                    attack_vector.clone(),
-                    result.actual_verdict,
-                ));
-                entry.1.push(true);
+            out.push_str("{\n");
+            for (key, field) in fields {
+                let identifier = key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+                let key = if identifier {
+                    key.clone()
+                } else {
+                    nickel_string(key)
+                };
+                out.push_str(&format!("{}{} = ", pad, key));
+                write_nickel_value(field, indent + 1, out);
+                out.push_str(",\n");
+            }
+            out.push_str(&format!("{}}}", "  ".repeat(indent)));
+        }
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for item in items {
+                out.push_str(&pad);
+                write_nickel_value(item, indent + 1, out);
+                out.push_str(",\n");
             }
+            out.push_str(&format!("{}]", "  ".repeat(indent)));
         }
+        Value::String(s) => out.push_str(&nickel_string(s)),
+        other => out.push_str(&other.to_string()),
+    }
+}
 
-        if verbose && matches!(verbosity, Verbosity::Verbose | Verbosity::Debug) {
-            let status = if result.passed { "BLOCKED" } else { "BYPASS" };
-            eprintln!("  {} [{}] {}", status, cat_key, test.name);
+/// JSON string escapes are valid Nickel; only interpolation needs escaping
+fn nickel_string(s: &str) -> String {
+    serde_json::to_string(s)
+        .expect("invariant: JSON serialization of str cannot fail")
+        .replace("%{", "\\%{")
+}
+
+// ============ Layered Config Functions ============
+
+/// Keys accepted in global and repository `config.toml` files
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    policy_file: Option<PathBuf>,
+    verbosity: Option<String>,
+    no_color: Option<bool>,
+    no_slm_cache: Option<bool>,
+    slm_backend: Option<String>,
+    slm_model: Option<String>,
+    slm_endpoint: Option<String>,
+    slm_accelerator: Option<String>,
+    slm_gpu_layers: Option<u32>,
+    slm_threads: Option<u32>,
+    slm_ctx_size: Option<u32>,
+}
+
+/// A config file and the directory its relative paths resolve against
+struct ConfigLayer {
+    label: &'static str,
+    path: PathBuf,
+    base: PathBuf,
+    file: ConfigFile,
+}
+
+/// An effective setting and the layer it came from
+#[derive(serde::Serialize)]
+struct Setting {
+    name: &'static str,
+    value: Option<String>,
+    origin: String,
+}
+
+/// `$XDG_CONFIG_HOME/conative/config.toml`, falling back to `~/.config/conative/config.toml`
+fn global_config_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+        .map(|dir| dir.join("conative").join("config.toml"))
+}
+
+fn load_config_layer(
+    label: &'static str,
+    path: PathBuf,
+    base: PathBuf,
+) -> Result<Option<ConfigLayer>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(Some(ConfigLayer {
+        label,
+        path,
+        base,
+        file,
+    }))
+}
+
+fn parse_value_enum<T: ValueEnum>(value: &str, layer: &ConfigLayer) -> Result<T, String> {
+    T::from_str(value, true).map_err(|e| format!("{}: {}", layer.path.display(), e))
+}
+
+/// Where an effective setting came from
+enum Source<'a> {
+    Flag(String),
+    Env(String),
+    Layer(&'a ConfigLayer),
+    Default,
+}
+
+impl Source<'_> {
+    fn describe(&self) -> String {
+        match self {
+            Source::Flag(long) => format!("flag --{}", long),
+            Source::Env(var) => format!("env {}", var),
+            Source::Layer(layer) => format!("{} {}", layer.label, layer.path.display()),
+            Source::Default => "default".to_string(),
+        }
+    }
+}
+
+/// Fill settings not given as flags or environment variables from the
+/// repository and global config files, recording where each came from
+fn apply_config_layers(cli: &mut Cli, matches: &ArgMatches) -> Result<Vec<Setting>, String> {
+    let mut layers = Vec::new();
+    if !cli.config_from_env {
+        let repo = PathBuf::from(".conative/config.toml");
+        layers.extend(load_config_layer("repo config", repo, PathBuf::from("."))?);
+        if let Some(global) = global_config_path() {
+            let base = global.parent().map(Path::to_path_buf).unwrap_or_default();
+            layers.extend(load_config_layer("global config", global, base)?);
         }
     }
+    apply_layers(cli, matches, &layers)
+}
+
+/// Flags and environment variables win, then `layers` in order
+fn apply_layers(
+    cli: &mut Cli,
+    matches: &ArgMatches,
+    layers: &[ConfigLayer],
+) -> Result<Vec<Setting>, String> {
+    let command = Cli::command();
+    let resolve = |id: &str, set: &dyn Fn(&ConfigFile) -> bool| {
+        let arg = command.get_arguments().find(|a| a.get_id() == id);
+        match matches.value_source(id) {
+            Some(ValueSource::CommandLine) => {
+                Source::Flag(arg.and_then(|a| a.get_long()).unwrap_or(id).to_string())
+            }
+            Some(ValueSource::EnvVariable) => Source::Env(
+                arg.and_then(|a| a.get_env())
+                    .map(|e| e.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            ),
+            _ => layers
+                .iter()
+                .find(|layer| set(&layer.file))
+                .map(Source::Layer)
+                .unwrap_or(Source::Default),
+        }
+    };
 
-    // Build summary
-    let mut by_category: HashMap<String, CategoryStats> = HashMap::new();
-    let mut total_blocked = 0;
-    let mut total_bypassed = 0;
-    let mut total_fp = 0;
+    let mut settings = Vec::new();
+    let mut record = |name: &'static str, value: Option<String>, source: &Source| {
+        settings.push(Setting {
+            name,
+            value,
+            origin: source.describe(),
+        });
+    };
 
-    for (cat, (blocked, bypassed, fps)) in &category_results {
-        let blocked_count = blocked.iter().filter(|&&b| b).count();
-        let bypassed_count = bypassed.len();
-        let fp_count = fps.iter().filter(|&&f| f).count();
+    let source = resolve("policy_file", &|f| f.policy_file.is_some());
+    if let Source::Layer(layer) = source {
+        cli.policy_file = layer.file.policy_file.as_ref().map(|p| layer.base.join(p));
+    }
+    record(
+        "policy_file",
+        cli.policy_file.as_ref().map(|p| p.display().to_string()),
+        &source,
+    );
 
-        total_blocked += blocked_count;
-        total_bypassed += bypassed_count;
-        total_fp += fp_count;
+    let source = resolve("verbosity", &|f| f.verbosity.is_some());
+    if let Source::Layer(layer) = source {
+        cli.verbosity =
+            parse_value_enum(layer.file.verbosity.as_deref().unwrap_or_default(), layer)?;
+    }
+    record("verbosity", Some(value_name(&cli.verbosity)), &source);
 
-        by_category.insert(
-            cat.clone(),
-            CategoryStats {
-                total: blocked.len() + bypassed.len() + fps.len(),
-                blocked: blocked_count,
-                bypassed: bypassed_count,
-                false_positives: fp_count,
-            },
-        );
+    let source = resolve("no_color", &|f| f.no_color.is_some());
+    if let Source::Layer(layer) = source {
+        cli.no_color = layer.file.no_color.unwrap_or_default();
     }
+    record("no_color", Some(cli.no_color.to_string()), &source);
 
-    let total = test_cases.len();
-    let summary = RedTeamSummary {
-        total,
-        blocked: total_blocked,
-        bypassed: total_bypassed,
-        false_positives: total_fp,
-        known_limitations: 0, // Could be parsed from test metadata
-        by_category,
-        bypass_rate: if total > 0 {
-            total_bypassed as f64 / total as f64
-        } else {
-            0.0
-        },
-        false_positive_rate: if total > 0 {
-            total_fp as f64 / total as f64
-        } else {
-            0.0
-        },
+    let source = resolve("no_slm_cache", &|f| f.no_slm_cache.is_some());
+    if let Source::Layer(layer) = source {
+        cli.no_slm_cache = layer.file.no_slm_cache.unwrap_or_default();
+    }
+    record("no_slm_cache", Some(cli.no_slm_cache.to_string()), &source);
+
+    let source = resolve("slm_backend", &|f| f.slm_backend.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_backend = Some(parse_value_enum(
+            layer.file.slm_backend.as_deref().unwrap_or_default(),
+            layer,
+        )?);
+    }
+    record(
+        "slm_backend",
+        cli.slm_backend.as_ref().map(value_name),
+        &source,
+    );
+
+    let source = resolve("slm_model", &|f| f.slm_model.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_model = layer.file.slm_model.clone();
+    }
+    record("slm_model", cli.slm_model.clone(), &source);
+
+    let source = resolve("slm_endpoint", &|f| f.slm_endpoint.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_endpoint = layer.file.slm_endpoint.clone();
+    }
+    record("slm_endpoint", cli.slm_endpoint.clone(), &source);
+
+    let source = resolve("slm_accelerator", &|f| f.slm_accelerator.is_some());
+    if let Source::Layer(layer) = source {
+        let value = layer.file.slm_accelerator.as_deref().unwrap_or_default();
+        cli.slm_accelerator = Some(parse_value_enum(value, layer)?);
+    }
+    record(
+        "slm_accelerator",
+        cli.slm_accelerator.as_ref().map(value_name),
+        &source,
+    );
+
+    let source = resolve("slm_gpu_layers", &|f| f.slm_gpu_layers.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_gpu_layers = layer.file.slm_gpu_layers;
+    }
+    record(
+        "slm_gpu_layers",
+        cli.slm_gpu_layers.map(|n| n.to_string()),
+        &source,
+    );
+
+    let source = resolve("slm_threads", &|f| f.slm_threads.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_threads = layer.file.slm_threads;
+    }
+    record(
+        "slm_threads",
+        cli.slm_threads.map(|n| n.to_string()),
+        &source,
+    );
+
+    let source = resolve("slm_ctx_size", &|f| f.slm_ctx_size.is_some());
+    if let Source::Layer(layer) = source {
+        cli.slm_ctx_size = layer.file.slm_ctx_size;
+    }
+    record(
+        "slm_ctx_size",
+        cli.slm_ctx_size.map(|n| n.to_string()),
+        &source,
+    );
+
+    Ok(settings)
+}
+
+/// Name a value enum is written as on the command line
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn show_settings(settings: &[Setting], origin: bool, format: &OutputFormat) -> i32 {
+    match format {
+        OutputFormat::Json => {
+            let rows: Vec<serde_json::Value> = settings
+                .iter()
+                .map(|s| {
+                    let mut row = serde_json::json!({ "name": s.name, "value": s.value });
+                    if origin {
+                        row["origin"] = serde_json::json!(s.origin);
+                    }
+                    row
+                })
+                .collect();
+            print_json(
+                OutputSchema::Settings,
+                &serde_json::json!({ "settings": rows }),
+            );
+        }
+        OutputFormat::Text | OutputFormat::Compact => {
+            for s in settings {
+                let value = s.value.as_deref().unwrap_or("(unset)");
+                if origin {
+                    println!("{:<16} = {:<24} # {}", s.name, value, s.origin);
+                } else {
+                    println!("{:<16} = {}", s.name, value);
+                }
+            }
+        }
+    }
+    0
+}
+
+// ============ Fix Functions ============
+
+/// A file's content before and after its safe fixes; `None` deletes it
+struct FileChange<'a> {
+    file: &'a str,
+    before: String,
+    after: Option<String>,
+}
+
+fn run_fix(oracle: &Oracle, path: &Path, apply: bool, format: &OutputFormat) -> i32 {
+    let plan = match oracle.plan_fixes(path) {
+        Ok(plan) => plan,
+        Err(e) => {
+            return CliError::new(ErrorKind::Input, format!("Failed to plan fixes: {}", e))
+                .report(format);
+        }
     };
+    let (safe, review): (Vec<&QuickFix>, Vec<&QuickFix>) = plan.iter().partition(|f| f.is_safe());
+
+    let mut by_file: BTreeMap<&str, Vec<&QuickFix>> = BTreeMap::new();
+    for fix in &safe {
+        by_file.entry(fix.file.as_str()).or_default().push(fix);
+    }
+    let mut changes = Vec::new();
+    for (file, fixes) in by_file {
+        // Offsets are into UTF-8 text; transcoded files would be rewritten
+        let before = match read_text_file(Path::new(file)) {
+            Ok(decoded) if !decoded.is_unusual() => decoded.text,
+            Ok(decoded) => {
+                eprintln!("Skipping {}: {} content", file, decoded.encoding);
+                continue;
+            }
+            Err(e) => {
+                return CliError::new(ErrorKind::Input, format!("Failed to read {}: {}", file, e))
+                    .report(format);
+            }
+        };
+        let after = if fixes.iter().any(|f| f.edits.contains(&FixEdit::DeleteFile)) {
+            None
+        } else {
+            let edits: Vec<FixEdit> = fixes.iter().flat_map(|f| f.edits.iter().cloned()).collect();
+            Some(apply_fix_edits(&before, &edits))
+        };
+        changes.push(FileChange {
+            file,
+            before,
+            after,
+        });
+    }
 
+    let diff: String = changes.iter().map(fix_diff).collect();
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&summary).expect("invariant: JSON serialization of struct cannot fail"));
+            let report = serde_json::json!({
+                "applied": apply,
+                "fixes": safe,
+                "review": review,
+                "diff": diff,
+            });
+            print_json(OutputSchema::Fix, &report);
         }
         OutputFormat::Compact => {
             println!(
-                "redteam total={} blocked={} bypassed={} fps={} score={}",
-                summary.total,
-                summary.blocked,
-                summary.bypassed,
-                summary.false_positives,
-                summary.security_score()
+                "fixes={} files={} review={} applied={}",
+                safe.len(),
+                changes.len(),
+                review.len(),
+                apply
             );
         }
         OutputFormat::Text => {
-            println!("=== Red-Team Test Results ===\n");
-            println!("Total Tests:     {}", summary.total);
-            println!(
-                "Blocked:         {} ({:.1}%)",
-                summary.blocked,
-                (summary.blocked as f64 / summary.total as f64) * 100.0
-            );
-            println!(
-                "Bypassed:        {} ({:.1}%)",
-                summary.bypassed,
-                summary.bypass_rate * 100.0
-            );
-            println!(
-                "False Positives: {} ({:.1}%)",
-                summary.false_positives,
-                summary.false_positive_rate * 100.0
-            );
-            println!("\nSecurity Score:  {}/100", summary.security_score());
+            for line in diff.lines() {
+                let style = match line.chars().next() {
+                    Some('+') if !line.starts_with("+++") => "32",
+                    Some('-') if !line.starts_with("---") => "31",
+                    Some('@') => "36",
+                    _ => "1",
+                };
+                println!("{}", paint(style, line));
+            }
+            if !review.is_empty() {
+                println!("\nNeeds review (apply from an editor with `conative lsp`):");
+                for fix in &review {
+                    println!("  {} ({})", fix.title, fix.file);
+                }
+            }
+            if changes.is_empty() {
+                println!("No safe fixes to apply.");
+            } else if apply {
+                println!("\nApplied {} fixes to {} files.", safe.len(), changes.len());
+            } else {
+                println!(
+                    "\n{} fixes for {} files previewed; re-run with --apply to write them.",
+                    safe.len(),
+                    changes.len()
+                );
+            }
+        }
+    }
 
-            if !bypasses.is_empty() {
-                println!("\n--- Bypasses ---");
-                for (name, attack, verdict) in &bypasses {
-                    println!("  {} [{:?}]", name, verdict);
-                    if verbose {
-                        println!("    Attack: {}", attack);
+    if apply {
+        for change in &changes {
+            let written = match &change.after {
+                Some(after) => std::fs::write(change.file, after),
+                None => std::fs::remove_file(change.file),
+            };
+            if let Err(e) = written {
+                return CliError::new(
+                    ErrorKind::Output,
+                    format!("Failed to write {}: {}", change.file, e),
+                )
+                .report(format);
+            }
+        }
+    }
+    0
+}
+
+/// Unified diff of one file's change
+fn fix_diff(change: &FileChange) -> String {
+    let after = change.after.as_deref().unwrap_or_default();
+    let file = change.file.trim_start_matches("./");
+    let new_header = match change.after {
+        Some(_) => format!("b/{}", file),
+        None => "/dev/null".to_string(),
+    };
+    similar::TextDiff::from_lines(change.before.as_str(), after)
+        .unified_diff()
+        .header(&format!("a/{}", file), &new_header)
+        .to_string()
+}
+
+// ============ LSP Functions ============
+
+/// Command that shows a diagnostic's remediation in the editor
+const LSP_REMEDIATION_COMMAND: &str = "conative.remediation";
+
+/// Open documents and where the workspace is rooted
+struct LspServer<'a> {
+    oracle: &'a Oracle,
+    check_on: CheckOn,
+    root: Option<PathBuf>,
+    documents: HashMap<String, String>,
+}
+
+fn run_lsp(oracle: &Oracle, check_on: CheckOn) -> i32 {
+    let mut reader = std::io::stdin().lock();
+    let mut writer = std::io::stdout().lock();
+    let mut server = LspServer {
+        oracle,
+        check_on,
+        root: None,
+        documents: HashMap::new(),
+    };
+    let mut shutdown = false;
+
+    loop {
+        let body = match read_lsp_message(&mut reader) {
+            Ok(Some(body)) => body,
+            // A client that exits without shutting down is an error (LSP spec)
+            Ok(None) => return if shutdown { 0 } else { 1 },
+            Err(e) => {
+                return CliError::new(ErrorKind::Input, format!("LSP stream failed: {}", e))
+                    .report(&OutputFormat::Text)
+            }
+        };
+        let message: serde_json::Value = match serde_json::from_slice(&body) {
+            Ok(m) => m,
+            Err(e) => {
+                let reply = lsp_error(
+                    serde_json::Value::Null,
+                    -32700,
+                    &format!("Parse error: {}", e),
+                );
+                if let Err(e) = write_lsp_message(&mut writer, &reply) {
+                    return CliError::new(ErrorKind::Output, format!("LSP stream failed: {}", e))
+                        .report(&OutputFormat::Text);
+                }
+                continue;
+            }
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let mut outgoing = Vec::new();
+        match method {
+            "initialize" => {
+                server.root = params["rootUri"]
+                    .as_str()
+                    .or_else(|| params["workspaceFolders"][0]["uri"].as_str())
+                    .map(uri_path);
+                outgoing.push(lsp_result(id, lsp_capabilities()));
+            }
+            "shutdown" => {
+                shutdown = true;
+                outgoing.push(lsp_result(id, serde_json::Value::Null));
+            }
+            "exit" => return if shutdown { 0 } else { 1 },
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    server.documents.insert(uri.to_string(), text.to_string());
+                    outgoing.push(server.publish(uri));
+                }
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change carries the whole document
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    server.documents.insert(uri.to_string(), text.to_string());
+                    if server.check_on == CheckOn::Change {
+                        outgoing.push(server.publish(uri));
                     }
                 }
             }
-
-            if !false_positives.is_empty() {
-                println!("\n--- False Positives ---");
-                for (name, attack) in &false_positives {
-                    println!("  {}", name);
-                    if verbose {
-                        println!("    Attack: {}", attack);
+            "textDocument/didSave" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    if let Some(text) = params["text"].as_str() {
+                        server.documents.insert(uri.to_string(), text.to_string());
                     }
+                    outgoing.push(server.publish(uri));
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    server.documents.remove(uri);
+                    outgoing.push(server.publish(uri));
+                }
+            }
+            "textDocument/codeAction" => outgoing.push(lsp_result(id, lsp_code_actions(params))),
+            "workspace/executeCommand" if params["command"] == LSP_REMEDIATION_COMMAND => {
+                if let Some(remediation) = params["arguments"][0].as_str() {
+                    outgoing.push(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": "window/showMessage",
+                        "params": { "type": 3, "message": remediation },
+                    }));
+                }
+                outgoing.push(lsp_result(id, serde_json::Value::Null));
+            }
+            // Unknown notifications are ignored; unknown requests get an error
+            _ => {
+                if let (Some(id), false) = (id, method.is_empty()) {
+                    outgoing.push(lsp_error(
+                        id,
+                        -32601,
+                        &format!("Method not found: {}", method),
+                    ));
                 }
             }
+        }
+
+        for message in &outgoing {
+            if let Err(e) = write_lsp_message(&mut writer, message) {
+                return CliError::new(ErrorKind::Output, format!("LSP stream failed: {}", e))
+                    .report(&OutputFormat::Text);
+            }
+        }
+    }
+}
 
-            println!("\n--- By Category ---");
-            for (cat, stats) in &summary.by_category {
-                println!(
-                    "  {}: {} total, {} blocked, {} bypassed, {} fps",
-                    cat, stats.total, stats.blocked, stats.bypassed, stats.false_positives
+impl LspServer<'_> {
+    /// `publishDiagnostics` for a document; closed documents publish none
+    fn publish(&self, uri: &str) -> serde_json::Value {
+        let diagnostics = match self.documents.get(uri) {
+            Some(text) => self.diagnostics(uri, text),
+            None => Vec::new(),
+        };
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    fn diagnostics(&self, uri: &str, text: &str) -> Vec<serde_json::Value> {
+        let path = self.document_path(uri);
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::ModifyFile { path: path.clone() },
+            content: text.to_string(),
+            files_affected: vec![path],
+            llm_confidence: 1.0,
+        };
+        let evaluation = match self.oracle.check_proposal(&proposal) {
+            Ok(e) => e,
+            Err(e) => {
+                return vec![lsp_diagnostic(
+                    text,
+                    0..0,
+                    1,
+                    None,
+                    &format!("Policy check failed: {}", e),
+                    None,
+                )];
+            }
+        };
+
+        let violations = evaluation.violations.iter().map(|v| {
+            let refusal = Refusal::from_violation(self.oracle.policy(), &v.violation_type);
+            let range = self.oracle.locate_violation(&v.violation_type, text).unwrap_or(0..0);
+            let remediation = violation_remediation(self.oracle.policy(), &v.violation_type)
+                .or(refusal.remediation.map(|r| r.summary));
+            let mut diagnostic =
+                lsp_diagnostic(text, range, 1, Some(refusal.code), &refusal.message, remediation.as_deref());
+            let fixes: Vec<serde_json::Value> = self
+                .oracle
+                .quick_fixes(&v.violation_type, text)
+                .iter()
+                .map(|fix| serde_json::json!({ "title": fix.title, "edit": lsp_workspace_edit(uri, text, fix) }))
+                .collect();
+            if !fixes.is_empty() {
+                diagnostic["data"]["fixes"] = serde_json::json!(fixes);
+            }
+            diagnostic
+        });
+        let concerns = evaluation.concerns.iter().map(|c| {
+            let refusal = Refusal::from_concern(&c.concern_type);
+            lsp_diagnostic(
+                text,
+                0..0,
+                2,
+                Some(refusal.code),
+                &refusal.message,
+                Some(&c.suggestion),
+            )
+        });
+        violations.chain(concerns).collect()
+    }
+
+    /// Document path as the policy sees it: relative to the workspace root
+    fn document_path(&self, uri: &str) -> String {
+        let path = uri_path(uri);
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok());
+        relative.unwrap_or(&path).to_string_lossy().to_string()
+    }
+}
+
+fn lsp_capabilities() -> serde_json::Value {
+    serde_json::json!({
+        "capabilities": {
+            "textDocumentSync": {
+                "openClose": true,
+                "change": 1,
+                "save": { "includeText": true },
+            },
+            "codeActionProvider": { "codeActionKinds": ["quickfix"] },
+            "executeCommandProvider": { "commands": [LSP_REMEDIATION_COMMAND] },
+        },
+        "serverInfo": { "name": "conative", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// One diagnostic; the remediation rides in `data` for code actions
+fn lsp_diagnostic(
+    text: &str,
+    range: std::ops::Range<usize>,
+    severity: u8,
+    code: Option<RefusalCode>,
+    message: &str,
+    remediation: Option<&str>,
+) -> serde_json::Value {
+    let mut diagnostic = serde_json::json!({
+        "range": { "start": lsp_position(text, range.start), "end": lsp_position(text, range.end) },
+        "severity": severity,
+        "source": "conative",
+        "message": message,
+    });
+    if let Some(code) = code {
+        diagnostic["code"] = serde_json::json!(code.numeric());
+    }
+    if let Some(remediation) = remediation {
+        diagnostic["data"] = serde_json::json!({ "remediation": remediation });
+    }
+    diagnostic
+}
+
+/// Byte offset as an LSP position, whose column counts UTF-16 units
+fn lsp_position(text: &str, offset: usize) -> serde_json::Value {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let character = text[line_start..offset].encode_utf16().count();
+    serde_json::json!({ "line": line, "character": character })
+}
+
+/// Quick fixes for conative diagnostics: machine edits first, then the
+/// remediation advice
+fn lsp_code_actions(params: &serde_json::Value) -> serde_json::Value {
+    let diagnostics = params["context"]["diagnostics"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut actions = Vec::new();
+    for d in diagnostics.iter().filter(|d| d["source"] == "conative") {
+        for fix in d["data"]["fixes"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+        {
+            actions.push(serde_json::json!({
+                "title": fix["title"],
+                "kind": "quickfix",
+                "diagnostics": [d],
+                "edit": fix["edit"],
+            }));
+        }
+        if let Some(remediation) = d["data"]["remediation"].as_str() {
+            actions.push(serde_json::json!({
+                "title": remediation,
+                "kind": "quickfix",
+                "diagnostics": [d],
+                "command": {
+                    "title": remediation,
+                    "command": LSP_REMEDIATION_COMMAND,
+                    "arguments": [remediation],
+                },
+            }));
+        }
+    }
+    serde_json::json!(actions)
+}
+
+/// A quick fix as an LSP `WorkspaceEdit` on the open document
+fn lsp_workspace_edit(uri: &str, text: &str, fix: &QuickFix) -> serde_json::Value {
+    let range = |start: usize, end: usize| serde_json::json!({ "start": lsp_position(text, start), "end": lsp_position(text, end) });
+    let mut edits = Vec::new();
+    let mut operations = Vec::new();
+    for edit in &fix.edits {
+        match edit {
+            FixEdit::Replace {
+                start,
+                end,
+                text: new_text,
+            } => {
+                edits
+                    .push(serde_json::json!({ "range": range(*start, *end), "newText": new_text }));
+            }
+            // Lines count from 1; like `apply_fix_edits`, skip line 0
+            FixEdit::DeleteLine { line: 0 } => {}
+            FixEdit::DeleteLine { line } => {
+                let start = serde_json::json!({ "line": line - 1, "character": 0 });
+                let end = serde_json::json!({ "line": line, "character": 0 });
+                edits.push(
+                    serde_json::json!({ "range": { "start": start, "end": end }, "newText": "" }),
+                );
+            }
+            FixEdit::Rename { to, scaffold } => {
+                // Swap the differing suffix (the extension) on the URI
+                let common = fix
+                    .file
+                    .bytes()
+                    .zip(to.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let new_uri = match uri.strip_suffix(&fix.file[common..]) {
+                    Some(base) => format!("{}{}", base, &to[common..]),
+                    None => continue,
+                };
+                operations.push(
+                    serde_json::json!({ "kind": "rename", "oldUri": uri, "newUri": new_uri }),
                 );
+                operations.push(serde_json::json!({
+                    "textDocument": { "uri": new_uri, "version": null },
+                    "edits": [{ "range": range(0, text.len()), "newText": scaffold }],
+                }));
+            }
+            FixEdit::DeleteFile => {
+                operations.push(serde_json::json!({ "kind": "delete", "uri": uri }))
             }
         }
     }
-
-    if summary.has_unexpected_bypasses() {
-        1
+    if operations.is_empty() {
+        serde_json::json!({ "changes": { uri: edits } })
     } else {
-        0
+        serde_json::json!({ "documentChanges": operations })
     }
 }
 
-/// Load red-team test cases with metadata
-fn load_redteam_cases(
-    path: &Path,
-    verbosity: &Verbosity,
-) -> Result<Vec<(TestCase, RedTeamCategory, String, bool)>, String> {
-    let mut cases = Vec::new();
+fn lsp_result(id: Option<serde_json::Value>, result: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
 
-    if path.is_file() {
-        if let Some(case) = load_redteam_file(path)? {
-            cases.push(case);
-        }
-    } else if path.is_dir() {
-        for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let entry_path = entry.path();
+fn lsp_error(id: serde_json::Value, code: i32, message: &str) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
 
-            if entry_path.is_dir() {
-                cases.extend(load_redteam_cases(&entry_path, verbosity)?);
-            } else if entry_path.extension().map(|s| s == "json").unwrap_or(false) {
-                match load_redteam_file(&entry_path) {
-                    Ok(Some(case)) => cases.push(case),
-                    Ok(None) => {}
-                    Err(e) => {
-                        if matches!(verbosity, Verbosity::Debug) {
-                            eprintln!("Skipping {}: {}", entry_path.display(), e);
-                        }
-                    }
-                }
+/// `file://` URI as a local path, with percent escapes decoded
+fn uri_path(uri: &str) -> PathBuf {
+    let raw = uri.strip_prefix("file://").unwrap_or(uri);
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        let escaped = raw
+            .get(i..i + 3)
+            .filter(|s| s.starts_with('%'))
+            .and_then(|s| u8::from_str_radix(&s[1..], 16).ok());
+        match escaped {
+            Some(b) => {
+                bytes.push(b);
+                i += 3;
+            }
+            None => {
+                bytes.push(raw.as_bytes()[i]);
+                i += 1;
             }
         }
-    } else {
-        return Err(format!("Path does not exist: {}", path.display()));
     }
-
-    Ok(cases)
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
 }
 
-/// Load a single red-team test case
-fn load_redteam_file(
-    path: &Path,
-) -> Result<Option<(TestCase, RedTeamCategory, String, bool)>, String> {
-    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-
-    #[derive(serde::Deserialize)]
-    struct RedTeamData {
-        proposal: Proposal,
-        expected_verdict: String,
-        #[serde(default)]
-        reasoning: String,
-        #[serde(default)]
-        redteam_category: Option<String>,
-        #[serde(default)]
-        attack_vector: Option<String>,
+/// Body of the next `Content-Length` framed message, or `None` at end of input
+fn read_lsp_message(reader: &mut impl std::io::BufRead) -> std::io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
     }
+    let length = length.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        )
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
 
-    let data: RedTeamData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+fn write_lsp_message(
+    writer: &mut impl std::io::Write,
+    message: &serde_json::Value,
+) -> std::io::Result<()> {
+    let body = serde_json::to_string(message)
+        .expect("invariant: JSON serialization of struct cannot fail");
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
 
-    // Skip non-redteam tests
-    let redteam_cat = match &data.redteam_category {
-        Some(c) => RedTeamCategory::from_str(c),
-        None => return Ok(None),
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_exit_codes_do_not_overlap_verdicts() {
+        let kinds = [
+            ErrorKind::Internal,
+            ErrorKind::Usage,
+            ErrorKind::Config,
+            ErrorKind::Input,
+            ErrorKind::Parse,
+            ErrorKind::Output,
+            ErrorKind::Model,
+            ErrorKind::Auth,
+        ];
+        let verdicts = [
+            Verdict::Allow,
+            Verdict::Warn,
+            Verdict::Escalate,
+            Verdict::Block,
+        ];
+        let mut seen = std::collections::HashSet::new();
+        for kind in kinds {
+            let code = kind.exit_code();
+            assert!(seen.insert(code), "{:?} reuses exit code {}", kind, code);
+            for verdict in &verdicts {
+                assert_ne!(
+                    code,
+                    verdict.exit_code(),
+                    "{:?} shares an exit code with {:?}",
+                    kind,
+                    verdict
+                );
+            }
+        }
+    }
 
-    let expected_verdict = match data.expected_verdict.as_str() {
-        "Compliant" => Verdict::Allow,
-        "HardViolation" => Verdict::Block,
-        "SoftConcern" => Verdict::Warn,
-        other => return Err(format!("Unknown verdict: {}", other)),
-    };
+    #[test]
+    fn test_code_frame_masks_hardcoded_secret() {
+        let oracle = Oracle::with_rsr_defaults();
+        let content = "fn main() {}\nlet api_key = \"sk-live-0123456789\";\n"; // scanner-allow: rust-secrets
+        let violation = ViolationType::ForbiddenPattern {
+            pattern: "hardcoded_secrets".to_string(),
+            file: "src/config.rs".to_string(),
+        };
+        let range = oracle.locate_violation(&violation, content).unwrap();
+
+        let (shown, masked) = masked_source(&oracle, &violation, content, range.clone());
+        assert!(!shown.contains("0123456789"));
+        assert!(shown[masked.clone()].starts_with("ap") && shown[masked].contains('*'));
+        assert_eq!(
+            line_column(&shown, range.start),
+            line_column(content, range.start)
+        );
 
-    let is_fp_check = matches!(redteam_cat, RedTeamCategory::FalsePositiveCheck);
+        let message = masked_message(
+            &oracle,
+            &violation,
+            content,
+            range.clone(),
+            content[range].to_string(),
+        );
+        assert!(!message.contains("0123456789"));
+    }
 
-    let test_case = TestCase {
-        name: path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string(),
-        description: data.reasoning,
-        request: GatingRequest::new(data.proposal),
-        expected_verdict,
-        expected_category: None,
-        expected_code: None,
-    };
+    #[test]
+    fn test_fail_policy_exit_codes() {
+        let policy = |fail_on, max_violations| FailPolicy {
+            fail_on,
+            max_violations,
+        };
+        // (violations, concerns) -> exit code, per --fail-on level
+        let cases = [
+            (FailOn::Violation, [0, 0, 1, 1]),
+            (FailOn::Concern, [0, 2, 1, 1]),
+            (FailOn::Never, [0, 0, 0, 0]),
+        ];
+        for (fail_on, codes) in cases {
+            let fail = policy(Some(fail_on), 0);
+            let runs = [(0, 0), (0, 3), (2, 0), (2, 3)];
+            for ((violations, concerns), code) in runs.into_iter().zip(codes) {
+                assert_eq!(
+                    fail.exit_code(violations, concerns, FailOn::Concern),
+                    code,
+                    "{:?} with {} violations and {} concerns",
+                    fail_on,
+                    violations,
+                    concerns
+                );
+            }
+        }
 
-    Ok(Some((
-        test_case,
-        redteam_cat,
-        data.attack_vector.unwrap_or_default(),
-        is_fp_check,
-    )))
-}
+        // Without --fail-on the command's default applies
+        assert_eq!(policy(None, 0).exit_code(0, 3, FailOn::Violation), 0);
+        assert_eq!(policy(None, 0).exit_code(0, 3, FailOn::Concern), 2);
 
-// ============ Regression Test Functions ============
+        // Tolerated violations still fail as concerns unless only violations fail
+        assert_eq!(
+            policy(Some(FailOn::Concern), 2).exit_code(2, 0, FailOn::Concern),
+            2
+        );
+        assert_eq!(
+            policy(Some(FailOn::Violation), 2).exit_code(2, 0, FailOn::Concern),
+            0
+        );
+        assert_eq!(
+            policy(Some(FailOn::Violation), 2).exit_code(3, 0, FailOn::Concern),
+            1
+        );
+    }
 
-fn run_regression_tests(
-    path: &Path,
-    baseline_path: &Path,
-    save_baseline: bool,
-    format: &OutputFormat,
-    strict: bool,
-    verbosity: &Verbosity,
-) -> i32 {
-    // Run tests first
-    let mut harness = TestHarness::new();
-    let test_cases = match load_test_cases(path, verbosity) {
-        Ok(cases) => cases,
-        Err(e) => {
-            eprintln!("Error loading test cases: {}", e);
-            return 3;
+    #[test]
+    fn test_csv_field_quotes_and_neutralizes_formulas() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("cr\rhere"), "\"cr\rhere\"");
+        assert_eq!(csv_field(""), "");
+
+        for formula in ["=1+1", "+1", "-1", "@SUM(A1)", "\tx"] {
+            assert_eq!(csv_field(formula), format!("'{}", formula));
         }
-    };
+        assert_eq!(
+            csv_field("=HYPERLINK(\"x\",\"y\")"),
+            "\"'=HYPERLINK(\"\"x\"\",\"\"y\"\")\""
+        );
+        assert_eq!(csv_field("\r=1"), "\"'\r=1\"");
+        // Only a leading character counts
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
 
-    if test_cases.is_empty() {
-        eprintln!("No test cases found in: {}", path.display());
-        return 3;
+    /// Tests that set CONATIVE_* variables hold this while clap reads them
+    static ENV: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn parse_cli(args: &[&str]) -> (Cli, ArgMatches) {
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        (Cli::from_arg_matches(&matches).unwrap(), matches)
     }
 
-    for test in &test_cases {
-        harness.run_test(test);
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("conative-main-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
     }
 
-    let summary = harness.summary();
+    #[test]
+    fn test_flags_then_env_then_repo_then_global_config() {
+        let dir = temp_dir();
+        let repo = dir.join("repo.toml");
+        let global = dir.join("global.toml");
+        std::fs::write(
+            &repo,
+            "policy_file = \"repo-policy.json\"\nslm_model = \"repo-model\"\n\
+             slm_endpoint = \"http://repo/v1\"\nverbosity = \"verbose\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &global,
+            "slm_model = \"global-model\"\nverbosity = \"quiet\"\nslm_threads = 4\n",
+        )
+        .unwrap();
+        let layers: Vec<ConfigLayer> = [("repo config", &repo), ("global config", &global)]
+            .into_iter()
+            .map(|(label, path)| {
+                load_config_layer(label, path.clone(), dir.clone())
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect();
+
+        let _env = ENV.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("CONATIVE_SLM_ENDPOINT", "http://env/v1");
+        let (mut cli, matches) = parse_cli(&["conative", "--slm-model", "flag-model", "policy"]);
+        std::env::remove_var("CONATIVE_SLM_ENDPOINT");
+        let settings = apply_layers(&mut cli, &matches, &layers).unwrap();
+        let setting = |name: &str| {
+            let s = settings.iter().find(|s| s.name == name).unwrap();
+            (s.value.clone().unwrap_or_default(), s.origin.clone())
+        };
+
+        assert_eq!(
+            setting("slm_model"),
+            ("flag-model".to_string(), "flag --slm-model".to_string())
+        );
+        assert_eq!(
+            setting("slm_endpoint"),
+            (
+                "http://env/v1".to_string(),
+                "env CONATIVE_SLM_ENDPOINT".to_string()
+            )
+        );
+        assert_eq!(setting("verbosity").0, "verbose");
+        assert!(setting("verbosity").1.starts_with("repo config"));
+        assert_eq!(
+            cli.policy_file.as_deref(),
+            Some(dir.join("repo-policy.json").as_path())
+        );
+        assert_eq!(setting("slm_threads").0, "4");
+        assert!(setting("slm_threads").1.starts_with("global config"));
+        assert_eq!(
+            setting("no_color"),
+            ("false".to_string(), "default".to_string())
+        );
 
-    if save_baseline {
-        // Create directory if needed
-        if let Some(parent) = baseline_path.parent() {
-            if !parent.exists() {
-                if let Err(e) = std::fs::create_dir_all(parent) {
-                    eprintln!("Failed to create baseline directory: {}", e);
-                    return 3;
-                }
-            }
-        }
+        // A bad value in a file names the file
+        std::fs::write(&global, "slm_backend = \"bogus\"\n").unwrap();
+        let bad = load_config_layer("global config", global.clone(), dir.clone())
+            .unwrap()
+            .unwrap();
+        let (mut cli, matches) = parse_cli(&["conative", "policy"]);
+        let err = apply_layers(&mut cli, &matches, &[bad]).err().unwrap();
+        assert!(err.contains("global.toml"), "{}", err);
 
-        // Get git commit if available
-        let git_commit = std::process::Command::new("git")
-            .args(["rev-parse", "HEAD"])
-            .output()
-            .ok()
-            .and_then(|o| String::from_utf8(o.stdout).ok())
-            .map(|s| s.trim().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-        let baseline = RegressionBaseline::from_summary(&summary, git_commit);
-        match baseline.to_json() {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(baseline_path, &json) {
-                    eprintln!("Failed to write baseline: {}", e);
-                    return 3;
-                }
-                println!("Baseline saved to: {}", baseline_path.display());
-                println!(
-                    "Tests: {} total, {} passed, {} failed",
-                    summary.total, summary.passed, summary.failed
-                );
-                return 0;
-            }
-            Err(e) => {
-                eprintln!("Failed to serialize baseline: {}", e);
-                return 3;
-            }
-        }
+    #[test]
+    fn test_config_from_env_reads_policy_unless_a_file_is_named() {
+        let dir = temp_dir();
+        let named = |name: &str| {
+            let mut policy = serde_json::to_value(Policy::rsr_default()).unwrap();
+            policy["name"] = serde_json::json!(name);
+            policy.to_string()
+        };
+        let file = dir.join("policy.json");
+        std::fs::write(&file, named("from-file")).unwrap();
+        let file = file.to_str().unwrap();
+
+        let _env = ENV.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("CONATIVE_POLICY", named("from-env"));
+        let load = |args: &[&str]| load_cli_policy(&parse_cli(args).0).map(|p| p.name);
+        let from_env = load(&["conative", "--config-from-env", "policy"]);
+        let from_file = load(&["conative", "--config-from-env", "-p", file, "policy"]);
+        let ignored = load(&["conative", "policy"]);
+        std::env::set_var("CONATIVE_POLICY", "{not json");
+        let malformed = load(&["conative", "--config-from-env", "policy"]);
+        std::env::remove_var("CONATIVE_POLICY");
+
+        assert_eq!(from_env.unwrap(), "from-env");
+        assert_eq!(from_file.unwrap(), "from-file");
+        assert_eq!(ignored.unwrap(), Policy::rsr_default().name);
+        assert!(malformed.unwrap_err().starts_with("CONATIVE_POLICY:"));
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    // Compare against baseline
-    let mut reg_harness = RegressionHarness::new();
-    if baseline_path.exists() {
-        if let Err(e) = reg_harness.load_baseline(baseline_path) {
-            eprintln!("Failed to load baseline: {}", e);
-            eprintln!("Run with --save to create a new baseline");
-            return 3;
-        }
-    } else {
-        eprintln!("No baseline found at: {}", baseline_path.display());
-        eprintln!("Run with --save to create a new baseline");
-        return 3;
+    #[test]
+    fn test_malformed_env_value_is_a_usage_error() {
+        let _env = ENV.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("CONATIVE_SLM_BACKEND", "bogus");
+        let parsed = Cli::command().try_get_matches_from(["conative", "policy"]);
+        std::env::remove_var("CONATIVE_SLM_BACKEND");
+
+        let err = parsed.unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert_eq!(report_clap_error(err), ErrorKind::Usage.exit_code());
     }
 
-    reg_harness.add_results(summary.results.clone());
-    let report = reg_harness.compare();
+    /// A scanned tree with a secret in a Rust file and a Python file
+    fn scanned_tree(secret_line: usize) -> (Oracle, DirectoryScanResult, PathBuf) {
+        let dir = temp_dir();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let mut config = "// SPDX-License-Identifier: MPL-2.0\n".repeat(secret_line - 1);
+        config.push_str("let password = \"hunter2hunter2\";\n"); // scanner-allow: rust-secrets
+        std::fs::write(dir.join("src/config.rs"), config).unwrap();
+        std::fs::write(dir.join("tool.py"), "import os\n").unwrap();
+        let oracle = Oracle::with_rsr_defaults();
+        let result = oracle.scan_directory(&dir).unwrap();
+        (oracle, result, dir)
+    }
 
-    match format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&report).expect("invariant: JSON serialization of struct cannot fail"));
-        }
-        OutputFormat::Compact => {
-            println!(
-                "regression compared={} stable={} regressed={} improved={} changed={} new={} removed={}",
-                report.total_compared,
-                report.stable_count,
-                report.regressions.len(),
-                report.improvements.len(),
-                report.behavior_changes.len(),
-                report.new_tests.len(),
-                report.removed_tests.len()
+    #[test]
+    fn test_codequality_report_shape() {
+        let (oracle, result, dir) = scanned_tree(2);
+        let report = codequality_report(&oracle, &result);
+        assert_eq!(
+            report.len(),
+            result.violations.len() + result.concerns.len()
+        );
+        for issue in &report {
+            let keys: BTreeSet<&str> = issue
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .collect();
+            assert_eq!(
+                keys,
+                BTreeSet::from([
+                    "check_name",
+                    "description",
+                    "fingerprint",
+                    "location",
+                    "severity"
+                ])
             );
+            let fingerprint = issue["fingerprint"].as_str().unwrap();
+            assert_eq!(fingerprint.len(), 64);
+            assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+            assert!(["critical", "major", "minor", "info"]
+                .contains(&issue["severity"].as_str().unwrap()));
+            assert!(issue["location"]["lines"]["begin"].as_u64().unwrap() >= 1);
         }
-        OutputFormat::Text => {
-            println!("=== Regression Report ===\n");
-            println!("{}", report.summary_text());
-
-            if let Some(ref commit) = report.baseline_commit {
-                println!("\nBaseline commit: {}", commit);
-            }
-
-            if !report.regressions.is_empty() {
-                println!("\n--- REGRESSIONS ({}) ---", report.regressions.len());
-                for reg in &report.regressions {
-                    println!(
-                        "  {} [{:?} -> {:?}]",
-                        reg.test_name, reg.baseline_verdict, reg.current_verdict
-                    );
-                    if let Some(ref err) = reg.error_message {
-                        println!("    Error: {}", err);
-                    }
-                }
-            }
-
-            if !report.improvements.is_empty() {
-                println!("\n--- IMPROVEMENTS ({}) ---", report.improvements.len());
-                for imp in &report.improvements {
-                    println!(
-                        "  {} [{:?} -> {:?}]",
-                        imp.test_name, imp.baseline_verdict, imp.current_verdict
-                    );
-                }
-            }
 
-            if !report.behavior_changes.is_empty() {
-                println!(
-                    "\n--- BEHAVIOR CHANGES ({}) ---",
-                    report.behavior_changes.len()
-                );
-                for change in &report.behavior_changes {
-                    println!(
-                        "  {} [{:?} -> {:?}]",
-                        change.test_name, change.baseline_verdict, change.current_verdict
-                    );
-                }
-            }
+        let secret = report
+            .iter()
+            .find(|i| i["check_name"] == "pattern:hardcoded_secrets")
+            .unwrap();
+        assert_eq!(secret["location"]["path"], "src/config.rs");
+        assert_eq!(secret["location"]["lines"]["begin"], 2);
+        assert_eq!(secret["severity"], "major");
+        let python = report
+            .iter()
+            .find(|i| i["location"]["path"] == "tool.py" && i["severity"] == "critical");
+        assert!(python.is_some(), "{:#?}", report);
+
+        // Fingerprints follow the rule and path, not the line
+        let (oracle, moved, moved_dir) = scanned_tree(5);
+        let moved = codequality_report(&oracle, &moved);
+        let moved_secret = moved
+            .iter()
+            .find(|i| i["check_name"] == "pattern:hardcoded_secrets")
+            .unwrap();
+        assert_eq!(moved_secret["location"]["lines"]["begin"], 5);
+        assert_eq!(moved_secret["fingerprint"], secret["fingerprint"]);
+        let fingerprints: BTreeSet<&str> = report
+            .iter()
+            .map(|i| i["fingerprint"].as_str().unwrap())
+            .collect();
+        assert_eq!(fingerprints.len(), report.len());
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&moved_dir);
+    }
 
-            if !report.new_tests.is_empty() {
-                println!("\n--- NEW TESTS ({}) ---", report.new_tests.len());
-                for name in &report.new_tests {
-                    println!("  {}", name);
-                }
-            }
+    #[test]
+    fn test_gitea_review_shape() {
+        let (oracle, result, dir) = scanned_tree(3);
+        let review = gitea_review(&oracle, &result);
+        assert_eq!(review["event"], "REQUEST_CHANGES");
+        assert_eq!(
+            review["body"],
+            format!(
+                "Conative Gating: {} violations, {} concerns in {} files",
+                result.violations.len(),
+                result.concerns.len(),
+                result.files_scanned
+            )
+        );
+        let comments = review["comments"].as_array().unwrap();
+        assert_eq!(
+            comments.len(),
+            result.violations.len() + result.concerns.len()
+        );
+        for comment in comments {
+            let keys: BTreeSet<&str> = comment
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .collect();
+            assert_eq!(keys, BTreeSet::from(["body", "new_position", "path"]));
+            assert!(!comment["path"].as_str().unwrap().starts_with('/'));
+        }
+        let secret = comments
+            .iter()
+            .find(|c| c["path"] == "src/config.rs")
+            .unwrap();
+        assert_eq!(secret["new_position"], 3);
+        assert_eq!(
+            secret["body"],
+            "**high** `pattern:hardcoded_secrets`: Forbidden pattern 'hardcoded_secrets' detected"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Concerns alone only comment
+        let clean = DirectoryScanResult {
+            violations: Vec::new(),
+            ..result
+        };
+        assert_eq!(gitea_review(&oracle, &clean)["event"], "COMMENT");
+    }
 
-            if !report.removed_tests.is_empty() {
-                println!("\n--- REMOVED TESTS ({}) ---", report.removed_tests.len());
-                for name in &report.removed_tests {
-                    println!("  {}", name);
-                }
+    /// The JSON Schema keywords `json_schema` emits, checked on `value`
+    fn assert_matches_schema(value: &serde_json::Value, schema: &serde_json::Value) {
+        let object = value.as_object().expect("outputs are objects");
+        let properties = schema["properties"].as_object().unwrap();
+        for name in schema["required"].as_array().unwrap() {
+            assert!(
+                object.contains_key(name.as_str().unwrap()),
+                "missing {}",
+                name
+            );
+        }
+        for (name, field) in object {
+            let declared = properties
+                .get(name)
+                .unwrap_or_else(|| panic!("{} is not in the schema", name));
+            if let Some(constant) = declared.get("const") {
+                assert_eq!(field, constant, "{}", name);
+                continue;
             }
+            let types: Vec<&str> = match &declared["type"] {
+                serde_json::Value::String(t) => vec![t.as_str()],
+                several => several
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|t| t.as_str().unwrap())
+                    .collect(),
+            };
+            let fits = types.iter().any(|t| match *t {
+                "string" => field.is_string(),
+                "integer" => field.is_i64() || field.is_u64(),
+                "number" => field.is_number(),
+                "boolean" => field.is_boolean(),
+                "object" => field.is_object(),
+                "array" => field.is_array(),
+                "null" => field.is_null(),
+                other => panic!("unexpected type {}", other),
+            });
+            assert!(fits, "{} = {} is not {:?}", name, field, types);
+        }
+    }
 
-            if report.has_regressions() {
-                println!(
-                    "\nWARNING: {} regression(s) detected!",
-                    report.regressions.len()
-                );
-            } else if report.stable_count == report.total_compared {
-                println!("\nAll tests stable.");
-            }
+    #[test]
+    fn test_decision_output_matches_its_schema() {
+        let runner = ContractRunner::new();
+        let schema = OutputSchema::Decision.json_schema();
+        assert_eq!(schema["$id"], "conative-decision-v1");
+        for (path, content) in [
+            (
+                "src/lib.rs",
+                "// SPDX-License-Identifier: MPL-2.0\nfn main() {}\n",
+            ),
+            ("src/config.rs", "let password = \"hunter2hunter2\";\n"), // scanner-allow: rust-secrets
+            ("tool.py", "import os\n"),
+        ] {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            let decision = runner.evaluate(&GatingRequest::new(proposal)).unwrap();
+            let output = serde_json::to_value(Envelope {
+                schema: OutputSchema::Decision.id(),
+                payload: &decision,
+            })
+            .unwrap();
+            assert_matches_schema(&output, &schema);
         }
     }
 
-    if strict && report.has_regressions() {
-        1
-    } else if report.has_regressions() {
-        2 // Warning exit code
-    } else {
-        0
+    #[test]
+    fn test_common_directory() {
+        let set = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<BTreeSet<_>>();
+        assert_eq!(
+            common_directory(&set(&["src/lib.rs"])),
+            Some("src/".to_string())
+        );
+        assert_eq!(
+            common_directory(&set(&["src/a/x.rs", "src/a/y.rs", "src/b/z.rs"])),
+            Some("src/".to_string())
+        );
+        assert_eq!(
+            common_directory(&set(&["src/a/x.rs", "src/a/deep/y.rs"])),
+            Some("src/a/".to_string())
+        );
+        // Files at the root, or in unrelated directories, share no directory
+        assert_eq!(common_directory(&set(&["tool.py"])), None);
+        assert_eq!(common_directory(&set(&["src/lib.rs", "tool.py"])), None);
+        assert_eq!(
+            common_directory(&set(&["src/lib.rs", "scripts/x.py"])),
+            None
+        );
+        // A shared name prefix is not a shared directory
+        assert_eq!(common_directory(&set(&["src/x.rs", "src2/y.rs"])), None);
+        assert_eq!(common_directory(&BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn test_scan_groups() {
+        let dir = temp_dir();
+        let secret = "let password = \"hunter2hunter2\";\n"; // scanner-allow: rust-secrets
+        for (file, content) in [
+            ("scripts/a.py", "import os\n"),
+            ("scripts/deep/b.py", "import sys\n"),
+            ("src/config.rs", secret),
+            ("lib/keys.rs", secret),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let oracle = Oracle::with_rsr_defaults();
+        let groups = |group_by| {
+            let result = oracle.scan_directory(&dir).unwrap();
+            scan_groups(oracle.policy(), &result, group_by)
+        };
+        let summary = |groups: &[ScanGroup]| {
+            groups
+                .iter()
+                .map(|g| (g.key.clone(), g.findings, g.files, g.under.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        // Largest group first; the secrets span src/ and lib/, so no directory
+        assert_eq!(
+            summary(&groups(GroupBy::Language)),
+            [
+                ("python".to_string(), 2, 2, Some("scripts/".to_string())),
+                ("rust".to_string(), 2, 2, None),
+            ]
+        );
+        // One file per group never names a directory; ties keep key order
+        let by_file = groups(GroupBy::File);
+        assert_eq!(
+            by_file.iter().map(|g| g.key.as_str()).collect::<Vec<_>>(),
+            [
+                "lib/keys.rs",
+                "scripts/a.py",
+                "scripts/deep/b.py",
+                "src/config.rs"
+            ]
+        );
+        assert!(by_file.iter().all(|g| g.under.is_none() && g.files == 1));
+
+        // A file at the root leaves the Python group with no directory
+        std::fs::write(dir.join("tool.py"), "import re\n").unwrap();
+        let by_rule = groups(GroupBy::Rule);
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(
+            summary(&by_rule),
+            [
+                ("forbidden_language:python".to_string(), 3, 3, None),
+                ("pattern:hardcoded_secrets".to_string(), 2, 2, None),
+            ]
+        );
+        assert!(by_rule
+            .iter()
+            .all(|g| g.violations == g.findings && g.concerns == 0));
     }
 }
@@ -9,11 +9,24 @@
 #![forbid(unsafe_code)]
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::io::Read;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use thiserror::Error;
 use uuid::Uuid;
 
+mod rulepack;
+#[cfg(feature = "tree-sitter")]
+mod structure;
+
+pub use rulepack::{
+    ResolvedRulePack, RulePack, RulePackEntry, RulePackLanguages, RulePackPolicy, RulePackRef,
+    RulePackRegistry, MAX_PACK_BYTES,
+};
+
 // ============ Core Types ============
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -29,6 +42,9 @@ pub enum ViolationType {
         language: String,
         file: String,
         context: String,
+        /// How content detection scored, for findings from content
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detection: Option<LanguageScore>,
     },
     ForbiddenToolchain {
         tool: String,
@@ -41,6 +57,101 @@ pub enum ViolationType {
         pattern: String,
         file: String,
     },
+    /// An edit drops content the policy requires to stay
+    RequiredPatternRemoved {
+        pattern: String,
+        file: String,
+    },
+    /// Binary or encoded-archive content that text rules cannot inspect
+    OpaqueContent {
+        kind: String,
+        file: String,
+        detail: String,
+    },
+    /// A manifest changes in a proposal set without its lockfile
+    LockfileNotUpdated {
+        manifest: String,
+        lockfiles: Vec<String>,
+    },
+    /// Rust `unsafe` blocks the `unsafe_code` policy does not accept
+    UnsafeBlock {
+        file: String,
+        /// First and last line of each offending block
+        blocks: Vec<(usize, usize)>,
+        /// A `// SAFETY:` comment would make the blocks acceptable
+        needs_safety_comment: bool,
+    },
+    /// Imports banned by an `imports` rule
+    ForbiddenImport {
+        rule: String,
+        file: String,
+        /// Each banned module with the line importing it
+        imports: Vec<(String, usize)>,
+    },
+}
+
+impl ViolationType {
+    /// Rule identifier, matching [`Policy::rule_ids`] where the rule is configurable
+    pub fn rule_id(&self) -> String {
+        match self {
+            ViolationType::ForbiddenLanguage { language, .. } => {
+                format!("forbidden_language:{}", language)
+            }
+            ViolationType::ForbiddenToolchain { tool, missing } => {
+                format!("toolchain:{}:{}", tool, missing)
+            }
+            ViolationType::SecurityViolation { .. } => "security".to_string(),
+            ViolationType::ForbiddenPattern { pattern, .. } => format!("pattern:{}", pattern),
+            ViolationType::RequiredPatternRemoved { pattern, .. } => {
+                format!("required:{}", pattern)
+            }
+            ViolationType::OpaqueContent { .. } => "opaque_content".to_string(),
+            ViolationType::LockfileNotUpdated { manifest, .. } => {
+                format!("lockfile:{}", file_name(manifest))
+            }
+            ViolationType::UnsafeBlock { .. } => "unsafe_blocks".to_string(),
+            ViolationType::ForbiddenImport { rule, .. } => format!("import:{}", rule),
+        }
+    }
+
+    /// Severity the oracle assigns to this kind of violation
+    pub fn severity(&self) -> Severity {
+        match self {
+            ViolationType::ForbiddenLanguage { .. } | ViolationType::SecurityViolation { .. } => {
+                Severity::Critical
+            }
+            _ => Severity::High,
+        }
+    }
+}
+
+/// One machine-applicable change to a file's original content
+///
+/// Edits within a fix never overlap; apply them from the end of the file
+/// backwards so earlier offsets and line numbers stay valid.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FixEdit {
+    /// Replace bytes `start..end` with `text`
+    Replace {
+        start: usize,
+        end: usize,
+        text: String,
+    },
+    /// Delete a line (1-based) and its newline
+    DeleteLine { line: usize },
+    /// Move the file to `to`, replacing its content with `scaffold`
+    Rename { to: String, scaffold: String },
+    /// Delete the file
+    DeleteFile,
+}
+
+/// Titled edits to one file that resolve a violation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuickFix {
+    pub title: String,
+    pub file: String,
+    pub edits: Vec<FixEdit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -48,7 +159,40 @@ pub enum ConcernType {
     VerbositySmell,
     PatternDeviation,
     UnusualStructure,
-    Tier2Language { language: String },
+    Tier2Language {
+        language: String,
+    },
+    /// Content was transcoded from a non-UTF-8 encoding
+    UnusualEncoding {
+        encoding: String,
+    },
+    /// Content scored close to, but under, a forbidden language's threshold
+    AmbiguousLanguage {
+        language: String,
+        score: LanguageScore,
+    },
+    /// A string literal built from many short fragments
+    SuspiciousObfuscation {
+        fragments: usize,
+        line: usize,
+    },
+}
+
+impl ConcernType {
+    /// Rule identifier for the check that raised this concern
+    pub fn rule_id(&self) -> String {
+        match self {
+            ConcernType::VerbositySmell => "verbosity".to_string(),
+            ConcernType::PatternDeviation => "pattern_deviation".to_string(),
+            ConcernType::UnusualStructure => "unusual_structure".to_string(),
+            ConcernType::Tier2Language { language } => format!("tier2_language:{}", language),
+            ConcernType::UnusualEncoding { .. } => "content_encoding".to_string(),
+            ConcernType::AmbiguousLanguage { language, .. } => {
+                format!("forbidden_language:{}", language)
+            }
+            ConcernType::SuspiciousObfuscation { .. } => "string_fragmentation".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,12 +204,51 @@ pub struct Proposal {
     pub llm_confidence: f32,
 }
 
+/// Changes to several files proposed as one unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalSet {
+    pub id: Uuid,
+    /// New content keyed by path
+    pub files: BTreeMap<String, String>,
+    pub llm_confidence: f32,
+}
+
+impl ProposalSet {
+    /// One `ModifyFile` proposal per file, in path order
+    pub fn proposals(&self) -> Vec<Proposal> {
+        self.files
+            .iter()
+            .map(|(path, content)| Proposal {
+                id: self.id,
+                action_type: ActionType::ModifyFile { path: path.clone() },
+                content: content.clone(),
+                files_affected: vec![path.clone()],
+                llm_confidence: self.llm_confidence,
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionType {
-    CreateFile { path: String },
-    ModifyFile { path: String },
-    DeleteFile { path: String },
-    ExecuteCommand { command: String },
+    CreateFile {
+        path: String,
+    },
+    ModifyFile {
+        path: String,
+    },
+    DeleteFile {
+        path: String,
+    },
+    ExecuteCommand {
+        command: String,
+    },
+    /// Replace a file's content: `before` is the current content and the
+    /// proposal's `content` the new one
+    EditFile {
+        path: String,
+        before: String,
+    },
 }
 
 // ============ Policy Configuration ============
@@ -77,6 +260,36 @@ pub struct Policy {
     pub toolchain: ToolchainPolicy,
     pub patterns: PatternPolicy,
     pub enforcement: EnforcementConfig,
+    /// Write rules for protected paths and branches
+    #[serde(default)]
+    pub protected: ProtectedPolicy,
+    /// Handling of binary and embedded-archive content
+    #[serde(default)]
+    pub binary: BinaryPolicy,
+    /// Folding of string literals split by concatenation
+    #[serde(default)]
+    pub obfuscation: ObfuscationPolicy,
+    /// Handling of `unsafe` blocks in Rust proposals
+    #[serde(default)]
+    pub unsafe_code: UnsafePolicy,
+    /// Modules and crates proposals must not import
+    #[serde(default)]
+    pub imports: ImportPolicy,
+    /// How proposal paths are compared against policy paths
+    #[serde(default)]
+    pub paths: PathPolicy,
+    /// Limits for reading file content during directory scans
+    #[serde(default)]
+    pub scan: ScanPolicy,
+    /// Extra rules for repositories without history
+    #[serde(default)]
+    pub new_repository: NewRepositoryPolicy,
+    /// Rules keyed on request metadata (tickets, environments)
+    #[serde(default)]
+    pub conditions: ConditionalPolicy,
+    /// Versioned rule bundles merged in by [`Policy::resolve_rulepacks`]
+    #[serde(default)]
+    pub rulepacks: RulePackPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -85,6 +298,9 @@ pub struct LanguagePolicy {
     pub tier2: Vec<LanguageConfig>,
     pub forbidden: Vec<LanguageConfig>,
     pub exceptions: Vec<ExceptionRule>,
+    /// Scoring of forbidden languages found in content
+    #[serde(default)]
+    pub detection: LanguageDetection,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,9 +308,131 @@ pub struct LanguageConfig {
     pub name: String,
     pub extensions: Vec<String>,
     pub markers: Vec<String>,
+    /// Interpreters that mark a `#!` line as this language, e.g. `python`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shebangs: Vec<String>,
+    /// Refusal code reported instead of the built-in language code: a
+    /// language code (100-199) or a custom one (1000-9999)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+    /// Tier 1 language suggested in place of this one when it is forbidden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alternative: Option<LanguageAlternative>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl LanguageConfig {
+    /// Remediation advice for porting away from this language, when the
+    /// policy names an alternative
+    pub fn alternative_summary(&self) -> Option<String> {
+        self.alternative.as_ref().map(|alt| {
+            alt.summary
+                .clone()
+                .unwrap_or_else(|| format!("Use {} instead of {}", alt.language, self.name))
+        })
+    }
+}
+
+/// Language a forbidden language's files should be ported to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageAlternative {
+    /// Name of the suggested language, matching a `tier1` entry
+    pub language: String,
+    /// Advice shown in remediation, "Use <language> instead of <name>" if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Weights for the signals that content is in a forbidden language
+///
+/// Signals add up to a score clamped to 0.0-1.0. At `block_threshold` or
+/// over, the language is a violation; within `ambiguity_margin` under it,
+/// a concern, so the `concern` action decides between warning and
+/// escalating. A file extension alone is left to the extension rule, and
+/// markers alone never count against a Tier 1 or Tier 2 extension.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageDetection {
+    /// First distinct marker found
+    #[serde(default = "default_marker_weight")]
+    pub marker: f64,
+    /// Each further distinct marker
+    #[serde(default = "default_extra_marker_weight")]
+    pub extra_marker: f64,
+    /// An affected file has one of the language's extensions
+    #[serde(default = "default_extension_weight")]
+    pub extension: f64,
+    /// The first line is a `#!` naming one of the language's interpreters
+    #[serde(default = "default_shebang_weight")]
+    pub shebang: f64,
+    /// A grammar parsed the content as the language (`tree-sitter` feature)
+    #[serde(default = "default_structure_weight")]
+    pub structure: f64,
+    /// Subtracted, besides the markers' weight, when an affected file has
+    /// a Tier 1 or Tier 2 extension
+    #[serde(default = "default_allowed_extension_weight")]
+    pub allowed_extension: f64,
+    #[serde(default = "default_detection_threshold")]
+    pub block_threshold: f64,
+    #[serde(default = "default_ambiguity_margin")]
+    pub ambiguity_margin: f64,
+}
+
+impl Default for LanguageDetection {
+    fn default() -> Self {
+        Self {
+            marker: default_marker_weight(),
+            extra_marker: default_extra_marker_weight(),
+            extension: default_extension_weight(),
+            shebang: default_shebang_weight(),
+            structure: default_structure_weight(),
+            allowed_extension: default_allowed_extension_weight(),
+            block_threshold: default_detection_threshold(),
+            ambiguity_margin: default_ambiguity_margin(),
+        }
+    }
+}
+
+fn default_marker_weight() -> f64 {
+    0.6
+}
+
+fn default_extra_marker_weight() -> f64 {
+    0.2
+}
+
+fn default_extension_weight() -> f64 {
+    0.4
+}
+
+fn default_shebang_weight() -> f64 {
+    0.6
+}
+
+fn default_structure_weight() -> f64 {
+    0.6
+}
+
+fn default_allowed_extension_weight() -> f64 {
+    0.3
+}
+
+fn default_detection_threshold() -> f64 {
+    0.6
+}
+
+fn default_ambiguity_margin() -> f64 {
+    0.3
+}
+
+/// Weighted evidence that content is written in one language
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageScore {
+    /// Sum of the signal weights, clamped to 0.0-1.0
+    pub score: f64,
+    /// Each signal that counted, with its weight
+    pub signals: Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExceptionRule {
     pub language: String,
     pub allowed_paths: Vec<String>,
@@ -104,19 +442,77 @@ pub struct ExceptionRule {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ToolchainPolicy {
     pub rules: Vec<ToolchainRule>,
+    /// Manifests that must change together with a lockfile
+    #[serde(default)]
+    pub lockfiles: Vec<LockfileRule>,
+}
+
+/// A manifest whose changes in a proposal set must come with a change to
+/// one of its lockfiles, in the manifest's directory or an ancestor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockfileRule {
+    /// Manifest file name, e.g. `Cargo.toml`
+    pub manifest: String,
+    pub lockfiles: Vec<String>,
+    /// Regex the new manifest must match for the rule to apply, e.g. a
+    /// dependency section; every change applies if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<String>,
 }
 
+/// A tool that may only be used alongside another, or not at all
+///
+/// Each side is matched by two marker groups: any one of `*_markers` (OR)
+/// and every one of `*_markers_all` (AND); an empty group imposes nothing.
+/// A rule with no requirement markers forbids the tool outright.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolchainRule {
     pub tool: String,
     pub tool_markers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_markers_all: Vec<String>,
+    /// Companion the tool needs; empty when the tool is forbidden
+    #[serde(default)]
     pub requires: String,
+    #[serde(default)]
     pub requires_markers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_markers_all: Vec<String>,
+    /// Paths where the tool may be used without its companion
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_paths: Vec<String>,
+    /// Refusal code reported instead of the generic toolchain code: a
+    /// toolchain code (200-299) or a custom one (1000-9999)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+}
+
+impl ToolchainRule {
+    /// Whether the rule forbids the tool rather than requiring a companion
+    pub fn forbids(&self) -> bool {
+        self.requires_markers.is_empty() && self.requires_markers_all.is_empty()
+    }
+
+    /// One-line summary, e.g. `npm requires deno`
+    pub fn describe(&self) -> String {
+        match (self.forbids(), self.allowed_paths.is_empty()) {
+            (true, true) => format!("{} is forbidden", self.tool),
+            (true, false) => format!(
+                "{} is forbidden outside {}",
+                self.tool,
+                self.allowed_paths.join(", ")
+            ),
+            (false, _) => format!("{} requires {}", self.tool, self.requires),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PatternPolicy {
     pub forbidden_patterns: Vec<ForbiddenPattern>,
+    /// Content that edits must not remove once present
+    #[serde(default)]
+    pub required_patterns: Vec<RequiredPattern>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,388 +521,4623 @@ pub struct ForbiddenPattern {
     pub regex: String,
     pub file_types: Vec<String>,
     pub reason: String,
+    /// Replacement for each match, offered as a quick fix (`$1` for capture
+    /// groups); without one the fix deletes the matching lines
+    #[serde(default)]
+    pub fix: Option<String>,
+    /// Refusal code reported instead of the generic pattern code: a
+    /// security (300-399) or pattern (400-499) code, or a custom one
+    /// (1000-9999) named after this rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u16>,
+    /// Offer no quick fix, for matches that need rewriting by hand
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_fix: bool,
+    /// Regexes exempting a match: text they match within the match found
+    /// by `regex` is allowed, e.g. `^http://internal\.example/`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EnforcementConfig {
-    pub slm_weight: f64,
-    pub escalate_threshold: f64,
-    pub block_threshold: f64,
-}
+impl ForbiddenPattern {
+    /// Security rule matching every file type, fixed by hand
+    fn security(name: &str, regex: &str, reason: &str, code: u16) -> Self {
+        Self {
+            name: name.to_string(),
+            regex: regex.to_string(),
+            file_types: vec!["*".to_string()],
+            reason: reason.to_string(),
+            fix: None,
+            code: Some(code),
+            no_fix: true,
+            allow: Vec::new(),
+        }
+    }
 
-impl Default for EnforcementConfig {
-    fn default() -> Self {
+    /// The same rule restricted to files ending in one of `file_types`
+    fn for_files(self, file_types: &[&str]) -> Self {
         Self {
-            slm_weight: 1.5,
-            escalate_threshold: 0.4,
-            block_threshold: 0.7,
+            file_types: file_types.iter().map(|t| t.to_string()).collect(),
+            ..self
         }
     }
-}
 
-// ============ Evaluation Results ============
+    /// Plaintext `http://` URLs in source and config files, except
+    /// loopback addresses and the XML, JSON Schema and vocabulary
+    /// identifiers that are never fetched
+    pub fn plaintext_http() -> Self {
+        Self {
+            allow: [
+                r"^xmlns",
+                r"^http://(localhost|127\.\d+\.\d+\.\d+|0\.0\.0\.0|\[::1\])(:\d+)?([/?#]|$)",
+                r"^http://(www\.)?w3\.org/",
+                r"^http://schemas\.",
+                r"^http://purl\.org/",
+                r"^http://json-schema\.org/",
+            ]
+            .iter()
+            .map(|a| a.to_string())
+            .collect(),
+            ..Self::security(
+                "plaintext_http",
+                r#"(xmlns(:[\w.-]+)?\s*=\s*["'])?http://[^\s"'<>)\]]+"#,
+                "Plaintext HTTP can be read and altered in transit; use https://",
+                302,
+            )
+            .for_files(&[
+                ".rs",
+                ".ts",
+                ".tsx",
+                ".js",
+                ".mjs",
+                ".res",
+                ".gleam",
+                ".ex",
+                ".exs",
+                ".ml",
+                ".py",
+                ".go",
+                ".java",
+                ".kt",
+                ".rb",
+                ".php",
+                ".sh",
+                ".c",
+                ".cpp",
+                ".h",
+                ".json",
+                ".toml",
+                ".yaml",
+                ".yml",
+                ".xml",
+                ".ini",
+                ".cfg",
+                ".conf",
+                ".env",
+                ".properties",
+                ".ncl",
+                ".nix",
+                ".tf",
+                "Dockerfile",
+            ])
+        }
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OracleEvaluation {
-    pub proposal_id: Uuid,
-    pub verdict: PolicyVerdict,
-    pub rules_checked: Vec<String>,
-    pub violations: Vec<Violation>,
-    pub concerns: Vec<Concern>,
+    /// Built-in pack for shell commands and SQL assembled from strings
+    ///
+    /// Each language gets its own rules, scoped by `file_types`, so a
+    /// Python idiom is never matched against Rust and vice versa.
+    pub fn injection() -> Vec<Self> {
+        const SHELL: &str = "Pass arguments to the program directly instead of through a shell";
+        const SQL: &str = "Use bound query parameters instead of building SQL from strings";
+        const JS: &[&str] = &[".js", ".mjs", ".cjs", ".ts", ".tsx"];
+        vec![
+            Self::security(
+                "shell_command_rust",
+                r#"(?s)Command::new\(\s*"(sh|bash|zsh|cmd(\.exe)?|powershell)"\s*\).{0,200}?(\.arg\(\s*"(-c|/C)"\s*\)\s*\.arg\(|\.args\(\s*&?\[\s*"(-c|/C)"\s*,)\s*&?(format!|[A-Za-z_])"#,
+                SHELL,
+                303,
+            )
+            .for_files(&[".rs"]),
+            Self::security(
+                "shell_command_python",
+                r#"\bos\.(system|popen)\(\s*(f["']|["'][^"'\n]*["']\s*(%|\+|\.format\())|\bsubprocess\.(run|call|Popen|check_call|check_output)\(\s*(f["']|["'][^"'\n]*["']\s*(%|\+|\.format\())[^\n]*shell\s*=\s*True"#,
+                SHELL,
+                303,
+            )
+            .for_files(&[".py"]),
+            Self::security(
+                "shell_command_js",
+                r#"\b(exec|execSync)\(\s*(`[^`]*\$\{|["'][^"'\n]*["']\s*\+)"#,
+                SHELL,
+                303,
+            )
+            .for_files(JS),
+            Self::security(
+                "sql_interpolation_rust",
+                r#"(?i)format!\(\s*"\s*(select\b[^"]*\bfrom|insert\s+into|update\b[^"]*\bset|delete\s+from)\b[^"]*\{[^}]*\}"#,
+                SQL,
+                304,
+            )
+            .for_files(&[".rs"]),
+            Self::security(
+                "sql_interpolation_python",
+                r#"(?i)\bf["']\s*(select\b[^"'\n]*\bfrom|insert\s+into|update\b[^"'\n]*\bset|delete\s+from)\b[^"'\n]*\{|["']\s*(select\b[^"'\n]*\bfrom|insert\s+into|update\b[^"'\n]*\bset|delete\s+from)\b[^"'\n]*["']\s*(%\s*[(\w]|\+\s*\w|\.format\()"#,
+                SQL,
+                304,
+            )
+            .for_files(&[".py"]),
+            Self::security(
+                "sql_interpolation_js",
+                r#"(?i)`\s*(select\b[^`]*\bfrom|insert\s+into|update\b[^`]*\bset|delete\s+from)\b[^`]*\$\{|["']\s*(select\b[^"'\n]*\bfrom|insert\s+into|update\b[^"'\n]*\bset|delete\s+from)\b[^"'\n]*["']\s*\+\s*\w"#,
+                SQL,
+                304,
+            )
+            .for_files(JS),
+            Self::security(
+                "sql_interpolation_go",
+                r#"(?i)fmt\.Sprintf\(\s*"\s*(select\b[^"]*\bfrom|insert\s+into|update\b[^"]*\bset|delete\s+from)\b[^"]*%[sdvq]"#,
+                SQL,
+                304,
+            )
+            .for_files(&[".go"]),
+        ]
+    }
+
+    /// Built-in pack for broken hashes, predictable keys, fixed IVs and
+    /// disabled certificate checks
+    pub fn weak_crypto() -> Vec<Self> {
+        vec![
+            Self::security(
+                "insecure_hash",
+                r#"(?i)\b(md5|sha-?1)\s*(::|\.[a-z_]|\()|(createHash|getInstance|new)\(\s*["'](md5|sha-?1)["']"#,
+                "MD5 and SHA-1 are broken; use SHA-256 or BLAKE3",
+                301,
+            ),
+            Self::security(
+                "weak_random_key",
+                r"(?i)\w*(key|nonce|salt|secret|token|seed)\w*\b[^=\n]*=[^;\n]*\b(thread_rng|rand::random|Math\.random)\b",
+                "Keys and nonces need a cryptographic RNG such as OsRng",
+                307,
+            ),
+            Self::security(
+                "hardcoded_iv",
+                r#"(?i)\b(iv|nonce)\s*(:[^=\n]+)?=\s*(b?["'][^"'\n]+["']|&?\[\s*(0x)?[0-9a-f]+(u8)?\s*[,;])|\b(iv|nonce)::from_slice\(\s*b?["']"#,
+                "IVs and nonces must be random per message",
+                308,
+            ),
+            Self::security(
+                "tls_verification_disabled",
+                r"(?i)\bverify\s*=\s*false\b|danger_accept_invalid_certs\s*\(\s*true|InsecureSkipVerify\s*:\s*true|rejectUnauthorized\s*:\s*false|CURLOPT_SSL_VERIFYPEER\s*,\s*(0|false)",
+                "Certificate verification must stay on",
+                309,
+            ),
+        ]
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Violation {
-    pub rule: String,
-    pub violation_type: ViolationType,
-    pub severity: Severity,
+/// Compiled forbidden pattern whose matches skip its allowlist
+struct PatternMatcher {
+    re: Regex,
+    allow: Vec<Regex>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Concern {
-    pub rule: String,
-    pub concern_type: ConcernType,
-    pub suggestion: String,
+impl PatternMatcher {
+    fn new(pattern: &ForbiddenPattern) -> Result<Self, regex::Error> {
+        Ok(Self {
+            re: Regex::new(&pattern.regex)?,
+            allow: pattern
+                .allow
+                .iter()
+                .map(|a| Regex::new(a))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn allowed(&self, text: &str) -> bool {
+        self.allow.iter().any(|a| a.is_match(text))
+    }
+
+    fn find_iter<'a>(&'a self, content: &'a str) -> impl Iterator<Item = regex::Match<'a>> + 'a {
+        self.re
+            .find_iter(content)
+            .filter(|m| !self.allowed(m.as_str()))
+    }
+
+    fn captures_iter<'a>(
+        &'a self,
+        content: &'a str,
+    ) -> impl Iterator<Item = regex::Captures<'a>> + 'a {
+        self.re
+            .captures_iter(content)
+            .filter(|caps| !self.allowed(&caps[0]))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum Severity {
-    Critical,
-    High,
-    Medium,
-    Low,
+/// Byte-level [`PatternMatcher`] for streamed file content
+struct ByteMatcher {
+    re: regex::bytes::Regex,
+    allow: Vec<regex::bytes::Regex>,
 }
 
-// ============ Directory Scanning ============
+impl ByteMatcher {
+    fn new(pattern: &ForbiddenPattern) -> Result<Self, regex::Error> {
+        Ok(Self {
+            re: regex::bytes::Regex::new(&pattern.regex)?,
+            allow: pattern
+                .allow
+                .iter()
+                .map(|a| regex::bytes::Regex::new(a))
+                .collect::<Result<_, _>>()?,
+        })
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DirectoryScanResult {
-    pub path: PathBuf,
-    pub verdict: PolicyVerdict,
-    pub files_scanned: usize,
-    pub violations: Vec<FileViolation>,
-    pub concerns: Vec<FileConcern>,
+    fn is_match(&self, haystack: &[u8]) -> bool {
+        self.re
+            .find_iter(haystack)
+            .any(|m| !self.allow.iter().any(|a| a.is_match(m.as_bytes())))
+    }
 }
 
+/// Content that must survive an `EditFile` (license headers, security checks)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileViolation {
-    pub file: PathBuf,
-    pub violation: ViolationType,
+pub struct RequiredPattern {
+    pub name: String,
+    pub regex: String,
+    /// File suffixes the rule applies to; `*` for all
+    #[serde(default = "all_file_types")]
+    pub file_types: Vec<String>,
+    pub reason: String,
+    /// Line `conative fix` inserts, as a comment, into files missing the pattern
+    #[serde(default)]
+    pub header: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileConcern {
-    pub file: PathBuf,
-    pub concern: ConcernType,
+fn all_file_types() -> Vec<String> {
+    vec!["*".to_string()]
 }
 
-// ============ Errors ============
+/// Content reading limits for `scan_directory`
+///
+/// Files are streamed in chunks rather than read whole, so large
+/// artifacts cost at most `chunk_bytes + chunk_overlap` of memory each.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanPolicy {
+    /// Run forbidden-pattern checks on file content
+    #[serde(default = "default_true")]
+    pub content_checks: bool,
+    /// Bytes read per file; the rest is skipped and the file reported
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Bytes read per chunk
+    #[serde(default = "default_chunk_bytes")]
+    pub chunk_bytes: usize,
+    /// Bytes carried between chunks so boundary-straddling matches are found
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: usize,
+}
 
-#[derive(Error, Debug)]
-pub enum OracleError {
-    #[error("Invalid proposal: {0}")]
-    InvalidProposal(String),
-    #[error("Policy parse error: {0}")]
-    PolicyParseError(String),
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-    #[error("Invalid regex: {0}")]
-    RegexError(#[from] regex::Error),
+fn default_true() -> bool {
+    true
 }
 
-// ============ Oracle Implementation ============
+fn default_max_file_bytes() -> u64 {
+    64 * 1024 * 1024
+}
 
-pub struct Oracle {
-    policy: Policy,
+fn default_chunk_bytes() -> usize {
+    1024 * 1024
 }
 
-impl Oracle {
-    pub fn new(policy: Policy) -> Self {
-        Self { policy }
-    }
+fn default_chunk_overlap() -> usize {
+    4096
+}
 
-    pub fn with_rsr_defaults() -> Self {
-        Self::new(Policy::rsr_default())
+impl Default for ScanPolicy {
+    fn default() -> Self {
+        Self {
+            content_checks: true,
+            max_file_bytes: default_max_file_bytes(),
+            chunk_bytes: default_chunk_bytes(),
+            chunk_overlap: default_chunk_overlap(),
+        }
     }
+}
 
-    /// Check a proposal against policy
-    pub fn check_proposal(&self, proposal: &Proposal) -> Result<OracleEvaluation, OracleError> {
-        let mut rules_checked = Vec::new();
-        let mut violations = Vec::new();
-        let mut concerns = Vec::new();
+/// Path comparison settings shared by exceptions, binary and protected paths
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PathPolicy {
+    /// Compare paths case-insensitively (Windows, default macOS volumes)
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
 
-        // Check forbidden languages in content
-        rules_checked.push("forbidden_languages_content".to_string());
-        for lang in &self.policy.languages.forbidden {
-            if self.content_contains_language(&proposal.content, lang) {
-                let is_excepted = self.check_exception(&proposal.files_affected, &lang.name);
-                if !is_excepted {
-                    violations.push(Violation {
-                        rule: format!("forbidden_language:{}", lang.name),
-                        violation_type: ViolationType::ForbiddenLanguage {
-                            language: lang.name.clone(),
-                            file: proposal.files_affected.first().cloned().unwrap_or_default(),
-                            context: self.extract_context(&proposal.content, &lang.markers),
-                        },
-                        severity: Severity::Critical,
-                    });
-                }
-            }
+impl PathPolicy {
+    /// Comparison form of a path: `/`-separated, case-folded if configured
+    pub fn key(&self, path: &str) -> String {
+        let path = normalize_path(path);
+        if self.case_insensitive {
+            path.to_lowercase()
+        } else {
+            path
         }
+    }
+}
 
-        // Check forbidden languages in file paths
-        rules_checked.push("forbidden_languages_files".to_string());
-        for file in &proposal.files_affected {
-            for lang in &self.policy.languages.forbidden {
-                if self.file_matches_language(file, lang) {
-                    let is_excepted = self.check_exception(std::slice::from_ref(file), &lang.name);
-                    if !is_excepted {
-                        violations.push(Violation {
-                            rule: format!("forbidden_file_extension:{}", lang.name),
-                            violation_type: ViolationType::ForbiddenLanguage {
-                                language: lang.name.clone(),
-                                file: file.clone(),
-                                context: format!(
-                                    "File extension matches forbidden language: {}",
-                                    lang.name
-                                ),
-                            },
-                            severity: Severity::Critical,
-                        });
-                    }
-                }
-            }
-        }
+/// `/`-separated form of a path, without a leading `./`
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut rest = path.as_str();
+    while let Some(stripped) = rest.strip_prefix("./") {
+        rest = stripped;
+    }
+    rest.to_string()
+}
 
-        // Check toolchain rules
-        rules_checked.push("toolchain_rules".to_string());
-        for rule in &self.policy.toolchain.rules {
-            let has_tool = self.content_has_markers(&proposal.content, &rule.tool_markers)
-                || self.files_have_markers(&proposal.files_affected, &rule.tool_markers);
-            let has_requires = self.content_has_markers(&proposal.content, &rule.requires_markers)
-                || self.files_have_markers(&proposal.files_affected, &rule.requires_markers);
+/// Last component of a path, or the path itself
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
 
-            if has_tool && !has_requires {
-                violations.push(Violation {
-                    rule: format!("toolchain:{}:{}", rule.tool, rule.requires),
-                    violation_type: ViolationType::ForbiddenToolchain {
-                        tool: rule.tool.clone(),
-                        missing: rule.requires.clone(),
-                    },
-                    severity: Severity::High,
-                });
-            }
-        }
+/// Whether proposals may carry binary or base64-embedded blobs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryPolicy {
+    /// Allow opaque content anywhere
+    #[serde(default)]
+    pub allow: bool,
+    /// Path fragments where opaque content is allowed (e.g. `assets/`)
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// List zip/tar members and check them against forbidden languages
+    #[serde(default = "default_inspect_archives")]
+    pub inspect_archives: bool,
+}
 
-        // Check forbidden patterns
-        rules_checked.push("forbidden_patterns".to_string());
-        for pattern in &self.policy.patterns.forbidden_patterns {
-            let re = Regex::new(&pattern.regex)?;
-            if re.is_match(&proposal.content) {
-                violations.push(Violation {
-                    rule: format!("pattern:{}", pattern.name),
-                    violation_type: ViolationType::ForbiddenPattern {
-                        pattern: pattern.name.clone(),
-                        file: proposal.files_affected.first().cloned().unwrap_or_default(),
-                    },
-                    severity: Severity::High,
-                });
-            }
-        }
+fn default_inspect_archives() -> bool {
+    true
+}
 
-        // Check tier2 languages (concerns, not violations)
-        rules_checked.push("tier2_languages".to_string());
-        for lang in &self.policy.languages.tier2 {
-            if self.content_contains_language(&proposal.content, lang) {
-                concerns.push(Concern {
-                    rule: format!("tier2_language:{}", lang.name),
-                    concern_type: ConcernType::Tier2Language {
-                        language: lang.name.clone(),
-                    },
-                    suggestion: format!(
-                        "Consider using a Tier 1 language instead of {}",
-                        lang.name
-                    ),
-                });
-            }
+impl Default for BinaryPolicy {
+    fn default() -> Self {
+        Self {
+            allow: false,
+            allowed_paths: Vec::new(),
+            inspect_archives: true,
         }
+    }
+}
 
-        let verdict = if !violations.is_empty() {
-            PolicyVerdict::HardViolation(violations[0].violation_type.clone())
-        } else if !concerns.is_empty() {
-            PolicyVerdict::SoftConcern(concerns[0].concern_type.clone())
-        } else {
-            PolicyVerdict::Compliant
-        };
+/// How string literals split by concatenation are put back together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObfuscationPolicy {
+    /// Join concatenated literals before secret and marker matching
+    #[serde(default = "default_true")]
+    pub fold_concatenation: bool,
+    /// Literals in one expression from which fragmentation is a concern;
+    /// 0 turns the concern off
+    #[serde(default = "default_min_fragments")]
+    pub min_fragments: usize,
+    /// Longest average fragment, in characters, that counts as fragmented
+    #[serde(default = "default_max_fragment_chars")]
+    pub max_fragment_chars: usize,
+}
 
-        Ok(OracleEvaluation {
-            proposal_id: proposal.id,
-            verdict,
-            rules_checked,
-            violations,
-            concerns,
-        })
+fn default_min_fragments() -> usize {
+    4
+}
+
+fn default_max_fragment_chars() -> usize {
+    3
+}
+
+impl Default for ObfuscationPolicy {
+    fn default() -> Self {
+        Self {
+            fold_concatenation: true,
+            min_fragments: default_min_fragments(),
+            max_fragment_chars: default_max_fragment_chars(),
+        }
     }
+}
 
-    /// Scan a directory for policy violations
-    pub fn scan_directory(&self, path: &Path) -> Result<DirectoryScanResult, OracleError> {
-        let mut violations = Vec::new();
-        let mut concerns = Vec::new();
-        let mut files_scanned = 0;
+impl ObfuscationPolicy {
+    /// Whether `chain` is split finely enough to look deliberate
+    pub fn fragmented(&self, chain: &ConcatChain) -> bool {
+        self.min_fragments > 0
+            && chain.fragments >= self.min_fragments
+            && chain.value.chars().count() <= chain.fragments * self.max_fragment_chars
+    }
+}
 
-        for entry in walkdir(path)? {
-            files_scanned += 1;
-            let file_path = entry.as_path();
+/// What an `unsafe` block in a Rust proposal needs to be accepted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsafeMode {
+    /// Accept every block
+    Allow,
+    /// Accept blocks justified by a `// SAFETY:` comment above them
+    #[default]
+    RequireSafetyComment,
+    /// Refuse every block
+    Forbid,
+}
 
-            // Check file extension against forbidden languages
-            for lang in &self.policy.languages.forbidden {
-                if self.file_matches_language(&file_path.to_string_lossy(), lang) {
-                    let is_excepted = self
-                        .check_exception(&[file_path.to_string_lossy().to_string()], &lang.name);
-                    if !is_excepted {
-                        violations.push(FileViolation {
-                            file: file_path.to_path_buf(),
-                            violation: ViolationType::ForbiddenLanguage {
-                                language: lang.name.clone(),
-                                file: file_path.to_string_lossy().to_string(),
-                                context: "File extension".to_string(),
-                            },
-                        });
-                    }
-                }
-            }
+/// Rules for `unsafe` blocks in Rust proposals
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UnsafePolicy {
+    #[serde(default)]
+    pub mode: UnsafeMode,
+    /// Path fragments where any unsafe block is accepted (e.g. `src/ffi/`)
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+}
 
-            // Check tier2 languages
-            for lang in &self.policy.languages.tier2 {
-                if self.file_matches_language(&file_path.to_string_lossy(), lang) {
-                    concerns.push(FileConcern {
-                        file: file_path.to_path_buf(),
-                        concern: ConcernType::Tier2Language {
-                            language: lang.name.clone(),
-                        },
-                    });
-                }
-            }
-        }
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ImportPolicy {
+    pub rules: Vec<ImportRule>,
+}
 
-        let verdict = if !violations.is_empty() {
-            PolicyVerdict::HardViolation(violations[0].violation.clone())
-        } else if !concerns.is_empty() {
-            PolicyVerdict::SoftConcern(concerns[0].concern.clone())
-        } else {
-            PolicyVerdict::Compliant
-        };
+/// Modules a language's files must not import, optionally only under
+/// some paths (e.g. no `reqwest` in code that must run offline)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRule {
+    pub name: String,
+    /// Import syntax the rule reads: `rust` (including `Cargo.toml`
+    /// dependencies), `python`, `javascript` (also TypeScript) or `go`
+    pub language: String,
+    /// Banned modules; each also bans everything under it, so
+    /// `std::process` covers `std::process::Command`
+    pub modules: Vec<String>,
+    /// Path fragments the rule is limited to; empty applies everywhere
+    #[serde(default)]
+    pub paths: Vec<String>,
+    pub reason: String,
+}
 
-        Ok(DirectoryScanResult {
-            path: path.to_path_buf(),
-            verdict,
-            files_scanned,
-            violations,
-            concerns,
-        })
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProtectedPolicy {
+    #[serde(default)]
+    pub rules: Vec<ProtectedRule>,
+}
+
+/// Writes that need more than an automatic Allow
+///
+/// A rule matches when any affected path matches one of `paths` and the
+/// request's branch matches one of `branches`; an empty list matches
+/// anything, but a rule needs at least one of the two. Patterns are globs
+/// where `*` stays within a path component and `**` crosses them;
+/// [`DEFAULT_BRANCH`] in `branches` stands for the repository's default
+/// branch once it has history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProtectedRule {
+    pub name: String,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub branches: Vec<String>,
+    #[serde(default)]
+    pub action: ProtectedAction,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConditionalPolicy {
+    #[serde(default)]
+    pub rules: Vec<ConditionalRule>,
+}
+
+/// Rule that applies when request metadata matches
+///
+/// Each `when` condition is `key` (present) or `key=value`; all must hold.
+/// The rule then covers writes matching `paths` (any write if empty) and
+/// raises the verdict to `action`, unless every `require` key is present
+/// with a non-empty value. With no `require` keys a covered write always
+/// triggers it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConditionalRule {
+    pub name: String,
+    #[serde(default)]
+    pub when: Vec<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub require: Vec<String>,
+    pub action: GateAction,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Branch pattern naming the repository's default branch
+pub const DEFAULT_BRANCH: &str = "@default";
+
+/// Rules applied while a repository has no history
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NewRepositoryPolicy {
+    /// Files the first proposals must create unless the workspace has them
+    #[serde(default)]
+    pub required_files: Vec<RequiredFile>,
+}
+
+/// File a new repository needs, matched by any of `paths`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequiredFile {
+    pub name: String,
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Minimum verdict for a write matching a protected rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ProtectedAction {
+    /// Hand to a human reviewer
+    #[default]
+    Escalate,
+    /// Refuse outright
+    Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnforcementConfig {
+    pub slm_weight: f64,
+    pub escalate_threshold: f64,
+    pub block_threshold: f64,
+    /// How internal errors (bad regex, IO) are turned into a verdict
+    #[serde(default)]
+    pub failure_mode: FailureMode,
+    /// Inference backend for the SLM spirit stage (oracle-only if unset)
+    #[serde(default)]
+    pub slm_backend: Option<SlmBackendConfig>,
+    /// Persistent cache of SLM results (disabled if unset)
+    #[serde(default)]
+    pub slm_cache: Option<SlmCacheConfig>,
+    /// Splitting of proposals larger than the SLM context
+    #[serde(default)]
+    pub slm_chunking: SlmChunking,
+    /// Verdict for each violation severity and for soft concerns
+    #[serde(default)]
+    pub actions: ActionMap,
+    /// Proposals the LLM is less confident in than this go to a human
+    #[serde(default = "default_escalate_below_confidence")]
+    pub escalate_below_confidence: f32,
+    /// Blocks in one session after which its proposals go to a human
+    /// (0 disables)
+    #[serde(default = "default_escalate_after_denials")]
+    pub escalate_after_denials: u32,
+    /// How much of a security match is kept in refusal evidence
+    #[serde(default)]
+    pub evidence_redaction: EvidenceRedaction,
+    /// Lines of source kept around a finding in refusal evidence
+    #[serde(default)]
+    pub evidence_context: ContextWindow,
+    /// Milliseconds a decision may take before the gate stops checking and
+    /// escalates what it has (0 disables)
+    #[serde(default = "default_evaluation_deadline_ms")]
+    pub evaluation_deadline_ms: u64,
+    /// Share of decisions audit logs keep, by verdict
+    #[serde(default)]
+    pub audit_sampling: AuditSampling,
+}
+
+/// Lines kept on each side of a finding's line, by severity, so the worst
+/// findings carry the most context
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContextWindow {
+    #[serde(default = "default_critical_context")]
+    pub critical: usize,
+    #[serde(default = "default_high_context")]
+    pub high: usize,
+    #[serde(default)]
+    pub medium: usize,
+    #[serde(default)]
+    pub low: usize,
+    /// Longest line kept; longer lines are clipped around the finding
+    #[serde(default = "default_max_line_chars")]
+    pub max_line_chars: usize,
+}
+
+impl ContextWindow {
+    /// Surrounding lines for a finding of this severity
+    pub fn lines_for(&self, severity: Severity) -> usize {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::High => self.high,
+            Severity::Medium => self.medium,
+            Severity::Low => self.low,
+        }
     }
+}
 
-    // Helper methods
-    fn content_contains_language(&self, content: &str, lang: &LanguageConfig) -> bool {
-        let content_lower = content.to_lowercase();
-        lang.markers
-            .iter()
-            .any(|m| content_lower.contains(&m.to_lowercase()))
+impl Default for ContextWindow {
+    fn default() -> Self {
+        Self {
+            critical: default_critical_context(),
+            high: default_high_context(),
+            medium: 0,
+            low: 0,
+            max_line_chars: default_max_line_chars(),
+        }
     }
+}
 
-    fn file_matches_language(&self, file: &str, lang: &LanguageConfig) -> bool {
-        let file_lower = file.to_lowercase();
-        lang.extensions
-            .iter()
-            .any(|ext| file_lower.ends_with(&ext.to_lowercase()))
+fn default_critical_context() -> usize {
+    2
+}
+
+fn default_high_context() -> usize {
+    1
+}
+
+fn default_max_line_chars() -> usize {
+    160
+}
+
+/// Fraction (0.0-1.0) of allowed decisions an audit log keeps; warnings,
+/// escalations and blocks are always kept
+///
+/// The choice is made by request ID, so a retried request is kept or
+/// dropped the same way each time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditSampling {
+    #[serde(default = "default_audit_allow_rate")]
+    pub allow: f64,
+}
+
+impl Default for AuditSampling {
+    fn default() -> Self {
+        Self {
+            allow: default_audit_allow_rate(),
+        }
     }
+}
 
-    fn content_has_markers(&self, content: &str, markers: &[String]) -> bool {
-        let content_lower = content.to_lowercase();
-        markers
-            .iter()
-            .any(|m| content_lower.contains(&m.to_lowercase()))
+fn default_audit_allow_rate() -> f64 {
+    1.0
+}
+
+/// The lines of `content` holding `range`, plus `lines` on each side
+///
+/// Both ends of `range` are moved onto char boundaries, so any byte offsets
+/// are safe. Lines longer than `max_chars` are clipped, around the finding
+/// on its own lines, with `...` marking the cut.
+pub fn extract_context(
+    content: &str,
+    range: std::ops::Range<usize>,
+    lines: usize,
+    max_chars: usize,
+) -> String {
+    let mut start = range.start.min(content.len());
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = range.end.clamp(start, content.len());
+    while !content.is_char_boundary(end) {
+        end += 1;
     }
 
-    fn files_have_markers(&self, files: &[String], markers: &[String]) -> bool {
-        for file in files {
-            let file_lower = file.to_lowercase();
-            for marker in markers {
-                if file_lower.contains(&marker.to_lowercase()) {
-                    return true;
-                }
-            }
+    let first = content[..start].matches('\n').count();
+    let last = first + content[start..end].matches('\n').count();
+    let mut offset = 0;
+    let mut kept = Vec::new();
+    for (index, line) in content.split('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+        if index + lines < first || index > last + lines {
+            continue;
         }
-        false
+        // Clip around the finding on its own lines, from the start elsewhere
+        let focus = if (first..=last).contains(&index) {
+            start.saturating_sub(line_start).min(line.len())
+        } else {
+            0
+        };
+        kept.push(clip_line(line, focus, max_chars));
     }
+    kept.join("\n")
+}
 
-    fn check_exception(&self, files: &[String], language: &str) -> bool {
-        for exc in &self.policy.languages.exceptions {
-            if exc.language.to_lowercase() == language.to_lowercase() {
-                for file in files {
-                    for allowed in &exc.allowed_paths {
-                        if file.contains(allowed) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-        false
+/// At most `max_chars` chars of `line`, starting shortly before byte `focus`
+fn clip_line(line: &str, focus: usize, max_chars: usize) -> String {
+    if line.chars().count() <= max_chars {
+        return line.to_string();
     }
+    let lead = max_chars / 4;
+    let focus_char = line.char_indices().take_while(|(i, _)| *i < focus).count();
+    let skip = focus_char.saturating_sub(lead);
+    let clipped: String = line.chars().skip(skip).take(max_chars).collect();
+    let head = if skip > 0 { "..." } else { "" };
+    let tail = if skip + max_chars < line.chars().count() {
+        "..."
+    } else {
+        ""
+    };
+    format!("{}{}{}", head, clipped, tail)
+}
 
-    fn extract_context(&self, content: &str, markers: &[String]) -> String {
-        for marker in markers {
-            if let Some(pos) = content.to_lowercase().find(&marker.to_lowercase()) {
-                let start = pos.saturating_sub(30);
-                let end = (pos + marker.len() + 30).min(content.len());
-                return format!("...{}...", &content[start..end]);
-            }
+/// Masking applied to the matched text of security evidence before it
+/// reaches decisions and audit logs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceRedaction {
+    /// Keep the match verbatim
+    None,
+    /// Keep the first and last two characters
+    #[default]
+    Partial,
+    /// Replace the whole match
+    Full,
+}
+
+fn default_escalate_below_confidence() -> f32 {
+    0.5
+}
+
+fn default_escalate_after_denials() -> u32 {
+    3
+}
+
+fn default_evaluation_deadline_ms() -> u64 {
+    5000
+}
+
+impl Default for EnforcementConfig {
+    fn default() -> Self {
+        Self {
+            slm_weight: 1.5,
+            escalate_threshold: 0.4,
+            block_threshold: 0.7,
+            failure_mode: FailureMode::default(),
+            slm_backend: None,
+            slm_cache: None,
+            slm_chunking: SlmChunking::default(),
+            actions: ActionMap::default(),
+            escalate_below_confidence: default_escalate_below_confidence(),
+            escalate_after_denials: default_escalate_after_denials(),
+            evidence_redaction: EvidenceRedaction::default(),
+            evidence_context: ContextWindow::default(),
+            evaluation_deadline_ms: default_evaluation_deadline_ms(),
+            audit_sampling: AuditSampling::default(),
         }
-        String::new()
     }
 }
 
-// Simple directory walker
-fn walkdir(path: &Path) -> Result<Vec<PathBuf>, OracleError> {
-    let mut files = Vec::new();
+/// Verdict the gate reaches for an oracle finding
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GateAction {
+    Allow,
+    Warn,
+    Escalate,
+    Block,
+}
+
+/// Mapping from the worst violation's severity (or a soft concern) to a
+/// verdict; the default blocks every violation and warns on concerns
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionMap {
+    #[serde(default = "block_action")]
+    pub critical: GateAction,
+    #[serde(default = "block_action")]
+    pub high: GateAction,
+    #[serde(default = "block_action")]
+    pub medium: GateAction,
+    #[serde(default = "block_action")]
+    pub low: GateAction,
+    #[serde(default = "warn_action")]
+    pub concern: GateAction,
+}
 
-    if path.is_file() {
-        files.push(path.to_path_buf());
-        return Ok(files);
+impl ActionMap {
+    /// Action for a violation of this severity
+    pub fn for_severity(&self, severity: Severity) -> GateAction {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::High => self.high,
+            Severity::Medium => self.medium,
+            Severity::Low => self.low,
+        }
     }
+}
 
-    if !path.exists() {
-        return Ok(files);
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self {
+            critical: GateAction::Block,
+            high: GateAction::Block,
+            medium: GateAction::Block,
+            low: GateAction::Block,
+            concern: GateAction::Warn,
+        }
     }
+}
 
-    for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        let entry_path = entry.path();
+fn block_action() -> GateAction {
+    GateAction::Block
+}
 
-        let name = entry_path.file_name().unwrap_or_default().to_string_lossy();
-        if name.starts_with('.') || name == "node_modules" || name == "target" || name == "_build" {
-            continue;
-        }
+fn warn_action() -> GateAction {
+    GateAction::Warn
+}
 
-        if entry_path.is_dir() {
-            files.extend(walkdir(&entry_path)?);
-        } else {
-            files.push(entry_path);
+/// Which SLM inference backend runs the spirit stage
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SlmBackendConfig {
+    /// Always-compliant stand-in
+    Placeholder,
+    /// Fixed score, for tests and dry runs
+    Mock {
+        #[serde(default)]
+        score: f64,
+    },
+    /// Local model through a llama.cpp binary
+    LlamaCpp {
+        model_path: String,
+        #[serde(default)]
+        binary: Option<String>,
+        /// Pinned SHA-256 digest of the weights
+        #[serde(default)]
+        sha256: Option<String>,
+        /// GPU offload, threads and context size
+        #[serde(default)]
+        runtime: LlamaCppRuntime,
+    },
+    /// OpenAI-compatible chat completions endpoint
+    #[serde(rename = "openai")]
+    OpenAi {
+        endpoint: String,
+        model: String,
+        /// Environment variable holding the API key
+        #[serde(default)]
+        api_key_env: Option<String>,
+    },
+    /// Encoder classifier (requires the `candle` feature)
+    Classifier { model_dir: String },
+    /// Cheap pre-filter backend in front of a full SLM backend
+    Cascade {
+        prefilter: Box<SlmBackendConfig>,
+        full: Box<SlmBackendConfig>,
+        /// Pre-filter score below which the full backend is skipped
+        #[serde(default = "default_pass_below")]
+        pass_below: f64,
+    },
+    /// Several backends whose votes are aggregated before the arbiter
+    Ensemble {
+        members: Vec<EnsembleMember>,
+        #[serde(default)]
+        voting: EnsembleVoting,
+        /// Minimum number of members that must answer
+        #[serde(default = "default_quorum")]
+        quorum: usize,
+    },
+}
+
+/// Hardware options for the llama.cpp backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LlamaCppRuntime {
+    #[serde(default)]
+    pub accelerator: AcceleratorConfig,
+    /// Layers to offload to the GPU (all if unset)
+    #[serde(default)]
+    pub n_gpu_layers: Option<u32>,
+    #[serde(default)]
+    pub threads: Option<u32>,
+    /// Context window in tokens
+    #[serde(default)]
+    pub context_size: Option<u32>,
+}
+
+/// Requested inference hardware; unavailable GPUs fall back to CPU
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AcceleratorConfig {
+    #[default]
+    Auto,
+    Cpu,
+    Cuda,
+    Metal,
+    Vulkan,
+}
+
+fn default_pass_below() -> f64 {
+    0.2
+}
+
+fn default_quorum() -> usize {
+    1
+}
+
+/// One backend in an SLM ensemble
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnsembleMember {
+    pub backend: SlmBackendConfig,
+    #[serde(default = "default_member_weight")]
+    pub weight: f64,
+}
+
+fn default_member_weight() -> f64 {
+    1.0
+}
+
+/// How ensemble votes are combined
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EnsembleVoting {
+    /// Weighted majority at the block threshold
+    #[default]
+    Majority,
+    /// Scores weighted by member weight and confidence
+    ConfidenceWeighted,
+}
+
+/// Where and for how long SLM results are cached
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlmCacheConfig {
+    #[serde(default = "default_cache_dir")]
+    pub dir: String,
+    #[serde(default = "default_cache_ttl")]
+    pub ttl_secs: u64,
+}
+
+impl Default for SlmCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_cache_dir(),
+            ttl_secs: default_cache_ttl(),
         }
     }
+}
 
-    Ok(files)
+fn default_cache_dir() -> String {
+    ".conative/cache/slm".to_string()
 }
 
-// ============ Default Policy ============
+fn default_cache_ttl() -> u64 {
+    24 * 60 * 60
+}
 
-impl Policy {
-    /// RSR-compliant default policy
-    pub fn rsr_default() -> Self {
-        Self {
-            name: "RSR Default Policy".to_string(),
-            languages: LanguagePolicy {
+/// How oversized proposals are split for the SLM
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlmChunking {
+    /// Largest chunk sent to the model, in bytes (~4 bytes per token)
+    #[serde(default = "default_chunk_chars")]
+    pub max_chars: usize,
+    #[serde(default)]
+    pub aggregate: ChunkAggregation,
+}
+
+impl Default for SlmChunking {
+    fn default() -> Self {
+        Self {
+            max_chars: default_chunk_chars(),
+            aggregate: ChunkAggregation::default(),
+        }
+    }
+}
+
+fn default_chunk_chars() -> usize {
+    8000
+}
+
+/// How chunk scores are combined
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkAggregation {
+    /// The worst chunk decides
+    #[default]
+    Max,
+    /// Average over chunks
+    Mean,
+}
+
+/// Behaviour of the gate when evaluation itself fails
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// Block the proposal (safe default)
+    #[default]
+    FailClosed,
+    /// Let the proposal through to human review (escalate) instead of blocking
+    FailOpen,
+}
+
+// ============ Evaluation Results ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleEvaluation {
+    pub proposal_id: Uuid,
+    pub verdict: PolicyVerdict,
+    pub rules_checked: Vec<String>,
+    pub violations: Vec<Violation>,
+    pub concerns: Vec<Concern>,
+    /// Outcome of every rule, recorded only when explaining
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<RuleTrace>,
+    /// The deadline passed before every rule ran; `rules_checked` lists
+    /// the rule groups that finished
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    /// Microseconds spent on each rule, recorded only when profiling
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rule_timings: Vec<(String, u64)>,
+}
+
+impl OracleEvaluation {
+    /// Highest severity among the violations
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.violations.iter().map(|v| v.severity).max()
+    }
+
+    /// First violation at the highest severity; it decides the verdict
+    pub fn worst_violation(&self) -> Option<&Violation> {
+        worst_violation(&self.violations)
+    }
+
+    /// Violations at or above `threshold`
+    pub fn violations_at_least(&self, threshold: Severity) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(move |v| v.severity.at_least(threshold))
+    }
+}
+
+fn worst_violation(violations: &[Violation]) -> Option<&Violation> {
+    violations
+        .iter()
+        .min_by_key(|v| std::cmp::Reverse(v.severity))
+}
+
+/// Evaluation of the rules checked so far; the worst violation decides
+/// the verdict, then the first concern
+fn evaluation(
+    proposal_id: Uuid,
+    rules_checked: Vec<String>,
+    violations: Vec<Violation>,
+    concerns: Vec<Concern>,
+    trace: Tracer,
+    timed_out: bool,
+) -> OracleEvaluation {
+    let verdict = if let Some(worst) = worst_violation(&violations) {
+        PolicyVerdict::HardViolation(worst.violation_type.clone())
+    } else if !concerns.is_empty() {
+        PolicyVerdict::SoftConcern(concerns[0].concern_type.clone())
+    } else {
+        PolicyVerdict::Compliant
+    };
+    OracleEvaluation {
+        proposal_id,
+        verdict,
+        rules_checked,
+        violations,
+        concerns,
+        trace: trace.rules,
+        timed_out,
+        rule_timings: trace.timings,
+    }
+}
+
+/// How one rule fared against a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOutcome {
+    Matched,
+    NoMatch,
+    /// Not applied: an exception, file type or action rules it out
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub outcome: RuleOutcome,
+    pub detail: String,
+}
+
+/// Collects rule outcomes for `--explain`; a no-op when explaining is off
+struct Tracer {
+    enabled: bool,
+    rules: Vec<RuleTrace>,
+    /// When the previous rule was recorded, if profiling
+    last: Option<Instant>,
+    timings: Vec<(String, u64)>,
+}
+
+impl Tracer {
+    fn new(enabled: bool, profile: bool) -> Self {
+        Self {
+            enabled,
+            rules: Vec::new(),
+            last: profile.then(Instant::now),
+            timings: Vec::new(),
+        }
+    }
+
+    /// Record a rule's outcome; when profiling, the time since the previous
+    /// record is charged to it, summed over every file it was checked on
+    fn record(&mut self, outcome: RuleOutcome, describe: impl FnOnce() -> (String, String)) {
+        if !self.enabled && self.last.is_none() {
+            return;
+        }
+        let (rule, detail) = describe();
+        if let Some(last) = self.last.as_mut() {
+            let now = Instant::now();
+            let us = now.duration_since(*last).as_micros() as u64;
+            *last = now;
+            match self.timings.iter_mut().find(|(r, _)| *r == rule) {
+                Some((_, total)) => *total += us,
+                None => self.timings.push((rule.clone(), us)),
+            }
+        }
+        if self.enabled {
+            self.rules.push(RuleTrace {
+                rule,
+                outcome,
+                detail,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub rule: String,
+    pub violation_type: ViolationType,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Concern {
+    pub rule: String,
+    pub concern_type: ConcernType,
+    pub suggestion: String,
+}
+
+/// How serious a violation is; orders `Low < Medium < High < Critical`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    fn rank(self) -> u8 {
+        match self {
+            Severity::Low => 0,
+            Severity::Medium => 1,
+            Severity::High => 2,
+            Severity::Critical => 3,
+        }
+    }
+
+    /// Whether this severity meets `threshold`
+    pub fn at_least(self, threshold: Severity) -> bool {
+        self >= threshold
+    }
+}
+
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// ============ Directory Scanning ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryScanResult {
+    pub path: PathBuf,
+    pub verdict: PolicyVerdict,
+    pub files_scanned: usize,
+    pub violations: Vec<FileViolation>,
+    pub concerns: Vec<FileConcern>,
+    /// Files larger than the scan budget, checked only up to it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncated: Vec<PathBuf>,
+    /// Findings a language exception let through
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exempted: Vec<FileExemption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileViolation {
+    pub file: PathBuf,
+    pub violation: ViolationType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConcern {
+    pub file: PathBuf,
+    pub concern: ConcernType,
+}
+
+/// One result of [`Oracle::scan_each`], in the order files are scanned
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    Violation(FileViolation),
+    Concern(FileConcern),
+    /// A file larger than the scan budget, checked only up to it
+    Truncated(PathBuf),
+    Exempted(FileExemption),
+}
+
+/// Outcome of scanning one file, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileVerdict {
+    Compliant,
+    Concern,
+    Violation,
+}
+
+/// A file whose verdict a proposed policy changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerdictChange {
+    pub file: PathBuf,
+    pub current: FileVerdict,
+    pub proposed: FileVerdict,
+    /// Rule IDs only the proposed policy raises
+    pub rules_added: Vec<String>,
+    /// Rule IDs only the current policy raises
+    pub rules_removed: Vec<String>,
+}
+
+/// Files of a tree whose verdict differs between two policies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyImpact {
+    pub path: PathBuf,
+    pub files_scanned: usize,
+    pub changes: Vec<VerdictChange>,
+}
+
+impl PolicyImpact {
+    /// Path of a changed file relative to the scanned root
+    pub fn relative_path(&self, file: &Path) -> String {
+        relative_to(&self.path, file)
+    }
+
+    /// Files the proposed policy treats more strictly
+    pub fn stricter(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|c| c.proposed > c.current)
+            .count()
+    }
+
+    /// Files the proposed policy treats more leniently
+    pub fn looser(&self) -> usize {
+        self.changes.len() - self.stricter()
+    }
+}
+
+/// A file a rule would have flagged but for an exception
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileExemption {
+    pub file: PathBuf,
+    /// Rule ID the exception waived, e.g. `forbidden_language:python`
+    pub rule: String,
+    /// The exception's reason and the path it allows
+    pub reason: String,
+}
+
+// ============ Errors ============
+
+#[derive(Error, Debug)]
+pub enum OracleError {
+    #[error("Invalid proposal: {0}")]
+    InvalidProposal(String),
+    #[error("Policy parse error: {0}")]
+    PolicyParseError(String),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid regex: {0}")]
+    RegexError(#[from] regex::Error),
+    #[error("Triage file error: {0}")]
+    TriageError(String),
+    #[error("Rule pack error: {0}")]
+    RulePack(String),
+}
+
+// ============ Oracle Implementation ============
+
+pub struct Oracle {
+    policy: Policy,
+    follow_symlinks: bool,
+    explain: bool,
+    profile: bool,
+}
+
+impl Oracle {
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy,
+            follow_symlinks: false,
+            explain: false,
+            profile: false,
+        }
+    }
+
+    /// Builder: follow symlinks during directory scans
+    ///
+    /// Off by default. When on, only links resolving inside the scanned
+    /// directory are followed, and each directory is visited once.
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Builder: record every rule's outcome in `OracleEvaluation::trace`
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    /// Builder: time every rule in `OracleEvaluation::rule_timings`
+    pub fn with_profile(mut self, profile: bool) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn with_rsr_defaults() -> Self {
+        Self::new(Policy::rsr_default())
+    }
+
+    /// Policy this oracle checks against
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Check a proposal against policy
+    pub fn check_proposal(&self, proposal: &Proposal) -> Result<OracleEvaluation, OracleError> {
+        self.check_proposal_until(proposal, None)
+    }
+
+    /// Check a proposal, stopping once `deadline` has passed
+    ///
+    /// The deadline is checked between rule groups and before each language
+    /// or pattern within the content-scanning groups, so one group over large
+    /// content cannot run long past it. A cut-short evaluation is `timed_out`
+    /// and its verdict covers only the rules it lists as checked, plus any
+    /// findings of the group it stopped in.
+    pub fn check_proposal_until(
+        &self,
+        proposal: &Proposal,
+        deadline: Option<Instant>,
+    ) -> Result<OracleEvaluation, OracleError> {
+        let past_deadline = || deadline.is_some_and(|d| Instant::now() >= d);
+        let mut rules_checked = Vec::new();
+        let mut violations = Vec::new();
+        let mut concerns = Vec::new();
+        let mut trace = Tracer::new(self.explain, self.profile);
+
+        // Transcode smuggled UTF-16 so the checks below see the real text
+        rules_checked.push("content_encoding".to_string());
+        let transcoded = transcode_content(&proposal.content);
+        match &transcoded {
+            Some(decoded) => {
+                concerns.push(encoding_concern(&decoded.encoding));
+                trace.record(RuleOutcome::Matched, || {
+                    (
+                        "content_encoding".to_string(),
+                        format!("transcoded from {}", decoded.encoding),
+                    )
+                });
+            }
+            None => trace.record(RuleOutcome::NoMatch, || {
+                (
+                    "content_encoding".to_string(),
+                    "content is UTF-8".to_string(),
+                )
+            }),
+        }
+        let transcoded_proposal = transcoded.map(|decoded| Proposal {
+            content: decoded.text,
+            ..proposal.clone()
+        });
+        let proposal = transcoded_proposal.as_ref().unwrap_or(proposal);
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Join split string literals so secrets and markers cut into pieces
+        // still match; folding keeps every offset outside the literals
+        rules_checked.push("string_concatenation".to_string());
+        let obfuscation = &self.policy.obfuscation;
+        let folded = match obfuscation.fold_concatenation {
+            true => fold_string_concatenation(&proposal.content),
+            false => FoldedContent::default(),
+        };
+        match folded.chains.len() {
+            0 => trace.record(RuleOutcome::NoMatch, || {
+                (
+                    "string_concatenation".to_string(),
+                    "no concatenated literals".to_string(),
+                )
+            }),
+            chains => trace.record(RuleOutcome::Matched, || {
+                (
+                    "string_concatenation".to_string(),
+                    format!("folded {} concatenated literals", chains),
+                )
+            }),
+        }
+        if let Some(chain) = folded
+            .chains
+            .iter()
+            .find(|chain| obfuscation.fragmented(chain))
+        {
+            let line = proposal.content[..chain.range.start].matches('\n').count() + 1;
+            trace.record(RuleOutcome::Matched, || {
+                (
+                    "string_fragmentation".to_string(),
+                    format!("{} fragments on line {}", chain.fragments, line),
+                )
+            });
+            concerns.push(Concern {
+                rule: "string_fragmentation".to_string(),
+                suggestion: "Write the string as one literal so it can be reviewed".to_string(),
+                concern_type: ConcernType::SuspiciousObfuscation {
+                    fragments: chain.fragments,
+                    line,
+                },
+            });
+        }
+        let folded_proposal = (!folded.chains.is_empty()).then(|| Proposal {
+            content: folded.text,
+            ..proposal.clone()
+        });
+        let proposal = folded_proposal.as_ref().unwrap_or(proposal);
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check forbidden languages in content
+        rules_checked.push("forbidden_languages_content".to_string());
+        let detected = detect_language(&proposal.content);
+        let detection = &self.policy.languages.detection;
+        for lang in &self.policy.languages.forbidden {
+            if past_deadline() {
+                // The group did not finish, so it is not listed as checked
+                rules_checked.pop();
+                return Ok(evaluation(
+                    proposal.id,
+                    rules_checked,
+                    violations,
+                    concerns,
+                    trace,
+                    true,
+                ));
+            }
+            let rule = format!("forbidden_language:{}", lang.name);
+            let Some(finding) = self.find_language(&proposal.content, lang, detected.as_ref())
+            else {
+                trace.record(RuleOutcome::NoMatch, || {
+                    (rule, "no content markers".to_string())
+                });
+                continue;
+            };
+            let score = self.language_score(proposal, lang, &finding);
+            if score.score < detection.block_threshold - detection.ambiguity_margin {
+                trace.record(RuleOutcome::NoMatch, || {
+                    (
+                        rule,
+                        format!("{} scored {:.2}", finding.detail, score.score),
+                    )
+                });
+                continue;
+            }
+            if let Some((_, allowed)) =
+                self.matching_exception(&proposal.files_affected, &lang.name)
+            {
+                trace.record(RuleOutcome::Skipped, || {
+                    (
+                        rule,
+                        format!("exception allows {} in {}", lang.name, allowed),
+                    )
+                });
+                continue;
+            }
+            if score.score < detection.block_threshold {
+                trace.record(RuleOutcome::Matched, || {
+                    (
+                        rule.clone(),
+                        format!("{} scored {:.2}, ambiguous", finding.detail, score.score),
+                    )
+                });
+                concerns.push(Concern {
+                    rule,
+                    suggestion: format!(
+                        "Content may be {} (score {:.2}); confirm it is not before accepting",
+                        lang.name, score.score
+                    ),
+                    concern_type: ConcernType::AmbiguousLanguage {
+                        language: lang.name.clone(),
+                        score,
+                    },
+                });
+                continue;
+            }
+            let context =
+                self.language_context(&proposal.content, finding.range, Severity::Critical);
+            trace.record(RuleOutcome::Matched, || {
+                (rule.clone(), format!("{} {}", finding.detail, context))
+            });
+            violations.push(Violation {
+                rule,
+                violation_type: ViolationType::ForbiddenLanguage {
+                    language: lang.name.clone(),
+                    file: proposal.files_affected.first().cloned().unwrap_or_default(),
+                    context,
+                    detection: Some(score),
+                },
+                severity: Severity::Critical,
+            });
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check forbidden languages in file paths
+        rules_checked.push("forbidden_languages_files".to_string());
+        for file in &proposal.files_affected {
+            for lang in &self.policy.languages.forbidden {
+                let rule = format!("forbidden_file_extension:{}", lang.name);
+                if !self.file_matches_language(file, lang) {
+                    trace.record(RuleOutcome::NoMatch, || {
+                        (rule, format!("{} is not a {} file", file, lang.name))
+                    });
+                    continue;
+                }
+                if let Some((_, allowed)) =
+                    self.matching_exception(std::slice::from_ref(file), &lang.name)
+                {
+                    trace.record(RuleOutcome::Skipped, || {
+                        (rule, format!("exception allows {} in {}", file, allowed))
+                    });
+                    continue;
+                }
+                trace.record(RuleOutcome::Matched, || {
+                    (
+                        rule.clone(),
+                        format!("{} has a {} extension", file, lang.name),
+                    )
+                });
+                violations.push(Violation {
+                    rule,
+                    violation_type: ViolationType::ForbiddenLanguage {
+                        language: lang.name.clone(),
+                        file: file.clone(),
+                        context: format!(
+                            "File extension matches forbidden language: {}",
+                            lang.name
+                        ),
+                        detection: None,
+                    },
+                    severity: Severity::Critical,
+                });
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check for forbidden languages hidden in allowed containers
+        rules_checked.push("polyglot_content".to_string());
+        let polyglots = self.find_polyglots(&proposal.files_affected, &proposal.content);
+        if polyglots.is_empty() {
+            trace.record(RuleOutcome::NoMatch, || {
+                ("polyglot_content".to_string(), "nothing hidden".to_string())
+            });
+        }
+        let mut hidden = HashSet::new();
+        for polyglot in polyglots {
+            let rule = format!("pattern:polyglot:{}", polyglot.language);
+            if !hidden.insert(polyglot.language.clone()) {
+                continue;
+            }
+            if let Some((_, allowed)) =
+                self.matching_exception(&proposal.files_affected, &polyglot.language)
+            {
+                trace.record(RuleOutcome::Skipped, || {
+                    (
+                        rule,
+                        format!("exception allows {} in {}", polyglot.language, allowed),
+                    )
+                });
+                continue;
+            }
+            trace.record(RuleOutcome::Matched, || {
+                (
+                    rule.clone(),
+                    format!("{} hidden in {}", polyglot.language, polyglot.container),
+                )
+            });
+            violations.push(Violation {
+                rule,
+                violation_type: ViolationType::ForbiddenPattern {
+                    pattern: format!("polyglot:{}", polyglot.language),
+                    file: proposal.files_affected.first().cloned().unwrap_or_default(),
+                },
+                severity: Severity::Critical,
+            });
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check toolchain rules
+        rules_checked.push("toolchain_rules".to_string());
+        for rule in &self.policy.toolchain.rules {
+            let has_tool =
+                self.marker_groups_match(proposal, &rule.tool_markers, &rule.tool_markers_all);
+            let has_requires = !rule.forbids()
+                && self.marker_groups_match(
+                    proposal,
+                    &rule.requires_markers,
+                    &rule.requires_markers_all,
+                );
+            let allowed = rule.allowed_paths.iter().find(|allowed| {
+                proposal
+                    .files_affected
+                    .iter()
+                    .any(|f| self.path_contains(f, allowed))
+            });
+            let id = format!("toolchain:{}:{}", rule.tool, rule.requires);
+
+            match (has_tool, has_requires, allowed) {
+                (false, ..) => trace.record(RuleOutcome::NoMatch, || {
+                    (id, format!("no {} markers", rule.tool))
+                }),
+                (true, true, _) => trace.record(RuleOutcome::NoMatch, || {
+                    (
+                        id,
+                        format!("{} is used alongside {}", rule.tool, rule.requires),
+                    )
+                }),
+                (true, false, Some(allowed)) => trace.record(RuleOutcome::Skipped, || {
+                    (id, format!("{} is allowed in {}", rule.tool, allowed))
+                }),
+                (true, false, None) => {
+                    trace.record(RuleOutcome::Matched, || (id.clone(), rule.describe()));
+                    violations.push(Violation {
+                        rule: id,
+                        violation_type: ViolationType::ForbiddenToolchain {
+                            tool: rule.tool.clone(),
+                            missing: rule.requires.clone(),
+                        },
+                        severity: Severity::High,
+                    });
+                }
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check forbidden patterns
+        rules_checked.push("forbidden_patterns".to_string());
+        for pattern in &self.policy.patterns.forbidden_patterns {
+            if past_deadline() {
+                // The group did not finish, so it is not listed as checked
+                rules_checked.pop();
+                return Ok(evaluation(
+                    proposal.id,
+                    rules_checked,
+                    violations,
+                    concerns,
+                    trace,
+                    true,
+                ));
+            }
+            let rule = format!("pattern:{}", pattern.name);
+            let applies = proposal.files_affected.is_empty()
+                || pattern.file_types.iter().any(|t| {
+                    t == "*"
+                        || proposal
+                            .files_affected
+                            .iter()
+                            .any(|f| f.ends_with(t.as_str()))
+                });
+            if !applies {
+                trace.record(RuleOutcome::Skipped, || {
+                    (
+                        rule,
+                        format!("applies to {} only", pattern.file_types.join(", ")),
+                    )
+                });
+                continue;
+            }
+            let matcher = PatternMatcher::new(pattern)?;
+            let found = matcher.find_iter(&proposal.content).next();
+            match found {
+                None => trace.record(RuleOutcome::NoMatch, || {
+                    (rule, format!("/{}/ did not match", pattern.regex))
+                }),
+                Some(m) => {
+                    trace.record(RuleOutcome::Matched, || {
+                        let line = proposal.content[..m.start()].matches('\n').count() + 1;
+                        (
+                            rule.clone(),
+                            format!("/{}/ matched on line {}", pattern.regex, line),
+                        )
+                    });
+                    violations.push(Violation {
+                        rule,
+                        violation_type: ViolationType::ForbiddenPattern {
+                            pattern: pattern.name.clone(),
+                            file: proposal.files_affected.first().cloned().unwrap_or_default(),
+                        },
+                        severity: Severity::High,
+                    });
+                }
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check required patterns survive an edit
+        if let ActionType::EditFile { path, before } = &proposal.action_type {
+            rules_checked.push("required_patterns".to_string());
+            for required in &self.policy.patterns.required_patterns {
+                let rule = format!("required:{}", required.name);
+                let applies = required
+                    .file_types
+                    .iter()
+                    .any(|t| t == "*" || path.ends_with(t.as_str()));
+                if !applies {
+                    trace.record(RuleOutcome::Skipped, || {
+                        (
+                            rule,
+                            format!("applies to {} only", required.file_types.join(", ")),
+                        )
+                    });
+                    continue;
+                }
+                let re = Regex::new(&required.regex)?;
+                if !re.is_match(before) {
+                    trace.record(RuleOutcome::NoMatch, || {
+                        (rule, "absent before the edit".to_string())
+                    });
+                } else if re.is_match(&proposal.content) {
+                    trace.record(RuleOutcome::NoMatch, || {
+                        (rule, "kept by the edit".to_string())
+                    });
+                } else {
+                    trace.record(RuleOutcome::Matched, || {
+                        (rule.clone(), "removed by the edit".to_string())
+                    });
+                    violations.push(Violation {
+                        rule,
+                        violation_type: ViolationType::RequiredPatternRemoved {
+                            pattern: required.name.clone(),
+                            file: path.clone(),
+                        },
+                        severity: Severity::High,
+                    });
+                }
+            }
+        } else {
+            for required in &self.policy.patterns.required_patterns {
+                trace.record(RuleOutcome::Skipped, || {
+                    (
+                        format!("required:{}", required.name),
+                        "only checked on edits".to_string(),
+                    )
+                });
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check unsafe blocks in Rust files
+        if proposal.files_affected.iter().any(|f| f.ends_with(".rs")) {
+            rules_checked.push("unsafe_blocks".to_string());
+            let policy = &self.policy.unsafe_code;
+            let blocks = find_unsafe_blocks(&proposal.content);
+            let allowed_path = proposal.files_affected.iter().find_map(|f| {
+                policy
+                    .allowed_paths
+                    .iter()
+                    .find(|p| self.path_contains(f, p))
+            });
+            let offending: Vec<(usize, usize)> = blocks
+                .iter()
+                .filter(|b| policy.mode == UnsafeMode::Forbid || !b.safety_comment)
+                .map(|b| (b.start_line, b.end_line))
+                .collect();
+            let rule = "unsafe_blocks".to_string();
+            if blocks.is_empty() {
+                trace.record(RuleOutcome::NoMatch, || {
+                    (rule, "no unsafe blocks".to_string())
+                });
+            } else if policy.mode == UnsafeMode::Allow || allowed_path.is_some() {
+                trace.record(RuleOutcome::Skipped, || {
+                    let why = match allowed_path {
+                        Some(p) => format!("unsafe allowed in {}", p),
+                        None => "unsafe allowed by policy".to_string(),
+                    };
+                    (rule, format!("{} unsafe blocks; {}", blocks.len(), why))
+                });
+            } else if offending.is_empty() {
+                trace.record(RuleOutcome::NoMatch, || {
+                    (
+                        rule,
+                        format!("{} unsafe blocks, each with a SAFETY comment", blocks.len()),
+                    )
+                });
+            } else {
+                trace.record(RuleOutcome::Matched, || {
+                    let (start, end) = offending[0];
+                    let detail = format!(
+                        "{} unsafe blocks refused, first on lines {}-{}",
+                        offending.len(),
+                        start,
+                        end
+                    );
+                    (rule.clone(), detail)
+                });
+                violations.push(Violation {
+                    rule,
+                    violation_type: ViolationType::UnsafeBlock {
+                        file: proposal.files_affected.first().cloned().unwrap_or_default(),
+                        blocks: offending,
+                        needs_safety_comment: policy.mode == UnsafeMode::RequireSafetyComment,
+                    },
+                    severity: Severity::High,
+                });
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check imports against the banned modules for the file's language
+        let file = proposal
+            .files_affected
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default();
+        if let Some(language) = import_language(file) {
+            rules_checked.push("imports".to_string());
+            let imports = parse_imports(language, file, &proposal.content);
+            for import_rule in self
+                .policy
+                .imports
+                .rules
+                .iter()
+                .filter(|r| r.language == language)
+            {
+                let rule = format!("import:{}", import_rule.name);
+                let in_scope = import_rule.paths.is_empty()
+                    || proposal
+                        .files_affected
+                        .iter()
+                        .any(|f| import_rule.paths.iter().any(|p| self.path_contains(f, p)));
+                if !in_scope {
+                    trace.record(RuleOutcome::Skipped, || {
+                        (
+                            rule,
+                            format!("applies under {} only", import_rule.paths.join(", ")),
+                        )
+                    });
+                    continue;
+                }
+                let banned: Vec<(String, usize)> = imports
+                    .iter()
+                    .filter(|i| {
+                        import_rule
+                            .modules
+                            .iter()
+                            .any(|m| module_within(language, &i.module, m))
+                    })
+                    .map(|i| (i.module.clone(), i.line))
+                    .collect();
+                match banned.first() {
+                    None => trace.record(RuleOutcome::NoMatch, || {
+                        (rule, format!("{} imports, none banned", imports.len()))
+                    }),
+                    Some((module, line)) => {
+                        trace.record(RuleOutcome::Matched, || {
+                            (rule.clone(), format!("imports {} on line {}", module, line))
+                        });
+                        violations.push(Violation {
+                            rule,
+                            violation_type: ViolationType::ForbiddenImport {
+                                rule: import_rule.name.clone(),
+                                file: file.to_string(),
+                                imports: banned,
+                            },
+                            severity: Severity::High,
+                        });
+                    }
+                }
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check binary and embedded-archive content
+        rules_checked.push("opaque_content".to_string());
+        let binary = &self.policy.binary;
+        match detect_opaque_content(&proposal.content, binary.inspect_archives) {
+            None => trace.record(RuleOutcome::NoMatch, || {
+                (
+                    "opaque_content".to_string(),
+                    "no binary or encoded payload".to_string(),
+                )
+            }),
+            Some(payload) => {
+                let file = proposal.files_affected.first().cloned().unwrap_or_default();
+                let allowed_path = proposal.files_affected.iter().find_map(|f| {
+                    binary
+                        .allowed_paths
+                        .iter()
+                        .find(|p| self.path_contains(f, p))
+                });
+                if binary.allow || allowed_path.is_some() {
+                    trace.record(RuleOutcome::Skipped, || {
+                        let why = match allowed_path {
+                            Some(p) => format!("binaries allowed in {}", p),
+                            None => "binaries allowed by policy".to_string(),
+                        };
+                        (
+                            "opaque_content".to_string(),
+                            format!("{} payload; {}", payload.kind, why),
+                        )
+                    });
+                } else {
+                    trace.record(RuleOutcome::Matched, || {
+                        (
+                            "opaque_content".to_string(),
+                            format!("{} payload: {}", payload.kind, payload.detail),
+                        )
+                    });
+                    violations.push(Violation {
+                        rule: "opaque_content".to_string(),
+                        violation_type: ViolationType::OpaqueContent {
+                            kind: payload.kind.clone(),
+                            file: file.clone(),
+                            detail: payload.detail.clone(),
+                        },
+                        severity: Severity::High,
+                    });
+                }
+                // Archive members are checked even where binaries are allowed
+                for member in &payload.members {
+                    for lang in &self.policy.languages.forbidden {
+                        if !self.file_matches_language(member, lang) {
+                            continue;
+                        }
+                        let rule = format!("forbidden_archive_member:{}", lang.name);
+                        let member_path = format!("{}!{}", file, member);
+                        let exception =
+                            self.matching_exception(std::slice::from_ref(&member_path), &lang.name);
+                        if let Some((_, allowed)) = exception {
+                            trace.record(RuleOutcome::Skipped, || {
+                                (
+                                    rule,
+                                    format!("exception allows {} in {}", member_path, allowed),
+                                )
+                            });
+                            continue;
+                        }
+                        trace.record(RuleOutcome::Matched, || {
+                            (
+                                rule.clone(),
+                                format!("{} archive member {}", payload.kind, member),
+                            )
+                        });
+                        violations.push(Violation {
+                            rule,
+                            violation_type: ViolationType::ForbiddenLanguage {
+                                language: lang.name.clone(),
+                                file: member_path,
+                                context: format!("{} archive member {}", payload.kind, member),
+                                detection: None,
+                            },
+                            severity: Severity::Critical,
+                        });
+                    }
+                }
+            }
+        }
+
+        if past_deadline() {
+            return Ok(evaluation(
+                proposal.id,
+                rules_checked,
+                violations,
+                concerns,
+                trace,
+                true,
+            ));
+        }
+
+        // Check tier2 languages (concerns, not violations)
+        rules_checked.push("tier2_languages".to_string());
+        for lang in &self.policy.languages.tier2 {
+            if past_deadline() {
+                // The group did not finish, so it is not listed as checked
+                rules_checked.pop();
+                return Ok(evaluation(
+                    proposal.id,
+                    rules_checked,
+                    violations,
+                    concerns,
+                    trace,
+                    true,
+                ));
+            }
+            let rule = format!("tier2_language:{}", lang.name);
+            if !self.content_contains_language(&proposal.content, lang) {
+                trace.record(RuleOutcome::NoMatch, || {
+                    (rule, "no content markers".to_string())
+                });
+                continue;
+            }
+            trace.record(RuleOutcome::Matched, || {
+                (rule.clone(), "content markers found".to_string())
+            });
+            concerns.push(Concern {
+                rule,
+                concern_type: ConcernType::Tier2Language {
+                    language: lang.name.clone(),
+                },
+                suggestion: format!("Consider using a Tier 1 language instead of {}", lang.name),
+            });
+        }
+
+        Ok(evaluation(
+            proposal.id,
+            rules_checked,
+            violations,
+            concerns,
+            trace,
+            false,
+        ))
+    }
+
+    /// Manifests in `set` that change without one of their lockfiles
+    pub fn stale_lockfiles(&self, set: &ProposalSet) -> Result<Vec<Violation>, OracleError> {
+        let mut violations = Vec::new();
+        for rule in &self.policy.toolchain.lockfiles {
+            let trigger = rule.trigger.as_deref().map(Regex::new).transpose()?;
+            for (path, content) in &set.files {
+                if file_name(path) != rule.manifest
+                    || trigger.as_ref().is_some_and(|re| !re.is_match(content))
+                {
+                    continue;
+                }
+                let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+                let locked = set.files.keys().any(|other| {
+                    rule.lockfiles.iter().any(|lock| file_name(other) == lock)
+                        && dir.starts_with(Path::new(other).parent().unwrap_or(Path::new("")))
+                });
+                if !locked {
+                    violations.push(Violation {
+                        rule: format!("lockfile:{}", rule.manifest),
+                        violation_type: ViolationType::LockfileNotUpdated {
+                            manifest: path.clone(),
+                            lockfiles: rule.lockfiles.clone(),
+                        },
+                        severity: Severity::Medium,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Check a proposal whose content was decoded from a file
+    ///
+    /// Adds an encoding concern when the file was not UTF-8.
+    pub fn check_decoded(
+        &self,
+        proposal: &Proposal,
+        encoding: &str,
+    ) -> Result<OracleEvaluation, OracleError> {
+        let mut evaluation = self.check_proposal(proposal)?;
+        let already = evaluation
+            .concerns
+            .iter()
+            .any(|c| matches!(c.concern_type, ConcernType::UnusualEncoding { .. }));
+        if encoding != encoding_rs::UTF_8.name() && !already {
+            evaluation.concerns.push(encoding_concern(encoding));
+            if evaluation.violations.is_empty() {
+                evaluation.verdict =
+                    PolicyVerdict::SoftConcern(evaluation.concerns[0].concern_type.clone());
+            }
+        }
+        Ok(evaluation)
+    }
+
+    /// Byte range of `content` that triggered a violation, for code frames
+    ///
+    /// Only content findings have a location; path, toolchain and removal
+    /// findings return `None`.
+    pub fn locate_violation(
+        &self,
+        violation: &ViolationType,
+        content: &str,
+    ) -> Option<std::ops::Range<usize>> {
+        let folded = self.folded(content);
+        let content = folded.as_str();
+        match violation {
+            ViolationType::ForbiddenPattern { pattern, .. } if pattern.starts_with("polyglot:") => {
+                self.match_ranges(violation, content).into_iter().next()
+            }
+            ViolationType::ForbiddenPattern { pattern, .. } => {
+                let rule = self
+                    .policy
+                    .patterns
+                    .forbidden_patterns
+                    .iter()
+                    .find(|p| &p.name == pattern)?;
+                PatternMatcher::new(rule)
+                    .ok()?
+                    .find_iter(content)
+                    .next()
+                    .map(|m| m.range())
+            }
+            ViolationType::ForbiddenLanguage { language, .. } => {
+                let lang = self
+                    .policy
+                    .languages
+                    .forbidden
+                    .iter()
+                    .find(|l| &l.name == language)?;
+                self.find_language(content, lang, detect_language(content).as_ref())
+                    .map(|finding| finding.range)
+            }
+            ViolationType::UnsafeBlock { blocks, .. } => {
+                let &(start_line, _) = blocks.first()?;
+                find_unsafe_blocks(content)
+                    .into_iter()
+                    .find(|b| b.start_line == start_line)
+                    .map(|b| b.range)
+            }
+            ViolationType::ForbiddenImport { imports, .. } => {
+                let &(_, line) = imports.first()?;
+                let start = content
+                    .split_inclusive('\n')
+                    .take(line - 1)
+                    .map(str::len)
+                    .sum();
+                let end = content[start..]
+                    .find('\n')
+                    .map_or(content.len(), |n| start + n);
+                Some(start..end)
+            }
+            _ => None,
+        }
+    }
+
+    /// Byte range of every match of a forbidden pattern in `content`,
+    /// skipping allowlisted ones; other violations give at most the one
+    /// range from [`Oracle::locate_violation`]
+    pub fn match_ranges(
+        &self,
+        violation: &ViolationType,
+        content: &str,
+    ) -> Vec<std::ops::Range<usize>> {
+        let ViolationType::ForbiddenPattern { pattern, file } = violation else {
+            return self
+                .locate_violation(violation, content)
+                .into_iter()
+                .collect();
+        };
+        if let Some(language) = pattern.strip_prefix("polyglot:") {
+            let polyglots = self.find_polyglots(std::slice::from_ref(file), content);
+            return polyglots
+                .into_iter()
+                .filter(|p| p.language == language)
+                .map(|p| p.range)
+                .collect();
+        }
+        let rules = &self.policy.patterns.forbidden_patterns;
+        let Some(Ok(matcher)) = rules
+            .iter()
+            .find(|p| &p.name == pattern)
+            .map(PatternMatcher::new)
+        else {
+            return Vec::new();
+        };
+        matcher
+            .find_iter(&self.folded(content))
+            .map(|m| m.range())
+            .collect()
+    }
+
+    /// `content` with split string literals joined, as the checks see it;
+    /// offsets into it are offsets into `content`
+    fn folded(&self, content: &str) -> String {
+        match self.policy.obfuscation.fold_concatenation {
+            true => fold_string_concatenation(content).text,
+            false => content.to_string(),
+        }
+    }
+
+    /// Machine-applicable fixes for a violation in `content`
+    ///
+    /// Forbidden patterns are rewritten with the rule's `fix` or have their
+    /// lines deleted; a file in a forbidden language with a Tier 1 port
+    /// target is renamed to a scaffold that keeps the original commented
+    /// out. Other violations need a person and get no fix.
+    pub fn quick_fixes(&self, violation: &ViolationType, content: &str) -> Vec<QuickFix> {
+        match violation {
+            ViolationType::ForbiddenPattern { pattern, file } => {
+                let rules = &self.policy.patterns.forbidden_patterns;
+                let Some(rule) = rules.iter().find(|p| &p.name == pattern && !p.no_fix) else {
+                    return Vec::new();
+                };
+                let Ok(matcher) = PatternMatcher::new(rule) else {
+                    return Vec::new();
+                };
+                let (title, edits) = match &rule.fix {
+                    Some(template) => {
+                        let edits: Vec<FixEdit> = matcher
+                            .captures_iter(content)
+                            .map(|caps| {
+                                let whole = caps.get(0).expect("invariant: group 0 always matches");
+                                let mut text = String::new();
+                                caps.expand(template, &mut text);
+                                FixEdit::Replace {
+                                    start: whole.start(),
+                                    end: whole.end(),
+                                    text,
+                                }
+                            })
+                            .collect();
+                        (format!("Rewrite '{}' matches", pattern), edits)
+                    }
+                    None => {
+                        let mut lines: Vec<usize> = matcher
+                            .find_iter(content)
+                            .map(|m| content[..m.start()].matches('\n').count() + 1)
+                            .collect();
+                        lines.dedup();
+                        let edits = lines
+                            .into_iter()
+                            .map(|line| FixEdit::DeleteLine { line })
+                            .collect();
+                        (format!("Delete lines matching '{}'", pattern), edits)
+                    }
+                };
+                if edits.is_empty() {
+                    return Vec::new();
+                }
+                vec![QuickFix {
+                    title,
+                    file: file.clone(),
+                    edits,
+                }]
+            }
+            ViolationType::ForbiddenLanguage { language, file, .. } => {
+                let source = self
+                    .policy
+                    .languages
+                    .forbidden
+                    .iter()
+                    .find(|l| &l.name == language);
+                let target = source.and_then(|l| l.alternative.as_ref()).and_then(|alt| {
+                    self.policy
+                        .languages
+                        .tier1
+                        .iter()
+                        .find(|l| l.name == alt.language)
+                });
+                let (Some(target), Some(source)) = (target, source) else {
+                    return Vec::new();
+                };
+                let Some(stem) = source
+                    .extensions
+                    .iter()
+                    .find_map(|ext| file.strip_suffix(ext.as_str()))
+                else {
+                    return Vec::new();
+                };
+                let Some(extension) = target.extensions.first() else {
+                    return Vec::new();
+                };
+                let to = format!("{}{}", stem, extension);
+                let mut scaffold = format!("// Ported from {}: rewrite in {}\n", file, target.name);
+                for line in content.lines() {
+                    scaffold.push_str(format!("// {}", line).trim_end());
+                    scaffold.push('\n');
+                }
+                vec![QuickFix {
+                    title: format!("Rename to {} and port to {}", to, target.name),
+                    file: file.clone(),
+                    edits: vec![FixEdit::Rename { to, scaffold }],
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Scan a directory for policy violations
+    pub fn scan_directory(&self, path: &Path) -> Result<DirectoryScanResult, OracleError> {
+        let mut violations = Vec::new();
+        let mut concerns = Vec::new();
+        let mut truncated = Vec::new();
+        let mut exempted = Vec::new();
+        let files_scanned = self.scan_each(path, |event| {
+            match event {
+                ScanEvent::Violation(v) => violations.push(v),
+                ScanEvent::Concern(c) => concerns.push(c),
+                ScanEvent::Truncated(file) => truncated.push(file),
+                ScanEvent::Exempted(e) => exempted.push(e),
+            }
+            ControlFlow::Continue(())
+        })?;
+
+        let verdict = if !violations.is_empty() {
+            PolicyVerdict::HardViolation(violations[0].violation.clone())
+        } else if !concerns.is_empty() {
+            PolicyVerdict::SoftConcern(concerns[0].concern.clone())
+        } else {
+            PolicyVerdict::Compliant
+        };
+
+        Ok(DirectoryScanResult {
+            path: path.to_path_buf(),
+            verdict,
+            files_scanned,
+            violations,
+            concerns,
+            truncated,
+            exempted,
+        })
+    }
+
+    /// Scan a directory, handing each finding to `emit` as soon as its file
+    /// is checked instead of collecting them; `emit` can stop the scan early.
+    /// Returns the number of files scanned.
+    pub fn scan_each(
+        &self,
+        path: &Path,
+        mut emit: impl FnMut(ScanEvent) -> ControlFlow<()>,
+    ) -> Result<usize, OracleError> {
+        let mut files_scanned = 0;
+        let patterns = self.scan_matchers()?;
+
+        let mut walk = Walk::new(path, self.follow_symlinks);
+        for entry in walk.run(path)? {
+            files_scanned += 1;
+            if self
+                .scan_file(&entry, &patterns, None, &mut emit)
+                .is_break()
+            {
+                break;
+            }
+        }
+
+        Ok(files_scanned)
+    }
+
+    /// Scan `path` under this policy and `proposed` in one walk, reading
+    /// each file once, and list the files whose verdict differs
+    pub fn policy_impact(
+        &self,
+        proposed: &Oracle,
+        path: &Path,
+    ) -> Result<PolicyImpact, OracleError> {
+        let current_patterns = self.scan_matchers()?;
+        let proposed_patterns = proposed.scan_matchers()?;
+        // Enough for either policy's budget, plus a byte to tell a file was cut short
+        let read_limit = [self, proposed]
+            .iter()
+            .filter(|o| o.policy.scan.content_checks)
+            .map(|o| o.policy.scan.max_file_bytes.saturating_add(1))
+            .max();
+
+        let mut files_scanned = 0;
+        let mut changes = Vec::new();
+        let mut walk = Walk::new(path, self.follow_symlinks);
+        for file in walk.run(path)? {
+            files_scanned += 1;
+            let mut bytes = Vec::new();
+            if let Some(limit) = read_limit {
+                let read =
+                    fs::File::open(&file).and_then(|f| f.take(limit).read_to_end(&mut bytes));
+                if let Err(e) = read {
+                    tracing::warn!("Could not read {}: {}", file.display(), e);
+                    bytes.clear();
+                }
+            }
+            let (current, current_rules) = self.file_verdict(&file, &current_patterns, &bytes);
+            let (after, proposed_rules) = proposed.file_verdict(&file, &proposed_patterns, &bytes);
+            if current != after {
+                changes.push(VerdictChange {
+                    rules_added: proposed_rules.difference(&current_rules).cloned().collect(),
+                    rules_removed: current_rules.difference(&proposed_rules).cloned().collect(),
+                    file,
+                    current,
+                    proposed: after,
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| a.file.cmp(&b.file));
+        Ok(PolicyImpact {
+            path: path.to_path_buf(),
+            files_scanned,
+            changes,
+        })
+    }
+
+    /// Compiled forbidden patterns for content checks, if the scan makes them
+    fn scan_matchers(&self) -> Result<Vec<(&ForbiddenPattern, ByteMatcher)>, OracleError> {
+        let mut patterns = Vec::new();
+        if self.policy.scan.content_checks {
+            for pattern in &self.policy.patterns.forbidden_patterns {
+                patterns.push((pattern, ByteMatcher::new(pattern)?));
+            }
+        }
+        Ok(patterns)
+    }
+
+    /// Verdict of one file, from its already read bytes, and the rules it hit
+    fn file_verdict(
+        &self,
+        file: &Path,
+        patterns: &[(&ForbiddenPattern, ByteMatcher)],
+        bytes: &[u8],
+    ) -> (FileVerdict, BTreeSet<String>) {
+        let mut verdict = FileVerdict::Compliant;
+        let mut rules = BTreeSet::new();
+        let _ = self.scan_file(file, patterns, Some(bytes), &mut |event| {
+            match event {
+                ScanEvent::Violation(v) => {
+                    verdict = FileVerdict::Violation;
+                    rules.insert(v.violation.rule_id());
+                }
+                ScanEvent::Concern(c) => {
+                    verdict = verdict.max(FileVerdict::Concern);
+                    rules.insert(c.concern.rule_id());
+                }
+                ScanEvent::Truncated(_) | ScanEvent::Exempted(_) => {}
+            }
+            ControlFlow::Continue(())
+        });
+        (verdict, rules)
+    }
+
+    /// Hand each finding for one scanned file to `emit`; content comes
+    /// from `bytes` when the caller has read it, else from the file
+    fn scan_file(
+        &self,
+        file_path: &Path,
+        patterns: &[(&ForbiddenPattern, ByteMatcher)],
+        bytes: Option<&[u8]>,
+        emit: &mut impl FnMut(ScanEvent) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        let limits = &self.policy.scan;
+
+        // Stream content through the forbidden patterns for this file type
+        let name = file_path.to_string_lossy();
+        let applicable: Vec<_> = patterns
+            .iter()
+            .filter(|(p, _)| {
+                p.file_types
+                    .iter()
+                    .any(|t| t == "*" || name.ends_with(t.as_str()))
+            })
+            .collect();
+        if !applicable.is_empty() {
+            let matchers: Vec<&ByteMatcher> = applicable.iter().map(|(_, m)| m).collect();
+            let scanned = match bytes {
+                Some(bytes) => scan_stream(bytes, &matchers, limits),
+                None => {
+                    fs::File::open(file_path).and_then(|file| scan_stream(file, &matchers, limits))
+                }
+            };
+            let (matched, cut_short) = match scanned {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("Could not read {}: {}", file_path.display(), e);
+                    (Vec::new(), false)
+                }
+            };
+            for (index, _) in matched.iter().enumerate().filter(|(_, m)| **m) {
+                emit(ScanEvent::Violation(FileViolation {
+                    file: file_path.to_path_buf(),
+                    violation: ViolationType::ForbiddenPattern {
+                        pattern: applicable[index].0.name.clone(),
+                        file: name.to_string(),
+                    },
+                }))?;
+            }
+            if cut_short {
+                emit(ScanEvent::Truncated(file_path.to_path_buf()))?;
+            }
+        }
+
+        // Check file extension against forbidden languages
+        for lang in &self.policy.languages.forbidden {
+            if self.file_matches_language(&file_path.to_string_lossy(), lang) {
+                let violation = ViolationType::ForbiddenLanguage {
+                    language: lang.name.clone(),
+                    file: file_path.to_string_lossy().to_string(),
+                    context: "File extension".to_string(),
+                    detection: None,
+                };
+                match self
+                    .matching_exception(&[file_path.to_string_lossy().to_string()], &lang.name)
+                {
+                    Some((exception, allowed)) => emit(ScanEvent::Exempted(FileExemption {
+                        file: file_path.to_path_buf(),
+                        rule: violation.rule_id(),
+                        reason: format!("{} (allowed in {})", exception.reason, allowed),
+                    }))?,
+                    None => emit(ScanEvent::Violation(FileViolation {
+                        file: file_path.to_path_buf(),
+                        violation,
+                    }))?,
+                }
+            }
+        }
+
+        // Check tier2 languages
+        for lang in &self.policy.languages.tier2 {
+            if self.file_matches_language(&file_path.to_string_lossy(), lang) {
+                emit(ScanEvent::Concern(FileConcern {
+                    file: file_path.to_path_buf(),
+                    concern: ConcernType::Tier2Language {
+                        language: lang.name.clone(),
+                    },
+                }))?;
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    // Helper methods
+    fn content_contains_language(&self, content: &str, lang: &LanguageConfig) -> bool {
+        let content_lower = content.to_lowercase();
+        lang.markers
+            .iter()
+            .any(|m| content_lower.contains(&m.to_lowercase()))
+    }
+
+    fn file_matches_language(&self, file: &str, lang: &LanguageConfig) -> bool {
+        let file_lower = file.to_lowercase();
+        lang.extensions
+            .iter()
+            .any(|ext| file_lower.ends_with(&ext.to_lowercase()))
+    }
+
+    /// Any of `any` (if given) and all of `all` appear in the proposal's
+    /// content or paths; false when both groups are empty
+    fn marker_groups_match(&self, proposal: &Proposal, any: &[String], all: &[String]) -> bool {
+        let present = |marker: &String| {
+            let marker = std::slice::from_ref(marker);
+            self.content_has_markers(&proposal.content, marker)
+                || self.files_have_markers(&proposal.files_affected, marker)
+        };
+        if any.is_empty() && all.is_empty() {
+            return false;
+        }
+        (any.is_empty() || any.iter().any(present)) && all.iter().all(present)
+    }
+
+    fn content_has_markers(&self, content: &str, markers: &[String]) -> bool {
+        let content_lower = content.to_lowercase();
+        markers
+            .iter()
+            .any(|m| content_lower.contains(&m.to_lowercase()))
+    }
+
+    fn files_have_markers(&self, files: &[String], markers: &[String]) -> bool {
+        for file in files {
+            let file_lower = file.to_lowercase();
+            for marker in markers {
+                if file_lower.contains(&marker.to_lowercase()) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn path_contains(&self, file: &str, fragment: &str) -> bool {
+        let paths = &self.policy.paths;
+        paths.key(file).contains(&paths.key(fragment))
+    }
+
+    /// Exception, and its path, that lets one of `files` use `language`
+    fn matching_exception(
+        &self,
+        files: &[String],
+        language: &str,
+    ) -> Option<(&ExceptionRule, &str)> {
+        for exc in &self.policy.languages.exceptions {
+            if exc.language.to_lowercase() == language.to_lowercase() {
+                for file in files {
+                    for allowed in &exc.allowed_paths {
+                        if self.path_contains(file, allowed) {
+                            return Some((exc, allowed));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Source around a language finding in `content`
+    fn language_context(
+        &self,
+        content: &str,
+        range: std::ops::Range<usize>,
+        severity: Severity,
+    ) -> String {
+        let window = &self.policy.enforcement.evidence_context;
+        extract_context(
+            content,
+            range,
+            window.lines_for(severity),
+            window.max_line_chars,
+        )
+    }
+
+    /// Every case-insensitive match of a marker of `lang` in `content`
+    fn marker_matches<'a>(
+        &self,
+        content: &'a str,
+        lang: &'a LanguageConfig,
+    ) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+        lang.markers
+            .iter()
+            .filter_map(|marker| Regex::new(&format!("(?i){}", regex::escape(marker))).ok())
+            .flat_map(move |re| re.find_iter(content).map(|m| m.range()).collect::<Vec<_>>())
+    }
+
+    /// Where `content` is written in `lang`, with how that was decided
+    ///
+    /// `detected` is [`detect_language`] on the whole content, which counts
+    /// without any marker. Without the `tree-sitter` feature, for languages
+    /// it has no grammar for and for content over 64 KiB the first marker
+    /// decides; otherwise a marker only counts when its statement parses as
+    /// the language, and adds structure when no other grammar parses it as
+    /// well. A `#!` line naming one of the language's interpreters counts last.
+    fn find_language(
+        &self,
+        content: &str,
+        lang: &LanguageConfig,
+        detected: Option<&StructuralMatch>,
+    ) -> Option<LanguageFinding> {
+        let parsed = |found: StructuralMatch, marker: bool| LanguageFinding {
+            range: found.range.clone(),
+            detail: format!(
+                "parsed {} ({:.2})",
+                found.node.as_deref().unwrap_or_default(),
+                found.confidence
+            ),
+            marker,
+            structure: true,
+        };
+        let mut markers = self.marker_matches(content, lang);
+        let found = match () {
+            #[cfg(feature = "tree-sitter")]
+            () if structure::has_grammar(&lang.name)
+                && content.len() <= structure::MAX_PARSE_BYTES =>
+            {
+                let offsets = markers.map(|range| range.start).collect();
+                match structure::confirm(&lang.name, content, offsets) {
+                    structure::Confirmation::Confirmed(found) => Some(parsed(found, true)),
+                    structure::Confirmation::Tied(range) => Some(LanguageFinding {
+                        range,
+                        detail: "content marker, parses as another language too".to_string(),
+                        marker: true,
+                        structure: false,
+                    }),
+                    structure::Confirmation::Refuted => None,
+                }
+            }
+            () => markers.next().map(|range| LanguageFinding {
+                range,
+                detail: "content marker".to_string(),
+                marker: true,
+                structure: false,
+            }),
+        };
+        found
+            .or_else(|| {
+                detected
+                    .filter(|d| d.language == lang.name)
+                    .map(|d| parsed(d.clone(), false))
+            })
+            .or_else(|| {
+                shebang_matches(content, lang).then(|| LanguageFinding {
+                    range: 0..content.find('\n').unwrap_or(content.len()),
+                    detail: "shebang".to_string(),
+                    marker: false,
+                    structure: false,
+                })
+            })
+    }
+
+    /// Forbidden languages hidden in comments, heredocs, `<script>`
+    /// elements or image files of otherwise allowed content
+    ///
+    /// A container that names a forbidden language (`python3 <<EOF`,
+    /// `<script type="text/python">`, `<?php` behind an image signature)
+    /// decides it; otherwise two distinct markers of a language inside it,
+    /// or a clean parse with the `tree-sitter` feature. Files already in a
+    /// forbidden language are left to the extension rule.
+    pub fn find_polyglots(&self, files: &[String], content: &str) -> Vec<Polyglot> {
+        let forbidden = &self.policy.languages.forbidden;
+        if files.iter().any(|file| {
+            forbidden
+                .iter()
+                .any(|lang| self.file_matches_language(file, lang))
+        }) {
+            return Vec::new();
+        }
+        let file = files.first().map_or("", String::as_str);
+        let mut found = Vec::new();
+        for (container, range, named) in polyglot_containers(file, content) {
+            let text = &content[range.clone()];
+            let lower = text.to_lowercase();
+            let words: Vec<&str> = named
+                .iter()
+                .flat_map(|hint| hint.split(['/', ' ', '\t']))
+                .collect();
+            let language = forbidden.iter().find_map(|lang| {
+                let by_name = words.iter().any(|word| {
+                    *word == lang.name
+                        || lang
+                            .extensions
+                            .iter()
+                            .any(|e| e.trim_start_matches('.') == *word)
+                        || names_interpreter(word, lang)
+                });
+                let distinct = lang
+                    .markers
+                    .iter()
+                    .filter(|m| lower.contains(&m.to_lowercase()))
+                    .count();
+                let by_content = distinct >= 2 || parses_as(&lang.name, text);
+                (by_name || by_content).then(|| lang.name.clone())
+            });
+            if let Some(language) = language {
+                found.push(Polyglot {
+                    language,
+                    container,
+                    range,
+                });
+            }
+        }
+        found
+    }
+
+    /// Weighted score of every signal that `proposal` is written in `lang`
+    fn language_score(
+        &self,
+        proposal: &Proposal,
+        lang: &LanguageConfig,
+        finding: &LanguageFinding,
+    ) -> LanguageScore {
+        let weights = &self.policy.languages.detection;
+        let mut signals = Vec::new();
+        let mut markers = 0.0;
+        if finding.marker {
+            let content = proposal.content.to_lowercase();
+            let distinct = lang
+                .markers
+                .iter()
+                .filter(|m| content.contains(&m.to_lowercase()))
+                .count();
+            let extra = distinct.saturating_sub(1) as f64 * weights.extra_marker;
+            markers = weights.marker + extra;
+            signals.push(("markers".to_string(), markers));
+        }
+        if finding.structure {
+            signals.push(("structure".to_string(), weights.structure));
+        }
+        if shebang_matches(&proposal.content, lang) {
+            signals.push(("shebang".to_string(), weights.shebang));
+        }
+        let files = &proposal.files_affected;
+        if files
+            .iter()
+            .any(|file| self.file_matches_language(file, lang))
+        {
+            signals.push(("extension".to_string(), weights.extension));
+        }
+        let languages = &self.policy.languages;
+        let allowed = languages.tier1.iter().chain(&languages.tier2);
+        if allowed.clone().any(|other| {
+            files
+                .iter()
+                .any(|file| self.file_matches_language(file, other))
+        }) {
+            // Keywords like `def ` or `import ` are common to many
+            // languages, so the file's allowed extension outweighs them
+            signals.push((
+                "allowed_extension".to_string(),
+                -(markers + weights.allowed_extension),
+            ));
+        }
+        let score = signals
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum::<f64>()
+            .clamp(0.0, 1.0);
+        LanguageScore { score, signals }
+    }
+}
+
+/// A forbidden language found in proposal content
+struct LanguageFinding {
+    /// The marker, parsed node or shebang line that gave it away
+    range: std::ops::Range<usize>,
+    /// How it was found, for the trace
+    detail: String,
+    marker: bool,
+    structure: bool,
+}
+
+/// The first line is a `#!` naming one of `lang`'s interpreters, directly
+/// or through `env`
+fn shebang_matches(content: &str, lang: &LanguageConfig) -> bool {
+    let Some(line) = content
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("#!"))
+    else {
+        return false;
+    };
+    line.split(|c: char| c == '/' || c.is_whitespace())
+        .any(|word| names_interpreter(word, lang))
+}
+
+/// `word` is one of `lang`'s interpreters, with or without a version
+/// suffix (`python3.12`)
+fn names_interpreter(word: &str, lang: &LanguageConfig) -> bool {
+    lang.shebangs.iter().any(|interpreter| {
+        word.strip_prefix(interpreter.as_str())
+            .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    })
+}
+
+/// Run byte regexes over a stream in chunks
+///
+/// Each chunk is searched together with the last `chunk_overlap` bytes of
+/// the one before, so a match up to that long is found across a boundary.
+/// Memory maps are avoided because the crate forbids `unsafe`. Returns a
+/// match flag per regex and whether `max_file_bytes` cut the stream short.
+fn scan_stream(
+    mut reader: impl Read,
+    matchers: &[&ByteMatcher],
+    limits: &ScanPolicy,
+) -> std::io::Result<(Vec<bool>, bool)> {
+    let mut matched = vec![false; matchers.len()];
+    let mut window: Vec<u8> = Vec::new();
+    let mut chunk = vec![0u8; limits.chunk_bytes.max(1)];
+    let mut total = 0u64;
+
+    loop {
+        let remaining = limits.max_file_bytes.saturating_sub(total);
+        if remaining == 0 {
+            let more = reader.read(&mut [0u8; 1])? > 0;
+            return Ok((matched, more));
+        }
+        let want = chunk
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let n = reader.read(&mut chunk[..want])?;
+        if n == 0 {
+            return Ok((matched, false));
+        }
+        total += n as u64;
+        window.extend_from_slice(&chunk[..n]);
+        for (flag, matcher) in matched.iter_mut().zip(matchers) {
+            *flag = *flag || matcher.is_match(&window);
+        }
+        if matched.iter().all(|m| *m) {
+            return Ok((matched, false));
+        }
+        if window.len() > limits.chunk_overlap {
+            window.drain(..window.len() - limits.chunk_overlap);
+        }
+    }
+}
+
+fn encoding_concern(encoding: &str) -> Concern {
+    Concern {
+        rule: "content_encoding".to_string(),
+        concern_type: ConcernType::UnusualEncoding {
+            encoding: encoding.to_string(),
+        },
+        suggestion: format!("Content was {}; commit it as UTF-8", encoding),
+    }
+}
+
+// Simple directory walker
+//
+// Symlinks are skipped unless following is enabled; followed links must
+// resolve inside the scan root, and visited directories are remembered so
+// a link back to an ancestor cannot loop.
+struct Walk {
+    root: Option<PathBuf>,
+    visited: HashSet<PathBuf>,
+}
+
+impl Walk {
+    fn new(path: &Path, follow_symlinks: bool) -> Self {
+        Self {
+            root: if follow_symlinks {
+                path.canonicalize().ok()
+            } else {
+                None
+            },
+            visited: HashSet::new(),
+        }
+    }
+
+    fn run(&mut self, path: &Path) -> Result<Vec<PathBuf>, OracleError> {
+        let mut files = Vec::new();
+
+        if path.is_file() {
+            files.push(path.to_path_buf());
+            return Ok(files);
+        }
+
+        if !path.exists() {
+            return Ok(files);
+        }
+
+        if let Ok(canonical) = path.canonicalize() {
+            if !self.visited.insert(canonical) {
+                return Ok(files);
+            }
+        }
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+
+            let name = entry_path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with('.')
+                || name == "node_modules"
+                || name == "target"
+                || name == "_build"
+            {
+                continue;
+            }
+
+            let file_type = entry.file_type()?;
+            let is_dir = if file_type.is_symlink() {
+                let Some(root) = &self.root else { continue };
+                match entry_path.canonicalize() {
+                    Ok(target) if target.starts_with(root) => target.is_dir(),
+                    _ => continue,
+                }
+            } else {
+                file_type.is_dir()
+            };
+
+            if is_dir {
+                files.extend(self.run(&entry_path)?);
+            } else {
+                files.push(entry_path);
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+// ============ Content Encoding ============
+
+/// Text decoded from bytes, with the encoding it was read as
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedContent {
+    pub text: String,
+    /// WHATWG encoding name, e.g. "UTF-8", "UTF-16LE", "windows-1252"
+    pub encoding: String,
+}
+
+impl DecodedContent {
+    /// Anything other than UTF-8 (which includes plain ASCII)
+    pub fn is_unusual(&self) -> bool {
+        self.encoding != encoding_rs::UTF_8.name()
+    }
+}
+
+/// Decode file bytes: BOM first, then a BOM-less UTF-16 check (ASCII in
+/// UTF-16 is also valid UTF-8), then UTF-8, then a statistical guess
+pub fn decode_bytes(bytes: &[u8]) -> DecodedContent {
+    let encoding = if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+        encoding
+    } else if let Some(encoding) = utf16_without_bom(bytes) {
+        encoding
+    } else if std::str::from_utf8(bytes).is_ok() {
+        encoding_rs::UTF_8
+    } else {
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(bytes, true);
+        detector.guess(None, true)
+    };
+    let (text, used, _) = encoding.decode(bytes);
+    DecodedContent {
+        text: text.into_owned(),
+        encoding: used.name().to_string(),
+    }
+}
+
+/// Read a file as text whatever its encoding
+pub fn read_text_file(path: &Path) -> std::io::Result<DecodedContent> {
+    fs::read(path).map(|bytes| decode_bytes(&bytes))
+}
+
+/// UTF-16 without a BOM shows up as NULs in every other byte of ASCII text
+fn utf16_without_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if bytes.len() < 4 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let even_nuls = bytes.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_nuls = bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    if odd_nuls * 10 >= pairs * 7 && even_nuls * 10 < pairs {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_nuls * 10 >= pairs * 7 && odd_nuls * 10 < pairs {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Transcode proposal content that carries another encoding's raw bytes
+///
+/// Content arriving as a JSON string can still hold UTF-16 code units one
+/// byte per character (a BOM, or NULs between ASCII letters), which would
+/// otherwise hide every marker. Returns `None` for ordinary text.
+pub fn transcode_content(content: &str) -> Option<DecodedContent> {
+    if content.chars().any(|c| c as u32 > 0xff) {
+        return None;
+    }
+    let bytes: Vec<u8> = content.chars().map(|c| c as u32 as u8).collect();
+    let bom = bytes.starts_with(&[0xff, 0xfe]) || bytes.starts_with(&[0xfe, 0xff]);
+    if !bom && utf16_without_bom(&bytes).is_none() {
+        return None;
+    }
+    Some(decode_bytes(&bytes)).filter(DecodedContent::is_unusual)
+}
+
+// ============ Unsafe Blocks ============
+
+/// An `unsafe { ... }` block in Rust source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsafeBlock {
+    /// Bytes from `unsafe` to the closing brace
+    pub range: std::ops::Range<usize>,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// A `// SAFETY:` comment ends the comment lines above the block, or
+    /// precedes it on its own line
+    pub safety_comment: bool,
+}
+
+/// Find the `unsafe` blocks in Rust `content`
+///
+/// Comments and string and char literals are skipped, so `unsafe` in a
+/// doc comment or message does not count; `unsafe fn` and `unsafe impl`
+/// are not blocks. An unclosed block runs to the end of the content.
+pub fn find_unsafe_blocks(content: &str) -> Vec<UnsafeBlock> {
+    let code = blank_rust_literals(content);
+    let ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+    let mut blocks = Vec::new();
+    let mut from = 0;
+    while let Some(found) = code[from..].windows(6).position(|w| w == b"unsafe") {
+        let start = from + found;
+        from = start + 6;
+        if (start > 0 && ident(code[start - 1])) || code.get(from).is_some_and(|&b| ident(b)) {
+            continue;
+        }
+        let open = from
+            + code[from..]
+                .iter()
+                .take_while(|b| b.is_ascii_whitespace())
+                .count();
+        if code.get(open) != Some(&b'{') {
+            continue;
+        }
+        let mut depth = 0usize;
+        let mut end = code.len();
+        for (i, &b) in code.iter().enumerate().skip(open) {
+            match b {
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let start_line = line_of(start);
+        blocks.push(UnsafeBlock {
+            range: start..end,
+            start_line,
+            end_line: line_of(end.saturating_sub(1).max(start)),
+            safety_comment: has_safety_comment(content, start),
+        });
+    }
+    blocks
+}
+
+/// Whether a `SAFETY:` comment precedes `offset` on its line, or ends the
+/// run of comment and attribute lines directly above it
+fn has_safety_comment(content: &str, offset: usize) -> bool {
+    let is_safety = |text: &str| text.to_ascii_uppercase().contains("SAFETY:");
+    let line_start = content[..offset].rfind('\n').map_or(0, |n| n + 1);
+    if is_safety(&content[line_start..offset]) {
+        return true;
+    }
+    for line in content[..line_start].lines().rev() {
+        let line = line.trim_start();
+        let comment = ["//", "/*", "*"].iter().any(|p| line.starts_with(p));
+        if comment && is_safety(line) {
+            return true;
+        }
+        if !comment && !line.starts_with("#[") {
+            return false;
+        }
+    }
+    false
+}
+
+/// `content` as bytes with comments and string and char literals blanked
+/// to spaces; newlines are kept, so offsets and line numbers still match
+fn blank_rust_literals(content: &str) -> Vec<u8> {
+    let mut code = content.as_bytes().to_vec();
+    for range in rust_literals(content) {
+        for b in &mut code[range] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    }
+    code
+}
+
+/// Byte ranges of the comments and string and char literals in `content`
+fn rust_literals(content: &str) -> Vec<std::ops::Range<usize>> {
+    let src = content.as_bytes();
+    let mut literals = Vec::new();
+    let find = |from: usize, needle: &[u8]| {
+        src[from..]
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map_or(src.len(), |n| from + n + needle.len())
+    };
+    let mut i = 0;
+    while i < src.len() {
+        let rest = &src[i..];
+        let after_ident = i > 0 && (src[i - 1].is_ascii_alphanumeric() || src[i - 1] == b'_');
+        let raw = match rest {
+            [b'r', b'#' | b'"', ..] if !after_ident => Some(1),
+            [b'b', b'r', b'#' | b'"', ..] if !after_ident => Some(2),
+            _ => None,
+        };
+        let end = if rest.starts_with(b"//") {
+            src[i..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(src.len(), |n| i + n)
+        } else if rest.starts_with(b"/*") {
+            let (mut depth, mut j) = (0usize, i);
+            loop {
+                if j >= src.len() {
+                    break src.len();
+                } else if src[j..].starts_with(b"/*") {
+                    depth += 1;
+                    j += 2;
+                } else if src[j..].starts_with(b"*/") {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        break j;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+        } else if let Some(prefix) = raw {
+            let hashes = src[i + prefix..].iter().take_while(|&&b| b == b'#').count();
+            let quote = i + prefix + hashes;
+            if src.get(quote) != Some(&b'"') {
+                i += 1;
+                continue;
+            }
+            let mut close = vec![b'#'; hashes + 1];
+            close[0] = b'"';
+            find(quote + 1, &close)
+        } else if src[i] == b'"' {
+            let mut j = i + 1;
+            while j < src.len() && src[j] != b'"' {
+                j += if src[j] == b'\\' { 2 } else { 1 };
+            }
+            (j + 1).min(src.len())
+        } else if src[i] == b'\'' {
+            // A char literal, or a lifetime left as is
+            let width = content[i + 1..].chars().next().map_or(0, char::len_utf8);
+            if src.get(i + 1) == Some(&b'\\') {
+                find((i + 3).min(src.len()), b"'")
+            } else if width > 0 && src.get(i + 1 + width) == Some(&b'\'') {
+                i + 2 + width
+            } else {
+                i += 1;
+                continue;
+            }
+        } else {
+            i += 1;
+            continue;
+        };
+        literals.push(i..end);
+        i = end;
+    }
+    literals
+}
+
+// ============ Imports ============
+
+/// A module named by an import statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Import {
+    /// Full module path, e.g. `std::process::Command`, `os.path` or `node:fs`
+    pub module: String,
+    pub line: usize,
+}
+
+/// Import syntax for `file`, as named by [`ImportRule::language`]
+pub fn import_language(file: &str) -> Option<&'static str> {
+    let lower = file.to_lowercase();
+    if lower.ends_with(".rs") || file_name(file) == "Cargo.toml" {
+        Some("rust")
+    } else if lower.ends_with(".py") {
+        Some("python")
+    } else if [".js", ".mjs", ".cjs", ".jsx", ".ts", ".tsx"]
+        .iter()
+        .any(|e| lower.ends_with(e))
+    {
+        Some("javascript")
+    } else if lower.ends_with(".go") {
+        Some("go")
+    } else {
+        None
+    }
+}
+
+/// Whether `module` is `banned` or lies under it
+fn module_within(language: &str, module: &str, banned: &str) -> bool {
+    let separator = match language {
+        "rust" => "::",
+        "python" => ".",
+        _ => "/",
+    };
+    module == banned
+        || module
+            .strip_prefix(banned)
+            .is_some_and(|rest| rest.starts_with(separator))
+}
+
+/// Modules imported by `content` of `file`, written in `language`
+///
+/// This reads import statements only: Rust `use` and `extern crate`
+/// (with `{}` groups expanded) and `Cargo.toml` dependency keys, Python
+/// `import` and `from ... import`, JavaScript `import`, `export ... from`
+/// and `require`, and Go `import`. Fully qualified paths in code are not
+/// imports.
+pub fn parse_imports(language: &str, file: &str, content: &str) -> Vec<Import> {
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+    let mut imports = Vec::new();
+    match language {
+        "rust" if file_name(file) == "Cargo.toml" => {
+            let mut in_dependencies = false;
+            for (index, line) in content.lines().enumerate() {
+                let line = line.trim();
+                if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                    let table = section.rsplit('.').find(|t| t.ends_with("dependencies"));
+                    in_dependencies = table.is_some();
+                    if let Some(name) = section.split_once("dependencies.").map(|(_, name)| name) {
+                        imports.push(Import {
+                            module: name.replace('-', "_"),
+                            line: index + 1,
+                        });
+                        in_dependencies = false;
+                    }
+                    continue;
+                }
+                let key = line.split(['=', '.']).next().unwrap_or_default().trim();
+                if in_dependencies && !key.is_empty() && !key.starts_with('#') {
+                    imports.push(Import {
+                        module: key.replace('-', "_"),
+                        line: index + 1,
+                    });
+                }
+            }
+        }
+        "rust" => {
+            let code = String::from_utf8(blank_rust_literals(content)).unwrap_or_default();
+            let statement =
+                Regex::new(r"\b(use|extern\s+crate)\s+([^;]+);").expect("invariant: valid regex");
+            let alias = Regex::new(r"\s+as\s+\w+").expect("invariant: valid regex");
+            for caps in statement.captures_iter(&code) {
+                let line = line_of(
+                    caps.get(0)
+                        .expect("invariant: group 0 always matches")
+                        .start(),
+                );
+                let tree: String = alias.replace_all(&caps[2], "").split_whitespace().collect();
+                for module in expand_use_tree("", tree.trim_start_matches("::")) {
+                    imports.push(Import { module, line });
+                }
+            }
+        }
+        "python" => {
+            let mut lines = content.lines().enumerate();
+            while let Some((index, line)) = lines.next() {
+                let mut statement = line
+                    .split('#')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                while statement.contains('(') && !statement.contains(')') {
+                    let Some((_, more)) = lines.next() else { break };
+                    statement.push(' ');
+                    statement.push_str(more.split('#').next().unwrap_or_default().trim());
+                }
+                let names = |list: &str| -> Vec<String> {
+                    list.trim_matches(|c| c == '(' || c == ')' || c == ' ')
+                        .split(',')
+                        .filter_map(|n| n.split_whitespace().next())
+                        .map(str::to_string)
+                        .collect()
+                };
+                if let Some(list) = statement.strip_prefix("import ") {
+                    for module in names(list) {
+                        imports.push(Import {
+                            module,
+                            line: index + 1,
+                        });
+                    }
+                } else if let Some((package, list)) = statement
+                    .strip_prefix("from ")
+                    .and_then(|rest| rest.split_once(" import "))
+                {
+                    let package = package.trim();
+                    for name in names(list) {
+                        let module = match name.as_str() {
+                            "*" => package.to_string(),
+                            _ => format!("{}.{}", package, name),
+                        };
+                        imports.push(Import {
+                            module,
+                            line: index + 1,
+                        });
+                    }
+                }
+            }
+        }
+        "javascript" => {
+            let statement = Regex::new(
+                r#"(?m)^\s*(import|export)\s+([^'";]*?\s+from\s+)?["']([^"']+)["']|\b(require|import)\(\s*["']([^"']+)["']\s*\)"#,
+            )
+            .expect("invariant: valid regex");
+            for caps in statement.captures_iter(content) {
+                let Some(module) = caps.get(3).or_else(|| caps.get(5)) else {
+                    continue;
+                };
+                let start = caps
+                    .get(1)
+                    .or_else(|| caps.get(4))
+                    .map_or(module.start(), |k| k.start());
+                imports.push(Import {
+                    module: module.as_str().to_string(),
+                    line: line_of(start),
+                });
+            }
+        }
+        "go" => {
+            let block = Regex::new(r#"(?m)^\s*import\s*(\(([^)]*)\)|[\w.]*\s*"[^"]+")"#)
+                .expect("invariant: valid regex");
+            let path = Regex::new(r#""([^"]+)""#).expect("invariant: valid regex");
+            for caps in block.captures_iter(content) {
+                let whole = caps.get(1).expect("invariant: group 1 always matches");
+                for m in path.captures_iter(whole.as_str()) {
+                    let offset = whole.start()
+                        + m.get(0).expect("invariant: group 0 always matches").start();
+                    imports.push(Import {
+                        module: m[1].to_string(),
+                        line: line_of(offset),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+    imports
+}
+
+/// Module paths named by a Rust use tree without whitespace or aliases,
+/// e.g. `std::{fs,process::{self,Command}}`
+fn expand_use_tree(prefix: &str, tree: &str) -> Vec<String> {
+    let join = |path: &str| match (prefix, path) {
+        ("", path) => path.to_string(),
+        (prefix, "" | "self") => prefix.to_string(),
+        (prefix, path) => format!("{}::{}", prefix, path),
+    };
+    let Some(open) = tree.find('{') else {
+        return vec![join(tree)];
+    };
+    let head = join(tree[..open].trim_end_matches("::"));
+    let inner = tree[open + 1..]
+        .strip_suffix('}')
+        .unwrap_or(&tree[open + 1..]);
+    let (mut items, mut depth, mut start) = (Vec::new(), 0usize, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+    items
+        .into_iter()
+        .filter(|item| !item.is_empty())
+        .flat_map(|item| expand_use_tree(&head, item))
+        .collect()
+}
+
+// ============ Structural Detection ============
+
+/// Content a grammar parsed as one language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuralMatch {
+    pub language: String,
+    /// Share of the text that parsed without errors, 0.0 to 1.0
+    pub confidence: f64,
+    /// First syntax node only that language has, such as `interface_declaration`
+    pub node: Option<String>,
+    /// Byte range of `node`, or of all the text without one
+    pub range: std::ops::Range<usize>,
+}
+
+/// Parse `content` as `language` (rust, typescript, python, go or java)
+///
+/// `None` without the `tree-sitter` feature or a grammar for `language`.
+pub fn parse_language(language: &str, content: &str) -> Option<StructuralMatch> {
+    #[cfg(feature = "tree-sitter")]
+    {
+        structure::parse_as(language, content)
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = (language, content);
+        None
+    }
+}
+
+/// `text` parses cleanly as `language`, with a node only it has
+fn parses_as(language: &str, text: &str) -> bool {
+    #[cfg(feature = "tree-sitter")]
+    {
+        structure::parse_as(language, text).is_some_and(|found| {
+            found.node.is_some() && found.confidence >= structure::MIN_CONFIDENCE
+        })
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = (language, text);
+        false
+    }
+}
+
+/// The language `content` parses as, when one grammar parses it cleanly
+/// and best; `None` without the `tree-sitter` feature or over 64 KiB
+pub fn detect_language(content: &str) -> Option<StructuralMatch> {
+    #[cfg(feature = "tree-sitter")]
+    {
+        structure::detect(content)
+    }
+    #[cfg(not(feature = "tree-sitter"))]
+    {
+        let _ = content;
+        None
+    }
+}
+
+// ============ Polyglot Content ============
+
+/// Code in one language hidden inside content of another kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Polyglot {
+    pub language: String,
+    /// What hides it: `block comment`, `line comments`, `heredoc`,
+    /// `script element` or an image such as `gif image`
+    pub container: String,
+    /// Byte range of the hidden code
+    pub range: std::ops::Range<usize>,
+}
+
+/// Extensions whose comments are `//` and `/* */`
+const C_STYLE_COMMENTS: &[&str] = &[
+    ".rs", ".zig", ".res", ".resi", ".c", ".h", ".cc", ".cpp", ".hpp", ".cs", ".swift", ".kt",
+    ".scala", ".js", ".mjs", ".cjs", ".css",
+];
+
+/// Signatures of image formats that still decode with text appended
+const IMAGE_SIGNATURES: &[(&str, &str)] = &[
+    ("GIF87a", "gif image"),
+    ("GIF89a", "gif image"),
+    ("\u{89}PNG", "png image"),
+    ("\u{ff}\u{d8}\u{ff}", "jpeg image"),
+];
+
+/// Regions of `content` that can carry another language: what contains
+/// each, its range and the language the container names, if any
+fn polyglot_containers(
+    file: &str,
+    content: &str,
+) -> Vec<(String, std::ops::Range<usize>, Option<String>)> {
+    let mut regions = Vec::new();
+    if let Some((signature, image)) = IMAGE_SIGNATURES
+        .iter()
+        .find(|(sig, _)| content.starts_with(sig))
+    {
+        let rest = &content[signature.len()..];
+        let named = match () {
+            _ if rest.contains("<?php") => Some("php".to_string()),
+            _ if rest.to_lowercase().contains("<script") => Some("javascript".to_string()),
+            _ => None,
+        };
+        regions.push((image.to_string(), signature.len()..content.len(), named));
+    }
+
+    let lower = file.to_lowercase();
+    if C_STYLE_COMMENTS.iter().any(|ext| lower.ends_with(ext)) {
+        let mut run: Option<std::ops::Range<usize>> = None;
+        for literal in rust_literals(content) {
+            let text = &content[literal.clone()];
+            if text.starts_with("/*") {
+                regions.push(("block comment".to_string(), literal, None));
+            } else if text.starts_with("//") {
+                match &mut run {
+                    Some(lines) if content[lines.end..literal.start].trim().is_empty() => {
+                        lines.end = literal.end
+                    }
+                    _ => {
+                        regions.extend(
+                            run.replace(literal)
+                                .map(|lines| ("line comments".to_string(), lines, None)),
+                        );
+                    }
+                }
+            }
+        }
+        regions.extend(run.map(|lines| ("line comments".to_string(), lines, None)));
+    }
+
+    // Upper-case tags with a closing line, so `x << SHIFT` is no heredoc
+    let heredoc = Regex::new(r#"(?m)^(.*?)<<[-~]?[ \t]*['"]?([A-Z_][A-Z0-9_]*)['"]?[^\n]*\n"#)
+        .expect("invariant: static regex is valid");
+    for caps in heredoc.captures_iter(content) {
+        let (Some(whole), Some(command), Some(tag)) = (caps.get(0), caps.get(1), caps.get(2))
+        else {
+            continue;
+        };
+        let body = whole.end();
+        let mut offset = body;
+        let end = content[body..].split_inclusive('\n').find_map(|line| {
+            let start = offset;
+            offset += line.len();
+            (line.trim() == tag.as_str()).then_some(start)
+        });
+        if let Some(end) = end {
+            regions.push((
+                "heredoc".to_string(),
+                body..end,
+                Some(command.as_str().to_string()),
+            ));
+        }
+    }
+
+    let script = Regex::new(r#"(?is)<(script|py-script)([^>]*)>(.*?)</(?:script|py-script)>"#)
+        .expect("invariant: static regex is valid");
+    let attribute = Regex::new(
+        r#"(?i)\b(?:type|lang|language)\s*=\s*["']?(?:text/|application/)?(?:x-)?([\w.-]+)"#,
+    )
+    .expect("invariant: static regex is valid");
+    for caps in script.captures_iter(content) {
+        let (Some(tag), Some(attributes), Some(body)) = (caps.get(1), caps.get(2), caps.get(3))
+        else {
+            continue;
+        };
+        let named = match tag.as_str().eq_ignore_ascii_case("py-script") {
+            true => Some("python".to_string()),
+            false => attribute
+                .captures(attributes.as_str())
+                .map(|a| a[1].to_lowercase()),
+        };
+        regions.push(("script element".to_string(), body.range(), named));
+    }
+    regions
+}
+
+// ============ String Concatenation ============
+
+/// String literals joined by concatenation into one value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConcatChain {
+    /// Byte range of the whole expression
+    pub range: std::ops::Range<usize>,
+    /// The joined literal contents, escapes left as written
+    pub value: String,
+    /// Number of literals joined
+    pub fragments: usize,
+}
+
+/// Content with its concatenated string literals folded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FoldedContent {
+    pub text: String,
+    pub chains: Vec<ConcatChain>,
+}
+
+/// A quoted string on one line, with escapes
+const STRING_LITERAL: &str = r#""(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'"#;
+
+/// Join string literals concatenated with `+`, `.`, `..`, `++`, `<>`, `||`
+/// or `~`, placed side by side (Python, C) or listed in Rust's `concat!`,
+/// and names bound to literals that are joined back together
+///
+/// Each chain becomes one literal in the first fragment's quotes, padded
+/// with spaces and the chain's newlines to its original length, so offsets
+/// and line numbers after it are unchanged.
+pub fn fold_string_concatenation(content: &str) -> FoldedContent {
+    let lit = STRING_LITERAL;
+    let joined = format!(r"(?:{lit})(?:(?:\s*(?:\+\+|\+|\.\.|\.|<>|\|\||~)\s*|\s+)(?:{lit}))+");
+    let concat = format!(r"concat!\(\s*(?:{lit})(?:\s*,\s*(?:{lit}))+\s*,?\s*\)");
+    let chain =
+        Regex::new(&format!("{concat}|{joined}")).expect("invariant: static regex is valid");
+    let literal = Regex::new(STRING_LITERAL).expect("invariant: static regex is valid");
+
+    let mut folds: Vec<(ConcatChain, String)> = chain
+        .find_iter(content)
+        .map(|m| {
+            let fragments: Vec<&str> = literal.find_iter(m.as_str()).map(|l| l.as_str()).collect();
+            let value: String = fragments.iter().map(|f| &f[1..f.len() - 1]).collect();
+            let quote = &fragments[0][..1];
+            let newlines = m.as_str().matches('\n').count();
+            let padding = m.len() - value.len() - 2 - newlines;
+            let text = format!(
+                "{quote}{value}{quote}{}{}",
+                " ".repeat(padding),
+                "\n".repeat(newlines)
+            );
+            let chain = ConcatChain {
+                range: m.range(),
+                value,
+                fragments: fragments.len(),
+            };
+            (chain, text)
+        })
+        .collect();
+    for (chain, text) in named_joins(content) {
+        let clear = |(folded, _): &(ConcatChain, String)| {
+            folded.range.end <= chain.range.start || chain.range.end <= folded.range.start
+        };
+        if folds.iter().all(clear) {
+            folds.push((chain, text));
+        }
+    }
+    folds.sort_by_key(|(chain, _)| chain.range.start);
+
+    let mut folded = FoldedContent {
+        text: String::with_capacity(content.len()),
+        chains: Vec::new(),
+    };
+    let mut last = 0;
+    for (chain, text) in folds {
+        folded.text.push_str(&content[last..chain.range.start]);
+        folded.text.push_str(&text);
+        last = chain.range.end;
+        folded.chains.push(chain);
+    }
+    folded.text.push_str(&content[last..]);
+    folded
+}
+
+/// Joins of names bound to string literals once (`A + B`,
+/// `format!("{}{}", A, B)`, `f"{A}{B}"`, `` `${A}${B}` ``), where the joined
+/// value fits in the expression
+///
+/// A function of no arguments that only returns such a join folds into a
+/// binding of its name, so `fn password() -> String { A + B }` reads as
+/// `password = "..."` to the secret rules.
+fn named_joins(content: &str) -> Vec<(ConcatChain, String)> {
+    let lit = STRING_LITERAL;
+    let binding = Regex::new(&format!(
+        r"(?m)^[ \t]*(?:pub(?:\([^)\n]*\))?[ \t]+)?(?:(?:const|static|let|var|val|final)[ \t]+(?:mut[ \t]+)?)?([A-Za-z_]\w*)[ \t]*(?::[^=\n]+)?=[ \t]*({lit})[ \t]*;?[ \t]*$"
+    ))
+    .expect("invariant: static regex is valid");
+    // A name bound to two different literals cannot be folded
+    let mut bound: HashMap<&str, Option<&str>> = HashMap::new();
+    for caps in binding.captures_iter(content) {
+        let (Some(name), Some(literal)) = (caps.get(1), caps.get(2)) else {
+            continue;
+        };
+        let value = &literal.as_str()[1..literal.len() - 1];
+        bound
+            .entry(name.as_str())
+            .and_modify(|v| {
+                if *v != Some(value) {
+                    *v = None
+                }
+            })
+            .or_insert(Some(value));
+    }
+    if bound.is_empty() {
+        return Vec::new();
+    }
+
+    let ident = r"[A-Za-z_]\w*";
+    let join = format!(
+        r#"format!\(\s*"(?:\{{\}})+"(?:\s*,\s*{ident})+\s*,?\s*\)|\bf"(?:\{{{ident}\}})+"|`(?:\$\{{{ident}\}})+`|\b{ident}(?:[ \t]*\+[ \t]*{ident}\b)+"#
+    );
+    let function = Regex::new(&format!(
+        r"\b(?:fn|def|function)[ \t]+({ident})[ \t]*\([ \t]*\)[^{{:\n]*[{{:]\s*(?:return[ \t]+)?({join})[ \t]*;?(?:\s*\}})?"
+    ))
+    .expect("invariant: static regex is valid");
+    let join = Regex::new(&join).expect("invariant: static regex is valid");
+    let names = Regex::new(ident).expect("invariant: static regex is valid");
+
+    // The joined value, if every name in `expression` is bound
+    let value = |expression: &str| -> Option<(String, usize)> {
+        let (names_at, placeholders) = match expression.strip_prefix("format!") {
+            Some(rest) => {
+                let args = rest.find("\",")? + 2;
+                (&rest[args..], Some(rest[..args].matches("{}").count()))
+            }
+            None => (expression.strip_prefix('f').unwrap_or(expression), None),
+        };
+        let parts: Vec<&str> = names
+            .find_iter(names_at)
+            .map(|n| bound.get(n.as_str()).copied().flatten())
+            .collect::<Option<_>>()?;
+        let matched = placeholders.is_none_or(|count| count == parts.len());
+        (parts.len() >= 2 && matched).then(|| (parts.concat(), parts.len()))
+    };
+    // `expression` spanning `range`, written as `text` padded to its length
+    let fold = |range: std::ops::Range<usize>, text: String, value: String, fragments| {
+        let newlines = content[range.clone()].matches('\n').count();
+        let padding = range.len().checked_sub(text.len() + newlines)?;
+        let text = format!("{}{}{}", text, " ".repeat(padding), "\n".repeat(newlines));
+        let chain = ConcatChain {
+            range,
+            value,
+            fragments,
+        };
+        Some((chain, text))
+    };
+
+    let mut folds: Vec<(ConcatChain, String)> = Vec::new();
+    for caps in function.captures_iter(content) {
+        let (Some(whole), Some(name), Some(expression)) = (caps.get(0), caps.get(1), caps.get(2))
+        else {
+            continue;
+        };
+        let Some((value, fragments)) = value(expression.as_str()) else {
+            continue;
+        };
+        let text = format!("{} = \"{}\"", name.as_str(), value);
+        folds.extend(fold(whole.range(), text, value, fragments));
+    }
+    for m in join.find_iter(content) {
+        let inside = folds
+            .iter()
+            .any(|(chain, _)| chain.range.start <= m.start() && m.end() <= chain.range.end);
+        let Some((value, fragments)) = value(m.as_str()).filter(|_| !inside) else {
+            continue;
+        };
+        folds.extend(fold(m.range(), format!("\"{}\"", value), value, fragments));
+    }
+    folds
+}
+
+// ============ Opaque Content ============
+
+/// A non-text payload found in proposal content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpaquePayload {
+    /// "zip", "tar", "gzip", "7z", "rar", "xz", "bzip2", "elf", "wasm",
+    /// "png", "jpeg", "gif", "binary" or "base64"
+    pub kind: String,
+    /// Human-readable size and encoding
+    pub detail: String,
+    /// Archive member names, when the archive could be listed
+    pub members: Vec<String>,
+}
+
+/// Shortest base64 block treated as an embedded blob
+const MIN_BASE64_BLOB: usize = 256;
+
+/// Detect raw binary or base64-embedded blobs in proposal content
+///
+/// Raw content counts as binary when it starts with a known file signature
+/// or, past a minimum length, more than 10% of it is control characters;
+/// stray NULs in short text are left to the text rules. Base64 is looked
+/// for in runs of lines made almost entirely of the base64 alphabet
+/// (wrapped blobs, data URIs).
+pub fn detect_opaque_content(content: &str, list_archives: bool) -> Option<OpaquePayload> {
+    let controls = content
+        .chars()
+        .filter(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c'))
+        .count();
+    if controls > 0 {
+        let bytes: Vec<u8> = content.chars().map(|c| c as u32 as u8).collect();
+        let raw = sniff_payload(&bytes, "raw", list_archives);
+        if raw.kind != "binary" || (content.len() >= 64 && controls * 10 > content.chars().count())
+        {
+            return Some(raw);
+        }
+    }
+
+    let encoded = longest_base64_block(content)?;
+    let engine = base64::engine::GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        base64::engine::GeneralPurposeConfig::new()
+            .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent),
+    );
+    let bytes = base64::Engine::decode(&engine, encoded.as_bytes()).ok()?;
+    let mut payload = sniff_payload(&bytes, "base64", list_archives);
+    if payload.kind == "binary" && is_mostly_text(&bytes) {
+        payload.kind = "base64".to_string();
+        payload.detail = format!("{} bytes of base64-encoded text", bytes.len());
+    }
+    Some(payload)
+}
+
+fn longest_base64_block(content: &str) -> Option<String> {
+    let token = Regex::new(r"[A-Za-z0-9+/]{16,}={0,2}").expect("invariant: static regex is valid");
+    let mut best = String::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let longest = token
+            .find_iter(trimmed)
+            .map(|m| m.as_str())
+            .max_by_key(|t| t.len());
+        match longest {
+            // The token must make up (nearly) the whole line to continue a block
+            Some(t) if t.len() * 10 >= trimmed.len() * 9 => current.push_str(t),
+            Some(t) if t.len() >= MIN_BASE64_BLOB => {
+                current.clear();
+                current.push_str(t);
+            }
+            _ => current.clear(),
+        }
+        if current.len() > best.len() {
+            best = current.clone();
+        }
+    }
+    (best.len() >= MIN_BASE64_BLOB).then_some(best)
+}
+
+fn is_mostly_text(bytes: &[u8]) -> bool {
+    let text = bytes
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace() || **b >= 0x80)
+        .count();
+    !bytes.contains(&0) && text * 100 >= bytes.len() * 95
+}
+
+fn sniff_payload(bytes: &[u8], encoding: &str, list_archives: bool) -> OpaquePayload {
+    let kind = if bytes.starts_with(b"PK\x03\x04") {
+        "zip"
+    } else if bytes.len() >= 262 && &bytes[257..262] == b"ustar" {
+        "tar"
+    } else if bytes.starts_with(&[0x1f, 0x8b]) {
+        "gzip"
+    } else if bytes.starts_with(b"7z\xbc\xaf\x27\x1c") {
+        "7z"
+    } else if bytes.starts_with(b"Rar!\x1a\x07") {
+        "rar"
+    } else if bytes.starts_with(b"\xfd7zXZ\x00") {
+        "xz"
+    } else if bytes.starts_with(b"BZh") {
+        "bzip2"
+    } else if bytes.starts_with(b"\x7fELF") {
+        "elf"
+    } else if bytes.starts_with(b"\0asm") {
+        "wasm"
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "png"
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        "jpeg"
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        "gif"
+    } else {
+        "binary"
+    };
+    let members = match (list_archives, kind) {
+        (true, "zip") => zip_members(bytes),
+        (true, "tar") => tar_members(bytes),
+        _ => Vec::new(),
+    };
+    OpaquePayload {
+        kind: kind.to_string(),
+        detail: format!("{} bytes of {} {} data", bytes.len(), encoding, kind),
+        members,
+    }
+}
+
+/// File names from zip local file headers
+fn zip_members(bytes: &[u8]) -> Vec<String> {
+    let mut members = Vec::new();
+    let mut offset = 0;
+    while let Some(pos) = bytes[offset..].windows(4).position(|w| w == b"PK\x03\x04") {
+        let header = offset + pos;
+        let field = |at: usize| {
+            bytes
+                .get(header + at..header + at + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        };
+        let Some(name_len) = field(26) else { break };
+        let start = header + 30;
+        match bytes.get(start..start + name_len) {
+            Some(name) => members.push(String::from_utf8_lossy(name).to_string()),
+            None => break,
+        }
+        offset = start + name_len;
+    }
+    members
+}
+
+/// File names from ustar headers
+fn tar_members(bytes: &[u8]) -> Vec<String> {
+    let mut members = Vec::new();
+    let mut offset = 0;
+    while let Some(header) = bytes.get(offset..offset + 512) {
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let name = header[..100].split(|b| *b == 0).next().unwrap_or_default();
+        members.push(String::from_utf8_lossy(name).to_string());
+        let size = std::str::from_utf8(&header[124..136])
+            .ok()
+            .and_then(|s| {
+                usize::from_str_radix(s.trim_matches(|c: char| c == '\0' || c == ' '), 8).ok()
+            })
+            .unwrap_or(0);
+        offset += 512 + size.div_ceil(512) * 512;
+    }
+    members
+}
+
+// ============ Repository Stats ============
+
+/// Aggregate policy posture of a scanned tree
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RepoStats {
+    pub files_scanned: usize,
+    pub violations: usize,
+    pub concerns: usize,
+    /// Files per tier: `tier1`, `tier2`, `forbidden` or `unclassified`
+    pub tiers: BTreeMap<String, usize>,
+    /// Files per recognised language
+    pub languages: BTreeMap<String, usize>,
+    /// Directories with findings, densest first
+    pub directories: Vec<DirectoryStats>,
+    /// Rules by number of findings, most triggered first
+    pub rules: Vec<RuleCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DirectoryStats {
+    /// Directory relative to the scanned root (`.` for the root itself)
+    pub path: String,
+    pub files: usize,
+    pub violations: usize,
+    /// Violations per file
+    pub density: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleCount {
+    pub rule: String,
+    pub count: usize,
+}
+
+/// Change in posture since an earlier [`RepoStats`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatsTrend {
+    pub files_scanned: i64,
+    pub violations: i64,
+    pub concerns: i64,
+    /// Tier deltas, including tiers present in only one of the two
+    pub tiers: BTreeMap<String, i64>,
+    /// Rules with a changed count
+    pub rules: BTreeMap<String, i64>,
+}
+
+impl RepoStats {
+    /// Deltas from `previous` to these stats
+    pub fn trend(&self, previous: &RepoStats) -> StatsTrend {
+        let delta = |now: usize, then: usize| now as i64 - then as i64;
+        let diff = |now: &BTreeMap<String, usize>, then: &BTreeMap<String, usize>| {
+            now.keys()
+                .chain(then.keys())
+                .map(|k| {
+                    let d = delta(*now.get(k).unwrap_or(&0), *then.get(k).unwrap_or(&0));
+                    (k.clone(), d)
+                })
+                .filter(|(_, d)| *d != 0)
+                .collect::<BTreeMap<_, _>>()
+        };
+        let counts =
+            |rules: &[RuleCount]| rules.iter().map(|r| (r.rule.clone(), r.count)).collect();
+        StatsTrend {
+            files_scanned: delta(self.files_scanned, previous.files_scanned),
+            violations: delta(self.violations, previous.violations),
+            concerns: delta(self.concerns, previous.concerns),
+            tiers: diff(&self.tiers, &previous.tiers),
+            rules: diff(&counts(&self.rules), &counts(&previous.rules)),
+        }
+    }
+}
+
+impl Oracle {
+    /// Scan a directory and summarise its policy posture
+    pub fn repository_stats(&self, path: &Path) -> Result<RepoStats, OracleError> {
+        let scan = self.scan_directory(path)?;
+        let relative_dir = |file: &Path| {
+            let parent = file.parent().unwrap_or(Path::new(""));
+            let relative = parent.strip_prefix(path).unwrap_or(parent);
+            match relative.to_string_lossy().replace('\\', "/") {
+                dir if dir.is_empty() => ".".to_string(),
+                dir => dir,
+            }
+        };
+
+        let mut stats = RepoStats {
+            files_scanned: scan.files_scanned,
+            violations: scan.violations.len(),
+            concerns: scan.concerns.len(),
+            ..RepoStats::default()
+        };
+        let mut files_per_dir: BTreeMap<String, usize> = BTreeMap::new();
+        let tiers = [
+            ("tier1", &self.policy.languages.tier1),
+            ("tier2", &self.policy.languages.tier2),
+            ("forbidden", &self.policy.languages.forbidden),
+        ];
+        for file in Walk::new(path, self.follow_symlinks).run(path)? {
+            *files_per_dir.entry(relative_dir(&file)).or_default() += 1;
+            let name = file.to_string_lossy();
+            let language = tiers.iter().find_map(|(tier, langs)| {
+                langs
+                    .iter()
+                    .find(|l| self.file_matches_language(&name, l))
+                    .map(|l| (*tier, l.name.as_str()))
+            });
+            let tier = match language {
+                Some((tier, lang)) => {
+                    *stats.languages.entry(lang.to_string()).or_default() += 1;
+                    tier
+                }
+                None => "unclassified",
+            };
+            *stats.tiers.entry(tier.to_string()).or_default() += 1;
+        }
+
+        let mut violations_per_dir: BTreeMap<String, usize> = BTreeMap::new();
+        let mut rules: BTreeMap<String, usize> = BTreeMap::new();
+        for v in &scan.violations {
+            *violations_per_dir.entry(relative_dir(&v.file)).or_default() += 1;
+            *rules.entry(v.violation.rule_id()).or_default() += 1;
+        }
+        for c in &scan.concerns {
+            *rules.entry(c.concern.rule_id()).or_default() += 1;
+        }
+
+        stats.directories = violations_per_dir
+            .into_iter()
+            .map(|(dir, violations)| {
+                let files = files_per_dir.get(&dir).copied().unwrap_or(0).max(1);
+                DirectoryStats {
+                    path: dir,
+                    files,
+                    violations,
+                    density: violations as f64 / files as f64,
+                }
+            })
+            .collect();
+        stats.directories.sort_by(|a, b| {
+            b.density
+                .total_cmp(&a.density)
+                .then(b.violations.cmp(&a.violations))
+                .then(a.path.cmp(&b.path))
+        });
+
+        stats.rules = rules
+            .into_iter()
+            .map(|(rule, count)| RuleCount { rule, count })
+            .collect();
+        stats
+            .rules
+            .sort_by(|a, b| b.count.cmp(&a.count).then(a.rule.cmp(&b.rule)));
+        Ok(stats)
+    }
+}
+
+// ============ Triage ============
+
+/// Decision recorded for a scan finding during triage
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriageOutcome {
+    /// Accepted; later scans drop the finding
+    Suppress,
+    /// Known and scheduled; still reported
+    FixLater,
+    /// Needs a maintainer decision; still reported
+    Escalate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TriageEntry {
+    /// Rule identifier, as from [`ViolationType::rule_id`]
+    pub rule: String,
+    /// File path relative to the scanned root, with `/` separators
+    pub file: String,
+    pub outcome: TriageOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Triage outcomes keyed by rule and file, stored as `.conative/triage.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TriageFile {
+    pub entries: Vec<TriageEntry>,
+}
+
+impl TriageFile {
+    /// Load a triage file; a missing file is empty
+    pub fn load(path: &Path) -> Result<Self, OracleError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| OracleError::TriageError(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), OracleError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| OracleError::TriageError(e.to_string()))?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    pub fn outcome(&self, rule: &str, file: &str) -> Option<TriageOutcome> {
+        self.entries
+            .iter()
+            .find(|e| e.rule == rule && e.file == file)
+            .map(|e| e.outcome)
+    }
+
+    /// Whether `rule` is suppressed for `file`, found by scanning `root`
+    pub fn suppresses(&self, root: &Path, rule: &str, file: &Path) -> bool {
+        self.outcome(rule, &relative_to(root, file)) == Some(TriageOutcome::Suppress)
+    }
+
+    /// Record an outcome, replacing any earlier one; `None` clears it
+    pub fn set(&mut self, rule: &str, file: &str, outcome: Option<TriageOutcome>) {
+        self.entries.retain(|e| !(e.rule == rule && e.file == file));
+        if let Some(outcome) = outcome {
+            self.entries.push(TriageEntry {
+                rule: rule.to_string(),
+                file: file.to_string(),
+                outcome,
+                note: None,
+            });
+        }
+    }
+}
+
+fn relative_to(root: &Path, file: &Path) -> String {
+    normalize_path(&file.strip_prefix(root).unwrap_or(file).to_string_lossy())
+}
+
+impl DirectoryScanResult {
+    /// Path of a scanned file relative to the scanned root, as triage keys it
+    pub fn relative_path(&self, file: &Path) -> String {
+        relative_to(&self.path, file)
+    }
+
+    /// Drop suppressed violations and concerns, returning how many were dropped
+    pub fn apply_triage(&mut self, triage: &TriageFile) -> usize {
+        let root = self.path.clone();
+        let suppressed = |rule: String, file: &Path| triage.suppresses(&root, &rule, file);
+        let before = self.violations.len() + self.concerns.len();
+        self.violations
+            .retain(|v| !suppressed(v.violation.rule_id(), &v.file));
+        self.concerns
+            .retain(|c| !suppressed(c.concern.rule_id(), &c.file));
+        self.verdict = if let Some(v) = self.violations.first() {
+            PolicyVerdict::HardViolation(v.violation.clone())
+        } else if let Some(c) = self.concerns.first() {
+            PolicyVerdict::SoftConcern(c.concern.clone())
+        } else {
+            PolicyVerdict::Compliant
+        };
+        before - self.violations.len() - self.concerns.len()
+    }
+}
+
+// ============ Fix Planning ============
+
+impl QuickFix {
+    /// Whether the fix can be applied without review: it keeps every line
+    /// of code, or removes a file whose port already exists
+    pub fn is_safe(&self) -> bool {
+        self.edits
+            .iter()
+            .all(|e| matches!(e, FixEdit::Replace { .. } | FixEdit::DeleteFile))
+    }
+}
+
+impl Oracle {
+    /// `content` with every forbidden pattern that has a fix rewritten,
+    /// e.g. secrets blanked, for storing proposals outside the repository
+    pub fn redact(&self, content: &str) -> String {
+        let mut redacted = content.to_string();
+        for rule in &self.policy.patterns.forbidden_patterns {
+            let (Some(template), Ok(matcher)) = (&rule.fix, PatternMatcher::new(rule)) else {
+                continue;
+            };
+            redacted = matcher
+                .re
+                .replace_all(&redacted, |caps: &regex::Captures| {
+                    let mut text = String::new();
+                    match matcher.allowed(&caps[0]) {
+                        true => text.push_str(&caps[0]),
+                        false => caps.expand(template, &mut text),
+                    }
+                    text
+                })
+                .into_owned();
+        }
+        redacted
+    }
+
+    /// Quick fixes for every file under `path`
+    ///
+    /// Besides the per-violation fixes this inserts missing required
+    /// headers, and deletes forbidden-language files whose port target
+    /// already exists instead of offering to rename them onto it.
+    pub fn plan_fixes(&self, path: &Path) -> Result<Vec<QuickFix>, OracleError> {
+        let scan = self.scan_directory(path)?;
+        let mut fixes: Vec<QuickFix> = Vec::new();
+        for v in &scan.violations {
+            let Ok(decoded) = read_text_file(&v.file) else {
+                continue;
+            };
+            for mut fix in self.quick_fixes(&v.violation, &decoded.text) {
+                if let [FixEdit::Rename { to, .. }] = &fix.edits[..] {
+                    if Path::new(to).exists() {
+                        fix.title = format!("Remove {}, already ported to {}", fix.file, to);
+                        fix.edits = vec![FixEdit::DeleteFile];
+                    }
+                }
+                if !fixes.contains(&fix) {
+                    fixes.push(fix);
+                }
+            }
+        }
+
+        let mut headers = Vec::new();
+        for required in &self.policy.patterns.required_patterns {
+            if let Some(header) = &required.header {
+                headers.push((required, Regex::new(&required.regex)?, header));
+            }
+        }
+        if headers.is_empty() {
+            return Ok(fixes);
+        }
+        for file in Walk::new(path, self.follow_symlinks).run(path)? {
+            let name = file.to_string_lossy();
+            let Some(comment) = line_comment(&name) else {
+                continue;
+            };
+            let Ok(decoded) = read_text_file(&file) else {
+                continue;
+            };
+            for (required, re, header) in &headers {
+                let applies = required
+                    .file_types
+                    .iter()
+                    .any(|t| t == "*" || name.ends_with(t.as_str()));
+                if !applies || re.is_match(&decoded.text) {
+                    continue;
+                }
+                // A shebang has to stay on the first line
+                let at = match decoded.text.starts_with("#!") {
+                    true => decoded
+                        .text
+                        .find('\n')
+                        .map(|i| i + 1)
+                        .unwrap_or(decoded.text.len()),
+                    false => 0,
+                };
+                fixes.push(QuickFix {
+                    title: format!("Insert {} header", required.name),
+                    file: name.to_string(),
+                    edits: vec![FixEdit::Replace {
+                        start: at,
+                        end: at,
+                        text: format!("{} {}\n", comment, header),
+                    }],
+                });
+            }
+        }
+        Ok(fixes)
+    }
+}
+
+/// Line comment marker for a file, by extension
+fn line_comment(file: &str) -> Option<&'static str> {
+    let extension = Path::new(file).extension()?.to_str()?;
+    match extension {
+        "rs" | "res" | "resi" | "zig" | "ts" | "tsx" | "js" | "go" | "java" | "kt" | "swift"
+        | "c" | "h" | "cpp" => Some("//"),
+        "ex" | "exs" | "ncl" | "py" | "sh" | "toml" | "yml" | "yaml" | "rb" | "nix" | "scm" => {
+            Some("#")
+        }
+        "hs" | "adb" | "ads" | "lua" | "sql" => Some("--"),
+        "rkt" => Some(";;"),
+        _ => None,
+    }
+}
+
+/// Apply in-file edits to `content`
+///
+/// Edits are applied from the end backwards; one that overlaps an edit
+/// already applied is skipped. File operations are left to the caller.
+pub fn apply_fix_edits(content: &str, edits: &[FixEdit]) -> String {
+    let line_range = |line: usize| {
+        let start = content
+            .split_inclusive('\n')
+            .take(line - 1)
+            .map(str::len)
+            .sum::<usize>();
+        let len = content[start..]
+            .split_inclusive('\n')
+            .next()
+            .map(str::len)
+            .unwrap_or(0);
+        (start, start + len, String::new())
+    };
+    let mut spans: Vec<(usize, usize, String)> = edits
+        .iter()
+        .filter_map(|edit| match edit {
+            FixEdit::Replace { start, end, text } if start <= end && *end <= content.len() => {
+                Some((*start, *end, text.clone()))
+            }
+            FixEdit::DeleteLine { line } if *line >= 1 => Some(line_range(*line)),
+            _ => None,
+        })
+        .collect();
+    spans.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    let mut out = content.to_string();
+    let mut floor = content.len() + 1;
+    for (start, end, text) in spans {
+        if end > floor {
+            continue;
+        }
+        out.replace_range(start..end, &text);
+        floor = start;
+    }
+    out
+}
+
+// ============ Default Policy ============
+
+impl Policy {
+    /// Regexes and globs that fail to compile, as `rule: error` messages
+    pub fn compile_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for pattern in &self.patterns.forbidden_patterns {
+            if let Err(e) = PatternMatcher::new(pattern) {
+                errors.push(format!("pattern:{}: {}", pattern.name, e));
+            }
+        }
+        for required in &self.patterns.required_patterns {
+            if let Err(e) = Regex::new(&required.regex) {
+                errors.push(format!("required:{}: {}", required.name, e));
+            }
+        }
+        for rule in &self.toolchain.lockfiles {
+            if let Some(Err(e)) = rule.trigger.as_deref().map(Regex::new) {
+                errors.push(format!("lockfile:{}: {}", rule.manifest, e));
+            }
+        }
+        for rule in &self.protected.rules {
+            for glob in rule.paths.iter().chain(&rule.branches) {
+                if let Err(e) = glob::Pattern::new(glob) {
+                    errors.push(format!("protected:{}: {}: {}", rule.name, glob, e));
+                }
+            }
+        }
+        for rule in &self.conditions.rules {
+            for glob in &rule.paths {
+                if let Err(e) = glob::Pattern::new(glob) {
+                    errors.push(format!("condition:{}: {}: {}", rule.name, glob, e));
+                }
+            }
+        }
+        for file in &self.new_repository.required_files {
+            for glob in &file.paths {
+                if let Err(e) = glob::Pattern::new(glob) {
+                    errors.push(format!("new_repository:{}: {}: {}", file.name, glob, e));
+                }
+            }
+        }
+        errors
+    }
+
+    /// Every rule identifier this policy can report in `Violation::rule`
+    /// or `Concern::rule`, in evaluation order
+    pub fn rule_ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+        for lang in &self.languages.forbidden {
+            ids.push(format!("forbidden_language:{}", lang.name));
+            ids.push(format!("forbidden_file_extension:{}", lang.name));
+            ids.push(format!("pattern:polyglot:{}", lang.name));
+        }
+        for rule in &self.toolchain.rules {
+            ids.push(format!("toolchain:{}:{}", rule.tool, rule.requires));
+        }
+        for rule in &self.toolchain.lockfiles {
+            ids.push(format!("lockfile:{}", rule.manifest));
+        }
+        for pattern in &self.patterns.forbidden_patterns {
+            ids.push(format!("pattern:{}", pattern.name));
+        }
+        for required in &self.patterns.required_patterns {
+            ids.push(format!("required:{}", required.name));
+        }
+        ids.push("unsafe_blocks".to_string());
+        for rule in &self.imports.rules {
+            ids.push(format!("import:{}", rule.name));
+        }
+        ids.push("opaque_content".to_string());
+        for lang in &self.languages.forbidden {
+            ids.push(format!("forbidden_archive_member:{}", lang.name));
+        }
+        ids.push("content_encoding".to_string());
+        for lang in &self.languages.tier2 {
+            ids.push(format!("tier2_language:{}", lang.name));
+        }
+        ids
+    }
+
+    /// RSR-compliant default policy
+    pub fn rsr_default() -> Self {
+        Self {
+            name: "RSR Default Policy".to_string(),
+            languages: LanguagePolicy {
                 tier1: vec![
                     LanguageConfig {
                         name: "rust".to_string(),
@@ -516,31 +5147,49 @@ pub fn rsr_default() -> Self {
                             "impl ".to_string(),
                             "pub fn".to_string(),
                         ],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "elixir".to_string(),
                         extensions: vec![".ex".to_string(), ".exs".to_string()],
                         markers: vec!["defmodule".to_string(), "def ".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "zig".to_string(),
                         extensions: vec![".zig".to_string()],
                         markers: vec!["const std".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "ada".to_string(),
                         extensions: vec![".adb".to_string(), ".ads".to_string()],
                         markers: vec!["procedure".to_string(), "package".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "haskell".to_string(),
                         extensions: vec![".hs".to_string()],
                         markers: vec!["module ".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "rescript".to_string(),
                         extensions: vec![".res".to_string(), ".resi".to_string()],
                         markers: vec!["@react.component".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                 ],
                 tier2: vec![
@@ -548,11 +5197,17 @@ pub fn rsr_default() -> Self {
                         name: "nickel".to_string(),
                         extensions: vec![".ncl".to_string()],
                         markers: vec![],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "racket".to_string(),
                         extensions: vec![".rkt".to_string()],
                         markers: vec!["#lang".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: None,
                     },
                 ],
                 forbidden: vec![
@@ -564,21 +5219,46 @@ pub fn rsr_default() -> Self {
                             ": number".to_string(),
                             "interface ".to_string(),
                         ],
+                        shebangs: vec![
+                            "ts-node".to_string(),
+                            "tsx".to_string(),
+                            "deno".to_string(),
+                        ],
+                        code: None,
+                        alternative: Some(LanguageAlternative {
+                            language: "rescript".to_string(),
+                            summary: Some("Use ReScript instead of TypeScript".to_string()),
+                        }),
                     },
                     LanguageConfig {
                         name: "python".to_string(),
                         extensions: vec![".py".to_string()],
                         markers: vec!["import ".to_string(), "def ".to_string()],
+                        shebangs: vec!["python".to_string()],
+                        code: None,
+                        alternative: None,
                     },
                     LanguageConfig {
                         name: "go".to_string(),
                         extensions: vec![".go".to_string()],
                         markers: vec!["package main".to_string(), "func ".to_string()],
+                        shebangs: Vec::new(),
+                        code: None,
+                        alternative: Some(LanguageAlternative {
+                            language: "rust".to_string(),
+                            summary: Some("Use Rust instead of Go".to_string()),
+                        }),
                     },
                     LanguageConfig {
                         name: "java".to_string(),
                         extensions: vec![".java".to_string()],
                         markers: vec!["public class".to_string()],
+                        shebangs: vec!["java".to_string()],
+                        code: None,
+                        alternative: Some(LanguageAlternative {
+                            language: "rust".to_string(),
+                            summary: Some("Use Rust/Tauri/Dioxus instead of Java".to_string()),
+                        }),
                     },
                 ],
                 exceptions: vec![ExceptionRule {
@@ -586,66 +5266,478 @@ pub fn rsr_default() -> Self {
                     allowed_paths: vec!["salt/".to_string(), "training/".to_string()],
                     reason: "Python allowed for Salt configs and ML training".to_string(),
                 }],
+                detection: LanguageDetection::default(),
+            },
+            toolchain: ToolchainPolicy {
+                rules: vec![
+                    ToolchainRule {
+                        tool: "npm".to_string(),
+                        tool_markers: vec!["package.json".to_string(), "npm install".to_string()],
+                        tool_markers_all: Vec::new(),
+                        requires: "deno".to_string(),
+                        requires_markers: vec!["deno.json".to_string()],
+                        requires_markers_all: Vec::new(),
+                        allowed_paths: Vec::new(),
+                        code: None,
+                    },
+                    ToolchainRule {
+                        tool: "pip".to_string(),
+                        tool_markers: vec!["requirements.txt".to_string()],
+                        tool_markers_all: Vec::new(),
+                        requires: String::new(),
+                        requires_markers: Vec::new(),
+                        requires_markers_all: Vec::new(),
+                        allowed_paths: vec!["salt/".to_string(), "training/".to_string()],
+                        code: Some(205),
+                    },
+                ],
+                lockfiles: vec![
+                    LockfileRule {
+                        manifest: "package.json".to_string(),
+                        lockfiles: vec!["deno.lock".to_string()],
+                        trigger: None,
+                    },
+                    LockfileRule {
+                        manifest: "Cargo.toml".to_string(),
+                        lockfiles: vec!["Cargo.lock".to_string()],
+                        trigger: Some(
+                            r"(?m)^\[(workspace\.)?((dev|build)-)?dependencies".to_string(),
+                        ),
+                    },
+                ],
+            },
+            patterns: PatternPolicy {
+                forbidden_patterns: std::iter::once(ForbiddenPattern {
+                    name: "hardcoded_secrets".to_string(),
+                    regex: r#"(?i)(password|secret|api_key)\s*=\s*["'][^"']{8,}["']"#.to_string(),
+                    file_types: vec!["*".to_string()],
+                    reason: "Hardcoded secrets detected".to_string(),
+                    fix: Some(r#"$1 = """#.to_string()),
+                    code: Some(300),
+                    no_fix: false,
+                    allow: Vec::new(),
+                })
+                .chain(ForbiddenPattern::weak_crypto())
+                .chain(std::iter::once(ForbiddenPattern::plaintext_http()))
+                .chain(ForbiddenPattern::injection())
+                .collect(),
+                required_patterns: vec![RequiredPattern {
+                    name: "spdx_header".to_string(),
+                    regex: r"SPDX-License-Identifier:".to_string(),
+                    file_types: vec!["*".to_string()],
+                    reason: "License headers must not be dropped".to_string(),
+                    header: None,
+                }],
+            },
+            enforcement: EnforcementConfig::default(),
+            protected: ProtectedPolicy {
+                rules: vec![
+                    ProtectedRule {
+                        name: "ci_workflows".to_string(),
+                        paths: vec![".github/workflows/**".to_string()],
+                        branches: Vec::new(),
+                        action: ProtectedAction::Escalate,
+                        reason: "CI workflows run with repository secrets".to_string(),
+                    },
+                    ProtectedRule {
+                        name: "release_branches".to_string(),
+                        paths: Vec::new(),
+                        branches: vec!["release/**".to_string()],
+                        action: ProtectedAction::Escalate,
+                        reason: "Release branches only take reviewed changes".to_string(),
+                    },
+                ],
+            },
+            binary: BinaryPolicy::default(),
+            obfuscation: ObfuscationPolicy::default(),
+            unsafe_code: UnsafePolicy::default(),
+            imports: ImportPolicy::default(),
+            paths: PathPolicy::default(),
+            scan: ScanPolicy::default(),
+            conditions: ConditionalPolicy::default(),
+            rulepacks: RulePackPolicy::default(),
+            new_repository: NewRepositoryPolicy {
+                required_files: vec![
+                    RequiredFile {
+                        name: "license".to_string(),
+                        paths: vec![
+                            "LICENSE*".to_string(),
+                            "COPYING*".to_string(),
+                            "LICENSES/*".to_string(),
+                        ],
+                        reason: "New repositories start with a license".to_string(),
+                    },
+                    RequiredFile {
+                        name: "policy".to_string(),
+                        paths: vec![
+                            ".conative/policy.ncl".to_string(),
+                            ".conative/policy.json".to_string(),
+                        ],
+                        reason: "Run conative init so the gate has a policy from the first commit"
+                            .to_string(),
+                    },
+                ],
+            },
+        }
+    }
+}
+
+// ============ Tests ============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oracle() -> Oracle {
+        Oracle::with_rsr_defaults()
+    }
+
+    #[test]
+    fn test_detects_typescript_file() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "util.ts".to_string(),
+            },
+            content: "Creating a utility file".to_string(),
+            files_affected: vec!["util.ts".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+        assert!(!result.violations.is_empty());
+    }
+
+    #[test]
+    fn test_detects_typescript_content() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::ModifyFile {
+                path: "file.txt".to_string(),
+            },
+            content: "const x: string = 'hello'".to_string(),
+            files_affected: vec!["file.txt".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+    }
+
+    #[test]
+    fn test_allows_rust() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "main.rs".to_string(),
+            },
+            content: "fn main() { println!(\"Hello\"); }".to_string(),
+            files_affected: vec!["main.rs".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+    }
+
+    #[test]
+    fn test_python_exception_in_salt() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "salt/config.py".to_string(),
+            },
+            content: "import os".to_string(),
+            files_affected: vec!["salt/config.py".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+    }
+
+    #[test]
+    fn test_toolchain_npm_without_deno() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "package.json".to_string(),
             },
-            toolchain: ToolchainPolicy {
-                rules: vec![ToolchainRule {
-                    tool: "npm".to_string(),
-                    tool_markers: vec!["package.json".to_string(), "npm install".to_string()],
-                    requires: "deno".to_string(),
-                    requires_markers: vec!["deno.json".to_string()],
-                }],
+            content: r#"{"name": "test", "version": "1.0.0"}"#.to_string(),
+            files_affected: vec!["package.json".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+    }
+
+    #[test]
+    fn test_toolchain_npm_with_deno() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "package.json".to_string(),
             },
-            patterns: PatternPolicy {
-                forbidden_patterns: vec![ForbiddenPattern {
-                    name: "hardcoded_secrets".to_string(),
-                    regex: r#"(?i)(password|secret|api_key)\s*=\s*["'][^"']{8,}["']"#.to_string(),
-                    file_types: vec!["*".to_string()],
-                    reason: "Hardcoded secrets detected".to_string(),
-                }],
+            content: r#"{"name": "test"} deno.json also present"#.to_string(),
+            files_affected: vec!["package.json".to_string(), "deno.json".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+    }
+
+    #[test]
+    fn test_detects_hardcoded_secret() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "config.rs".to_string(),
             },
-            enforcement: EnforcementConfig::default(),
-        }
+            content: r#"let password = "supersecretpassword123""#.to_string(), // test fixture — scanner-allow: rust-secrets
+            files_affected: vec!["config.rs".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
     }
-}
 
-// ============ Tests ============
+    // ============ Additional Unit Tests ============
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_empty_proposal_compliant() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "README.md".to_string(),
+            },
+            content: "# Documentation".to_string(),
+            files_affected: vec!["README.md".to_string()],
+            llm_confidence: 0.5,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        assert!(result.violations.is_empty());
+    }
+
+    #[test]
+    fn test_multiple_violations_reported() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "main.ts".to_string(),
+            },
+            content: r#"const x: string = 'hello'; let password = "secret123""#.to_string(),  // scanner-allow: rust-secrets
+            files_affected: vec!["main.ts".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+        // Should report at least the TypeScript violation
+        assert!(!result.violations.is_empty());
+    }
+
+    #[test]
+    fn test_tier2_language_generates_concern() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "config.ncl".to_string(),
+            },
+            content: "{}".to_string(),
+            files_affected: vec!["config.ncl".to_string()],
+            llm_confidence: 0.8,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        // Tier2 languages without markers might be compliant or concerns depending on detection
+        assert!(matches!(result.verdict, PolicyVerdict::Compliant | PolicyVerdict::SoftConcern(_)));
+    }
+
+    #[test]
+    fn test_elixir_tier1_allowed() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "module.ex".to_string(),
+            },
+            content: "defmodule MyModule, do: :ok".to_string(),
+            files_affected: vec!["module.ex".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+    }
+
+    #[test]
+    fn test_rust_impl_block_allowed() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "lib.rs".to_string(),
+            },
+            content: "impl MyStruct { pub fn new() -> Self { Self {} } }".to_string(),
+            files_affected: vec!["lib.rs".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+    }
+
+    #[test]
+    fn test_ada_allowed() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "main.adb".to_string(),
+            },
+            content: "with Ada.Text_IO;\nprocedure Hello is\nbegin\n  Ada.Text_IO.Put_Line(\"Hello\");\nend Hello;".to_string(),
+            files_affected: vec!["main.adb".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+    }
+
+    #[test]
+    fn test_haskell_allowed() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "Main.hs".to_string(),
+            },
+            content: "module Main where\nmain = putStrLn \"Hello\"".to_string(),
+            files_affected: vec!["Main.hs".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+    }
+
+    #[test]
+    fn test_rescript_component_allowed() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "Component.res".to_string(),
+            },
+            content: "@react.component\nlet make = () => <div>\"Hello\"</div>".to_string(),
+            files_affected: vec!["Component.res".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+    }
+
+    #[test]
+    fn test_proposal_with_correct_violation_severity() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "test.ts".to_string(),
+            },
+            content: "const x: string = 'test'".to_string(),
+            files_affected: vec!["test.ts".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        assert!(!result.violations.is_empty());
+        assert_eq!(result.violations[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn test_toolchain_violation_severity() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "package.json".to_string(),
+            },
+            content: "{}".to_string(),
+            files_affected: vec!["package.json".to_string()],
+            llm_confidence: 0.9,
+        };
+
+        let result = oracle.check_proposal(&proposal).unwrap();
+        if !result.violations.is_empty() {
+            assert_eq!(result.violations[0].severity, Severity::High);
+        }
+    }
+
+    #[test]
+    fn test_rules_checked_counter() {
+        let oracle = oracle();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "test.rs".to_string(),
+            },
+            content: "fn main() {}".to_string(),
+            files_affected: vec!["test.rs".to_string()],
+            llm_confidence: 0.9,
+        };
 
-    fn oracle() -> Oracle {
-        Oracle::with_rsr_defaults()
+        let result = oracle.check_proposal(&proposal).unwrap();
+        // Should have checked multiple rules (forbidden languages, toolchain, patterns, tier2)
+        assert!(!result.rules_checked.is_empty());
+        assert!(result.rules_checked.len() >= 4);
     }
 
     #[test]
-    fn test_detects_typescript_file() {
+    fn test_proposal_id_preserved_in_evaluation() {
+        let proposal_id = Uuid::new_v4();
         let oracle = oracle();
         let proposal = Proposal {
-            id: Uuid::new_v4(),
+            id: proposal_id,
             action_type: ActionType::CreateFile {
-                path: "util.ts".to_string(),
+                path: "test.rs".to_string(),
             },
-            content: "Creating a utility file".to_string(),
-            files_affected: vec!["util.ts".to_string()],
+            content: "fn main() {}".to_string(),
+            files_affected: vec!["test.rs".to_string()],
             llm_confidence: 0.9,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
-        assert!(!result.violations.is_empty());
+        assert_eq!(result.proposal_id, proposal_id);
     }
 
     #[test]
-    fn test_detects_typescript_content() {
+    fn test_go_forbidden() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
-            action_type: ActionType::ModifyFile {
-                path: "file.txt".to_string(),
+            action_type: ActionType::CreateFile {
+                path: "main.go".to_string(),
             },
-            content: "const x: string = 'hello'".to_string(),
-            files_affected: vec!["file.txt".to_string()],
+            content: "package main\nfunc main() {}".to_string(),
+            files_affected: vec!["main.go".to_string()],
             llm_confidence: 0.9,
         };
 
@@ -654,49 +5746,50 @@ fn test_detects_typescript_content() {
     }
 
     #[test]
-    fn test_allows_rust() {
+    fn test_java_forbidden() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "main.rs".to_string(),
+                path: "Main.java".to_string(),
             },
-            content: "fn main() { println!(\"Hello\"); }".to_string(),
-            files_affected: vec!["main.rs".to_string()],
+            content: "public class Main { }".to_string(),
+            files_affected: vec!["Main.java".to_string()],
             llm_confidence: 0.9,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
     }
 
     #[test]
-    fn test_python_exception_in_salt() {
+    fn test_concern_for_racket() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "salt/config.py".to_string(),
+                path: "script.rkt".to_string(),
             },
-            content: "import os".to_string(),
-            files_affected: vec!["salt/config.py".to_string()],
-            llm_confidence: 0.9,
+            content: "#lang racket".to_string(),
+            files_affected: vec!["script.rkt".to_string()],
+            llm_confidence: 0.8,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+        assert!(matches!(result.verdict, PolicyVerdict::SoftConcern(_)));
+        assert!(!result.concerns.is_empty());
     }
 
     #[test]
-    fn test_toolchain_npm_without_deno() {
+    fn test_python_forbidden_outside_exceptions() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "package.json".to_string(),
+                path: "script.py".to_string(),
             },
-            content: r#"{"name": "test", "version": "1.0.0"}"#.to_string(),
-            files_affected: vec!["package.json".to_string()],
+            content: "import os".to_string(),
+            files_affected: vec!["script.py".to_string()],
             llm_confidence: 0.9,
         };
 
@@ -705,31 +5798,31 @@ fn test_toolchain_npm_without_deno() {
     }
 
     #[test]
-    fn test_toolchain_npm_with_deno() {
+    fn test_python_allowed_in_training() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "package.json".to_string(),
+                path: "training/model.py".to_string(),
             },
-            content: r#"{"name": "test"} deno.json also present"#.to_string(),
-            files_affected: vec!["package.json".to_string(), "deno.json".to_string()],
+            content: "import os".to_string(),
+            files_affected: vec!["training/model.py".to_string()],
             llm_confidence: 0.9,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
     }
 
     #[test]
-    fn test_detects_hardcoded_secret() {
+    fn test_secret_api_key_detected() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
                 path: "config.rs".to_string(),
             },
-            content: r#"let password = "supersecretpassword123""#.to_string(), // test fixture — scanner-allow: rust-secrets
+            content: r#"const API_KEY = "abcdef1234567890abcdef""#.to_string(), // test fixture — scanner-allow: rust-secrets
             files_affected: vec!["config.rs".to_string()],
             llm_confidence: 0.9,
         };
@@ -738,322 +5831,1710 @@ fn test_detects_hardcoded_secret() {
         assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
     }
 
-    // ============ Additional Unit Tests ============
+    #[test]
+    fn test_rule_ids_cover_reported_rules() {
+        let policy = Policy::rsr_default();
+        let ids = policy.rule_ids();
+        assert!(ids.contains(&"forbidden_language:typescript".to_string()));
+        assert!(ids.contains(&"toolchain:npm:deno".to_string()));
+        assert!(ids.contains(&"pattern:hardcoded_secrets".to_string()));
+
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: "main.ts".to_string(),
+            },
+            content: "const x: string = 'y'".to_string(),
+            files_affected: vec!["main.ts".to_string()],
+            llm_confidence: 0.9,
+        };
+        let result = Oracle::new(policy).check_proposal(&proposal).unwrap();
+        for v in &result.violations {
+            assert!(ids.contains(&v.rule), "unknown rule id {}", v.rule);
+        }
+    }
 
     #[test]
-    fn test_empty_proposal_compliant() {
+    fn test_context_extraction_respects_char_boundaries() {
         let oracle = oracle();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "README.md".to_string(),
+                path: "lib.tsx".to_string(),
             },
-            content: "# Documentation".to_string(),
-            files_affected: vec!["README.md".to_string()],
-            llm_confidence: 0.5,
+            content: "de\u{301}fmodule App do\n  def 你\u{301}sta🦀rt, do: :你=ok\n\u{feff}end"
+                .to_string(),
+            files_affected: vec!["lib.tsx".to_string()],
+            llm_confidence: 0.9,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
-        assert!(result.violations.is_empty());
+        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
     }
 
     #[test]
-    fn test_multiple_violations_reported() {
+    fn test_llama_cpp_runtime_config_defaults() {
+        let config: SlmBackendConfig =
+            serde_json::from_str(r#"{"kind": "llama_cpp", "model_path": "m.gguf"}"#).unwrap();
+        let SlmBackendConfig::LlamaCpp { runtime, .. } = config else {
+            panic!("expected llama_cpp config");
+        };
+        assert_eq!(runtime, LlamaCppRuntime::default());
+        assert_eq!(runtime.accelerator, AcceleratorConfig::Auto);
+
+        let config: SlmBackendConfig = serde_json::from_str(
+            r#"{"kind": "llama_cpp", "model_path": "m.gguf",
+                "runtime": {"accelerator": "metal", "n_gpu_layers": 20, "context_size": 8192}}"#,
+        )
+        .unwrap();
+        let SlmBackendConfig::LlamaCpp { runtime, .. } = config else {
+            panic!("expected llama_cpp config");
+        };
+        assert_eq!(runtime.accelerator, AcceleratorConfig::Metal);
+        assert_eq!(runtime.n_gpu_layers, Some(20));
+        assert_eq!(runtime.threads, None);
+    }
+
+    #[test]
+    fn test_protected_rule_defaults() {
+        let rule: ProtectedRule =
+            serde_json::from_str(r#"{"name": "ci", "paths": [".github/**"]}"#).unwrap();
+        assert_eq!(rule.action, ProtectedAction::Escalate);
+        assert!(rule.branches.is_empty());
+
+        let mut json = serde_json::to_value(Policy::rsr_default()).unwrap();
+        json.as_object_mut().unwrap().remove("protected");
+        let policy: Policy = serde_json::from_value(json).unwrap();
+        assert!(policy.protected.rules.is_empty());
+    }
+
+    #[test]
+    fn test_proposal_set_splits_per_file() {
+        let set = ProposalSet {
+            id: Uuid::new_v4(),
+            files: [("b.rs", "fn b() {}"), ("a.rs", "fn a() {}")]
+                .into_iter()
+                .map(|(p, c)| (p.to_string(), c.to_string()))
+                .collect(),
+            llm_confidence: 0.8,
+        };
+        let proposals = set.proposals();
+        assert_eq!(proposals.len(), 2);
+        assert_eq!(proposals[0].files_affected, vec!["a.rs".to_string()]);
+        assert_eq!(proposals[1].content, "fn b() {}");
+        assert!(proposals.iter().all(|p| p.id == set.id));
+    }
+
+    #[test]
+    fn test_edit_detects_removed_required_pattern() {
         let oracle = oracle();
+        let edit = |before: &str, after: &str| Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::EditFile {
+                path: "src/lib.rs".to_string(),
+                before: before.to_string(),
+            },
+            content: after.to_string(),
+            files_affected: vec!["src/lib.rs".to_string()],
+            llm_confidence: 0.9,
+        };
+        let header = "// SPDX-License-Identifier: MPL-2.0\nfn a() {}\n";
+
+        let stripped = oracle.check_proposal(&edit(header, "fn a() {}\n")).unwrap();
+        assert!(matches!(
+            &stripped.verdict,
+            PolicyVerdict::HardViolation(ViolationType::RequiredPatternRemoved { pattern, .. }) if pattern == "spdx_header"
+        ));
+        assert!(stripped
+            .rules_checked
+            .contains(&"required_patterns".to_string()));
+
+        let kept = oracle.check_proposal(&edit(
+            header,
+            "// SPDX-License-Identifier: MPL-2.0\nfn b() {}\n",
+        ));
+        assert!(matches!(kept.unwrap().verdict, PolicyVerdict::Compliant));
+        // Never had a header: nothing was removed
+        let never = oracle.check_proposal(&edit("fn a() {}\n", "fn b() {}\n"));
+        assert!(matches!(never.unwrap().verdict, PolicyVerdict::Compliant));
+    }
+
+    fn zip_with(name: &str) -> Vec<u8> {
+        let mut bytes = b"PK\x03\x04".to_vec();
+        bytes.extend_from_slice(&[0u8; 22]);
+        bytes.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 2]);
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(&[0x42u8; 256]);
+        bytes
+    }
+
+    fn blob_proposal(path: &str, bytes: &[u8]) -> Proposal {
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes);
+        let wrapped: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(76)
+            .map(|c| std::str::from_utf8(c).unwrap())
+            .collect();
+        Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: path.to_string(),
+            },
+            content: wrapped.join("\n"),
+            files_affected: vec![path.to_string()],
+            llm_confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_embedded_archive_is_opaque_and_listed() {
+        let payload =
+            detect_opaque_content(&blob_proposal("x", &zip_with("tool.py")).content, true).unwrap();
+        assert_eq!(payload.kind, "zip");
+        assert_eq!(payload.members, ["tool.py"]);
+        assert!(detect_opaque_content("fn main() {}\n", true).is_none());
+
+        let mut policy = Policy::rsr_default();
+        policy.binary.allowed_paths.push("assets/".to_string());
+        let oracle = Oracle::new(policy);
+        let blocked = oracle
+            .check_proposal(&blob_proposal("vendor/blob.txt", &zip_with("tool.py")))
+            .unwrap();
+        assert!(blocked
+            .rules_checked
+            .contains(&"opaque_content".to_string()));
+        assert!(blocked
+            .violations
+            .iter()
+            .any(|v| matches!(v.violation_type, ViolationType::OpaqueContent { .. })));
+
+        // Allowed location: the blob passes, its forbidden member does not
+        let allowed = oracle
+            .check_proposal(&blob_proposal("assets/pack.txt", &zip_with("tool.py")))
+            .unwrap();
+        assert!(!allowed
+            .violations
+            .iter()
+            .any(|v| matches!(v.violation_type, ViolationType::OpaqueContent { .. })));
+        assert!(allowed.violations.iter().any(|v| matches!(
+            &v.violation_type,
+            ViolationType::ForbiddenLanguage { file, .. } if file == "assets/pack.txt!tool.py"
+        )));
+        let clean = oracle
+            .check_proposal(&blob_proposal("assets/pack.txt", &zip_with("lib.rs")))
+            .unwrap();
+        assert!(clean.violations.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_skips_symlinks_unless_following() {
+        let base = std::env::temp_dir().join(format!("conative-walk-{}", Uuid::new_v4()));
+        let (repo, outside) = (base.join("repo"), base.join("outside"));
+        fs::create_dir_all(repo.join("src")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(repo.join("src/lib.rs"), "pub fn a() {}").unwrap();
+        fs::write(outside.join("leak.ts"), "const x: number = 1;").unwrap();
+        std::os::unix::fs::symlink(&outside, repo.join("linked")).unwrap();
+        std::os::unix::fs::symlink(repo.join("src"), repo.join("src_alias")).unwrap();
+        std::os::unix::fs::symlink(&repo, repo.join("src/loop")).unwrap();
+
+        let default = oracle().scan_directory(&repo).unwrap();
+        assert_eq!(default.files_scanned, 1);
+
+        // Inside links are followed once; the one leaving the repo is not
+        let following = oracle()
+            .with_follow_symlinks(true)
+            .scan_directory(&repo)
+            .unwrap();
+        assert_eq!(following.files_scanned, 1);
+        assert!(following.violations.is_empty());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_windows_paths_match_exceptions() {
+        let python = |path: &str| Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: path.to_string(),
+            },
+            content: "import os\ndef main(): pass".to_string(),
+            files_affected: vec![path.to_string()],
+            llm_confidence: 0.9,
+        };
+        let compliant = |oracle: &Oracle, path: &str| {
+            oracle
+                .check_proposal(&python(path))
+                .unwrap()
+                .violations
+                .is_empty()
+        };
+
+        let strict = oracle();
+        assert!(compliant(&strict, ".\\salt\\states\\web.py"));
+        assert!(!compliant(&strict, "Salt\\states\\web.py"));
+
+        let mut policy = Policy::rsr_default();
+        policy.paths.case_insensitive = true;
+        let folded = Oracle::new(policy);
+        assert!(compliant(&folded, "Salt\\States\\web.py"));
+        assert!(!compliant(&folded, "src\\web.py"));
+        assert_eq!(normalize_path(".\\salt\\x.py"), "salt/x.py");
+    }
+
+    #[test]
+    fn test_decode_bytes_detects_encodings() {
+        let utf16 = |text: &str, bom: bool| {
+            let mut bytes = if bom { vec![0xff, 0xfe] } else { Vec::new() };
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        };
+        let plain = decode_bytes(b"fn main() {}");
+        assert_eq!(plain.encoding, "UTF-8");
+        assert!(!plain.is_unusual());
+
+        for bytes in [utf16("import os\n", true), utf16("import os\n", false)] {
+            let decoded = decode_bytes(&bytes);
+            assert_eq!(
+                (decoded.text.as_str(), decoded.encoding.as_str()),
+                ("import os\n", "UTF-16LE")
+            );
+        }
+        let latin1 = decode_bytes(b"# Caf\xe9 cr\xe8me br\xfbl\xe9e, d\xe9j\xe0 vu\n");
+        assert!(latin1.is_unusual());
+        assert!(latin1.text.contains("Café crème"));
+    }
+
+    #[test]
+    fn test_utf16_content_is_transcoded_before_checks() {
+        let smuggled: String = "interface Foo { x: string }\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .map(char::from)
+            .collect();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "main.ts".to_string(),
+                path: "notes.txt".to_string(),
             },
-            content: r#"const x: string = 'hello'; let password = "secret123""#.to_string(),  // scanner-allow: rust-secrets
-            files_affected: vec!["main.ts".to_string()],
+            content: smuggled,
+            files_affected: vec!["notes.txt".to_string()],
+            llm_confidence: 0.9,
+        };
+        let result = oracle().check_proposal(&proposal).unwrap();
+        assert!(matches!(
+            &result.verdict,
+            PolicyVerdict::HardViolation(ViolationType::ForbiddenLanguage { language, .. }) if language == "typescript"
+        ));
+        assert!(result.concerns.iter().any(|c| c.concern_type
+            == ConcernType::UnusualEncoding {
+                encoding: "UTF-16LE".to_string()
+            }));
+
+        let plain = Proposal {
+            content: "fn main() {}".to_string(),
+            ..proposal
+        };
+        let decoded = oracle().check_decoded(&plain, "windows-1252").unwrap();
+        assert!(matches!(
+            decoded.verdict,
+            PolicyVerdict::SoftConcern(ConcernType::UnusualEncoding { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scan_stream_finds_matches_across_chunks() {
+        let re = ByteMatcher {
+            re: regex::bytes::Regex::new("SECRET_[0-9]{4}").unwrap(),
+            allow: Vec::new(),
+        };
+        let limits = ScanPolicy {
+            chunk_bytes: 8,
+            chunk_overlap: 16,
+            max_file_bytes: 1024,
+            ..ScanPolicy::default()
+        };
+        let content = format!("{}SECRET_1234{}", "x".repeat(13), "y".repeat(40));
+        let (matched, cut) = scan_stream(content.as_bytes(), &[&re], &limits).unwrap();
+        assert_eq!((matched, cut), (vec![true], false));
+
+        let budget = ScanPolicy {
+            max_file_bytes: 10,
+            ..limits
+        };
+        let (matched, cut) = scan_stream(content.as_bytes(), &[&re], &budget).unwrap();
+        assert_eq!((matched, cut), (vec![false], true));
+    }
+
+    #[test]
+    fn test_scan_checks_file_content() {
+        let dir = std::env::temp_dir().join(format!("conative-scan-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let padding = "// filler\n".repeat(200_000);
+        fs::write(
+            dir.join("config.rs"),
+            format!("{}let password = \"hunter2hunter2\";\n", padding),
+        )
+        .unwrap(); // scanner-allow: rust-secrets
+        fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+
+        let result = oracle().scan_directory(&dir).unwrap();
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].file.ends_with("config.rs"));
+        assert!(result.truncated.is_empty());
+
+        let mut policy = Policy::rsr_default();
+        policy.scan.max_file_bytes = 1024;
+        let capped = Oracle::new(policy).scan_directory(&dir).unwrap();
+        assert!(capped.violations.is_empty());
+        assert_eq!(capped.truncated.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_each_stops_when_asked() {
+        let dir = std::env::temp_dir().join(format!("conative-scan-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["a.py", "b.py", "c.py"] {
+            fs::write(dir.join(name), "import os\n").unwrap();
+        }
+
+        let mut events = Vec::new();
+        let scanned = oracle()
+            .scan_each(&dir, |event| {
+                events.push(event);
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        assert_eq!(scanned, 1);
+        assert!(matches!(events.as_slice(), [ScanEvent::Violation(_)]));
+        assert_eq!(oracle().scan_directory(&dir).unwrap().violations.len(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_policy_impact_lists_changed_verdicts() {
+        let dir = std::env::temp_dir().join(format!("conative-impact-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("tools")).unwrap();
+        fs::write(dir.join("tools/gen.py"), "import os\n").unwrap();
+        fs::write(dir.join("app.py"), "import os\n").unwrap();
+        fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+
+        let mut policy = Policy::rsr_default();
+        policy.languages.exceptions.push(ExceptionRule {
+            language: "python".to_string(),
+            allowed_paths: vec!["tools/".to_string()],
+            reason: "Code generators".to_string(),
+        });
+        let impact = oracle().policy_impact(&Oracle::new(policy), &dir).unwrap();
+        assert_eq!(impact.files_scanned, 3);
+        assert_eq!(impact.changes.len(), 1);
+        let change = &impact.changes[0];
+        assert_eq!(impact.relative_path(&change.file), "tools/gen.py");
+        assert_eq!(
+            (change.current, change.proposed),
+            (FileVerdict::Violation, FileVerdict::Compliant)
+        );
+        assert_eq!(change.rules_removed, vec!["forbidden_language:python"]);
+        assert_eq!((impact.stricter(), impact.looser()), (0, 1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_records_exemptions() {
+        let dir = std::env::temp_dir().join(format!("conative-scan-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("ops")).unwrap();
+        fs::write(dir.join("ops/deploy.py"), "import os\n").unwrap();
+        fs::write(dir.join("tool.py"), "import os\n").unwrap();
+
+        let mut policy = Policy::rsr_default();
+        policy.languages.exceptions.push(ExceptionRule {
+            language: "python".to_string(),
+            allowed_paths: vec!["ops/".to_string()],
+            reason: "Deployment scripts".to_string(),
+        });
+        let result = Oracle::new(policy).scan_directory(&dir).unwrap();
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].file.ends_with("tool.py"));
+        assert_eq!(result.exempted.len(), 1);
+        assert!(result.exempted[0].file.ends_with("ops/deploy.py"));
+        assert_eq!(result.exempted[0].rule, "forbidden_language:python");
+        assert_eq!(
+            result.exempted[0].reason,
+            "Deployment scripts (allowed in ops/)"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compile_errors_name_the_rule() {
+        let mut policy = Policy::rsr_default();
+        assert!(policy.compile_errors().is_empty());
+        policy.patterns.forbidden_patterns[0].regex = "(unclosed".to_string();
+        policy.protected.rules[0].paths.push("src/[".to_string());
+        let errors = policy.compile_errors();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("pattern:hardcoded_secrets: "));
+        assert!(errors[1].starts_with("protected:ci_workflows: src/[: "));
+    }
+
+    #[test]
+    fn test_locate_violation_finds_marker_range() {
+        let oracle = oracle();
+        let content = "fn main() {}\nconst x: string = 'hello'\n";
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            action_type: ActionType::ModifyFile {
+                path: "file.txt".to_string(),
+            },
+            content: content.to_string(),
+            files_affected: vec!["file.txt".to_string()],
             llm_confidence: 0.9,
         };
 
         let result = oracle.check_proposal(&proposal).unwrap();
+        let range = oracle
+            .locate_violation(&result.violations[0].violation_type, content)
+            .expect("content marker has a location");
+        assert!(range.start >= "fn main() {}\n".len());
+        assert!(content[range].contains(": string"));
+
+        let toolchain = ViolationType::ForbiddenToolchain {
+            tool: "npm".to_string(),
+            missing: "deno".to_string(),
+        };
+        assert!(oracle.locate_violation(&toolchain, content).is_none());
+    }
+
+    #[test]
+    fn test_repository_stats_tally_tiers_and_rules() {
+        let dir = std::env::temp_dir().join(format!("conative-stats-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("web")).unwrap();
+        fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("README"), "docs\n").unwrap();
+        fs::write(dir.join("web/app.ts"), "export {}\n").unwrap();
+        fs::write(dir.join("web/util.ts"), "export {}\n").unwrap();
+        fs::write(dir.join("web/lib.rs"), "pub fn a() {}\n").unwrap();
+
+        let stats = oracle().repository_stats(&dir).unwrap();
+        assert_eq!(stats.files_scanned, 5);
+        assert_eq!(stats.tiers["tier1"], 2);
+        assert_eq!(stats.tiers["forbidden"], 2);
+        assert_eq!(stats.tiers["unclassified"], 1);
+        assert_eq!(stats.languages["typescript"], 2);
+        assert_eq!(stats.directories.len(), 1);
+        assert_eq!(stats.directories[0].path, "web");
+        assert!((stats.directories[0].density - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            stats.rules[0],
+            RuleCount {
+                rule: "forbidden_language:typescript".to_string(),
+                count: 2
+            }
+        );
+
+        fs::remove_file(dir.join("web/util.ts")).unwrap();
+        let trend = oracle().repository_stats(&dir).unwrap().trend(&stats);
+        assert_eq!(trend.violations, -1);
+        assert_eq!(trend.tiers["forbidden"], -1);
+        assert!(!trend.tiers.contains_key("tier1"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_triage_suppresses_only_marked_findings() {
+        let dir = std::env::temp_dir().join(format!("conative-triage-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("app.ts"), "export {}\n").unwrap();
+        fs::write(dir.join("util.ts"), "export {}\n").unwrap();
+
+        let mut result = oracle().scan_directory(&dir).unwrap();
+        let app = result.relative_path(&dir.join("app.ts"));
+        assert_eq!(app, "app.ts");
+        let mut triage = TriageFile::default();
+        triage.set(
+            "forbidden_language:typescript",
+            &app,
+            Some(TriageOutcome::FixLater),
+        );
+        triage.set(
+            "forbidden_language:typescript",
+            &app,
+            Some(TriageOutcome::Suppress),
+        );
+        assert_eq!(triage.entries.len(), 1);
+
+        let path = dir.join(".conative/triage.json");
+        triage.save(&path).unwrap();
+        let loaded = TriageFile::load(&path).unwrap();
+        assert_eq!(loaded, triage);
+
+        assert_eq!(result.apply_triage(&loaded), 1);
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].file.ends_with("util.ts"));
         assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
-        // Should report at least the TypeScript violation
-        assert!(!result.violations.is_empty());
+
+        triage.set("forbidden_language:typescript", &app, None);
+        assert!(triage.entries.is_empty());
+        assert!(TriageFile::load(&dir.join("missing.json"))
+            .unwrap()
+            .entries
+            .is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quick_fixes_strip_secret_and_port_typescript() {
+        let oracle = oracle();
+        let secret = ViolationType::ForbiddenPattern {
+            pattern: "hardcoded_secrets".to_string(),
+            file: "config.rs".to_string(),
+        };
+        let content = "fn f() {}\nlet password = \"hunter2hunter2\";\n"; // scanner-allow: rust-secrets
+        let fixes = oracle.quick_fixes(&secret, content);
+        assert_eq!(fixes.len(), 1);
+        match &fixes[0].edits[..] {
+            [FixEdit::Replace { start, end, text }] => {
+                assert_eq!(&content[*start..*end], "password = \"hunter2hunter2\""); // scanner-allow: rust-secrets
+                assert_eq!(text, "password = \"\"");
+            }
+            other => panic!("unexpected edits: {:?}", other),
+        }
+
+        let typescript = ViolationType::ForbiddenLanguage {
+            language: "typescript".to_string(),
+            file: "src/app.ts".to_string(),
+            context: String::new(),
+            detection: None,
+        };
+        let fixes = oracle.quick_fixes(&typescript, "const x: string = 'a'\n");
+        match &fixes[0].edits[..] {
+            [FixEdit::Rename { to, scaffold }] => {
+                assert_eq!(to, "src/app.res");
+                assert!(scaffold.contains("// const x: string = 'a'"));
+            }
+            other => panic!("unexpected edits: {:?}", other),
+        }
+
+        let python = ViolationType::ForbiddenLanguage {
+            language: "python".to_string(),
+            file: "app.py".to_string(),
+            context: String::new(),
+            detection: None,
+        };
+        assert!(oracle.quick_fixes(&python, "import os\n").is_empty());
+
+        let mut policy = Policy::rsr_default();
+        let config = policy
+            .languages
+            .forbidden
+            .iter_mut()
+            .find(|l| l.name == "python")
+            .unwrap();
+        config.alternative = Some(LanguageAlternative {
+            language: "haskell".to_string(),
+            summary: None,
+        });
+        assert_eq!(
+            config.alternative_summary().as_deref(),
+            Some("Use haskell instead of python")
+        );
+        let fixes = Oracle::new(policy).quick_fixes(&python, "import os\n");
+        assert!(matches!(&fixes[0].edits[..], [FixEdit::Rename { to, .. }] if to == "app.hs"));
+    }
+
+    #[test]
+    fn test_plan_fixes_inserts_headers_and_removes_ported_files() {
+        let dir = std::env::temp_dir().join(format!("conative-fix-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("app.ts"), "export {}\n").unwrap();
+        fs::write(
+            dir.join("app.res"),
+            "// SPDX-License-Identifier: MPL-2.0\nlet x = 1\n",
+        )
+        .unwrap();
+        fs::write(dir.join("run.sh"), "#!/bin/sh\necho hi\n").unwrap();
+
+        let mut policy = Policy::rsr_default();
+        policy.patterns.required_patterns[0].header =
+            Some("SPDX-License-Identifier: MPL-2.0".to_string());
+        let fixes = Oracle::new(policy).plan_fixes(&dir).unwrap();
+
+        let removal = fixes
+            .iter()
+            .find(|f| f.file.ends_with("app.ts") && f.title.starts_with("Remove"))
+            .unwrap();
+        assert_eq!(removal.edits, vec![FixEdit::DeleteFile]);
+        assert!(removal.is_safe());
+        let header = fixes.iter().find(|f| f.file.ends_with("run.sh")).unwrap();
+        let content = fs::read_to_string(dir.join("run.sh")).unwrap();
+        assert_eq!(
+            apply_fix_edits(&content, &header.edits),
+            "#!/bin/sh\n# SPDX-License-Identifier: MPL-2.0\necho hi\n"
+        );
+        assert!(!fixes.iter().any(|f| f.file.ends_with("app.res")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_fix_edits_skips_overlaps() {
+        let content = "a\nb\nc\n";
+        let edits = vec![
+            FixEdit::DeleteLine { line: 2 },
+            FixEdit::Replace {
+                start: 2,
+                end: 3,
+                text: "B".to_string(),
+            },
+            FixEdit::Replace {
+                start: 0,
+                end: 1,
+                text: "A".to_string(),
+            },
+        ];
+        assert_eq!(apply_fix_edits(content, &edits), "A\nc\n");
+    }
+
+    #[test]
+    fn test_worst_violation_decides_verdict() {
+        assert!(Severity::Critical > Severity::High);
+        assert!(Severity::Medium.at_least(Severity::Low));
+        assert!(!Severity::Medium.at_least(Severity::High));
+        assert_eq!(
+            [Severity::Low, Severity::Critical, Severity::Medium]
+                .iter()
+                .max(),
+            Some(&Severity::Critical)
+        );
+
+        // The opaque blob (High) is found before its Python member (Critical)
+        let result = oracle()
+            .check_proposal(&blob_proposal("vendor/blob.txt", &zip_with("tool.py")))
+            .unwrap();
+        assert_eq!(result.violations[0].severity, Severity::High);
+        assert_eq!(result.max_severity(), Some(Severity::Critical));
+        assert!(matches!(
+            result.verdict,
+            PolicyVerdict::HardViolation(ViolationType::ForbiddenLanguage { .. })
+        ));
+        assert_eq!(result.violations_at_least(Severity::Critical).count(), 1);
+        assert_eq!(result.violations_at_least(Severity::High).count(), 2);
     }
 
     #[test]
-    fn test_tier2_language_generates_concern() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "config.ncl".to_string(),
-            },
-            content: "{}".to_string(),
-            files_affected: vec!["config.ncl".to_string()],
-            llm_confidence: 0.8,
-        };
+    fn test_extract_context_lines_and_char_boundaries() {
+        let content = "one\ntwo\nlet é = 1;\nfour\nfive";
+        let at = content.find('é').unwrap();
+        assert_eq!(extract_context(content, at..at + 2, 0, 80), "let é = 1;");
+        assert_eq!(
+            extract_context(content, at..at + 2, 1, 80),
+            "two\nlet é = 1;\nfour"
+        );
+        assert_eq!(extract_context(content, at..at + 2, 5, 80), content);
+        // Offsets inside a multi-byte char are moved onto a boundary
+        assert_eq!(
+            extract_context(content, at + 1..at + 1, 0, 80),
+            "let é = 1;"
+        );
+        assert_eq!(extract_context(content, 999..1000, 0, 80), "five");
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        // Tier2 languages without markers might be compliant or concerns depending on detection
-        assert!(matches!(result.verdict, PolicyVerdict::Compliant | PolicyVerdict::SoftConcern(_)));
+        let long = format!("{}SECRET{}", "a".repeat(100), "b".repeat(100));
+        let clipped = extract_context(&long, 100..106, 0, 20);
+        assert!(clipped.starts_with("...") && clipped.ends_with("..."));
+        assert!(clipped.contains("SECRET"));
+
+        let window = ContextWindow::default();
+        assert!(window.lines_for(Severity::Critical) > window.lines_for(Severity::Low));
     }
 
     #[test]
-    fn test_elixir_tier1_allowed() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "module.ex".to_string(),
-            },
-            content: "defmodule MyModule, do: :ok".to_string(),
-            files_affected: vec!["module.ex".to_string()],
-            llm_confidence: 0.9,
+    fn test_toolchain_marker_groups_and_forbidden_tools() {
+        let mut policy = Policy::rsr_default();
+        policy.toolchain.rules.push(ToolchainRule {
+            tool: "cargo".to_string(),
+            tool_markers: vec!["Cargo.toml".to_string()],
+            tool_markers_all: vec!["[dependencies]".to_string()],
+            requires: "rust-toolchain".to_string(),
+            requires_markers: vec![
+                "rust-toolchain.toml".to_string(),
+                "rust-toolchain".to_string(),
+            ],
+            requires_markers_all: Vec::new(),
+            allowed_paths: Vec::new(),
+            code: None,
+        });
+        let oracle = Oracle::new(policy);
+        let check = |files: &[&str], content: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: files[0].to_string(),
+                },
+                content: content.to_string(),
+                files_affected: files.iter().map(|f| f.to_string()).collect(),
+                llm_confidence: 0.9,
+            };
+            oracle.check_proposal(&proposal).unwrap().violations
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        // Both groups must match before the requirement is checked
+        assert!(check(&["Cargo.toml"], "[package]\nname = \"x\"").is_empty());
+        let unpinned = check(&["Cargo.toml"], "[dependencies]\nserde = \"1\"");
+        assert_eq!(unpinned.len(), 1);
+        assert_eq!(unpinned[0].rule, "toolchain:cargo:rust-toolchain");
+        assert!(check(
+            &["Cargo.toml", "rust-toolchain.toml"],
+            "[dependencies]\nserde = \"1\""
+        )
+        .is_empty());
+
+        // requirements.txt is forbidden outside the Python exception paths
+        let pip = check(&["requirements.txt"], "serde==1.0");
+        assert!(
+            matches!(&pip[0].violation_type, ViolationType::ForbiddenToolchain { tool, missing }
+            if tool == "pip" && missing.is_empty())
+        );
+        assert!(check(&["training/requirements.txt"], "torch==2.0").is_empty());
     }
 
     #[test]
-    fn test_rust_impl_block_allowed() {
+    fn test_weak_crypto_pack() {
         let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "lib.rs".to_string(),
-            },
-            content: "impl MyStruct { pub fn new() -> Self { Self {} } }".to_string(),
-            files_affected: vec!["lib.rs".to_string()],
-            llm_confidence: 0.9,
+        let rules = |content: &str| -> Vec<String> {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: "src/crypto.rs".to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec!["src/crypto.rs".to_string()],
+                llm_confidence: 0.9,
+            };
+            oracle
+                .check_proposal(&proposal)
+                .unwrap()
+                .violations
+                .into_iter()
+                .map(|v| v.rule)
+                .collect()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        assert_eq!(
+            rules("let digest = md5::compute(data);"),
+            vec!["pattern:insecure_hash"]
+        );
+        assert_eq!(
+            rules("h = hashlib.sha1(data)"),
+            vec!["pattern:insecure_hash"]
+        );
+        assert_eq!(
+            rules("crypto.createHash('md5')"),
+            vec!["pattern:insecure_hash"]
+        );
+        assert_eq!(
+            rules("let key: [u8; 32] = rand::thread_rng().gen();"),
+            vec!["pattern:weak_random_key"]
+        );
+        assert_eq!(rules("let iv = [0u8; 16];"), vec!["pattern:hardcoded_iv"]);
+        assert_eq!(
+            rules("let nonce = Nonce::from_slice(b\"unique nonce\");"),
+            vec!["pattern:hardcoded_iv"]
+        );
+        assert_eq!(
+            rules("requests.get(url, verify=False)"),
+            vec!["pattern:tls_verification_disabled"]
+        );
+        assert_eq!(
+            rules("Client::builder().danger_accept_invalid_certs(true)"),
+            vec!["pattern:tls_verification_disabled"]
+        );
+
+        // Safe usage and prose pass
+        assert!(rules("let digest = Sha256::digest(data);").is_empty());
+        assert!(rules("let key: [u8; 32] = OsRng.gen();").is_empty());
+        assert!(rules("let iv = generate_iv(&mut OsRng);").is_empty());
+        assert!(rules("// SHA-1 was retired. See MD5.").is_empty());
+
+        // Deleting the line would break the code, so no fix is offered
+        let hash = ViolationType::ForbiddenPattern {
+            pattern: "insecure_hash".to_string(),
+            file: "src/crypto.rs".to_string(),
+        };
+        assert!(oracle.quick_fixes(&hash, "md5::compute(data)").is_empty());
     }
 
     #[test]
-    fn test_ada_allowed() {
+    fn test_plaintext_http_rule() {
+        let check = |oracle: &Oracle, path: &str, content: &str| -> Vec<String> {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            oracle
+                .check_proposal(&proposal)
+                .unwrap()
+                .violations
+                .into_iter()
+                .map(|v| v.rule)
+                .collect()
+        };
         let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "main.adb".to_string(),
-            },
-            content: "with Ada.Text_IO;\nprocedure Hello is\nbegin\n  Ada.Text_IO.Put_Line(\"Hello\");\nend Hello;".to_string(),
-            files_affected: vec!["main.adb".to_string()],
-            llm_confidence: 0.9,
+        let rules = |content: &str| check(&oracle, "config/app.toml", content);
+
+        assert_eq!(
+            rules("endpoint = \"http://api.example.com/v1\""),
+            vec!["pattern:plaintext_http"]
+        );
+        assert!(rules("endpoint = \"https://api.example.com/v1\"").is_empty());
+        assert!(rules("endpoint = \"http://localhost:8080/v1\"").is_empty());
+        assert!(rules("endpoint = \"http://127.0.0.1\"").is_empty());
+        assert!(rules("$schema = \"http://json-schema.org/draft-07/schema#\"").is_empty());
+        assert!(check(
+            &oracle,
+            "pom.xml",
+            "<project xmlns=\"http://maven.apache.org/POM/4.0.0\">"
+        )
+        .is_empty());
+        assert!(check(&oracle, "README.md", "See http://example.com").is_empty());
+
+        // A localhost-looking host is still remote
+        assert_eq!(
+            rules("url = \"http://localhost.evil.com/\""),
+            vec!["pattern:plaintext_http"]
+        );
+
+        // Every match is reported, allowlisted ones skipped
+        let content =
+            "a = \"http://localhost/\"\nb = \"http://one.example\"\nc = \"http://two.example\"";
+        let violation = ViolationType::ForbiddenPattern {
+            pattern: "plaintext_http".to_string(),
+            file: "config/app.toml".to_string(),
         };
+        let ranges = oracle.match_ranges(&violation, content);
+        let found: Vec<&str> = ranges.into_iter().map(|r| &content[r]).collect();
+        assert_eq!(found, vec!["http://one.example", "http://two.example"]);
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        // Policy allowlist entries exempt internal hosts
+        let mut policy = Policy::rsr_default();
+        let rule = policy
+            .patterns
+            .forbidden_patterns
+            .iter_mut()
+            .find(|p| p.name == "plaintext_http")
+            .unwrap();
+        rule.allow
+            .push(r"^http://[\w.-]+\.internal(/|$)".to_string());
+        let internal = Oracle::new(policy);
+        assert!(check(
+            &internal,
+            "src/main.rs",
+            "get(\"http://billing.internal/\")"
+        )
+        .is_empty());
+        assert_eq!(
+            check(&internal, "src/main.rs", "get(\"http://billing.example/\")"),
+            vec!["pattern:plaintext_http"]
+        );
+
+        let mut broken = Policy::rsr_default();
+        broken.patterns.forbidden_patterns[0]
+            .allow
+            .push("(unclosed".to_string());
+        assert_eq!(broken.compile_errors().len(), 1);
     }
 
     #[test]
-    fn test_haskell_allowed() {
+    fn test_injection_pack_is_language_aware() {
         let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "Main.hs".to_string(),
-            },
-            content: "module Main where\nmain = putStrLn \"Hello\"".to_string(),
-            files_affected: vec!["Main.hs".to_string()],
-            llm_confidence: 0.9,
+        let rules = |path: &str, content: &str| -> Vec<String> {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            let violations = oracle.check_proposal(&proposal).unwrap().violations;
+            // Python and Go files also trip the language rules
+            violations
+                .into_iter()
+                .map(|v| v.rule)
+                .filter(|r| r.starts_with("pattern:"))
+                .collect()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        let shell = "let cmd = format!(\"rm {}\", path);\nCommand::new(\"sh\").arg(\"-c\").arg(&cmd).status()?;";
+        assert_eq!(
+            rules("src/clean.rs", shell),
+            vec!["pattern:shell_command_rust"]
+        );
+        assert_eq!(
+            rules(
+                "src/clean.rs",
+                "Command::new(\"sh\").args([\"-c\", &format!(\"rm {}\", dir)])"
+            ),
+            vec!["pattern:shell_command_rust"]
+        );
+        assert_eq!(
+            rules("tools/clean.py", "os.system(f\"rm {path}\")"),
+            vec!["pattern:shell_command_python"]
+        );
+        assert_eq!(
+            rules(
+                "src/db.rs",
+                "let q = format!(\"SELECT * FROM users WHERE id = {}\", id);"
+            ),
+            vec!["pattern:sql_interpolation_rust"]
+        );
+        assert_eq!(
+            rules(
+                "app/db.py",
+                "cur.execute(\"DELETE FROM t WHERE id = %s\" % uid)"
+            ),
+            vec!["pattern:sql_interpolation_python"]
+        );
+        assert_eq!(
+            rules(
+                "db/query.go",
+                "q := fmt.Sprintf(\"SELECT * FROM t WHERE id = %d\", id)"
+            ),
+            vec!["pattern:sql_interpolation_go"]
+        );
+
+        // Fixed commands, direct arguments and bound parameters pass
+        assert!(rules(
+            "src/clean.rs",
+            "Command::new(\"sh\").arg(\"-c\").arg(\"ls -la\")"
+        )
+        .is_empty());
+        assert!(rules("src/clean.rs", "Command::new(\"rm\").arg(path)").is_empty());
+        assert!(rules(
+            "src/db.rs",
+            "sqlx::query(\"SELECT * FROM users WHERE id = $1\").bind(id)"
+        )
+        .is_empty());
+        assert!(rules(
+            "app/db.py",
+            "cur.execute(\"DELETE FROM t WHERE id = %s\", (uid,))"
+        )
+        .is_empty());
+        assert!(rules("app/log.py", "log(f\"Selected {n} items from the list\")").is_empty());
+
+        // Each language's idioms only apply to its own files
+        assert!(rules("src/notes.rs", "os.system(f\"rm {path}\")").is_empty());
+        assert!(rules(
+            "docs/sql.md",
+            "let q = format!(\"SELECT * FROM users WHERE id = {}\", id);"
+        )
+        .is_empty());
     }
 
     #[test]
-    fn test_rescript_component_allowed() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "Component.res".to_string(),
-            },
-            content: "@react.component\nlet make = () => <div>\"Hello\"</div>".to_string(),
-            files_affected: vec!["Component.res".to_string()],
-            llm_confidence: 0.9,
-        };
+    fn test_find_unsafe_blocks() {
+        let content = r##"// unsafe { in a comment }
+/// Calls `unsafe { x }` in docs
+unsafe fn raw(p: *const u8) -> u8 {
+    // SAFETY: callers pass a valid pointer
+    unsafe { *p }
+}
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+fn f<'a>(s: &'a str) -> char {
+    let msg = "unsafe { not code }";
+    let quote = '"';
+    let v = unsafe {
+        raw(s.as_ptr())
+    };
+    let r = r#"unsafe {"#; /* SAFETY: too far away */
+    unsafe { g() }
+}"##;
+        let blocks = find_unsafe_blocks(content);
+        let spans: Vec<(usize, usize, bool)> = blocks
+            .iter()
+            .map(|b| (b.start_line, b.end_line, b.safety_comment))
+            .collect();
+        assert_eq!(spans, vec![(5, 5, true), (11, 13, false), (15, 15, false)]);
+        assert_eq!(&content[blocks[0].range.clone()], "unsafe { *p }");
     }
 
     #[test]
-    fn test_proposal_with_correct_violation_severity() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "test.ts".to_string(),
-            },
-            content: "const x: string = 'test'".to_string(),
-            files_affected: vec!["test.ts".to_string()],
-            llm_confidence: 0.9,
+    fn test_unsafe_block_modes() {
+        let content = concat!(
+            "fn f(p: *const u8) -> u8 {\n    unsafe { *p }\n}\n\n",
+            "fn g(p: *const u8) -> u8 {\n    // SAFETY: p is valid\n    unsafe { *p }\n}",
+        );
+        let check = |policy: &Policy, path: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            let eval = Oracle::new(policy.clone())
+                .check_proposal(&proposal)
+                .unwrap();
+            eval.violations
+                .into_iter()
+                .map(|v| v.violation_type)
+                .collect::<Vec<_>>()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(!result.violations.is_empty());
-        assert_eq!(result.violations[0].severity, Severity::Critical);
+        let mut policy = Policy::rsr_default();
+        assert_eq!(
+            check(&policy, "src/ptr.rs"),
+            vec![ViolationType::UnsafeBlock {
+                file: "src/ptr.rs".to_string(),
+                blocks: vec![(2, 2)],
+                needs_safety_comment: true,
+            }]
+        );
+        // Only Rust files are checked
+        assert!(check(&policy, "docs/ptr.md").is_empty());
+
+        policy.unsafe_code.mode = UnsafeMode::Forbid;
+        let forbidden = check(&policy, "src/ptr.rs");
+        assert!(
+            matches!(&forbidden[..], [ViolationType::UnsafeBlock { blocks, needs_safety_comment: false, .. }]
+            if blocks == &vec![(2, 2), (7, 7)])
+        );
+        let range = oracle().locate_violation(&forbidden[0], content).unwrap();
+        assert_eq!(&content[range], "unsafe { *p }");
+
+        policy
+            .unsafe_code
+            .allowed_paths
+            .push("src/ffi/".to_string());
+        assert!(check(&policy, "src/ffi/ptr.rs").is_empty());
+
+        policy.unsafe_code.mode = UnsafeMode::Allow;
+        assert!(check(&policy, "src/ptr.rs").is_empty());
     }
 
     #[test]
-    fn test_toolchain_violation_severity() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "package.json".to_string(),
-            },
-            content: "{}".to_string(),
-            files_affected: vec!["package.json".to_string()],
-            llm_confidence: 0.9,
+    fn test_parse_imports() {
+        let modules = |language: &str, file: &str, content: &str| -> Vec<(String, usize)> {
+            parse_imports(language, file, content)
+                .into_iter()
+                .map(|i| (i.module, i.line))
+                .collect()
+        };
+        let owned = |items: &[(&str, usize)]| -> Vec<(String, usize)> {
+            items.iter().map(|(m, l)| (m.to_string(), *l)).collect()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        if !result.violations.is_empty() {
-            assert_eq!(result.violations[0].severity, Severity::High);
-        }
+        let rust = concat!(
+            "use std::{fs, process::{self, Command as Cmd}};\n// use reqwest;\n",
+            "let s = \"use ureq;\";\npub use ::tokio::net;\nextern crate libc;",
+        );
+        assert_eq!(
+            modules("rust", "src/lib.rs", rust),
+            owned(&[
+                ("std::fs", 1),
+                ("std::process", 1),
+                ("std::process::Command", 1),
+                ("tokio::net", 4),
+                ("libc", 5)
+            ])
+        );
+
+        let cargo = concat!(
+            "[package]\nname = \"x\"\n\n[dependencies]\nserde = \"1\"\n",
+            "reqwest-middleware.workspace = true\n\n[dependencies.ureq]\nversion = \"2\"",
+        );
+        assert_eq!(
+            modules("rust", "Cargo.toml", cargo),
+            owned(&[("serde", 5), ("reqwest_middleware", 6), ("ureq", 8)])
+        );
+
+        let python = "import os, sys as system\nfrom subprocess import (\n    Popen,\n    run,\n)\nfrom json import *";
+        assert_eq!(
+            modules("python", "x.py", python),
+            owned(&[
+                ("os", 1),
+                ("sys", 1),
+                ("subprocess.Popen", 2),
+                ("subprocess.run", 2),
+                ("json", 6)
+            ])
+        );
+
+        let js = concat!(
+            "import fs from 'node:fs';\nimport { a,\n  b } from \"./util\";\n",
+            "export * from 'lodash/fp';\nconst cp = require('child_process');",
+        );
+        assert_eq!(
+            modules("javascript", "x.ts", js),
+            owned(&[
+                ("node:fs", 1),
+                ("./util", 2),
+                ("lodash/fp", 4),
+                ("child_process", 5)
+            ])
+        );
+
+        let go = "package main\n\nimport \"fmt\"\nimport (\n\t\"os/exec\"\n\tnet \"net/http\"\n)";
+        assert_eq!(
+            modules("go", "main.go", go),
+            owned(&[("fmt", 3), ("os/exec", 5), ("net/http", 6)])
+        );
     }
 
     #[test]
-    fn test_rules_checked_counter() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "test.rs".to_string(),
+    fn test_import_rules() {
+        let mut policy = Policy::rsr_default();
+        policy.imports.rules = vec![
+            ImportRule {
+                name: "sandbox_no_processes".to_string(),
+                language: "rust".to_string(),
+                modules: vec!["std::process".to_string()],
+                paths: vec!["sandbox/".to_string()],
+                reason: "Sandboxed code must not spawn processes".to_string(),
             },
-            content: "fn main() {}".to_string(),
-            files_affected: vec!["test.rs".to_string()],
-            llm_confidence: 0.9,
+            ImportRule {
+                name: "offline".to_string(),
+                language: "rust".to_string(),
+                modules: vec!["reqwest".to_string()],
+                paths: Vec::new(),
+                reason: "The gate runs offline".to_string(),
+            },
+        ];
+        let oracle = Oracle::new(policy);
+        let check = |path: &str, content: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            let violations = oracle.check_proposal(&proposal).unwrap().violations;
+            violations
+                .into_iter()
+                .map(|v| v.violation_type)
+                .collect::<Vec<_>>()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        // Should have checked multiple rules (forbidden languages, toolchain, patterns, tier2)
-        assert!(!result.rules_checked.is_empty());
-        assert!(result.rules_checked.len() >= 4);
+        let spawn = "use std::process::Command;\nuse std::fs;";
+        assert_eq!(
+            check("sandbox/run.rs", spawn),
+            vec![ViolationType::ForbiddenImport {
+                rule: "sandbox_no_processes".to_string(),
+                file: "sandbox/run.rs".to_string(),
+                imports: vec![("std::process::Command".to_string(), 1)],
+            }]
+        );
+        assert!(check("src/run.rs", spawn).is_empty());
+        // `std::processor` is not under `std::process`
+        assert!(check("sandbox/run.rs", "use std::processor;").is_empty());
+
+        let manifest = "[dependencies]\nserde = \"1\"\nreqwest = { version = \"0.12\" }";
+        let found = check("Cargo.toml", manifest);
+        assert!(
+            matches!(&found[..], [ViolationType::ForbiddenImport { rule, imports, .. }]
+            if rule == "offline" && imports == &vec![("reqwest".to_string(), 3)])
+        );
+        let range = oracle.locate_violation(&found[0], manifest).unwrap();
+        assert_eq!(&manifest[range], "reqwest = { version = \"0.12\" }");
+        assert!(oracle
+            .policy()
+            .rule_ids()
+            .contains(&"import:offline".to_string()));
     }
 
+    #[cfg(feature = "tree-sitter")]
     #[test]
-    fn test_proposal_id_preserved_in_evaluation() {
-        let proposal_id = Uuid::new_v4();
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: proposal_id,
-            action_type: ActionType::CreateFile {
-                path: "test.rs".to_string(),
-            },
-            content: "fn main() {}".to_string(),
-            files_affected: vec!["test.rs".to_string()],
-            llm_confidence: 0.9,
+    fn test_structural_language_detection() {
+        let oracle = oracle().with_explain(true);
+        let check = |content: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: "docs/notes.md".to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec!["docs/notes.md".to_string()],
+                llm_confidence: 0.9,
+            };
+            oracle.check_proposal(&proposal).unwrap()
+        };
+        let languages = |content: &str| -> Vec<String> {
+            let result = check(content);
+            result
+                .violations
+                .into_iter()
+                .map(|v| v.rule)
+                .filter(|r| r.starts_with("forbidden_language:"))
+                .collect()
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.proposal_id, proposal_id);
+        // Markers in prose and Rust type annotations are not TypeScript
+        assert!(languages("The interface between the gate and the oracle is small.").is_empty());
+        assert!(languages("pub struct User {\n    name: String,\n    age: u32,\n}\n").is_empty());
+
+        // A fenced TypeScript interface is, and its node is the evidence
+        let fenced = "Example:\n\n```ts\ninterface User {\n  name: string;\n}\n```\n";
+        let result = check(fenced);
+        let trace = result
+            .trace
+            .iter()
+            .find(|t| t.rule == "forbidden_language:typescript")
+            .unwrap();
+        assert_eq!(trace.outcome, RuleOutcome::Matched);
+        assert!(trace.detail.starts_with("parsed interface_declaration"));
+        let violation = &result.violations[0].violation_type;
+        assert!(fenced[oracle.locate_violation(violation, fenced).unwrap()]
+            .starts_with("interface User"));
+
+        // Code without any marker is caught by parsing the whole content
+        assert_eq!(
+            languages("function total(price:number, qty:number) {\n  return price * qty;\n}\n"),
+            vec!["forbidden_language:typescript"]
+        );
+
+        let go = parse_language("go", "package main\n\nfunc main() {}\n").unwrap();
+        assert_eq!(go.node.as_deref(), Some("package_clause"));
+        assert_eq!(go.confidence, 1.0);
+        assert_eq!(
+            detect_language("def greet(name):\n    return name\n")
+                .unwrap()
+                .language,
+            "python"
+        );
+        assert!(detect_language("Just a sentence of prose.").is_none());
+        assert!(parse_language("cobol", "IDENTIFICATION DIVISION.").is_none());
     }
 
     #[test]
-    fn test_go_forbidden() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "main.go".to_string(),
-            },
-            content: "package main\nfunc main() {}".to_string(),
-            files_affected: vec!["main.go".to_string()],
-            llm_confidence: 0.9,
-        };
-
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+    fn test_tier1_files_with_shared_keywords_are_not_ambiguous() {
+        // From the training corpus: elixir_genserver and hardcoded_secret
+        let elixir = "defmodule MyApp.Worker do\n  use GenServer\n\n  def start_link(opts) do\n    GenServer.start_link(__MODULE__, opts, name: __MODULE__)\n  end\n\n  @impl true\n  def init(opts) do\n    {:ok, %{count: 0, opts: opts}}\n  end\nend\n";
+        let rust = "//! Application configuration\n\npub struct Config {\n    pub api_key: String,\n    pub database_url: String,\n}\n\nimpl Default for Config {\n    fn default() -> Self {\n        Self {\n            api_key: \"sk-12345678901234567890\".to_string(),\n            database_url: std::env::var(\"DATABASE_URL\").unwrap_or_default(),\n        }\n    }\n}\n";
+        let oracle = Oracle::with_rsr_defaults();
+        for (path, content) in [("lib/worker.ex", elixir), ("src/config.rs", rust)] {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            let result = oracle.check_proposal(&proposal).unwrap();
+            assert!(
+                !result
+                    .violations
+                    .iter()
+                    .any(|v| matches!(v.violation_type, ViolationType::ForbiddenLanguage { .. })),
+                "{}",
+                path
+            );
+            assert!(
+                !result
+                    .concerns
+                    .iter()
+                    .any(|c| matches!(c.concern_type, ConcernType::AmbiguousLanguage { .. })),
+                "{}",
+                path
+            );
+        }
     }
 
     #[test]
-    fn test_java_forbidden() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "Main.java".to_string(),
-            },
-            content: "public class Main { }".to_string(),
-            files_affected: vec!["Main.java".to_string()],
-            llm_confidence: 0.9,
+    fn test_language_detection_scores() {
+        let mut policy = Policy::rsr_default();
+        policy.languages.forbidden.push(LanguageConfig {
+            name: "perl".to_string(),
+            extensions: vec![".pl".to_string()],
+            markers: vec!["use strict".to_string(), "my $".to_string()],
+            shebangs: vec!["perl".to_string()],
+            code: None,
+            alternative: None,
+        });
+        let check = |oracle: &Oracle, path: &str, content: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            oracle.check_proposal(&proposal).unwrap()
         };
+        let oracle = Oracle::new(policy.clone());
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+        // Two markers in a neutral file block, with the score as evidence
+        let result = check(&oracle, "notes.txt", "use strict;\nmy $x = 1;\n");
+        match &result.verdict {
+            PolicyVerdict::HardViolation(ViolationType::ForbiddenLanguage {
+                detection: Some(score),
+                ..
+            }) => {
+                assert!((score.score - 0.8).abs() < 1e-9);
+                assert_eq!(score.signals.len(), 1);
+                assert_eq!(score.signals[0].0, "markers");
+            }
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+
+        // Markers in a Tier 1 file are outweighed by its extension
+        let result = check(&oracle, "src/lib.rs", "use strict;\nlet s = \"my $x\";\n");
+        assert!(matches!(result.verdict, PolicyVerdict::Compliant));
+
+        // A shebang still counts there, less the allowed extension: ambiguous
+        let result = check(&oracle, "src/lib.rs", "#!/usr/bin/perl\nmy $x = 1;\n");
+        assert!(result.violations.is_empty());
+        match &result.verdict {
+            PolicyVerdict::SoftConcern(ConcernType::AmbiguousLanguage { language, score }) => {
+                assert_eq!(language, "perl");
+                assert!((score.score - 0.3).abs() < 1e-9);
+                assert!(score
+                    .signals
+                    .iter()
+                    .any(|(signal, weight)| signal == "allowed_extension" && *weight < 0.0));
+            }
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+
+        // A shebang alone is enough, with or without a version suffix
+        for shebang in ["#!/usr/bin/perl -w", "#!/usr/bin/env perl5.36"] {
+            let result = check(&oracle, "bin/tool", &format!("{}\nprint 1;\n", shebang));
+            assert_eq!(result.violations.len(), 1, "{}", shebang);
+        }
+        assert!(
+            check(&oracle, "bin/tool", "#!/usr/bin/env perlish\nprint 1;\n")
+                .concerns
+                .is_empty()
+        );
+
+        // A narrower margin drops the ambiguous finding altogether
+        let mut narrow = policy;
+        narrow.languages.detection.ambiguity_margin = 0.2;
+        let result = check(&Oracle::new(narrow), "src/lib.rs", "let s = \"my $x\";\n");
+        assert_eq!(result.verdict, PolicyVerdict::Compliant);
     }
 
     #[test]
-    fn test_concern_for_racket() {
-        let oracle = oracle();
-        let proposal = Proposal {
-            id: Uuid::new_v4(),
-            action_type: ActionType::CreateFile {
-                path: "script.rkt".to_string(),
-            },
-            content: "#lang racket".to_string(),
-            files_affected: vec!["script.rkt".to_string()],
-            llm_confidence: 0.8,
+    fn test_polyglot_detection() {
+        let check = |oracle: &Oracle, path: &str, content: &str| {
+            let proposal = Proposal {
+                id: Uuid::new_v4(),
+                action_type: ActionType::CreateFile {
+                    path: path.to_string(),
+                },
+                content: content.to_string(),
+                files_affected: vec![path.to_string()],
+                llm_confidence: 0.9,
+            };
+            oracle.check_proposal(&proposal).unwrap()
+        };
+        let hidden = |result: &OracleEvaluation| {
+            let found = result
+                .violations
+                .iter()
+                .find_map(|v| match &v.violation_type {
+                    ViolationType::ForbiddenPattern { pattern, .. }
+                        if v.severity == Severity::Critical =>
+                    {
+                        Some(pattern)
+                    }
+                    _ => None,
+                });
+            found
+                .cloned()
+                .unwrap_or_else(|| panic!("no polyglot violation: {:?}", result.verdict))
         };
+        let oracle = oracle();
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::SoftConcern(_)));
-        assert!(!result.concerns.is_empty());
+        // TypeScript in a Rust block comment
+        let content =
+            "/*\ninterface User {\n  id: string;\n  name: string;\n}\n*/\npub fn main() {}\n";
+        let result = check(&oracle, "src/polyglot.rs", content);
+        assert_eq!(hidden(&result), "polyglot:typescript");
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.rule == "pattern:polyglot:typescript"));
+
+        // A heredoc fed to a named interpreter, even without markers
+        let content = "#!/bin/sh\npython3 - <<'EOF'\nprint(open('/etc/passwd').read())\nEOF\n";
+        let found = oracle.find_polyglots(&["run.sh".to_string()], content);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].container, "heredoc");
+        assert_eq!(
+            &content[found[0].range.clone()],
+            "print(open('/etc/passwd').read())\n"
+        );
+        assert_eq!(
+            hidden(&check(&oracle, "run.sh", content)),
+            "polyglot:python"
+        );
+
+        // A script element whose type names the language
+        let content = "<html><script type=\"text/python\">x = 1</script></html>\n";
+        assert_eq!(
+            hidden(&check(&oracle, "index.html", content)),
+            "polyglot:python"
+        );
+
+        // PHP behind a GIF signature, once the policy forbids PHP
+        let mut policy = Policy::rsr_default();
+        policy.languages.forbidden.push(LanguageConfig {
+            name: "php".to_string(),
+            extensions: vec![".php".to_string()],
+            markers: vec!["<?php".to_string()],
+            shebangs: vec!["php".to_string()],
+            code: None,
+            alternative: None,
+        });
+        let content = "GIF89a\u{1}\u{0}<?php system($_GET['c']); ?>";
+        assert_eq!(
+            hidden(&check(&Oracle::new(policy), "avatar.gif", content)),
+            "polyglot:php"
+        );
+        assert!(oracle
+            .find_polyglots(&["avatar.gif".to_string()], content)
+            .is_empty());
+
+        // Prose comments, bit shifts and JavaScript script elements are fine
+        let content =
+            "// Parse the header; see the interface notes\nlet x = y << SHIFT;\nfn f() {}\n";
+        assert!(oracle
+            .find_polyglots(&["src/lib.rs".to_string()], content)
+            .is_empty());
+        let content = "<script type=\"module\">console.log(1)</script>\n";
+        assert!(oracle
+            .find_polyglots(&["index.html".to_string()], content)
+            .is_empty());
+
+        // An exception for the language lets it through
+        let content = "cat <<EOF\nimport os\ndef main(): pass\nEOF\n";
+        assert_eq!(
+            oracle
+                .find_polyglots(&["training/run.sh".to_string()], content)
+                .len(),
+            1
+        );
+        assert!(check(&oracle, "training/run.sh", content)
+            .violations
+            .is_empty());
     }
 
     #[test]
-    fn test_python_forbidden_outside_exceptions() {
-        let oracle = oracle();
-        let proposal = Proposal {
+    fn test_split_strings_are_folded() {
+        let content = "let k = \"pass\" + \"word = 'hunter2hunter2'\";\nlet n = 1;\n";
+        let folded = fold_string_concatenation(content);
+        assert_eq!(folded.text.len(), content.len());
+        assert!(folded
+            .text
+            .starts_with("let k = \"password = 'hunter2hunter2'\"     ;\nlet n = 1;"));
+        assert_eq!(folded.chains[0].fragments, 2);
+
+        // Newlines inside a chain move after it, so later lines keep their numbers
+        let content = "x = (\"api_\"\n     \"key\")\ny = 2\n";
+        let folded = fold_string_concatenation(content);
+        assert_eq!(folded.text.lines().nth(2), Some("y = 2"));
+        assert_eq!(folded.chains[0].value, "api_key");
+        assert_eq!(
+            fold_string_concatenation("concat!(\"a\", \"b\", )").text,
+            format!("\"ab\"{}", " ".repeat(15))
+        );
+
+        // Lifetimes and lone literals are left alone
+        let content = "fn f<'a>(s: &'a str) -> &'a str { \"x\" }\n";
+        assert!(fold_string_concatenation(content).chains.is_empty());
+
+        // Names bound to literals are joined back, keeping offsets
+        let content = "const A: &str = \"ab\";\nconst B: &str = \"cd\";\n\
+                       pub fn key() -> String {\n    format!(\"{}{}\", A, B)\n}\nlet n = 1;\n";
+        let folded = fold_string_concatenation(content);
+        assert_eq!(folded.text.len(), content.len());
+        assert_eq!(folded.text.lines().nth(5), Some("let n = 1;"));
+        assert!(folded.text.contains("key = \"abcd\""));
+        assert_eq!(folded.chains[0].fragments, 2);
+        let content = "LEFT = 'ab'\nRIGHT = 'cd'\nz = f\"{LEFT}{RIGHT}\"\nw = LEFT + RIGHT\n";
+        let values: Vec<String> = fold_string_concatenation(content)
+            .chains
+            .into_iter()
+            .map(|c| c.value)
+            .collect();
+        assert_eq!(values, ["abcd", "abcd"]);
+        // Rebound names, unbound names and unmatched placeholders are not
+        for content in [
+            "let a = \"ab\";\nlet a = \"xy\";\nlet b = \"cd\";\nlet k = a + b;\n",
+            "let a = \"ab\";\nlet k = a + b;\n",
+            "let a = \"ab\";\nlet b = \"cd\";\nlet k = format!(\"{}-{}\", a, b);\n",
+        ] {
+            assert!(
+                fold_string_concatenation(content).chains.is_empty(),
+                "{}",
+                content
+            );
+        }
+
+        let proposal = |content: &str| Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "script.py".to_string(),
+                path: "src/lib.rs".to_string(),
             },
-            content: "import os".to_string(),
-            files_affected: vec!["script.py".to_string()],
+            content: content.to_string(),
+            files_affected: vec!["src/lib.rs".to_string()],
             llm_confidence: 0.9,
         };
+        let oracle = oracle();
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+        // The secret only exists once the pieces are joined
+        let content = "let k = \"api_key = '\" + \"sk_live_0123456789'\";\n";
+        let result = oracle.check_proposal(&proposal(content)).unwrap();
+        let secret = result
+            .violations
+            .iter()
+            .find(|v| v.rule == "pattern:hardcoded_secrets")
+            .unwrap();
+        let range = oracle
+            .locate_violation(&secret.violation_type, content)
+            .unwrap();
+        assert_eq!(range.start, content.find("api_key").unwrap());
+
+        // A secret whose parts are named and joined in a function
+        let content = "const P1: &str = \"super\";\nconst P2: &str = \"secret\";\n\
+                       const P3: &str = \"password123\";\n\n\
+                       pub fn get_password() -> String {\n    format!(\"{}{}{}\", P1, P2, P3)\n}\n";
+        let result = oracle.check_proposal(&proposal(content)).unwrap();
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| v.rule == "pattern:hardcoded_secrets"));
+
+        // Folding can be turned off
+        let mut policy = Policy::rsr_default();
+        policy.obfuscation.fold_concatenation = false;
+        let result = Oracle::new(policy)
+            .check_proposal(&proposal(content))
+            .unwrap();
+        assert!(result
+            .violations
+            .iter()
+            .all(|v| v.rule != "pattern:hardcoded_secrets"));
+
+        // Fine fragmentation is a concern in itself
+        let content = "fn f() {}\nlet s = \"im\" + \"po\" + \"rt\" + \" o\" + \"s\";\n";
+        let result = oracle.check_proposal(&proposal(content)).unwrap();
+        let concern = result
+            .concerns
+            .iter()
+            .find(|c| c.rule == "string_fragmentation")
+            .unwrap();
+        assert!(matches!(
+            concern.concern_type,
+            ConcernType::SuspiciousObfuscation {
+                fragments: 5,
+                line: 2
+            }
+        ));
+        let content = "let q = \"SELECT id, name \" + \"FROM users \" + \"WHERE active \" + \"ORDER BY name\";\n";
+        let result = oracle.check_proposal(&proposal(content)).unwrap();
+        assert!(result
+            .concerns
+            .iter()
+            .all(|c| c.rule != "string_fragmentation"));
     }
 
     #[test]
-    fn test_python_allowed_in_training() {
-        let oracle = oracle();
+    fn test_deadline_cuts_evaluation_short() {
+        let oracle = Oracle::with_rsr_defaults();
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "training/model.py".to_string(),
+                path: "src/lib.rs".to_string(),
             },
-            content: "import os".to_string(),
-            files_affected: vec!["training/model.py".to_string()],
+            content: "pub fn f() {}\n".to_string(),
+            files_affected: vec!["src/lib.rs".to_string()],
             llm_confidence: 0.9,
         };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert_eq!(result.verdict, PolicyVerdict::Compliant);
+        let full = oracle.check_proposal(&proposal).unwrap();
+        assert!(!full.timed_out);
+        let later = Instant::now() + std::time::Duration::from_secs(60);
+        let in_time = oracle.check_proposal_until(&proposal, Some(later)).unwrap();
+        assert_eq!(in_time.rules_checked, full.rules_checked);
+        assert!(!in_time.timed_out);
+
+        // A passed deadline leaves only the first rule group checked
+        let partial = oracle
+            .check_proposal_until(&proposal, Some(Instant::now()))
+            .unwrap();
+        assert!(partial.timed_out);
+        assert_eq!(partial.rules_checked, vec!["content_encoding".to_string()]);
+        assert!(matches!(partial.verdict, PolicyVerdict::Compliant));
+        assert!(!serde_json::to_string(&full).unwrap().contains("timed_out"));
     }
 
     #[test]
-    fn test_secret_api_key_detected() {
-        let oracle = oracle();
+    fn test_deadline_stops_within_a_rule_group() {
+        // Forbidden patterns dominate the run: many of them over large content
+        let mut policy = Policy::rsr_default();
+        policy.patterns.forbidden_patterns = (0..20)
+            .map(|i| ForbiddenPattern {
+                name: format!("slow_{}", i),
+                regex: format!("[xyz][0-9]{{3}}[xyz]{}", i),
+                file_types: vec!["*".to_string()],
+                reason: String::new(),
+                fix: None,
+                code: None,
+                no_fix: false,
+                allow: Vec::new(),
+            })
+            .collect();
+        let oracle = Oracle::new(policy).with_explain(true);
         let proposal = Proposal {
             id: Uuid::new_v4(),
             action_type: ActionType::CreateFile {
-                path: "config.rs".to_string(),
+                path: "src/lib.rs".to_string(),
             },
-            content: r#"const API_KEY = "abcdef1234567890abcdef""#.to_string(), // test fixture — scanner-allow: rust-secrets
-            files_affected: vec!["config.rs".to_string()],
+            content: "// abc def x12 y345\n".repeat(10_000),
+            files_affected: vec!["src/lib.rs".to_string()],
             llm_confidence: 0.9,
         };
+        let patterns_run = |evaluation: &OracleEvaluation| {
+            evaluation
+                .trace
+                .iter()
+                .filter(|t| t.rule.starts_with("pattern:slow_"))
+                .count()
+        };
 
-        let result = oracle.check_proposal(&proposal).unwrap();
-        assert!(matches!(result.verdict, PolicyVerdict::HardViolation(_)));
+        let start = Instant::now();
+        let full = oracle.check_proposal(&proposal).unwrap();
+        let took = start.elapsed();
+        assert_eq!(patterns_run(&full), 20);
+
+        // Halfway through the run is partway through the patterns
+        let deadline = Instant::now() + took / 2;
+        let partial = oracle
+            .check_proposal_until(&proposal, Some(deadline))
+            .unwrap();
+        assert!(partial.timed_out);
+        assert!((1..20).contains(&patterns_run(&partial)));
+        // The unfinished group is left out of the checked rules
+        assert_eq!(
+            partial.rules_checked.last().map(String::as_str),
+            Some("toolchain_rules")
+        );
     }
 }
@@ -0,0 +1,395 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Rule packs: named, versioned bundles of rules a policy includes
+//!
+//! A pack is a JSON file of language, pattern and toolchain rules, e.g.
+//! `security-strict@0.4`. Packs are looked up as `<name>@<version>.json`
+//! in the pack directory. Packs missing there are fetched by the caller
+//! from a registry listing each pack's URL and SHA-256 digest, and handed
+//! over with [`RulePackPolicy::install`] once that digest checks out; the
+//! oracle itself only reads local files. A reference may also pin the
+//! digest itself, so a pack cannot change under a policy unnoticed.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{ExceptionRule, ForbiddenPattern, LanguageConfig, OracleError, Policy, ToolchainRule};
+
+/// Largest pack file read, local or downloaded
+pub const MAX_PACK_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Rule packs a policy includes, and where they are resolved from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RulePackPolicy {
+    #[serde(default)]
+    pub include: Vec<RulePackRef>,
+    /// Directory of `<name>@<version>.json` packs; registry downloads are kept here
+    #[serde(default = "default_rulepack_dir")]
+    pub dir: PathBuf,
+    /// Registry listing downloadable packs: an HTTP(S) URL or a JSON file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+}
+
+fn default_rulepack_dir() -> PathBuf {
+    PathBuf::from(".conative/rulepacks")
+}
+
+impl Default for RulePackPolicy {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            dir: default_rulepack_dir(),
+            registry: None,
+        }
+    }
+}
+
+impl RulePackPolicy {
+    /// Where `reference` is kept in the pack directory
+    pub fn path_for(&self, reference: &RulePackRef) -> PathBuf {
+        self.dir.join(format!("{}.json", reference))
+    }
+
+    /// Keep downloaded pack bytes, already checked against the registry's
+    /// digest, in the pack directory once they hold `reference` and match
+    /// any pin; returns their digest
+    pub fn install(&self, reference: &RulePackRef, bytes: &[u8]) -> Result<String, OracleError> {
+        let (_, sha256) = verify_pack(reference, bytes)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path_for(reference), bytes)?;
+        Ok(sha256)
+    }
+}
+
+/// A pack a policy includes by name and version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RulePackRef {
+    pub name: String,
+    pub version: String,
+    /// Expected SHA-256 of the pack file (hex, optionally prefixed with `sha256:`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl fmt::Display for RulePackRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+/// A versioned bundle of rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub languages: RulePackLanguages,
+    #[serde(default)]
+    pub patterns: Vec<ForbiddenPattern>,
+    #[serde(default)]
+    pub toolchain: Vec<ToolchainRule>,
+}
+
+/// Language rules a pack adds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulePackLanguages {
+    #[serde(default)]
+    pub tier1: Vec<LanguageConfig>,
+    #[serde(default)]
+    pub tier2: Vec<LanguageConfig>,
+    #[serde(default)]
+    pub forbidden: Vec<LanguageConfig>,
+    #[serde(default)]
+    pub exceptions: Vec<ExceptionRule>,
+}
+
+/// Registry of downloadable rule packs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulePackRegistry {
+    #[serde(default)]
+    pub rulepacks: Vec<RulePackEntry>,
+}
+
+/// One pack version in a registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePackEntry {
+    pub name: String,
+    pub version: String,
+    /// HTTP(S) URL or local path of the pack file
+    pub url: String,
+    /// Expected SHA-256 digest (hex, optionally prefixed with `sha256:`)
+    pub sha256: String,
+}
+
+impl RulePackRegistry {
+    pub fn find(&self, reference: &RulePackRef) -> Option<&RulePackEntry> {
+        self.rulepacks
+            .iter()
+            .find(|p| p.name == reference.name && p.version == reference.version)
+    }
+}
+
+/// A pack merged into a policy
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ResolvedRulePack {
+    pub name: String,
+    pub version: String,
+    /// `sha256:<hex>` digest of the pack file
+    pub sha256: String,
+}
+
+impl Policy {
+    /// Merge every included rule pack from the pack directory into the
+    /// policy
+    ///
+    /// The policy's own rules win over a pack's rules of the same name, and
+    /// earlier packs over later ones, so resolving twice changes nothing.
+    pub fn resolve_rulepacks(&mut self) -> Result<Vec<ResolvedRulePack>, OracleError> {
+        let mut resolved = Vec::new();
+        for reference in self.rulepacks.include.clone() {
+            let (pack, sha256) = load_pack(&self.rulepacks, &reference)?;
+            if reference.sha256.is_none() {
+                tracing::warn!(
+                    "Rule pack {} is not pinned; add its sha256 ({})",
+                    reference,
+                    sha256
+                );
+            }
+            self.merge_rulepack(&pack);
+            resolved.push(ResolvedRulePack {
+                name: pack.name,
+                version: pack.version,
+                sha256,
+            });
+        }
+        Ok(resolved)
+    }
+
+    /// Add a pack's rules that the policy does not already name
+    pub fn merge_rulepack(&mut self, pack: &RulePack) {
+        let languages = &mut self.languages;
+        let mut known: HashSet<String> = languages
+            .tier1
+            .iter()
+            .chain(&languages.tier2)
+            .chain(&languages.forbidden)
+            .map(|l| l.name.to_lowercase())
+            .collect();
+        for (tier, extra) in [
+            (&mut languages.tier1, &pack.languages.tier1),
+            (&mut languages.tier2, &pack.languages.tier2),
+            (&mut languages.forbidden, &pack.languages.forbidden),
+        ] {
+            // A language keeps the tier the policy, or an earlier pack, gave it
+            for lang in extra {
+                if known.insert(lang.name.to_lowercase()) {
+                    tier.push(lang.clone());
+                }
+            }
+        }
+        for exception in &pack.languages.exceptions {
+            if !self.languages.exceptions.contains(exception) {
+                self.languages.exceptions.push(exception.clone());
+            }
+        }
+        for pattern in &pack.patterns {
+            if !self
+                .patterns
+                .forbidden_patterns
+                .iter()
+                .any(|p| p.name == pattern.name)
+            {
+                self.patterns.forbidden_patterns.push(pattern.clone());
+            }
+        }
+        for rule in &pack.toolchain {
+            if !self.toolchain.rules.iter().any(|r| r.tool == rule.tool) {
+                self.toolchain.rules.push(rule.clone());
+            }
+        }
+    }
+}
+
+/// Read a pack from the pack directory
+fn load_pack(
+    packs: &RulePackPolicy,
+    reference: &RulePackRef,
+) -> Result<(RulePack, String), OracleError> {
+    let path = packs.path_for(reference);
+    if !path.exists() {
+        let mut message = format!("{}: not in {}", reference, packs.dir.display());
+        if let Some(registry) = &packs.registry {
+            message.push_str(&format!(" and not fetched from registry {}", registry));
+        }
+        return Err(OracleError::RulePack(message));
+    }
+    verify_pack(reference, &read_limited(fs::File::open(&path)?)?)
+}
+
+/// Parse pack bytes, checking they hold `reference` and match its pin
+fn verify_pack(reference: &RulePackRef, bytes: &[u8]) -> Result<(RulePack, String), OracleError> {
+    let error = |message: String| OracleError::RulePack(format!("{}: {}", reference, message));
+    let actual = digest(bytes);
+    if let Some(pin) = &reference.sha256 {
+        if actual != normalize_digest(pin) {
+            return Err(error(format!(
+                "pack is {}, policy pins {}",
+                actual,
+                normalize_digest(pin)
+            )));
+        }
+    }
+    let pack: RulePack = serde_json::from_slice(bytes).map_err(|e| error(e.to_string()))?;
+    if pack.name != reference.name || pack.version != reference.version {
+        return Err(error(format!("file holds {}@{}", pack.name, pack.version)));
+    }
+    Ok((pack, actual))
+}
+
+fn read_limited(reader: impl Read) -> Result<Vec<u8>, OracleError> {
+    let mut bytes = Vec::new();
+    reader.take(MAX_PACK_BYTES + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > MAX_PACK_BYTES {
+        return Err(OracleError::RulePack(format!(
+            "pack is over {} bytes",
+            MAX_PACK_BYTES
+        )));
+    }
+    Ok(bytes)
+}
+
+/// `sha256:<hex>` digest of pack bytes
+pub fn digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+/// Canonical `sha256:<lowercase hex>` form of a configured digest
+fn normalize_digest(digest: &str) -> String {
+    let hex = digest.trim();
+    let hex = hex.strip_prefix("sha256:").unwrap_or(hex);
+    format!("sha256:{}", hex.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK: &str = r#"{
+        "name": "security-strict",
+        "version": "0.4",
+        "languages": {
+            "tier1": [{"name": "python", "extensions": [".py"], "markers": []}],
+            "forbidden": [{"name": "perl", "extensions": [".pl"], "markers": ["use strict;"]}]
+        },
+        "patterns": [
+            {"name": "aws_keys", "regex": "AKIA[0-9A-Z]{16}", "file_types": ["*"], "reason": "AWS access key"},
+            {"name": "hardcoded_secrets", "regex": "x", "file_types": ["*"], "reason": "overridden"}
+        ]
+    }"#;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("conative-rulepack-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn reference(sha256: Option<String>) -> RulePackRef {
+        RulePackRef {
+            name: "security-strict".to_string(),
+            version: "0.4".to_string(),
+            sha256,
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_policy_rules() {
+        let mut policy = Policy::rsr_default();
+        let secrets = policy.patterns.forbidden_patterns[0].regex.clone();
+        let pack: RulePack = serde_json::from_str(PACK).unwrap();
+        policy.merge_rulepack(&pack);
+        policy.merge_rulepack(&pack);
+
+        let patterns = &policy.patterns.forbidden_patterns;
+        assert_eq!(patterns.iter().filter(|p| p.name == "aws_keys").count(), 1);
+        assert_eq!(
+            patterns
+                .iter()
+                .find(|p| p.name == "hardcoded_secrets")
+                .unwrap()
+                .regex,
+            secrets
+        );
+        // python stays forbidden; perl is new
+        assert!(!policy.languages.tier1.iter().any(|l| l.name == "python"));
+        assert!(policy.languages.forbidden.iter().any(|l| l.name == "perl"));
+    }
+
+    #[test]
+    fn test_resolve_checks_pinned_digest() {
+        let dir = temp_dir();
+        fs::write(dir.join("security-strict@0.4.json"), PACK).unwrap();
+        let mut policy = Policy::rsr_default();
+        policy.rulepacks.dir = dir.clone();
+
+        policy.rulepacks.include = vec![reference(Some(digest(PACK.as_bytes())))];
+        let resolved = policy.clone().resolve_rulepacks().unwrap();
+        assert_eq!(resolved[0].sha256, digest(PACK.as_bytes()));
+
+        policy.rulepacks.include = vec![reference(Some("sha256:00".to_string()))];
+        let err = policy.resolve_rulepacks().unwrap_err().to_string();
+        assert!(
+            err.contains("security-strict@0.4: pack is sha256:"),
+            "{}",
+            err
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_keeps_only_the_referenced_pinned_pack() {
+        let dir = temp_dir();
+        let mut packs = RulePackPolicy {
+            dir: dir.join("packs"),
+            ..Default::default()
+        };
+        let mut policy = Policy::rsr_default();
+        policy.rulepacks = packs.clone();
+        policy.rulepacks.include = vec![reference(None)];
+        let err = policy.clone().resolve_rulepacks().unwrap_err().to_string();
+        assert!(err.contains("security-strict@0.4: not in"), "{}", err);
+
+        let pinned = reference(Some("sha256:00".to_string()));
+        assert!(packs.install(&pinned, PACK.as_bytes()).is_err());
+        let other = RulePackRef {
+            version: "0.5".to_string(),
+            ..reference(None)
+        };
+        assert!(packs.install(&other, PACK.as_bytes()).is_err());
+        assert!(!packs.dir.exists());
+
+        let sha256 = packs.install(&reference(None), PACK.as_bytes()).unwrap();
+        assert_eq!(sha256, digest(PACK.as_bytes()));
+        assert!(packs.path_for(&reference(None)).exists());
+        let resolved = policy.resolve_rulepacks().unwrap();
+        assert_eq!(resolved[0].sha256, sha256);
+        assert!(policy
+            .patterns
+            .forbidden_patterns
+            .iter()
+            .any(|p| p.name == "aws_keys"));
+
+        packs.registry = Some("https://example.org/registry.json".to_string());
+        fs::remove_dir_all(&dir).unwrap();
+        let err = load_pack(&packs, &reference(None)).unwrap_err().to_string();
+        assert!(err.contains("not fetched from registry"), "{}", err);
+    }
+}
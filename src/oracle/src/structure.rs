@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Structural language detection (tree-sitter)
+//!
+//! Markers are substrings, so `interface ` in prose reads as TypeScript
+//! and `a:string` without the space slips past. Parsing settles both: text
+//! counts as a language when its grammar parses it almost without errors
+//! and the tree holds a node only that language has.
+
+use std::ops::Range;
+
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::StructuralMatch;
+
+/// Share of error-free text needed for a parse to count
+pub(crate) const MIN_CONFIDENCE: f64 = 0.9;
+
+/// Largest content worth parsing; error recovery on big inputs in the
+/// wrong grammar is slow, so larger content keeps to markers
+pub(crate) const MAX_PARSE_BYTES: usize = 64 * 1024;
+
+/// Grammars by policy language name, with the node kinds that identify it
+const GRAMMARS: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "function_item",
+            "impl_item",
+            "struct_item",
+            "enum_item",
+            "trait_item",
+            "use_declaration",
+            "mod_item",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            "interface_declaration",
+            "type_alias_declaration",
+            "type_annotation",
+            "enum_declaration",
+            "abstract_class_declaration",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "function_definition",
+            "class_definition",
+            "import_statement",
+            "import_from_statement",
+        ],
+    ),
+    (
+        "go",
+        &[
+            "package_clause",
+            "function_declaration",
+            "method_declaration",
+            "import_declaration",
+        ],
+    ),
+    (
+        "java",
+        &[
+            "class_declaration",
+            "interface_declaration",
+            "method_declaration",
+            "package_declaration",
+        ],
+    ),
+];
+
+fn language(name: &str) -> Option<Language> {
+    Some(match name {
+        "rust" => tree_sitter_rust::LANGUAGE.into(),
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "python" => tree_sitter_python::LANGUAGE.into(),
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        "java" => tree_sitter_java::LANGUAGE.into(),
+        _ => return None,
+    })
+}
+
+/// Whether a grammar exists for the policy language `name`
+pub(crate) fn has_grammar(name: &str) -> bool {
+    GRAMMARS.iter().any(|(grammar, _)| *grammar == name)
+}
+
+fn parse(name: &str, text: &str) -> Option<(Tree, &'static [&'static str])> {
+    let (_, kinds) = GRAMMARS.iter().find(|(grammar, _)| *grammar == name)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language(name)?).ok()?;
+    Some((parser.parse(text, None)?, kinds))
+}
+
+/// Bytes under `node` that failed to parse, and its first identifying node
+fn scan<'t>(node: Node<'t>, kinds: &[&str]) -> (usize, Option<Node<'t>>) {
+    let mut errors = 0;
+    let mut identifying: Option<Node> = None;
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            errors += node.byte_range().len().max(1);
+            continue;
+        }
+        if identifying.is_none_or(|n| node.start_byte() < n.start_byte())
+            && kinds.contains(&node.kind())
+        {
+            identifying = Some(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    (errors, identifying)
+}
+
+fn structural_match(
+    name: &str,
+    text: &str,
+    errors: usize,
+    identifying: Option<Node>,
+) -> StructuralMatch {
+    let size = text.trim().len().max(1);
+    StructuralMatch {
+        language: name.to_string(),
+        confidence: 1.0 - errors.min(size) as f64 / size as f64,
+        node: identifying.map(|n| n.kind().to_string()),
+        range: identifying.map_or(0..text.len(), |n| n.byte_range()),
+    }
+}
+
+/// Parse all of `text` as `name`; `None` without a grammar
+pub(crate) fn parse_as(name: &str, text: &str) -> Option<StructuralMatch> {
+    let (tree, kinds) = parse(name, text)?;
+    let (errors, identifying) = scan(tree.root_node(), kinds);
+    Some(structural_match(name, text, errors, identifying))
+}
+
+/// What parsing made of the markers of one language
+pub(crate) enum Confirmation {
+    /// A statement parses as the language better than as any other
+    Confirmed(StructuralMatch),
+    /// A statement parses as the language, but as well as another; the
+    /// range is its identifying node
+    Tied(Range<usize>),
+    /// No marker sits in a statement that parses as the language
+    Refuted,
+}
+
+/// Check the top-level statements around `offsets` in `content` against
+/// `name`, first confirmed wins; each paragraph is parsed once
+pub(crate) fn confirm(name: &str, content: &str, mut offsets: Vec<usize>) -> Confirmation {
+    offsets.sort_unstable();
+    let mut tied = None;
+    let mut parsed: Option<(usize, String, Tree, &[&str])> = None;
+    let mut tried: Vec<Range<usize>> = Vec::new();
+    for offset in offsets {
+        if tried.iter().any(|range| range.contains(&offset)) {
+            continue;
+        }
+        let (start, text) = paragraph(content, offset);
+        if parsed
+            .as_ref()
+            .is_none_or(|(parsed_start, ..)| *parsed_start != start)
+        {
+            let Some((tree, kinds)) = parse(name, &text) else {
+                return Confirmation::Refuted;
+            };
+            parsed = Some((start, text, tree, kinds));
+        }
+        let Some((start, text, tree, kinds)) = parsed.as_ref() else {
+            return Confirmation::Refuted;
+        };
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let Some(statement) = root
+            .children(&mut cursor)
+            .find(|n| n.end_byte() > offset - start)
+        else {
+            continue;
+        };
+        tried.push(
+            statement.start_byte() + start..statement.end_byte().max(offset - start + 1) + start,
+        );
+        if statement.is_error() {
+            continue;
+        }
+        let source = &text[statement.byte_range()];
+        let (errors, identifying) = scan(statement, kinds);
+        let found = structural_match(name, source, errors, identifying);
+        let Some(node) = identifying.filter(|_| found.confidence >= MIN_CONFIDENCE) else {
+            continue;
+        };
+        let range = node.start_byte() + start..node.end_byte() + start;
+        let rivals = GRAMMARS.iter().filter(|(other, _)| *other != name);
+        if rivals
+            .filter_map(|(other, _)| parse_as(other, source))
+            .all(|p| p.confidence < found.confidence)
+        {
+            return Confirmation::Confirmed(StructuralMatch { range, ..found });
+        }
+        tied.get_or_insert(range);
+    }
+    tied.map_or(Confirmation::Refuted, Confirmation::Tied)
+}
+
+/// The one grammar that parses all of `text` confidently, more cleanly
+/// than any other, and finds a node only its language has
+pub(crate) fn detect(text: &str) -> Option<StructuralMatch> {
+    if text.len() > MAX_PARSE_BYTES {
+        return None;
+    }
+    let mut parses: Vec<StructuralMatch> = GRAMMARS
+        .iter()
+        .filter_map(|(name, _)| parse_as(name, text))
+        .collect();
+    parses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let best = parses.first()?;
+    let runner_up = parses.get(1).map_or(0.0, |p| p.confidence);
+    let clear = best.confidence >= MIN_CONFIDENCE && best.confidence > runner_up;
+    (clear && best.node.is_some()).then(|| best.clone())
+}
+
+/// The paragraph around `offset`, blank lines apart, with Markdown fence
+/// lines blanked so a fenced snippet parses as code; returns its start
+fn paragraph(content: &str, offset: usize) -> (usize, String) {
+    let start = content[..offset].rfind("\n\n").map_or(0, |n| n + 2);
+    let end = content[offset..]
+        .find("\n\n")
+        .map_or(content.len(), |n| offset + n);
+    let text = content[start..end]
+        .split_inclusive('\n')
+        .map(|line| {
+            let fence = ["```", "~~~"]
+                .iter()
+                .any(|f| line.trim_start().starts_with(f));
+            match fence {
+                true => line
+                    .chars()
+                    .map(|c| if c == '\n' { c } else { ' ' })
+                    .collect(),
+                false => line.to_string(),
+            }
+        })
+        .collect();
+    (start, text)
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Rule packs a policy includes but the pack directory lacks, fetched from
+//! the policy's registry
+//!
+//! The oracle only reads packs from local files; downloads are checked
+//! against the registry's digest here and handed to it to keep.
+
+use policy_oracle::{RulePackPolicy, RulePackRef, RulePackRegistry, MAX_PACK_BYTES};
+use slm_evaluator::models::{fetch_verified, open_source};
+use slm_evaluator::SlmError;
+use std::io::Read;
+
+/// Fetch every included pack missing from the pack directory; returns the
+/// packs fetched
+pub(crate) fn fetch_rulepacks(packs: &RulePackPolicy) -> Result<Vec<RulePackRef>, String> {
+    let missing: Vec<&RulePackRef> = packs
+        .include
+        .iter()
+        .filter(|reference| !packs.path_for(reference).exists())
+        .collect();
+    // Without a registry, resolving reports the missing packs
+    let (Some(source), false) = (&packs.registry, missing.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let registry = load_registry(source)?;
+    let mut fetched = Vec::new();
+    for reference in missing {
+        let entry = registry
+            .find(reference)
+            .ok_or_else(|| format!("{}: not listed in registry {}", reference, source))?;
+        let bytes = fetch_verified(&entry.url, &entry.sha256, MAX_PACK_BYTES)
+            .map_err(|e| format!("{}: {}", reference, fetch_error(e)))?;
+        packs
+            .install(reference, &bytes)
+            .map_err(|e| e.to_string())?;
+        fetched.push(reference.clone());
+    }
+    Ok(fetched)
+}
+
+/// Registry from an HTTP(S) URL or a local JSON file
+fn load_registry(source: &str) -> Result<RulePackRegistry, String> {
+    let mut raw = Vec::new();
+    open_source(source)
+        .map_err(fetch_error)?
+        .take(MAX_PACK_BYTES)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("{}: {}", source, e))?;
+    serde_json::from_slice(&raw).map_err(|e| format!("{}: {}", source, e))
+}
+
+/// A failed fetch, without the model wording of the error it came as
+fn fetch_error(error: SlmError) -> String {
+    match error {
+        SlmError::FingerprintMismatch { expected, actual } => {
+            format!("download is {}, registry lists {}", actual, expected)
+        }
+        SlmError::ModelFetch(message) => message,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use policy_oracle::Policy;
+
+    const PACK: &str = r#"{
+        "name": "security-strict",
+        "version": "0.4",
+        "patterns": [
+            {"name": "aws_keys", "regex": "AKIA[0-9A-Z]{16}", "file_types": ["*"], "reason": "AWS access key"}
+        ]
+    }"#;
+
+    /// `sha256:<hex>` of `PACK`
+    fn pack_digest() -> String {
+        use sha2::Digest;
+        format!("sha256:{:x}", sha2::Sha256::digest(PACK.as_bytes()))
+    }
+
+    #[test]
+    fn test_missing_packs_are_fetched_from_the_registry() {
+        let dir = std::env::temp_dir().join(format!("conative-rulepacks-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("published.json");
+        std::fs::write(&source, PACK).unwrap();
+        let registry = dir.join("registry.json");
+        let listing = |sha256: &str| {
+            let entry = serde_json::json!({
+                "name": "security-strict", "version": "0.4", "url": source, "sha256": sha256
+            });
+            serde_json::json!({ "rulepacks": [entry] }).to_string()
+        };
+        let mut policy = Policy::rsr_default();
+        policy.rulepacks.dir = dir.join("packs");
+        policy.rulepacks.registry = Some(registry.to_string_lossy().to_string());
+        policy.rulepacks.include = vec![RulePackRef {
+            name: "security-strict".to_string(),
+            version: "0.4".to_string(),
+            sha256: None,
+        }];
+
+        // A download that does not match the registry is not kept
+        std::fs::write(&registry, listing("sha256:00")).unwrap();
+        let err = fetch_rulepacks(&policy.rulepacks).unwrap_err();
+        assert!(
+            err.starts_with("security-strict@0.4: download is sha256:"),
+            "{}",
+            err
+        );
+        assert!(!dir.join("packs").exists());
+
+        std::fs::write(&registry, listing(&pack_digest())).unwrap();
+        let fetched = fetch_rulepacks(&policy.rulepacks).unwrap();
+        assert_eq!(fetched, policy.rulepacks.include);
+        assert!(fetch_rulepacks(&policy.rulepacks).unwrap().is_empty());
+        let resolved = policy.resolve_rulepacks().unwrap();
+        assert_eq!(resolved[0].sha256, pack_digest());
+        assert!(policy
+            .patterns
+            .forbidden_patterns
+            .iter()
+            .any(|p| p.name == "aws_keys"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
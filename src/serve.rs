@@ -0,0 +1,1393 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! `conative serve`: gating decisions over HTTP
+//!
+//! The accept loop answers probes itself and queues everything else for a
+//! fixed pool of workers; a full queue is refused with `429` rather than
+//! left to grow. Policies are reloaded in place by a watcher thread.
+
+use crate::{
+    contract_runner, policy_checks, CliError, DoctorCheck, DoctorStatus, Envelope, ErrorKind,
+    OutputFormat, OutputSchema,
+};
+use gating_contract::{
+    AdmissionReview, AuthError, Capability, ContractRunner, GatingRequest, GatingSetRequest,
+    PolicyChange, Refusal, RouteError, SourceCredential, SourceRegistry, TenantRegistry, Verdict,
+    DEFAULT_TENANT,
+};
+use policy_oracle::Policy;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Seconds a refused client is asked to wait before retrying
+const SERVE_RETRY_AFTER_SECS: u64 = 1;
+
+/// How often the server checks for SIGHUP and a changed policy file
+const SERVE_RELOAD_POLL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Header naming the tenant a request is for
+const TENANT_HEADER: &str = "X-Conative-Tenant";
+
+/// How `conative serve` listens, bounds its work and follows its policy
+pub(crate) struct ServeOptions {
+    pub(crate) listen: String,
+    pub(crate) workers: usize,
+    pub(crate) queue: usize,
+    pub(crate) max_body: u64,
+    pub(crate) policy_file: Option<PathBuf>,
+    /// Reload policies whenever their files change
+    pub(crate) watch: bool,
+    pub(crate) audit_log: Option<PathBuf>,
+    /// Credentials evaluation requests must present
+    pub(crate) auth: Option<SourceRegistry>,
+    pub(crate) tenants: TenantRegistry,
+}
+
+/// Read a `--tenants` file; its paths are relative to it
+pub(crate) fn load_tenants(path: &Path) -> Result<TenantRegistry, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut registry: TenantRegistry =
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if let Some(error) = registry.compile_errors().first() {
+        return Err(format!("{}: tenant {}", path.display(), error));
+    }
+    registry.resolve_paths(path.parent().unwrap_or(Path::new("")));
+    Ok(registry)
+}
+
+pub(crate) fn load_source_registry(path: &Path) -> Result<SourceRegistry, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let registry: SourceRegistry =
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    match registry.compile_errors().first() {
+        Some(error) => Err(format!("{}: {}", path.display(), error)),
+        None => Ok(registry),
+    }
+}
+
+/// A policy being served with its runner and audit log; a reload swaps
+/// all three at once
+pub(crate) struct ServedPolicy {
+    policy: Policy,
+    fingerprint: String,
+    runner: ContractRunner,
+    audit: Option<std::sync::Mutex<gating_contract::AuditLog<std::fs::File>>>,
+}
+
+impl ServedPolicy {
+    /// Ready `policy` for serving; a rule that does not compile refuses it
+    pub(crate) fn new(policy: Policy, audit_log: Option<&Path>) -> Result<Self, String> {
+        if let Some(error) = policy.compile_errors().first() {
+            return Err(error.clone());
+        }
+        let runner = contract_runner(&policy);
+        let audit = match audit_log {
+            Some(path) => {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                Some(std::sync::Mutex::new(runner.audit_log(file)))
+            }
+            None => None,
+        };
+        Ok(Self {
+            fingerprint: gating_contract::policy_fingerprint(&policy),
+            policy,
+            runner,
+            audit,
+        })
+    }
+
+    /// Evaluate `request`, under its `policy_override` if it has one, and
+    /// append its audit entry; a decision that cannot be audited is an error
+    fn evaluate(
+        &self,
+        request: &GatingRequest,
+    ) -> Result<gating_contract::GatingDecision, CliError> {
+        let overridden = match &request.policy_override {
+            Some(policy) => match policy.compile_errors().first() {
+                Some(error) => {
+                    return Err(CliError::new(
+                        ErrorKind::Config,
+                        format!("policy_override: {}", error),
+                    ))
+                }
+                None => Some(contract_runner(policy)),
+            },
+            None => None,
+        };
+        let runner = overridden.as_ref().unwrap_or(&self.runner);
+        let decision = runner.evaluate(request).map_err(|e| {
+            CliError::new(
+                ErrorKind::Internal,
+                format!("Error evaluating request: {}", e),
+            )
+        })?;
+        self.write_audit(runner, request, &decision)?;
+        Ok(decision)
+    }
+
+    /// Evaluate a proposal set and audit it as its summary request
+    pub(crate) fn evaluate_set(
+        &self,
+        request: &GatingSetRequest,
+    ) -> Result<gating_contract::GatingDecision, CliError> {
+        let decision = self.runner.evaluate_set(request).map_err(|e| {
+            CliError::new(
+                ErrorKind::Internal,
+                format!("Error evaluating request: {}", e),
+            )
+        })?;
+        self.write_audit(&self.runner, &request.summary_request(), &decision)?;
+        Ok(decision)
+    }
+
+    fn write_audit(
+        &self,
+        runner: &ContractRunner,
+        request: &GatingRequest,
+        decision: &gating_contract::GatingDecision,
+    ) -> Result<(), CliError> {
+        if let Some(audit) = &self.audit {
+            let entry = runner.audit(request, decision);
+            audit
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .write(entry)
+                .map_err(|e| {
+                    CliError::new(ErrorKind::Output, format!("Error writing audit log: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// The policy requests are evaluated against, swapped whole on reload
+type ActivePolicy = std::sync::RwLock<std::sync::Arc<ServedPolicy>>;
+
+fn active(policy: &ActivePolicy) -> std::sync::Arc<ServedPolicy> {
+    std::sync::Arc::clone(&policy.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Loads the default policy for `None`, a tenant's policy file otherwise
+pub(crate) type PolicyLoader<'a> = dyn Fn(Option<&Path>) -> Result<Policy, String> + Sync + 'a;
+
+/// A policy served under a name, with its own audit log and counters
+struct Tenant {
+    name: String,
+    /// `None` for the default policy, loaded through the CLI flags
+    policy_file: Option<PathBuf>,
+    audit_log: Option<PathBuf>,
+    policy: ActivePolicy,
+    /// Decisions made, by verdict
+    decisions: std::sync::Mutex<BTreeMap<String, u64>>,
+    /// Why the last reload failed, until one succeeds
+    reload_error: std::sync::Mutex<Option<String>>,
+}
+
+impl Tenant {
+    fn new(
+        name: &str,
+        policy: Policy,
+        policy_file: Option<PathBuf>,
+        audit_log: Option<PathBuf>,
+    ) -> Result<Self, String> {
+        let served = ServedPolicy::new(policy, audit_log.as_deref())
+            .map_err(|e| format!("tenant {}: {}", name, e))?;
+        Ok(Self {
+            name: name.to_string(),
+            policy_file,
+            audit_log,
+            policy: std::sync::RwLock::new(std::sync::Arc::new(served)),
+            decisions: std::sync::Mutex::new(BTreeMap::new()),
+            reload_error: std::sync::Mutex::new(None),
+        })
+    }
+
+    fn record(&self, verdict: Verdict) {
+        let verdict = format!("{:?}", verdict).to_lowercase();
+        *self
+            .decisions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(verdict)
+            .or_default() += 1;
+    }
+}
+
+/// What the accept loop and the workers share
+struct ServeState {
+    /// The default tenant first, then those in `routes` in order
+    tenants: Vec<Tenant>,
+    routes: TenantRegistry,
+    /// Credentials evaluation requests must present
+    auth: Option<SourceRegistry>,
+    max_body: u64,
+    /// Requests the queue holds before new ones are refused
+    capacity: usize,
+    /// Requests waiting in the queue
+    queued: AtomicUsize,
+}
+
+impl ServeState {
+    /// The tenant a request is gated by; see [`TenantRegistry::route`]
+    fn route(
+        &self,
+        named: Option<&str>,
+        repository: Option<&str>,
+        credential: Option<&SourceCredential>,
+    ) -> Result<&Tenant, CliError> {
+        match self.routes.route(named, repository, credential) {
+            Ok(name) => Ok(self
+                .tenants
+                .iter()
+                .find(|t| t.name == name)
+                .expect("invariant: every routed tenant is served")),
+            Err(RouteError::UnknownTenant(name)) => {
+                let hint = format!("Name a tenant from --tenants, or omit {}", TENANT_HEADER);
+                let message = format!("No tenant named {}", name);
+                Err(CliError::new(ErrorKind::Usage, message).with_hint(hint))
+            }
+            Err(e) => Err(CliError::new(ErrorKind::Auth, e.to_string())),
+        }
+    }
+
+    /// The next queued request, once a worker is free to take it
+    fn dequeue(
+        &self,
+        pending: &std::sync::Mutex<std::sync::mpsc::Receiver<tiny_http::Request>>,
+    ) -> Option<tiny_http::Request> {
+        let request = pending.lock().ok()?.recv().ok()?;
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+        Some(request)
+    }
+}
+
+/// A response the accept loop sends itself instead of queueing
+struct Answer {
+    request: tiny_http::Request,
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+}
+
+impl Answer {
+    fn send(self, reply: &[u8]) {
+        let headers: Vec<(&str, &str)> =
+            self.headers.iter().map(|(n, v)| (*n, v.as_str())).collect();
+        respond(self.request, self.status, reply, &headers);
+    }
+}
+
+/// The default tenant from `policy`, then each `--tenants` entry
+fn load_served_tenants(
+    policy: Policy,
+    options: &ServeOptions,
+    load: &PolicyLoader<'_>,
+) -> Result<Vec<Tenant>, String> {
+    let mut tenants = vec![Tenant::new(
+        DEFAULT_TENANT,
+        policy,
+        options.policy_file.clone(),
+        options.audit_log.clone(),
+    )?];
+    for config in &options.tenants.tenants {
+        let policy = load(Some(&config.policy_file))
+            .map_err(|e| format!("tenant {}: {}", config.name, e))?;
+        tenants.push(Tenant::new(
+            &config.name,
+            policy,
+            Some(config.policy_file.clone()),
+            config.audit_log.clone(),
+        )?);
+    }
+    Ok(tenants)
+}
+
+pub(crate) fn run_serve(policy: Policy, options: &ServeOptions, load: &PolicyLoader<'_>) -> i32 {
+    let tenants = match load_served_tenants(policy, options, load) {
+        Ok(t) => t,
+        Err(e) => return CliError::new(ErrorKind::Config, e).report(&OutputFormat::Text),
+    };
+    let server = match tiny_http::Server::http(&options.listen) {
+        Ok(s) => s,
+        Err(e) => {
+            return CliError::new(
+                ErrorKind::Config,
+                format!("Cannot listen on {}: {}", options.listen, e),
+            )
+            .report(&OutputFormat::Text)
+        }
+    };
+    eprintln!(
+        "Serving decisions on http://{} with {} worker(s)",
+        options.listen, options.workers
+    );
+    for tenant in &tenants[1..] {
+        eprintln!(
+            "Serving tenant {} from {}",
+            tenant.name,
+            active(&tenant.policy).policy.name
+        );
+    }
+
+    let state = ServeState {
+        tenants,
+        routes: options.tenants.clone(),
+        auth: options.auth.clone(),
+        max_body: options.max_body,
+        capacity: options.queue,
+        queued: AtomicUsize::new(0),
+    };
+    let (queue, pending) = std::sync::mpsc::sync_channel::<tiny_http::Request>(options.queue);
+    let pending = std::sync::Mutex::new(pending);
+    let state = &state;
+    std::thread::scope(|scope| {
+        scope.spawn(|| watch_policy(options, load, &state.tenants));
+        for _ in 0..options.workers {
+            scope.spawn(|| {
+                // Each worker reuses its buffers across requests
+                let mut body = Vec::new();
+                let mut reply = Vec::new();
+                while let Some(mut request) = state.dequeue(&pending) {
+                    let (status, headers) = handle(state, &mut request, &mut body, &mut reply);
+                    Answer {
+                        request,
+                        status,
+                        headers,
+                    }
+                    .send(&reply);
+                }
+            });
+        }
+
+        let mut reply = Vec::new();
+        for request in server.incoming_requests() {
+            reply.clear();
+            if let Some(answer) = accept(state, &queue, request, &mut reply) {
+                answer.send(&reply);
+            }
+        }
+        drop(queue);
+    });
+    0
+}
+
+/// Answer a probe, or queue `request` for the workers; `None` once queued
+///
+/// Probes are answered here, so a full queue cannot starve them. A request
+/// arriving at a full queue is refused with `429` and a `Retry-After`.
+fn accept(
+    state: &ServeState,
+    queue: &std::sync::mpsc::SyncSender<tiny_http::Request>,
+    request: tiny_http::Request,
+    reply: &mut Vec<u8>,
+) -> Option<Answer> {
+    if let Some((status, content_type)) = serve_probe(&request, state, reply) {
+        let headers = vec![("Content-Type", content_type.to_string())];
+        return Some(Answer {
+            request,
+            status,
+            headers,
+        });
+    }
+    // Counted before it is sent, so a worker never takes it uncounted
+    state.queued.fetch_add(1, Ordering::Relaxed);
+    let sent = queue.try_send(request);
+    if sent.is_err() {
+        state.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+    match sent {
+        Ok(()) => None,
+        Err(std::sync::mpsc::TrySendError::Full(request)) => {
+            let refusal = Refusal::rate_limited(state.capacity, SERVE_RETRY_AFTER_SECS);
+            let payload = serde_json::json!({ "refusal": refusal });
+            write_envelope(reply, Some(OutputSchema::Refusal), &payload);
+            let headers = vec![("Retry-After", SERVE_RETRY_AFTER_SECS.to_string())];
+            Some(Answer {
+                request,
+                status: 429,
+                headers,
+            })
+        }
+        Err(std::sync::mpsc::TrySendError::Disconnected(request)) => {
+            let error = CliError::new(ErrorKind::Internal, "No worker is running");
+            let status = serve_error(reply, 503, error);
+            Some(Answer {
+                request,
+                status,
+                headers: Vec::new(),
+            })
+        }
+    }
+}
+
+/// Reload every tenant's policy on SIGHUP, and with `--watch` a tenant's
+/// whenever its policy file's modification time changes
+fn watch_policy(options: &ServeOptions, load: &PolicyLoader<'_>, tenants: &[Tenant]) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    let hangup = std::sync::Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    if let Err(e) =
+        signal_hook::flag::register(signal_hook::consts::SIGHUP, std::sync::Arc::clone(&hangup))
+    {
+        eprintln!("SIGHUP will not reload the policy: {}", e);
+    }
+    let modified = |tenant: &Tenant| {
+        let path = tenant.policy_file.as_deref().filter(|_| options.watch)?;
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    };
+    let mut seen: Vec<_> = tenants.iter().map(modified).collect();
+
+    loop {
+        std::thread::sleep(SERVE_RELOAD_POLL);
+        let hung_up = hangup.swap(false, Ordering::Relaxed);
+        for (tenant, seen) in tenants.iter().zip(&mut seen) {
+            let now = modified(tenant);
+            let changed = now != *seen;
+            *seen = now;
+            if !hung_up && !changed {
+                continue;
+            }
+            let label = match tenant.name.as_str() {
+                DEFAULT_TENANT => String::new(),
+                name => format!(" for tenant {}", name),
+            };
+            match reload_policy(load, tenant) {
+                Ok(Some(change)) => eprintln!(
+                    "Reloaded policy \"{}\"{}: {} -> {}",
+                    change.policy_name, label, change.previous_fingerprint, change.fingerprint
+                ),
+                Ok(None) => eprintln!("Policy unchanged{}, nothing reloaded", label),
+                Err(e) => eprintln!(
+                    "Policy reload failed{}, still serving the previous policy: {}",
+                    label, e
+                ),
+            }
+        }
+    }
+}
+
+/// Load a tenant's policy again and swap it in if it compiles, recording
+/// the change in its audit log; the current policy keeps serving on error,
+/// and the tenant reports not ready until a reload succeeds
+fn reload_policy(load: &PolicyLoader<'_>, tenant: &Tenant) -> Result<Option<PolicyChange>, String> {
+    let reloaded = swap_policy(load, tenant);
+    *tenant
+        .reload_error
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = reloaded.as_ref().err().cloned();
+    reloaded
+}
+
+fn swap_policy(load: &PolicyLoader<'_>, tenant: &Tenant) -> Result<Option<PolicyChange>, String> {
+    let policy = &tenant.policy;
+    let loaded = load(
+        tenant
+            .policy_file
+            .as_deref()
+            .filter(|_| tenant.name != DEFAULT_TENANT),
+    )?;
+    let Some(change) = PolicyChange::between(&active(policy).policy, &loaded) else {
+        return Ok(None);
+    };
+    let served = ServedPolicy::new(loaded, tenant.audit_log.as_deref())?;
+    if let Some(audit) = &served.audit {
+        audit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .write_policy_change(&change)
+            .map_err(|e| format!("Error writing audit log: {}", e))?;
+    }
+    *policy.write().unwrap_or_else(|e| e.into_inner()) = std::sync::Arc::new(served);
+    Ok(Some(change))
+}
+
+/// Answer a health, readiness, build-info or metrics probe with its
+/// status and content type; `None` for any other request
+///
+/// Readiness runs the `conative doctor` policy checks against every
+/// tenant's policy: its rules compile and its SLM model files are present.
+/// A server whose queue is full, or whose last reload of any policy failed,
+/// is not ready either.
+fn serve_probe(
+    request: &tiny_http::Request,
+    state: &ServeState,
+    reply: &mut Vec<u8>,
+) -> Option<(u16, &'static str)> {
+    const JSON: &str = "application/json";
+    if request.method() != &tiny_http::Method::Get {
+        return None;
+    }
+    let tenants = &state.tenants;
+    let default = active(&tenants[0].policy);
+    match request.url() {
+        "/healthz" => {
+            write_envelope(reply, None, &serde_json::json!({ "status": "ok" }));
+            Some((200, JSON))
+        }
+        "/readyz" => {
+            let queued = state.queued.load(Ordering::Relaxed);
+            // A queue of 0 hands requests straight to idle workers
+            let mut checks = vec![if queued < state.capacity.max(1) {
+                let detail = format!("{} of {} queued", queued, state.capacity);
+                DoctorCheck::new("queue", DoctorStatus::Ok, detail)
+            } else {
+                let detail = format!("full with {} queued", queued);
+                DoctorCheck::new("queue", DoctorStatus::Fail, detail)
+            }];
+            for tenant in tenants {
+                let start = checks.len();
+                policy_checks(&active(&tenant.policy).policy, &mut checks);
+                let reload_error = tenant
+                    .reload_error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                if let Some(error) = reload_error.as_ref() {
+                    let detail = format!("reload failed, serving the previous policy: {}", error);
+                    checks.push(DoctorCheck::new("reload", DoctorStatus::Fail, detail));
+                }
+                if tenant.name != DEFAULT_TENANT {
+                    for check in &mut checks[start..] {
+                        check.name = format!("{}:{}", tenant.name, check.name);
+                    }
+                }
+            }
+            let ready = !checks
+                .iter()
+                .any(|c| matches!(c.status, DoctorStatus::Fail));
+            write_envelope(
+                reply,
+                Some(OutputSchema::Doctor),
+                &serde_json::json!({ "checks": checks }),
+            );
+            Some((if ready { 200 } else { 503 }, JSON))
+        }
+        "/buildinfo" => {
+            let mut info = serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "git_sha": option_env!("CONATIVE_GIT_SHA"),
+                "policy_name": default.policy.name,
+                "policy_fingerprint": default.fingerprint,
+            });
+            if tenants.len() > 1 {
+                let named: Vec<serde_json::Value> = tenants[1..]
+                    .iter()
+                    .map(|tenant| {
+                        let served = active(&tenant.policy);
+                        serde_json::json!({
+                            "name": tenant.name,
+                            "policy_name": served.policy.name,
+                            "policy_fingerprint": served.fingerprint,
+                        })
+                    })
+                    .collect();
+                info["tenants"] = serde_json::json!(named);
+            }
+            write_envelope(reply, Some(OutputSchema::BuildInfo), &info);
+            Some((200, JSON))
+        }
+        "/metrics" => {
+            use std::io::Write;
+            let _ = writeln!(
+                reply,
+                "# HELP conative_decisions_total Gating decisions made, by tenant and verdict"
+            );
+            let _ = writeln!(reply, "# TYPE conative_decisions_total counter");
+            for tenant in tenants {
+                for (verdict, count) in tenant
+                    .decisions
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                {
+                    let _ = writeln!(
+                        reply,
+                        "conative_decisions_total{{tenant=\"{}\",verdict=\"{}\"}} {}",
+                        tenant.name, verdict, count
+                    );
+                }
+            }
+            Some((200, "text/plain; version=0.0.4"))
+        }
+        _ => None,
+    }
+}
+
+/// Answer a queued request into `reply`, returning its status and any
+/// headers beyond the content type
+fn handle(
+    state: &ServeState,
+    request: &mut tiny_http::Request,
+    body: &mut Vec<u8>,
+    reply: &mut Vec<u8>,
+) -> (u16, Vec<(&'static str, String)>) {
+    body.clear();
+    reply.clear();
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    // Senders are checked before their bodies are read
+    let credential = match state
+        .auth
+        .as_ref()
+        .map(|registry| registry.authenticate(bearer_token(request)))
+        .transpose()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            let error = CliError::new(ErrorKind::Auth, e.to_string());
+            let status = serve_error(reply, e.status(), error);
+            return (status, vec![("WWW-Authenticate", "Bearer".to_string())]);
+        }
+    };
+    let named = header(request, TENANT_HEADER).map(str::to_string);
+    let named = named.as_deref();
+    let status = match (request.method(), path.as_str()) {
+        (tiny_http::Method::Post, "/v1/evaluate") => {
+            match read_body(request, state.max_body, body) {
+                Ok(()) => serve_evaluate(state, named, credential, body, reply),
+                Err((status, error)) => serve_error(reply, status, error),
+            }
+        }
+        (tiny_http::Method::Post, "/v1/admission") => {
+            match read_body(request, state.max_body, body) {
+                Ok(()) => serve_admission(state, named, credential, body, reply),
+                Err((status, error)) => serve_error(reply, status, error),
+            }
+        }
+        (
+            method,
+            "/healthz" | "/readyz" | "/buildinfo" | "/metrics" | "/v1/evaluate" | "/v1/admission",
+        ) => {
+            let message = format!("{} is not allowed on {}", method, path);
+            serve_error(reply, 405, CliError::new(ErrorKind::Usage, message))
+        }
+        _ => serve_error(
+            reply,
+            404,
+            CliError::new(ErrorKind::Usage, format!("No endpoint at {}", path)),
+        ),
+    };
+    (status, Vec::new())
+}
+
+pub(crate) fn header<'a>(request: &'a tiny_http::Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+/// API key sent as `Authorization: Bearer <key>`
+fn bearer_token(request: &tiny_http::Request) -> Option<&str> {
+    header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Read a request body of at most `max_body` bytes into `body`
+pub(crate) fn read_body(
+    request: &mut tiny_http::Request,
+    max_body: u64,
+    body: &mut Vec<u8>,
+) -> Result<(), (u16, CliError)> {
+    use std::io::Read;
+    let too_large = || {
+        let error = CliError::new(
+            ErrorKind::Input,
+            format!("Request body exceeds {} bytes", max_body),
+        )
+        .with_hint("Split the proposal, or raise --max-body");
+        (413, error)
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > max_body)
+    {
+        return Err(too_large());
+    }
+    request
+        .as_reader()
+        .take(max_body + 1)
+        .read_to_end(body)
+        .map_err(|e| {
+            (
+                400,
+                CliError::new(ErrorKind::Input, format!("Failed to read request: {}", e)),
+            )
+        })?;
+    if body.len() as u64 > max_body {
+        return Err(too_large());
+    }
+    Ok(())
+}
+
+/// Evaluate a GatingRequest body against its tenant's policy and write
+/// its decision
+///
+/// Without a credential registry no sender may override the policy.
+fn serve_evaluate(
+    state: &ServeState,
+    named: Option<&str>,
+    credential: Option<&SourceCredential>,
+    body: &[u8],
+    reply: &mut Vec<u8>,
+) -> u16 {
+    let mut request = match serde_json::from_slice::<GatingRequest>(body) {
+        Ok(r) => r,
+        Err(e) => {
+            let error = CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to parse request JSON: {}", e),
+            );
+            return serve_error(reply, 400, error);
+        }
+    };
+    let authorized = match credential {
+        Some(credential) => credential
+            .authorize(&mut request)
+            .map_err(|e| e.to_string()),
+        None if request.policy_override.is_some() => {
+            Err("policy_override needs a credential".to_string())
+        }
+        None => Ok(()),
+    };
+    if let Err(message) = authorized {
+        let mut error = CliError::new(ErrorKind::Auth, message);
+        if request.policy_override.is_some() {
+            error = error.with_hint(
+                "Send overrides with a credential holding the policy_override capability",
+            );
+        }
+        return serve_error(reply, 403, error);
+    }
+    let repository = request.context.repository.as_ref().map(|r| r.name.as_str());
+    let tenant = match state.route(named, repository, credential) {
+        Ok(t) => t,
+        Err(error) => return unrouted(reply, error),
+    };
+    match active(&tenant.policy).evaluate(&request) {
+        Ok(decision) => {
+            tenant.record(decision.verdict);
+            write_envelope(reply, Some(OutputSchema::Decision), &decision);
+            200
+        }
+        Err(error) => {
+            let status = if matches!(error.code, ErrorKind::Config) {
+                400
+            } else {
+                500
+            };
+            serve_error(reply, status, error)
+        }
+    }
+}
+
+/// Answer an AdmissionReview body in kind, for a Kubernetes validating
+/// webhook; reviews go to the named tenant or the default policy
+fn serve_admission(
+    state: &ServeState,
+    named: Option<&str>,
+    credential: Option<&SourceCredential>,
+    body: &[u8],
+    reply: &mut Vec<u8>,
+) -> u16 {
+    if let Some(Err(e)) = credential.map(|c| c.require(Capability::Evaluate)) {
+        return refuse(reply, e);
+    }
+    let tenant = match state.route(named, None, credential) {
+        Ok(t) => t,
+        Err(error) => return unrouted(reply, error),
+    };
+    let review = match serde_json::from_slice::<AdmissionReview>(body) {
+        Ok(r) => r,
+        Err(e) => {
+            let error = CliError::new(
+                ErrorKind::Parse,
+                format!("Failed to parse AdmissionReview: {}", e),
+            );
+            return serve_error(reply, 400, error);
+        }
+    };
+    let Some(request) = &review.request else {
+        return serve_error(
+            reply,
+            400,
+            CliError::new(ErrorKind::Input, "AdmissionReview has no request"),
+        );
+    };
+    let mut set = request.gating_request();
+    if let (Some(credential), Some(set)) = (credential, set.as_mut()) {
+        if let Err(e) = credential.authorize_set(set) {
+            return refuse(reply, e);
+        }
+    }
+    let served = active(&tenant.policy);
+    let decision = match set.map(|set| served.evaluate_set(&set)).transpose() {
+        Ok(d) => d,
+        Err(error) => return serve_error(reply, 500, error),
+    };
+    if let Some(decision) = &decision {
+        tenant.record(decision.verdict);
+    }
+    match review.reply(decision.as_ref()) {
+        Ok(answer) => {
+            write_envelope(reply, None, &answer);
+            200
+        }
+        Err(e) => serve_error(reply, 400, CliError::new(ErrorKind::Input, e.to_string())),
+    }
+}
+
+/// Answer a sender that may not make the request
+fn refuse(reply: &mut Vec<u8>, error: AuthError) -> u16 {
+    let status = error.status();
+    serve_error(
+        reply,
+        status,
+        CliError::new(ErrorKind::Auth, error.to_string()),
+    )
+}
+
+/// Answer a request no tenant may gate: `404` for an unknown tenant,
+/// `403` for one the sender may not use
+fn unrouted(reply: &mut Vec<u8>, error: CliError) -> u16 {
+    let status = if matches!(error.code, ErrorKind::Auth) {
+        403
+    } else {
+        404
+    };
+    serve_error(reply, status, error)
+}
+
+/// Write `error` in its JSON envelope and pass `status` through
+pub(crate) fn serve_error(reply: &mut Vec<u8>, status: u16, error: CliError) -> u16 {
+    write_envelope(
+        reply,
+        Some(OutputSchema::Error),
+        &serde_json::json!({ "error": &error }),
+    );
+    status
+}
+
+/// Write `payload` as JSON, under `schema` when it has one
+pub(crate) fn write_envelope<T: serde::Serialize>(
+    reply: &mut Vec<u8>,
+    schema: Option<OutputSchema>,
+    payload: &T,
+) {
+    let written = match schema {
+        Some(schema) => serde_json::to_writer(
+            &mut *reply,
+            &Envelope {
+                schema: schema.id(),
+                payload,
+            },
+        ),
+        None => serde_json::to_writer(&mut *reply, payload),
+    };
+    written.expect("invariant: JSON serialization of struct cannot fail");
+}
+
+/// Send `reply`, as JSON unless `headers` set a Content-Type
+pub(crate) fn respond(
+    request: tiny_http::Request,
+    status: u16,
+    reply: &[u8],
+    headers: &[(&str, &str)],
+) {
+    let typed = headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("Content-Type"));
+    let headers = [("Content-Type", "application/json")]
+        .iter()
+        .filter(|_| !typed)
+        .chain(headers)
+        .map(|(name, value)| {
+            tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes())
+                .expect("invariant: header is ASCII")
+        })
+        .collect();
+    let response = tiny_http::Response::new(status.into(), headers, reply, Some(reply.len()), None);
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to send response: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use policy_oracle::{ActionType, Proposal};
+
+    fn state(max_body: u64, capacity: usize) -> ServeState {
+        let tenant = Tenant::new(DEFAULT_TENANT, Policy::rsr_default(), None, None).unwrap();
+        ServeState {
+            tenants: vec![tenant],
+            routes: TenantRegistry::default(),
+            auth: None,
+            max_body,
+            capacity,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Status and checks `/readyz` answers with
+    fn readiness(state: &ServeState) -> (u16, Vec<(String, String)>) {
+        let mut reply = Vec::new();
+        let probe = request(tiny_http::Method::Get, "/readyz", String::new());
+        let (status, _) = serve_probe(&probe, state, &mut reply).unwrap();
+        let reply: serde_json::Value = serde_json::from_slice(&reply).unwrap();
+        let checks = reply["checks"].as_array().unwrap().iter();
+        let checks = checks.map(|c| {
+            (
+                c["name"].as_str().unwrap().to_string(),
+                c["status"].as_str().unwrap().to_string(),
+            )
+        });
+        (status, checks.collect())
+    }
+
+    fn evaluate_body(path: &str, content: &str) -> String {
+        let proposal = Proposal {
+            id: uuid::Uuid::new_v4(),
+            action_type: ActionType::CreateFile {
+                path: path.to_string(),
+            },
+            content: content.to_string(),
+            files_affected: vec![path.to_string()],
+            llm_confidence: 0.9,
+        };
+        serde_json::to_string(&GatingRequest::new(proposal)).unwrap()
+    }
+
+    fn request(method: tiny_http::Method, path: &str, body: String) -> tiny_http::Request {
+        tiny_http::TestRequest::new()
+            .with_method(method)
+            .with_path(path)
+            .with_body(Box::leak(body.into_boxed_str()))
+            .into()
+    }
+
+    fn post(path: &str, body: String) -> tiny_http::Request {
+        request(tiny_http::Method::Post, path, body)
+    }
+
+    /// POST sent with `Authorization: Bearer <key>` and `headers`
+    fn post_with(
+        path: &str,
+        body: String,
+        key: &str,
+        headers: &[(&str, &str)],
+    ) -> tiny_http::Request {
+        let authorization = format!("Bearer {}", key);
+        let mut request = tiny_http::TestRequest::new()
+            .with_method(tiny_http::Method::Post)
+            .with_path(path)
+            .with_body(Box::leak(body.into_boxed_str()));
+        for (name, value) in [("Authorization", authorization.as_str())]
+            .iter()
+            .chain(headers)
+        {
+            request = request.with_header(tiny_http::Header::from_bytes(*name, *value).unwrap());
+        }
+        request.into()
+    }
+
+    fn post_with_key(path: &str, body: String, key: &str) -> tiny_http::Request {
+        post_with(path, body, key, &[])
+    }
+
+    fn admission_body() -> String {
+        serde_json::json!({
+            "apiVersion": "admission.k8s.io/v1",
+            "kind": "AdmissionReview",
+            "request": {
+                "uid": "uid-1",
+                "kind": { "group": "", "version": "v1", "kind": "ConfigMap" },
+                "name": "app",
+                "namespace": "prod",
+                "operation": "CREATE",
+                "object": { "kind": "ConfigMap", "data": { "mode": "strict" } }
+            }
+        })
+        .to_string()
+    }
+
+    fn with_registry(mut state: ServeState) -> ServeState {
+        let credential = |name: &str, source: &str, capabilities: serde_json::Value| {
+            serde_json::json!({
+                "name": name,
+                "key_sha256": gating_contract::api_key_digest(&format!("{}-key", name)),
+                "source": source,
+                "capabilities": capabilities,
+            })
+        };
+        let registry = serde_json::json!({ "credentials": [
+            credential("agent", "claude-code", serde_json::json!(["evaluate"])),
+            credential("kube", "admission", serde_json::json!(["evaluate"])),
+            credential("overrider", "claude-code", serde_json::json!(["policy_override"])),
+        ]});
+        let mut registry: SourceRegistry = serde_json::from_value(registry).unwrap();
+        let mut web = registry.credentials[0].clone();
+        web.name = "web".to_string();
+        web.key_sha256 = gating_contract::api_key_digest("web-key");
+        web.tenants = vec!["web".to_string()];
+        registry.credentials.push(web);
+        state.auth = Some(registry);
+        state
+    }
+
+    /// Status and JSON reply a worker gives `request`
+    fn run(state: &ServeState, mut request: tiny_http::Request) -> (u16, serde_json::Value) {
+        let (mut body, mut reply) = (Vec::new(), Vec::new());
+        let (status, _) = handle(state, &mut request, &mut body, &mut reply);
+        (status, serde_json::from_slice(&reply).unwrap())
+    }
+
+    #[test]
+    fn test_evaluate_answers_with_decision() {
+        let state = state(1 << 20, 4);
+        let body = evaluate_body("src/app.ts", "export const x = 1;");
+        let (status, reply) = run(&state, post("/v1/evaluate", body));
+        assert_eq!(status, 200);
+        assert_eq!(reply["schema"], "conative-decision-v1");
+        assert_eq!(reply["verdict"], "Block");
+
+        let body = evaluate_body("src/lib.rs", "pub fn f() {}");
+        let (status, reply) = run(&state, post("/v1/evaluate", body));
+        assert_eq!((status, reply["verdict"].as_str()), (200, Some("Allow")));
+    }
+
+    #[test]
+    fn test_oversized_body_is_refused_with_413() {
+        let state = state(64, 4);
+        let body = evaluate_body("src/lib.rs", &"x".repeat(256));
+        let (status, reply) = run(&state, post("/v1/evaluate", body));
+        assert_eq!(status, 413);
+        assert_eq!(reply["error"]["code"], "input");
+        assert!(reply["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("exceeds 64 bytes"));
+
+        // The limit applies to every endpoint that reads a body
+        let (status, _) = run(&state, post("/v1/admission", "x".repeat(65)));
+        assert_eq!(status, 413);
+    }
+
+    #[test]
+    fn test_unknown_endpoints_and_methods_are_refused() {
+        let state = state(1 << 20, 4);
+        let (status, _) = run(&state, post("/v2/evaluate", String::new()));
+        assert_eq!(status, 404);
+        let get = request(tiny_http::Method::Get, "/v1/evaluate", String::new());
+        let (status, reply) = run(&state, get);
+        assert_eq!(status, 405);
+        assert_eq!(reply["error"]["code"], "usage");
+        let (status, reply) = run(&state, post("/v1/evaluate", "{".to_string()));
+        assert_eq!(
+            (status, reply["error"]["code"].as_str()),
+            (400, Some("parse"))
+        );
+    }
+
+    #[test]
+    fn test_full_queue_is_refused_with_429() {
+        let state = state(1 << 20, 1);
+        let (queue, pending) = std::sync::mpsc::sync_channel(state.capacity);
+        let mut reply = Vec::new();
+        let body = || evaluate_body("src/lib.rs", "pub fn f() {}");
+        assert!(accept(&state, &queue, post("/v1/evaluate", body()), &mut reply).is_none());
+
+        let answer = accept(&state, &queue, post("/v1/evaluate", body()), &mut reply).unwrap();
+        assert_eq!(answer.status, 429);
+        assert_eq!(answer.headers, vec![("Retry-After", "1".to_string())]);
+        let refusal: serde_json::Value = serde_json::from_slice(&reply).unwrap();
+        assert_eq!(refusal["schema"], "conative-refusal-v1");
+        assert_eq!(refusal["refusal"]["category"], "RateLimited");
+
+        // Probes are answered ahead of the full queue
+        reply.clear();
+        let probe = request(tiny_http::Method::Get, "/healthz", String::new());
+        let answer = accept(&state, &queue, probe, &mut reply).unwrap();
+        assert_eq!(answer.status, 200);
+
+        // A worker taking the queued request frees its place
+        let pending = std::sync::Mutex::new(pending);
+        let mut queued = state.dequeue(&pending).unwrap();
+        assert!(accept(&state, &queue, post("/v1/evaluate", body()), &mut reply).is_none());
+        let (mut body, mut reply) = (Vec::new(), Vec::new());
+        let (status, _) = handle(&state, &mut queued, &mut body, &mut reply);
+        assert_eq!(status, 200);
+
+        // With no worker left, requests are refused rather than queued
+        drop(pending);
+        let answer = accept(
+            &state,
+            &queue,
+            post("/v1/evaluate", String::new()),
+            &mut reply,
+        );
+        assert_eq!(answer.unwrap().status, 503);
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_serving_the_previous_policy() {
+        let dir = std::env::temp_dir().join(format!("conative-reload-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("policy.json");
+        let log = dir.join("audit.jsonl");
+        let write = |policy: &Policy| std::fs::write(&file, serde_json::to_string(policy).unwrap());
+        write(&Policy::rsr_default()).unwrap();
+        let load = |path: Option<&Path>| crate::load_policy(path);
+        let policy = load(Some(&file)).unwrap();
+        let tenant = Tenant::new("web", policy, Some(file.clone()), Some(log.clone())).unwrap();
+        let before = active(&tenant.policy);
+        assert!(reload_policy(&load, &tenant).unwrap().is_none());
+
+        // Neither a file that does not parse nor rules that do not compile
+        // replace the served policy
+        std::fs::write(&file, "{ \"name\": ").unwrap();
+        assert!(reload_policy(&load, &tenant).is_err());
+        let mut broken = Policy::rsr_default();
+        broken.patterns.forbidden_patterns[0].regex = "(unclosed".to_string();
+        write(&broken).unwrap();
+        let error = reload_policy(&load, &tenant).unwrap_err();
+        assert!(error.starts_with("pattern:hardcoded_secrets: "));
+        assert!(std::sync::Arc::ptr_eq(&before, &active(&tenant.policy)));
+        let request: GatingRequest =
+            serde_json::from_str(&evaluate_body("src/app.ts", "")).unwrap();
+        let decision = active(&tenant.policy).evaluate(&request).unwrap();
+        assert_eq!(decision.verdict, Verdict::Block);
+
+        // A valid change is swapped in and recorded
+        let mut renamed = Policy::rsr_default();
+        renamed.name = "Renamed".to_string();
+        write(&renamed).unwrap();
+        let change = reload_policy(&load, &tenant).unwrap().unwrap();
+        assert_eq!(change.previous_fingerprint, before.fingerprint);
+        assert_eq!(active(&tenant.policy).policy.name, "Renamed");
+        let audit = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            audit
+                .lines()
+                .filter(|l| l.contains("\"policy_change\""))
+                .count(),
+            1
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_healthz_and_readyz() {
+        let state = state(1 << 20, 1);
+        let mut reply = Vec::new();
+        let probe = request(tiny_http::Method::Get, "/healthz", String::new());
+        assert_eq!(
+            serve_probe(&probe, &state, &mut reply),
+            Some((200, "application/json"))
+        );
+        assert_eq!(reply, br#"{"status":"ok"}"#);
+        let post = post("/healthz", String::new());
+        assert_eq!(serve_probe(&post, &state, &mut reply), None);
+
+        let (status, checks) = readiness(&state);
+        assert_eq!(status, 200);
+        assert_eq!(checks[0], ("queue".to_string(), "ok".to_string()));
+        assert!(checks.iter().any(|(name, _)| name == "rules"));
+        assert!(checks.iter().all(|(_, status)| status != "fail"));
+    }
+
+    #[test]
+    fn test_not_ready_while_the_queue_is_full() {
+        let state = state(1 << 20, 1);
+        let (queue, pending) = std::sync::mpsc::sync_channel(state.capacity);
+        let pending = std::sync::Mutex::new(pending);
+        let body = evaluate_body("src/lib.rs", "pub fn f() {}");
+        let mut reply = Vec::new();
+        assert!(accept(&state, &queue, post("/v1/evaluate", body), &mut reply).is_none());
+        let (status, checks) = readiness(&state);
+        assert_eq!(status, 503);
+        assert_eq!(checks[0], ("queue".to_string(), "fail".to_string()));
+
+        state.dequeue(&pending).unwrap();
+        assert_eq!(readiness(&state).0, 200);
+    }
+
+    #[test]
+    fn test_not_ready_while_a_policy_reload_is_failing() {
+        let mut state = state(1 << 20, 4);
+        let policy = Policy::rsr_default();
+        let tenant = Tenant::new("web", policy, Some(PathBuf::from("web.json")), None).unwrap();
+        state.tenants.push(tenant);
+        let fail = |_: Option<&Path>| Err("web.json: expected value".to_string());
+        assert!(reload_policy(&fail, &state.tenants[1]).is_err());
+        let (status, checks) = readiness(&state);
+        assert_eq!(status, 503);
+        assert!(checks.contains(&("web:reload".to_string(), "fail".to_string())));
+
+        // The next good reload makes the tenant ready again
+        let load = |_: Option<&Path>| Ok(Policy::rsr_default());
+        assert!(reload_policy(&load, &state.tenants[1]).unwrap().is_none());
+        assert_eq!(readiness(&state).0, 200);
+    }
+
+    #[test]
+    fn test_credentials_are_checked_on_every_evaluation_endpoint() {
+        let state = with_registry(state(1 << 20, 4));
+        let evaluate = || evaluate_body("src/lib.rs", "pub fn f() {}");
+
+        let mut unsigned = post("/v1/evaluate", evaluate());
+        let (mut body, mut reply) = (Vec::new(), Vec::new());
+        let (status, headers) = handle(&state, &mut unsigned, &mut body, &mut reply);
+        assert_eq!(status, 401);
+        assert_eq!(headers, vec![("WWW-Authenticate", "Bearer".to_string())]);
+        let (status, reply) = run(
+            &state,
+            post_with_key("/v1/admission", admission_body(), "guess"),
+        );
+        assert_eq!(
+            (status, reply["error"]["code"].as_str()),
+            (401, Some("auth"))
+        );
+
+        // A key without the evaluate capability is refused on both endpoints
+        for (path, body) in [
+            ("/v1/evaluate", evaluate()),
+            ("/v1/admission", admission_body()),
+        ] {
+            let (status, reply) = run(&state, post_with_key(path, body, "overrider-key"));
+            assert_eq!(status, 403, "{}", path);
+            assert!(reply["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("lacks the Evaluate capability"));
+        }
+
+        let (status, _) = run(
+            &state,
+            post_with_key("/v1/evaluate", evaluate(), "agent-key"),
+        );
+        assert_eq!(status, 200);
+        let (status, reply) = run(
+            &state,
+            post_with_key("/v1/admission", admission_body(), "kube-key"),
+        );
+        assert_eq!(status, 200);
+        assert_eq!(reply["response"]["allowed"], true);
+        // Admission reviews speak for the "admission" source only
+        let (status, _) = run(
+            &state,
+            post_with_key("/v1/admission", admission_body(), "agent-key"),
+        );
+        assert_eq!(status, 403);
+    }
+
+    #[test]
+    fn test_tenants_are_routed_audited_and_counted_apart() {
+        let dir = std::env::temp_dir().join(format!("conative-tenants-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut routes: TenantRegistry = serde_json::from_value(serde_json::json!({
+            "tenants": [{
+                "name": "web",
+                "policy_file": "web.json",
+                "repositories": ["acme/web-*"],
+                "audit_log": "web.jsonl"
+            }]
+        }))
+        .unwrap();
+        routes.resolve_paths(&dir);
+        let mut lenient = Policy::rsr_default();
+        lenient.languages.forbidden.clear();
+        let config = &routes.tenants[0];
+        let web = Tenant::new("web", lenient, None, config.audit_log.clone()).unwrap();
+        let mut state = with_registry(state(1 << 20, 4));
+        let default_log = dir.join("default.jsonl");
+        state.tenants[0] = Tenant::new(
+            DEFAULT_TENANT,
+            Policy::rsr_default(),
+            None,
+            Some(default_log.clone()),
+        )
+        .unwrap();
+        state.tenants.push(web);
+        state.routes = routes;
+
+        let typescript = |repository: &str| {
+            let mut request: GatingRequest =
+                serde_json::from_str(&evaluate_body("src/app.ts", "export const x = 1;")).unwrap();
+            request.context.repository = Some(gating_contract::RepositoryContext {
+                name: repository.to_string(),
+                default_branch: None,
+                branch: None,
+                policy_file: None,
+                root: None,
+                is_new: false,
+            });
+            serde_json::to_string(&request).unwrap()
+        };
+        let evaluate = |body: String, key: &str, tenant: Option<&str>| {
+            let headers: Vec<_> = tenant.map(|t| (TENANT_HEADER, t)).into_iter().collect();
+            let (status, reply) = run(&state, post_with("/v1/evaluate", body, key, &headers));
+            (status, reply["verdict"].as_str().map(str::to_string))
+        };
+        let allow = (200, Some("Allow".to_string()));
+        let block = (200, Some("Block".to_string()));
+
+        // Routed by repository, else by the tenant header
+        assert_eq!(
+            evaluate(typescript("acme/web-shop"), "agent-key", None),
+            allow
+        );
+        assert_eq!(evaluate(typescript("acme/api"), "agent-key", None), block);
+        assert_eq!(
+            evaluate(typescript("acme/api"), "agent-key", Some("web")),
+            allow
+        );
+        assert_eq!(
+            evaluate(typescript("acme/api"), "agent-key", Some("ops")),
+            (404, None)
+        );
+
+        // A credential scoped to web can use no other tenant's policy, even
+        // when its request matches none
+        assert_eq!(
+            evaluate(typescript("acme/web-shop"), "web-key", None),
+            allow
+        );
+        assert_eq!(
+            evaluate(typescript("acme/api"), "web-key", None),
+            (403, None)
+        );
+        assert_eq!(
+            evaluate(typescript("acme/api"), "web-key", Some(DEFAULT_TENANT)),
+            (403, None)
+        );
+        let review = post_with("/v1/admission", admission_body(), "web-key", &[]);
+        assert_eq!(run(&state, review).0, 403);
+
+        let audited = |log: &Path| std::fs::read_to_string(log).unwrap().lines().count();
+        assert_eq!(audited(state.tenants[1].audit_log.as_ref().unwrap()), 3);
+        assert_eq!(audited(&default_log), 1);
+
+        let mut reply = Vec::new();
+        let probe = request(tiny_http::Method::Get, "/metrics", String::new());
+        let (status, content_type) = serve_probe(&probe, &state, &mut reply).unwrap();
+        assert_eq!((status, content_type), (200, "text/plain; version=0.0.4"));
+        let metrics = String::from_utf8(reply).unwrap();
+        assert!(metrics.contains("conative_decisions_total{tenant=\"web\",verdict=\"allow\"} 3\n"));
+        assert!(
+            metrics.contains("conative_decisions_total{tenant=\"default\",verdict=\"block\"} 1\n")
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
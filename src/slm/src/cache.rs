@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Persistent inference cache
+//!
+//! Entries are keyed by a SHA-256 over the backend name, the model
+//! fingerprint, the context and the content, so swapping weights misses
+//! naturally. Each entry is one JSON file; expired or unreadable entries
+//! are treated as misses.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::SlmEvaluation;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Seconds since the Unix epoch
+    created_at: u64,
+    model_fingerprint: Option<String>,
+    evaluation: SlmEvaluation,
+}
+
+/// On-disk cache of backend evaluations
+pub struct InferenceCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl InferenceCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Builder: how long entries stay valid
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cache key for one evaluation
+    pub fn key(backend: &str, fingerprint: Option<&str>, content: &str, context: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [backend, fingerprint.unwrap_or("unpinned"), context, content] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Cached evaluation for `key`, if present, fresh and from the same model
+    pub fn get(&self, key: &str, fingerprint: Option<&str>) -> Option<SlmEvaluation> {
+        let path = self.path(key);
+        let entry: CacheEntry = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())?;
+
+        let expired = now_secs().saturating_sub(entry.created_at) >= self.ttl.as_secs();
+        if expired || entry.model_fingerprint.as_deref() != fingerprint {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(entry.evaluation)
+    }
+
+    /// Store an evaluation; failures are logged, never fatal
+    pub fn put(&self, key: &str, fingerprint: Option<&str>, evaluation: &SlmEvaluation) {
+        let entry = CacheEntry {
+            created_at: now_secs(),
+            model_fingerprint: fingerprint.map(str::to_string),
+            evaluation: evaluation.clone(),
+        };
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&tmp, serde_json::to_vec(&entry)?))
+            .and_then(|_| std::fs::rename(&tmp, &path));
+        if let Err(e) = result {
+            tracing::warn!("Could not write SLM cache entry {}: {}", path.display(), e);
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Splitting proposals that exceed the model's context window
+//!
+//! Content is cut at structural boundaries first: a top-level (unindented)
+//! line that follows a blank line or a closing `}` / `end`. Sections are
+//! packed greedily into chunks; a section that is still too large is split
+//! on line boundaries.
+
+use serde::{Deserialize, Serialize};
+
+/// How per-chunk scores become one score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkAggregate {
+    /// Worst chunk decides (a single bad section is enough)
+    #[default]
+    Max,
+    /// Average over chunks
+    Mean,
+}
+
+/// A slice of a proposal, with 1-based inclusive line numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub index: usize,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub text: String,
+}
+
+/// Score of the chunk that scored worst, reported as evidence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkScore {
+    /// 0-based chunk index
+    pub index: usize,
+    /// Total number of chunks evaluated
+    pub of: usize,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub spirit_score: f64,
+}
+
+/// Split `content` into chunks of at most `max_chars` bytes
+pub fn chunk_content(content: &str, max_chars: usize) -> Vec<Chunk> {
+    let max_chars = max_chars.max(1);
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+    // Sections as [start, end) line ranges
+    let mut sections = Vec::new();
+    let mut start = 0;
+    for i in 1..lines.len() {
+        if is_boundary(lines[i - 1], lines[i]) {
+            sections.push((start, i));
+            start = i;
+        }
+    }
+    if start < lines.len() {
+        sections.push((start, lines.len()));
+    }
+
+    let mut chunks = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let size = |(s, e): (usize, usize)| lines[s..e].iter().map(|l| l.len()).sum::<usize>();
+
+    for section in sections {
+        match current {
+            Some((s, _)) if size((s, section.1)) <= max_chars => current = Some((s, section.1)),
+            _ => {
+                if let Some(range) = current.take() {
+                    push_range(&mut chunks, &lines, range, max_chars);
+                }
+                current = Some(section);
+            }
+        }
+    }
+    if let Some(range) = current {
+        push_range(&mut chunks, &lines, range, max_chars);
+    }
+    chunks
+}
+
+fn is_boundary(previous: &str, line: &str) -> bool {
+    let top_level = !line.trim().is_empty() && !line.starts_with([' ', '\t']);
+    let previous = previous.trim();
+    top_level && (previous.is_empty() || matches!(previous, "}" | "};" | "end"))
+}
+
+/// Emit one range as a chunk, splitting by lines (and within very long lines)
+fn push_range(
+    chunks: &mut Vec<Chunk>,
+    lines: &[&str],
+    (start, end): (usize, usize),
+    max_chars: usize,
+) {
+    let mut text = String::new();
+    let mut first = start;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        if !text.is_empty() && text.len() + line.len() > max_chars {
+            emit(chunks, first, i - 1, std::mem::take(&mut text));
+            first = i;
+        }
+        if line.len() > max_chars {
+            for piece in split_at_char_boundaries(line, max_chars) {
+                emit(chunks, i, i, piece.to_string());
+            }
+            first = i + 1;
+            continue;
+        }
+        text.push_str(line);
+    }
+    if !text.is_empty() {
+        emit(chunks, first, end - 1, text);
+    }
+}
+
+fn emit(chunks: &mut Vec<Chunk>, first: usize, last: usize, text: String) {
+    chunks.push(Chunk {
+        index: chunks.len(),
+        start_line: first as u32 + 1,
+        end_line: last as u32 + 1,
+        text,
+    });
+}
+
+fn split_at_char_boundaries(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_chars {
+        let mut cut = max_chars;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let cut = cut.max(rest.chars().next().map_or(1, char::len_utf8));
+        pieces.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_content_is_one_chunk() {
+        let chunks = chunk_content("fn main() {}\n", 100);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 1));
+    }
+
+    #[test]
+    fn test_splits_on_top_level_items() {
+        let content = "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\nfn c() {\n    3\n}\n";
+        let chunks = chunk_content(content, 20);
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "fn a() {\n    1\n}\n\n",
+                "fn b() {\n    2\n}\n",
+                "fn c() {\n    3\n}\n"
+            ]
+        );
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (5, 7));
+        assert_eq!(
+            chunks.iter().map(|c| c.text.as_str()).collect::<String>(),
+            content
+        );
+    }
+
+    #[test]
+    fn test_oversized_sections_split_on_lines_and_chars() {
+        let body: String = (0..10)
+            .map(|i| format!("    let x{} = {};\n", i, i))
+            .collect();
+        let content = format!("fn big() {{\n{}}}\n{}\n", body, "é".repeat(30));
+        let chunks = chunk_content(&content, 40);
+        assert!(chunks.iter().all(|c| c.text.len() <= 40));
+        assert_eq!(
+            chunks.iter().map(|c| c.text.as_str()).collect::<String>(),
+            content
+        );
+        assert!(chunks.windows(2).all(|w| w[0].end_line <= w[1].start_line));
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Encoder classifier backend (candle)
+//!
+//! Runs a small fine-tuned BERT-style sequence classifier exported in the
+//! Hugging Face layout: `config.json` (with `id2label`), `tokenizer.json`
+//! and `model.safetensors`. One forward pass yields a probability per
+//! spirit category, which makes it cheap enough to run on every proposal.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::{Linear, Module, VarBuilder};
+use candle_transformers::models::bert::{BertModel, Config};
+use tokenizers::Tokenizer;
+use uuid::Uuid;
+
+use crate::{SlmBackend, SlmError, SlmEvaluation};
+
+/// Labels treated as "no spirit violation"
+const COMPLIANT_LABELS: &[&str] = &["compliant", "clean", "ok", "none"];
+
+/// Labels that mark content as an attempt to manipulate the gate
+const ADVERSARIAL_LABELS: &[&str] = &["adversarial", "prompt_injection", "injection"];
+
+/// BERT-style sequence classifier scoring spirit categories
+pub struct ClassifierBackend {
+    model: BertModel,
+    pooler: Option<Linear>,
+    head: Linear,
+    tokenizer: Tokenizer,
+    labels: Vec<String>,
+    compliant: usize,
+    max_tokens: usize,
+    device: Device,
+    fingerprint: Option<String>,
+}
+
+impl ClassifierBackend {
+    /// Load a classifier from a model directory (CPU)
+    pub fn load(model_dir: impl AsRef<Path>) -> Result<Self, SlmError> {
+        let dir = model_dir.as_ref();
+        let device = Device::Cpu;
+        let unavailable = |what: &str, e: &dyn std::fmt::Display| {
+            SlmError::BackendUnavailable(format!("{}/{}: {}", dir.display(), what, e))
+        };
+
+        let raw = std::fs::read_to_string(dir.join("config.json"))
+            .map_err(|e| unavailable("config.json", &e))?;
+        let config: Config =
+            serde_json::from_str(&raw).map_err(|e| unavailable("config.json", &e))?;
+        let labels = read_labels(&raw).map_err(|e| unavailable("config.json", &e))?;
+        let compliant = labels
+            .iter()
+            .position(|l| COMPLIANT_LABELS.contains(&l.to_lowercase().as_str()))
+            .unwrap_or(0);
+
+        let tokenizer = Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| unavailable("tokenizer.json", &e))?;
+        let weights = dir.join("model.safetensors");
+        let fingerprint = crate::models::file_fingerprint(&weights).ok();
+        let tensors = candle_core::safetensors::load(&weights, &device)
+            .map_err(|e| unavailable("model.safetensors", &e))?;
+        let vb = VarBuilder::from_tensors(tensors, DType::F32, &device);
+
+        let model = BertModel::load(vb.clone(), &config).map_err(|e| unavailable("encoder", &e))?;
+        let hidden = config.hidden_size;
+        let pooler = candle_nn::linear(hidden, hidden, vb.pp("bert.pooler.dense"))
+            .or_else(|_| candle_nn::linear(hidden, hidden, vb.pp("pooler.dense")))
+            .ok();
+        let head = candle_nn::linear(hidden, labels.len(), vb.pp("classifier"))
+            .map_err(|e| unavailable("classifier head", &e))?;
+
+        Ok(Self {
+            model,
+            pooler,
+            head,
+            tokenizer,
+            labels,
+            compliant,
+            max_tokens: config.max_position_embeddings,
+            device,
+            fingerprint,
+        })
+    }
+
+    /// Category probabilities for `content`, in label order
+    pub fn classify(&self, content: &str) -> Result<Vec<(String, f64)>, SlmError> {
+        let inference = |e: candle_core::Error| SlmError::InferenceError(e.to_string());
+
+        let encoding = self
+            .tokenizer
+            .encode(content, true)
+            .map_err(|e| SlmError::InferenceError(e.to_string()))?;
+        let len = encoding.get_ids().len().min(self.max_tokens);
+        let ids = Tensor::new(&encoding.get_ids()[..len], &self.device)
+            .and_then(|t| t.unsqueeze(0))
+            .map_err(inference)?;
+        let mask = Tensor::new(&encoding.get_attention_mask()[..len], &self.device)
+            .and_then(|t| t.unsqueeze(0))
+            .map_err(inference)?;
+        let type_ids = ids.zeros_like().map_err(inference)?;
+
+        let hidden = self
+            .model
+            .forward(&ids, &type_ids, Some(&mask))
+            .map_err(inference)?;
+        // [CLS] token embedding, optionally through the BERT pooler
+        let mut cls = hidden
+            .narrow(1, 0, 1)
+            .and_then(|t| t.squeeze(1))
+            .map_err(inference)?;
+        if let Some(pooler) = &self.pooler {
+            cls = pooler
+                .forward(&cls)
+                .and_then(|t| t.tanh())
+                .map_err(inference)?;
+        }
+        let probs = self
+            .head
+            .forward(&cls)
+            .and_then(|logits| candle_nn::ops::softmax_last_dim(&logits))
+            .and_then(|p| p.squeeze(0))
+            .and_then(|p| p.to_vec1::<f32>())
+            .map_err(inference)?;
+
+        Ok(self
+            .labels
+            .iter()
+            .cloned()
+            .zip(probs.into_iter().map(f64::from))
+            .collect())
+    }
+}
+
+impl SlmBackend for ClassifierBackend {
+    fn name(&self) -> &str {
+        "classifier"
+    }
+
+    fn evaluate(&self, content: &str, _context: &str) -> Result<SlmEvaluation, SlmError> {
+        let probs = self.classify(content)?;
+        let compliant = probs.get(self.compliant).map(|(_, p)| *p).unwrap_or(0.0);
+        let (top, top_p) = probs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.compliant)
+            .map(|(_, (label, p))| (label.as_str(), *p))
+            .fold(
+                ("none", 0.0),
+                |best, cur| if cur.1 > best.1 { cur } else { best },
+            );
+        let adversarial = probs.iter().any(|(label, p)| {
+            *p >= 0.5 && ADVERSARIAL_LABELS.contains(&label.to_lowercase().as_str())
+        });
+
+        Ok(SlmEvaluation {
+            proposal_id: Uuid::new_v4(),
+            spirit_score: 1.0 - compliant,
+            confidence: top_p.max(compliant),
+            reasoning: format!("Classifier: most likely violation {} (p={:.2})", top, top_p),
+            should_block: false,
+            categories: probs.into_iter().collect::<BTreeMap<_, _>>(),
+            model_fingerprint: None,
+            votes: Vec::new(),
+            cache_hit: false,
+            worst_chunk: None,
+            adversarial,
+        })
+    }
+
+    fn fingerprint(&self) -> Option<String> {
+        self.fingerprint.clone()
+    }
+}
+
+/// Label names from `id2label`, ordered by class index
+fn read_labels(raw: &str) -> Result<Vec<String>, String> {
+    let value: serde_json::Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    let map = value
+        .get("id2label")
+        .and_then(|m| m.as_object())
+        .ok_or_else(|| "missing id2label".to_string())?;
+
+    let mut labels = vec![String::new(); map.len()];
+    for (index, label) in map {
+        let slot = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| labels.get_mut(i))
+            .ok_or_else(|| format!("id2label index out of range: {}", index))?;
+        *slot = label.as_str().unwrap_or_default().to_string();
+    }
+    Ok(labels)
+}
@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Model management: registry lookup, download and SHA-256 pinning
+//!
+//! A registry is a JSON document listing model weights by name with their
+//! download URL and expected digest. Pulled weights live in a local store
+//! (`~/.cache/conative/models` by default) and are only kept if the digest
+//! matches, so the fingerprint of a stored file is also its identity.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::SlmError;
+
+/// On-disk format of model weights
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFormat {
+    Gguf,
+    Onnx,
+    Safetensors,
+}
+
+impl ModelFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ModelFormat::Gguf => "gguf",
+            ModelFormat::Onnx => "onnx",
+            ModelFormat::Safetensors => "safetensors",
+        }
+    }
+}
+
+/// A named model in a registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelEntry {
+    pub name: String,
+    /// HTTP(S) URL or local path of the weights
+    pub url: String,
+    /// Expected SHA-256 digest (hex, optionally prefixed with `sha256:`)
+    pub sha256: String,
+    pub format: ModelFormat,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Registry of downloadable models
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelRegistry {
+    #[serde(default)]
+    pub models: Vec<ModelEntry>,
+}
+
+impl ModelRegistry {
+    /// Load a registry from an HTTP(S) URL or a local JSON file
+    pub fn load(source: &str) -> Result<Self, SlmError> {
+        let mut raw = String::new();
+        open_source(source)?
+            .read_to_string(&mut raw)
+            .map_err(|e| SlmError::ModelFetch(format!("{}: {}", source, e)))?;
+        serde_json::from_str(&raw).map_err(|e| SlmError::ModelFetch(format!("{}: {}", source, e)))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&ModelEntry> {
+        self.models.iter().find(|m| m.name == name)
+    }
+}
+
+/// Result of pulling a model into the store
+#[derive(Debug, Clone, Serialize)]
+pub struct PulledModel {
+    pub name: String,
+    pub path: PathBuf,
+    pub fingerprint: String,
+    pub bytes: u64,
+    /// False when a verified copy was already present
+    pub downloaded: bool,
+}
+
+/// Local directory of verified model weights
+pub struct ModelStore {
+    root: PathBuf,
+}
+
+impl ModelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/conative/models`, falling back to `~/.cache/conative/models`
+    pub fn default_root() -> PathBuf {
+        let cache = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+            .unwrap_or_else(|| PathBuf::from(".cache"));
+        cache.join("conative").join("models")
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the weights for `entry` are stored; a name that is not a
+    /// plain file name, and so could point outside the store, is refused
+    pub fn path_for(&self, entry: &ModelEntry) -> Result<PathBuf, SlmError> {
+        let name = &entry.name;
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            return Err(SlmError::ModelFetch(format!(
+                "model name {:?} must be a plain file name",
+                name
+            )));
+        }
+        Ok(self
+            .root
+            .join(format!("{}.{}", name, entry.format.extension())))
+    }
+
+    /// Download (if needed) and verify a model
+    ///
+    /// An existing file is reused when its digest matches; otherwise the
+    /// weights are fetched to a `.part` file and only moved into place after
+    /// the digest checks out.
+    pub fn pull(&self, entry: &ModelEntry, force: bool) -> Result<PulledModel, SlmError> {
+        let expected = normalize_digest(&entry.sha256);
+        let path = self.path_for(entry)?;
+        let io = |e: std::io::Error| SlmError::ModelFetch(format!("{}: {}", path.display(), e));
+
+        if path.exists() && !force {
+            let fingerprint = file_fingerprint(&path).map_err(io)?;
+            if fingerprint == expected {
+                return Ok(PulledModel {
+                    name: entry.name.clone(),
+                    bytes: std::fs::metadata(&path).map_err(io)?.len(),
+                    path,
+                    fingerprint,
+                    downloaded: false,
+                });
+            }
+        }
+
+        std::fs::create_dir_all(&self.root).map_err(io)?;
+        let part = path.with_extension(format!("{}.part", entry.format.extension()));
+        let mut reader = open_source(&entry.url)?;
+        let (fingerprint, bytes) = {
+            let mut file = std::fs::File::create(&part).map_err(io)?;
+            copy_hashed(&mut reader, &mut file).map_err(io)?
+        };
+
+        if fingerprint != expected {
+            let _ = std::fs::remove_file(&part);
+            return Err(SlmError::FingerprintMismatch {
+                expected,
+                actual: fingerprint,
+            });
+        }
+        std::fs::rename(&part, &path).map_err(io)?;
+
+        Ok(PulledModel {
+            name: entry.name.clone(),
+            path,
+            fingerprint,
+            bytes,
+            downloaded: true,
+        })
+    }
+}
+
+/// `sha256:<hex>` digest of a file
+pub fn file_fingerprint(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    copy_hashed(&mut file, &mut std::io::sink()).map(|(fingerprint, _)| fingerprint)
+}
+
+/// Canonical `sha256:<lowercase hex>` form of a configured digest
+pub fn normalize_digest(digest: &str) -> String {
+    let hex = digest.trim();
+    let hex = hex.strip_prefix("sha256:").unwrap_or(hex);
+    format!("sha256:{}", hex.to_lowercase())
+}
+
+/// Reader over an HTTP(S) URL or a local file
+pub fn open_source(source: &str) -> Result<Box<dyn Read>, SlmError> {
+    let error = |e: String| SlmError::ModelFetch(format!("{}: {}", source, e));
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = ureq::get(source).call().map_err(|e| error(e.to_string()))?;
+        Ok(Box::new(response.into_reader()))
+    } else {
+        let file = std::fs::File::open(source).map_err(|e| error(e.to_string()))?;
+        Ok(Box::new(file))
+    }
+}
+
+/// At most `limit` bytes of an HTTP(S) URL or a local file, refused unless
+/// their digest is `sha256`
+pub fn fetch_verified(source: &str, sha256: &str, limit: u64) -> Result<Vec<u8>, SlmError> {
+    let mut bytes = Vec::new();
+    let (actual, read) = copy_hashed(&mut open_source(source)?.take(limit + 1), &mut bytes)
+        .map_err(|e| SlmError::ModelFetch(format!("{}: {}", source, e)))?;
+    if read > limit {
+        return Err(SlmError::ModelFetch(format!(
+            "{}: over {} bytes",
+            source, limit
+        )));
+    }
+    let expected = normalize_digest(sha256);
+    if actual != expected {
+        return Err(SlmError::FingerprintMismatch { expected, actual });
+    }
+    Ok(bytes)
+}
+
+/// Stream `reader` into `writer`, returning the digest and byte count
+fn copy_hashed(reader: &mut dyn Read, writer: &mut dyn Write) -> std::io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        writer.write_all(&buf[..n])?;
+        total += n as u64;
+    }
+    Ok((format!("sha256:{:x}", hasher.finalize()), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("conative-{}-{}", tag, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(url: &Path, sha256: &str) -> ModelEntry {
+        ModelEntry {
+            name: "tiny".to_string(),
+            url: url.to_string_lossy().to_string(),
+            sha256: sha256.to_string(),
+            format: ModelFormat::Gguf,
+            description: String::new(),
+        }
+    }
+
+    // echo -n weights | sha256sum
+    const WEIGHTS_SHA256: &str = "9a129038d9a00aed0cf6a7ea059ca50a813449061ab87848cf1a13eafdf33b2c";
+
+    #[test]
+    fn test_pull_verifies_and_reuses() {
+        let dir = scratch_dir("pull");
+        let source = dir.join("source.gguf");
+        std::fs::write(&source, b"weights").unwrap();
+        let digest = normalize_digest(WEIGHTS_SHA256);
+        assert_eq!(file_fingerprint(&source).unwrap(), digest);
+        let store = ModelStore::new(dir.join("store"));
+
+        let pulled = store
+            .pull(&entry(&source, &WEIGHTS_SHA256.to_uppercase()), false)
+            .unwrap();
+        assert!(pulled.downloaded);
+        assert_eq!(pulled.fingerprint, digest);
+        assert_eq!(pulled.bytes, 7);
+        assert_eq!(pulled.path, dir.join("store").join("tiny.gguf"));
+
+        let again = store.pull(&entry(&source, &digest), false).unwrap();
+        assert!(!again.downloaded);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pull_rejects_digest_mismatch() {
+        let dir = scratch_dir("mismatch");
+        let source = dir.join("source.gguf");
+        std::fs::write(&source, b"tampered").unwrap();
+        let store = ModelStore::new(dir.join("store"));
+
+        let result = store.pull(&entry(&source, WEIGHTS_SHA256), false);
+        assert!(matches!(result, Err(SlmError::FingerprintMismatch { .. })));
+        assert!(!store
+            .path_for(&entry(&source, WEIGHTS_SHA256))
+            .unwrap()
+            .exists());
+        assert_eq!(std::fs::read_dir(dir.join("store")).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_model_names_stay_inside_the_store() {
+        let store = ModelStore::new("/models");
+        let named = |name: &str| ModelEntry {
+            name: name.to_string(),
+            ..entry(Path::new("source.gguf"), WEIGHTS_SHA256)
+        };
+        assert_eq!(
+            store.path_for(&named("spirit-q4")).unwrap(),
+            Path::new("/models/spirit-q4.gguf")
+        );
+        for name in ["", "../escape", "nested/model", "..", "a\\b", "/etc/passwd"] {
+            assert!(
+                matches!(store.path_for(&named(name)), Err(SlmError::ModelFetch(_))),
+                "{:?}",
+                name
+            );
+            assert!(store.pull(&named(name), false).is_err());
+        }
+    }
+
+    #[test]
+    fn test_fetch_verified_checks_digest_and_size() {
+        let dir = scratch_dir("fetch");
+        let source = dir.join("weights");
+        std::fs::write(&source, b"weights").unwrap();
+        let source = source.to_string_lossy();
+
+        assert_eq!(
+            fetch_verified(&source, WEIGHTS_SHA256, 7).unwrap(),
+            b"weights"
+        );
+        assert!(matches!(
+            fetch_verified(&source, "sha256:00", 7),
+            Err(SlmError::FingerprintMismatch { .. })
+        ));
+        assert!(matches!(
+            fetch_verified(&source, WEIGHTS_SHA256, 6),
+            Err(SlmError::ModelFetch(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry: ModelRegistry = serde_json::from_str(
+            r#"{"models": [{"name": "spirit-q4", "url": "https://example.org/spirit.gguf", "sha256": "abc", "format": "gguf"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            registry.find("spirit-q4").unwrap().format,
+            ModelFormat::Gguf
+        );
+        assert!(registry.find("missing").is_none());
+    }
+}
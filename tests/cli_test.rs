@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! CLI Input Tests
+//!
+//! Runs the built `conative` binary on piped stdin and proposal
+//! directories, checking the JSON it prints and the exit code.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use uuid::Uuid;
+
+/// Run `conative` with `args`, piping `stdin` into it
+fn conative(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_conative"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("should start conative");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin)
+        .expect("should write stdin");
+    child.wait_with_output().expect("should run conative")
+}
+
+fn json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).expect("stdout should be JSON")
+}
+
+fn proposal(path: &str, content: &str) -> String {
+    serde_json::json!({
+        "id": Uuid::new_v4(),
+        "action_type": { "CreateFile": { "path": path } },
+        "content": content,
+        "files_affected": [path],
+        "llm_confidence": 0.95,
+    })
+    .to_string()
+}
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("conative-cli-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).expect("should create temp dir");
+    dir
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).expect("should create parent");
+    std::fs::write(path, content).expect("should write file");
+}
+
+#[test]
+fn validate_reads_a_proposal_from_stdin() {
+    let rust = proposal("src/lib.rs", "fn main() {}\n");
+    let output = conative(&["validate", "-", "--format", "json"], rust.as_bytes());
+
+    assert_eq!(output.status.code(), Some(0));
+    let result = json(&output);
+    assert_eq!(result["schema"], "conative-validate-v1");
+    assert_eq!(result["verdict"], "Compliant");
+}
+
+#[test]
+fn validate_reports_malformed_stdin_as_a_parse_error() {
+    let output = conative(&["validate", "-", "--format", "json"], b"{not json");
+
+    assert_eq!(output.status.code(), Some(7));
+    let error = json(&output);
+    assert_eq!(error["schema"], "conative-error-v1");
+    assert_eq!(error["error"]["code"], "parse");
+    assert!(error["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to parse proposal JSON"));
+}
+
+#[test]
+fn validate_directory_reports_each_file() {
+    let dir = temp_dir();
+    write(&dir.join("bad.json"), "{not json");
+    write(
+        &dir.join("good.json"),
+        &proposal("src/lib.rs", "fn main() {}\n"),
+    );
+    write(
+        &dir.join("nested/ts.json"),
+        &proposal("src/x.ts", "export const x: number = 1;\n"),
+    );
+    write(&dir.join("notes.txt"), "not a proposal");
+
+    let output = conative(
+        &["validate", dir.to_str().unwrap(), "--format", "json"],
+        b"",
+    );
+    let batch = json(&output);
+    std::fs::remove_dir_all(&dir).ok();
+
+    // The violation decides the exit code; the unreadable file is only listed
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(batch["schema"], "conative-validate-batch-v1");
+    assert_eq!(batch["total"], 3);
+    assert_eq!(batch["compliant"], 1);
+    assert_eq!(batch["violations"], 1);
+    assert_eq!(batch["errors"], 1);
+
+    let results = batch["results"].as_array().unwrap();
+    let files: Vec<&str> = results
+        .iter()
+        .map(|r| r["file"].as_str().unwrap())
+        .collect();
+    assert!(files[0].ends_with("bad.json"));
+    assert!(files[1].ends_with("good.json"));
+    assert!(files[2].ends_with("ts.json"));
+    assert!(results[0]["verdict"].is_null());
+    assert!(results[0]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to parse proposal JSON"));
+    assert_eq!(results[1]["verdict"], "Compliant");
+    assert!(results[1]["error"].is_null());
+    assert!(results[2]["verdict"]["HardViolation"].is_object());
+}
+
+#[test]
+fn validate_batch_fails_with_the_first_error_when_nothing_violates() {
+    let dir = temp_dir();
+    write(&dir.join("bad.json"), "{not json");
+    let rust = proposal("src/lib.rs", "fn main() {}\n");
+
+    let bad = dir.join("bad.json");
+    let output = conative(
+        &["validate", "-", bad.to_str().unwrap(), "--format", "json"],
+        rust.as_bytes(),
+    );
+    let batch = json(&output);
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(output.status.code(), Some(7));
+    assert_eq!(batch["total"], 2);
+    assert_eq!(batch["results"][0]["file"], "-");
+    assert_eq!(batch["results"][0]["verdict"], "Compliant");
+    assert!(batch["results"][1]["error"].is_string());
+}
+
+#[test]
+fn check_reads_content_from_stdin_under_the_assumed_path() {
+    let output = conative(
+        &[
+            "check",
+            "--content",
+            "-",
+            "--assume-path",
+            "tool.py",
+            "--format",
+            "json",
+        ],
+        b"import os\nprint(os.getcwd())\n",
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let result = json(&output);
+    assert_eq!(result["schema"], "conative-check-v1");
+    let forbidden = &result["verdict"]["HardViolation"]["ForbiddenLanguage"];
+    assert_eq!(forbidden["language"], "python");
+    assert_eq!(forbidden["file"], "tool.py");
+}
+
+#[test]
+fn check_names_stdin_content_stdin_without_an_assumed_path() {
+    let output = conative(
+        &["check", "--content", "-", "--format", "json"],
+        b"#!/usr/bin/env python3\nimport os\ndef main():\n    print(os.getcwd())\n",
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let result = json(&output);
+    assert_eq!(
+        result["verdict"]["HardViolation"]["ForbiddenLanguage"]["file"],
+        "stdin"
+    );
+}
+
+#[test]
+fn check_decodes_stdin_bytes_like_a_file() {
+    // UTF-16LE with a byte order mark is transcoded before the rules run
+    let utf16: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain("import os\n".encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    let output = conative(
+        &[
+            "check",
+            "--content",
+            "-",
+            "--assume-path",
+            "tool.py",
+            "--format",
+            "json",
+        ],
+        &utf16,
+    );
+    let result = json(&output);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        result["verdict"]["HardViolation"]["ForbiddenLanguage"]["language"],
+        "python"
+    );
+    let encoding = result["concerns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["rule"] == "content_encoding")
+        .expect("should note the encoding");
+    assert_eq!(
+        encoding["concern_type"]["UnusualEncoding"]["encoding"],
+        "UTF-16LE"
+    );
+
+    // Bytes that are not UTF-8 are read rather than rejected
+    let output = conative(
+        &["check", "--content", "-", "--format", "json"],
+        b"caf\xe9\n",
+    );
+    let result = json(&output);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        result["verdict"]["SoftConcern"]["UnusualEncoding"]["encoding"],
+        "windows-1252"
+    );
+}
+
+#[test]
+fn check_accepts_empty_stdin() {
+    let output = conative(&["check", "--content", "-", "--format", "json"], b"");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(json(&output)["verdict"], "Compliant");
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Generative Property Tests for the Oracle
+//!
+//! Proptest counterpart of `conative contract fuzz`: random paths and
+//! content checked against the same invariants:
+//! - No panics on arbitrary (including non-ASCII) input
+//! - Determinism: same proposal → same verdict and refusal code
+//! - Whitespace stability: trailing/leading newlines never flip Allow ↔ Block
+//!
+//! Runs are kept short by default; set `PROPTEST_CASES` for more cases, and
+//! run the ignored long-run variant with `cargo test -- --ignored`.
+
+use gating_contract::{ContractRunner, FuzzHarness, GatingRequest, Verdict};
+use policy_oracle::{ActionType, Proposal};
+use proptest::prelude::*;
+use uuid::Uuid;
+
+/// Cases per property: `PROPTEST_CASES`, or a short default run
+fn cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(32)
+}
+
+fn create_proposal(path: &str, content: &str) -> Proposal {
+    Proposal {
+        id: Uuid::new_v4(),
+        action_type: ActionType::CreateFile {
+            path: path.to_string(),
+        },
+        content: content.to_string(),
+        files_affected: vec![path.to_string()],
+        llm_confidence: 0.9,
+    }
+}
+
+fn outcome(runner: &ContractRunner, path: &str, content: &str) -> (Verdict, Option<u16>) {
+    let decision = runner
+        .evaluate(&GatingRequest::new(create_proposal(path, content)))
+        .expect("evaluation should not error");
+    (decision.verdict, decision.refusal.map(|r| r.code.numeric()))
+}
+
+fn path_strategy() -> impl Strategy<Value = String> {
+    (
+        prop::sample::select(vec!["src/lib", "salt/x", "training/m", "README", "a/b c"]),
+        prop::sample::select(vec![
+            ".rs", ".ts", ".TS", ".py", ".go", ".java", ".ex", ".ncl", ".md", "", ".ts.bak",
+        ]),
+    )
+        .prop_map(|(stem, ext)| format!("{}{}", stem, ext))
+}
+
+fn content_strategy() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        "\\PC{0,200}",
+        "(fn main|import os|const x: string|package main|password = \"[a-z]{8,12}\"| |\n|你|\u{200b}){0,20}",
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(cases()))]
+
+    #[test]
+    fn oracle_never_panics_and_is_deterministic(
+        path in path_strategy(),
+        content in content_strategy(),
+    ) {
+        let runner = ContractRunner::new();
+        let first = outcome(&runner, &path, &content);
+        let second = outcome(&runner, &path, &content);
+        prop_assert_eq!(first, second);
+    }
+
+    #[test]
+    fn newline_padding_never_flips_allow_and_block(
+        path in path_strategy(),
+        content in content_strategy(),
+    ) {
+        let runner = ContractRunner::new();
+        let (base, _) = outcome(&runner, &path, &content);
+        for padded in [format!("{}\n", content), format!("\n{}", content)] {
+            let (other, _) = outcome(&runner, &path, &padded);
+            let flipped = matches!(
+                (base, other),
+                (Verdict::Allow, Verdict::Block) | (Verdict::Block, Verdict::Allow)
+            );
+            prop_assert!(!flipped, "{:?} became {:?} for {:?}", base, other, padded);
+        }
+    }
+}
+
+fn run_fuzz_harness(iterations: usize) {
+    let report = FuzzHarness::new(0x5eed)
+        .with_max_content_len(8 * 1024)
+        .run(iterations);
+    assert_eq!(report.iterations, iterations);
+    assert!(
+        report.passed(),
+        "fuzz failures (seed {}): {:#?}",
+        report.seed,
+        report.failures
+    );
+}
+
+#[test]
+fn builtin_fuzz_harness_finds_no_panics() {
+    run_fuzz_harness(25);
+}
+
+#[test]
+#[ignore = "long run; cargo test -- --ignored"]
+fn builtin_fuzz_harness_long_run() {
+    run_fuzz_harness(300);
+}
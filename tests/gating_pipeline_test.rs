@@ -70,7 +70,7 @@ fn e2e_forbidden_typescript_blocked_at_gate() {
     let refusal = decision.refusal.unwrap();
     assert!(!refusal.evidence.is_empty());
     assert!(refusal.remediation.is_some());
-    assert!(refusal.remediation.unwrap().contains("ReScript"));
+    assert!(refusal.remediation.unwrap().summary.contains("ReScript"));
 }
 
 #[test]
@@ -422,3 +422,38 @@ fn e2e_request_context_preserved_in_audit() {
     assert_eq!(audit.source, "test-suite");
     assert_eq!(audit.session_id, Some("session-abc123".to_string()));
 }
+
+#[test]
+fn e2e_embedder_controls_clock_and_ids() {
+    use gating_contract::ManualClock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let start = chrono::DateTime::UNIX_EPOCH + chrono::Duration::days(20_000);
+    let clock = Arc::new(ManualClock::new(start));
+    let counter = Arc::new(AtomicU64::new(1));
+    let ids = {
+        let counter = Arc::clone(&counter);
+        move || Uuid::from_u128(counter.fetch_add(1, Ordering::Relaxed).into())
+    };
+    let runner = ContractRunner::new()
+        .with_clock(clock.clone())
+        .with_id_source(Arc::new(ids));
+
+    let request = GatingRequest::new(create_proposal("lib.rs", "pub fn test() {}"));
+    let first = runner.evaluate(&request).expect("should evaluate");
+    assert_eq!(first.timestamp, start);
+    assert_eq!(first.decision_id, Uuid::from_u128(1));
+
+    clock.advance(chrono::Duration::seconds(90));
+    let second = runner.evaluate(&request).expect("should evaluate");
+    let audit = runner.audit(&request, &second);
+    assert_eq!(
+        second.timestamp - first.timestamp,
+        chrono::Duration::seconds(90)
+    );
+    assert_eq!(runner.now(), second.timestamp);
+    assert_eq!(second.decision_id, Uuid::from_u128(2));
+    assert_eq!(audit.audit_id, Uuid::from_u128(3));
+    assert_eq!(audit.timestamp, second.timestamp);
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright (c) Jonathan D.A. Jewell <j.d.a.jewell@open.ac.uk>
+//! Golden Snapshot Tests for Decision Serialization
+//!
+//! Captures the full GatingDecision JSON for a canonical corpus and fails
+//! when field names, taxonomy mappings or remediation text change.
+//! Regenerate after an intentional change with:
+//!
+//!   UPDATE_SNAPSHOTS=1 cargo test --test snapshot_test
+
+use gating_contract::{GatingRequest, SnapshotHarness, SnapshotStatus, TestCase, Verdict};
+use policy_oracle::{ActionType, Proposal};
+use uuid::Uuid;
+
+fn create_proposal(path: &str, content: &str) -> Proposal {
+    Proposal {
+        id: Uuid::new_v4(),
+        action_type: ActionType::CreateFile {
+            path: path.to_string(),
+        },
+        content: content.to_string(),
+        files_affected: vec![path.to_string()],
+        llm_confidence: 0.95,
+    }
+}
+
+fn case(name: &str, path: &str, content: &str, verdict: Verdict) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        description: String::new(),
+        request: GatingRequest::new(create_proposal(path, content)),
+        expected_verdict: verdict,
+        expected_category: None,
+        expected_code: None,
+        category: String::new(),
+        tags: Vec::new(),
+        quarantined: false,
+    }
+}
+
+fn canonical_corpus() -> Vec<TestCase> {
+    vec![
+        case(
+            "allow_rust",
+            "src/lib.rs",
+            "pub fn add(a: i32, b: i32) -> i32 { a + b }",
+            Verdict::Allow,
+        ),
+        case(
+            "block_typescript",
+            "src/main.ts",
+            "const x: string = 'y';",
+            Verdict::Block,
+        ),
+        case("block_python", "src/app.py", "import os", Verdict::Block),
+        case(
+            "allow_python_exception",
+            "salt/states/init.py",
+            "import salt",
+            Verdict::Allow,
+        ),
+        case(
+            "block_npm_without_deno",
+            "package.json",
+            "npm install left-pad",
+            Verdict::Block,
+        ),
+        case(
+            "block_hardcoded_secret",
+            "src/config.rs",
+            r#"password = "hunter2hunter2""#,
+            Verdict::Block,
+        ),
+        case(
+            "warn_tier2_racket",
+            "tools/gen.rkt",
+            "#lang racket",
+            Verdict::Warn,
+        ),
+    ]
+}
+
+#[test]
+fn decision_json_matches_golden_snapshots() {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+
+    let report = SnapshotHarness::new(dir)
+        .with_update(update)
+        .run(&canonical_corpus())
+        .expect("snapshot run should not error");
+
+    let failures: Vec<String> = report
+        .results
+        .iter()
+        .filter_map(|r| match &r.status {
+            SnapshotStatus::Mismatched { diff } => {
+                Some(format!("{}:\n  {}", r.name, diff.join("\n  ")))
+            }
+            SnapshotStatus::Missing => Some(format!("{}: snapshot missing", r.name)),
+            _ => None,
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "Decision serialization changed (rerun with UPDATE_SNAPSHOTS=1 if intended):\n{}",
+        failures.join("\n")
+    );
+}